@@ -2,18 +2,27 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::metrics::BlockRelayerMetrics;
-use anyhow::{ensure, format_err, Result};
+use anyhow::{bail, ensure, format_err, Result};
 use futures::FutureExt;
-use network_api::messages::{CompactBlockMessage, NotificationMessage, PeerCompactBlockMessage};
-use network_api::{NetworkService, PeerId, PeerProvider, PeerSelector, PeerStrategy};
+use lru::LruCache;
+use network_api::messages::{
+    Announcement, AnnouncementType, CompactBlockMessage, NotificationMessage,
+    PeerAnnouncementMessage, PeerCompactBlockMessage, PeerEvent, PeerMessage,
+};
+use network_api::{
+    NetworkService, PeerId, PeerProvider, PeerSelector, PeerStrategy, ReputationChange,
+};
 use starcoin_chain::verifier::StaticVerifier;
+use starcoin_chain_api::ConnectBlockError;
 use starcoin_config::NodeConfig;
 use starcoin_config::G_CRATE_VERSION;
 use starcoin_crypto::HashValue;
 use starcoin_logger::prelude::*;
 use starcoin_network::NetworkServiceRef;
-use starcoin_network_rpc_api::GetTxnsWithHash;
-use starcoin_service_registry::{ActorService, EventHandler, ServiceContext, ServiceFactory};
+use starcoin_network_rpc_api::{GetBlockTxns, GetTxnsWithHash};
+use starcoin_service_registry::{
+    ActorService, EventHandler, ServiceContext, ServiceFactory, ServiceRef,
+};
 use starcoin_sync::block_connector::BlockConnectorService;
 use starcoin_sync::verified_rpc_client::VerifiedRpcClient;
 use starcoin_sync_api::PeerNewBlock;
@@ -29,15 +38,80 @@ use starcoin_types::{
     system_events::NewHeadBlock,
     transaction::SignedUserTransaction,
 };
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::convert::TryInto;
 use std::sync::Arc;
 
+/// Max count of the peers which relay the compact block to us in high bandwidth mode.
+const HIGH_BANDWIDTH_PEERS: usize = 3;
+
+/// Max count of the recently received blocks, which are not fetched and processed again.
+const KNOWN_BLOCKS_CACHE_SIZE: usize = 1024;
+
+/// The blocks recently relayed to us, and the peers which relay them first.
+pub(crate) struct RelayedBlocks {
+    /// The blocks filled or imported, the same block from other peers is ignored.
+    /// A block is only known after it is filled, so an invalid compact block from one peer does
+    /// not make us ignore the valid one from other peers.
+    known_blocks: LruCache<HashValue, ()>,
+    /// The peer whose compact block of the block is filled first,
+    /// it is promoted to high bandwidth mode after the block is verified and connected.
+    first_relayers: LruCache<HashValue, PeerId>,
+}
+
+impl RelayedBlocks {
+    pub(crate) fn new(cap: usize) -> Self {
+        Self {
+            known_blocks: LruCache::new(cap),
+            first_relayers: LruCache::new(cap),
+        }
+    }
+
+    /// Return the blocks which are not known, without duplicates, in the same order.
+    pub(crate) fn filter_new_blocks(&self, block_ids: Vec<HashValue>) -> Vec<HashValue> {
+        let mut seen = HashSet::new();
+        block_ids
+            .into_iter()
+            .filter(|block_id| !self.known_blocks.contains(block_id) && seen.insert(*block_id))
+            .collect()
+    }
+
+    /// The compact block of the block relayed by the peer is filled.
+    pub(crate) fn on_filled(&mut self, block_id: HashValue, peer_id: PeerId) {
+        self.known_blocks.put(block_id, ());
+        if !self.first_relayers.contains(&block_id) {
+            self.first_relayers.put(block_id, peer_id);
+        }
+    }
+
+    /// The block is connected to the chain, return the peer which relay it first, if any.
+    pub(crate) fn on_connected(&mut self, block_id: HashValue) -> Option<PeerId> {
+        self.known_blocks.put(block_id, ());
+        self.first_relayers.pop(&block_id)
+    }
+}
+
+/// The compact block relayed by the peer is filled, and sent to the block connector.
+#[derive(Clone, Debug)]
+pub struct CompactBlockFilled {
+    pub block_id: HashValue,
+    pub peer_id: PeerId,
+}
+
+/// The peer relays a compact block whose txns do not match the body hash of its header.
+const REP_INVALID_COMPACT_BLOCK: ReputationChange =
+    ReputationChange::new(i32::min_value() / 2, "InvalidCompactBlock");
+
 pub struct BlockRelayer {
     txpool: TxPoolService,
     sync_status: Option<SyncStatus>,
     time_service: Arc<dyn TimeService>,
     metrics: Option<BlockRelayerMetrics>,
+    /// The peers which most recently relay new blocks to us first, front is the latest.
+    high_bandwidth_peers: VecDeque<PeerId>,
+    /// The peers which have been requested a compact block relay mode.
+    negotiated_peers: HashSet<PeerId>,
+    relayed_blocks: RelayedBlocks,
 }
 
 impl ServiceFactory<Self> for BlockRelayer {
@@ -64,6 +138,9 @@ impl BlockRelayer {
             sync_status: None,
             time_service,
             metrics,
+            high_bandwidth_peers: VecDeque::new(),
+            negotiated_peers: HashSet::new(),
+            relayed_blocks: RelayedBlocks::new(KNOWN_BLOCKS_CACHE_SIZE),
         }
     }
 
@@ -91,14 +168,17 @@ impl BlockRelayer {
         )));
     }
 
-    async fn fill_compact_block(
-        txpool: TxPoolService,
+    /// Fill the compact block by prefilled txns and txpool, and request the missing txns by index from the peer.
+    /// Fallback to request the missing txns by hash if the peer do not support request by index.
+    pub(crate) async fn fill_compact_block<P: TxPoolSyncService>(
+        txpool: P,
         rpc_client: VerifiedRpcClient,
         compact_block: CompactBlock,
         peer_id: PeerId,
         metrics: Option<BlockRelayerMetrics>,
     ) -> Result<Block> {
         let expect_txn_len = compact_block.txn_len();
+        let block_id = compact_block.header.id();
         let mut filled_from_txpool: u64 = 0;
         let mut filled_from_prefilled: u64 = 0;
         let mut filled_from_network: u64 = 0;
@@ -108,56 +188,79 @@ impl BlockRelayer {
         } else {
             let mut txns: Vec<Option<SignedUserTransaction>> = vec![None; expect_txn_len];
 
-            let mut missing_txn_short_ids = HashSet::new();
-            // Fill the block txns by tx pool
-            for (index, short_id) in compact_block.short_ids.iter().enumerate() {
-                if let Some(txn) = txpool.find_txn(&short_id.0) {
-                    filled_from_txpool += 1;
-                    txns[index] = Some(txn);
-                } else {
-                    missing_txn_short_ids.insert(short_id);
-                }
-            }
-
-            //TODO move prefilled before txpool
-
             // Fill the block txns by prefilled txn
             for prefilled_txn in compact_block.prefilled_txn {
                 if prefilled_txn.index as usize >= txns.len() {
                     continue;
                 }
-                let id = prefilled_txn.tx.id();
                 txns[prefilled_txn.index as usize] = Some(prefilled_txn.tx);
                 filled_from_prefilled += 1;
-                missing_txn_short_ids.remove(&ShortId(id));
             }
-            // Fetch the missing txns from peer
-            let missing_txn_ids: Vec<HashValue> = missing_txn_short_ids
+
+            // Fill the block txns by tx pool
+            for (index, short_id) in compact_block.short_ids.iter().enumerate() {
+                if txns[index].is_some() {
+                    continue;
+                }
+                if let Some(txn) = txpool.find_txn(&short_id.0) {
+                    filled_from_txpool += 1;
+                    txns[index] = Some(txn);
+                }
+            }
+
+            let missing_indexes = txns
                 .iter()
-                .map(|&short_id| short_id.0)
-                .collect();
-            let mut fetched_missing_txn_map: HashMap<ShortId, Result<SignedUserTransaction>> =
-                if missing_txn_ids.is_empty() {
-                    HashMap::new()
-                } else {
-                    let (_, fetched_missing_txn) = rpc_client
-                        .get_txns(
-                            Some(peer_id),
-                            GetTxnsWithHash {
-                                ids: missing_txn_ids,
-                            },
+                .enumerate()
+                .filter(|(_, txn)| txn.is_none())
+                .map(|(index, _)| index as u64)
+                .collect::<Vec<_>>();
+            if !missing_indexes.is_empty() {
+                debug!(
+                    "[block-relay] compact block {} missing {} txns, request from peer {}",
+                    block_id,
+                    missing_indexes.len(),
+                    peer_id
+                );
+                match rpc_client
+                    .get_block_txns(
+                        peer_id.clone(),
+                        GetBlockTxns {
+                            block_id,
+                            indexes: missing_indexes.clone(),
+                        },
+                    )
+                    .await
+                {
+                    Ok(Some(fetched_txns)) => {
+                        for (index, txn) in missing_indexes.into_iter().zip(fetched_txns) {
+                            let index = index as usize;
+                            let expect_id = compact_block.short_ids[index].0;
+                            if txn.id() != expect_id {
+                                bail!(
+                                    "Peer {} return txn {} at index {} of block {}, but expect txn {}",
+                                    peer_id,
+                                    txn.id(),
+                                    index,
+                                    block_id,
+                                    expect_id
+                                );
+                            }
+                            txns[index] = Some(txn);
+                            filled_from_network += 1;
+                        }
+                    }
+                    result => {
+                        debug!(
+                            "[block-relay] request block txns by index from peer {} failed: {:?}, fallback to request by hash",
+                            peer_id, result
+                        );
+                        filled_from_network += Self::fetch_missing_txns_by_hash(
+                            &rpc_client,
+                            &compact_block.short_ids,
+                            &mut txns,
+                            peer_id,
                         )
                         .await?;
-                    fetched_missing_txn
-                        .into_iter()
-                        .map(|data| (ShortId(data.id()), data.try_into()))
-                        .collect()
-                };
-            for (index, short_id) in compact_block.short_ids.iter().enumerate() {
-                if txns[index].is_none() {
-                    if let Some(txn) = fetched_missing_txn_map.remove(short_id) {
-                        txns[index] = Some(txn?);
-                        filled_from_network += 1;
                     }
                 }
             }
@@ -169,7 +272,7 @@ impl BlockRelayer {
                 expect_txn_len
             );
 
-            if let Some(metrics) = metrics {
+            if let Some(metrics) = metrics.as_ref() {
                 metrics
                     .txns_filled_total
                     .with_label_values(&["expect"])
@@ -194,57 +297,280 @@ impl BlockRelayer {
         let block = Block::new(compact_block.header, body);
         //ensure the block is filled correct.
         StaticVerifier::verify_body_hash(&block)?;
+        if let Some(metrics) = metrics {
+            let result = if filled_from_network == 0 {
+                "hit"
+            } else {
+                "round_trip"
+            };
+            metrics
+                .compact_block_reconstruct_total
+                .with_label_values(&[result])
+                .inc();
+        }
         Ok(block)
     }
 
+    /// Fetch the missing txns by hash, return the count of fetched txns.
+    async fn fetch_missing_txns_by_hash(
+        rpc_client: &VerifiedRpcClient,
+        short_ids: &[ShortId],
+        txns: &mut [Option<SignedUserTransaction>],
+        peer_id: PeerId,
+    ) -> Result<u64> {
+        let missing_txn_ids: Vec<HashValue> = short_ids
+            .iter()
+            .zip(txns.iter())
+            .filter(|(_, txn)| txn.is_none())
+            .map(|(short_id, _)| short_id.0)
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        let (_, fetched_missing_txn) = rpc_client
+            .get_txns(
+                Some(peer_id),
+                GetTxnsWithHash {
+                    ids: missing_txn_ids,
+                },
+            )
+            .await?;
+        let mut fetched_missing_txn_map: HashMap<ShortId, Result<SignedUserTransaction>> =
+            fetched_missing_txn
+                .into_iter()
+                .map(|data| (ShortId(data.id()), data.try_into()))
+                .collect();
+        let mut fetched: u64 = 0;
+        for (index, short_id) in short_ids.iter().enumerate() {
+            if txns[index].is_none() {
+                if let Some(txn) = fetched_missing_txn_map.remove(short_id) {
+                    txns[index] = Some(txn?);
+                    fetched += 1;
+                }
+            }
+        }
+        Ok(fetched)
+    }
+
+    async fn process_compact_block(
+        txpool: TxPoolService,
+        network: NetworkServiceRef,
+        relayer: ServiceRef<BlockRelayer>,
+        block_connector_service: ServiceRef<BlockConnectorService>,
+        compact_block: CompactBlock,
+        peer_id: PeerId,
+        metrics: Option<BlockRelayerMetrics>,
+    ) -> Result<()> {
+        let block_id = compact_block.header.id();
+        if let Ok(Some((_, _, _, version))) = txpool.get_store().get_failed_block_by_id(block_id) {
+            if version == *G_CRATE_VERSION {
+                warn!("Block is failed block : {:?}", block_id);
+            }
+        } else {
+            let peer = network.get_peer(peer_id.clone()).await?.ok_or_else(|| {
+                format_err!(
+                    "CompatBlockMessage's peer {} is not connected",
+                    peer_id.clone()
+                )
+            })?;
+            let peer_selector = PeerSelector::new(vec![peer], PeerStrategy::default(), None);
+            let rpc_client = VerifiedRpcClient::new(peer_selector, network.clone());
+            let _timer = metrics
+                .as_ref()
+                .map(|metrics| metrics.txns_filled_time.start_timer());
+            let block = match BlockRelayer::fill_compact_block(
+                txpool.clone(),
+                rpc_client,
+                compact_block,
+                peer_id.clone(),
+                metrics.clone(),
+            )
+            .await
+            {
+                Ok(block) => block,
+                Err(e) => {
+                    if let Some(metrics) = metrics.as_ref() {
+                        metrics
+                            .compact_block_reconstruct_total
+                            .with_label_values(&["failed"])
+                            .inc();
+                    }
+                    if let Some(ConnectBlockError::VerifyBlockFailed(_, _)) =
+                        e.downcast_ref::<ConnectBlockError>()
+                    {
+                        network.report_peer(peer_id, REP_INVALID_COMPACT_BLOCK);
+                    }
+                    return Err(e);
+                }
+            };
+
+            relayer.notify(CompactBlockFilled {
+                block_id,
+                peer_id: peer_id.clone(),
+            })?;
+            block_connector_service.notify(PeerNewBlock::new(peer_id, block))?;
+        }
+        Ok(())
+    }
+
+    /// The block is connected to the chain, promote the peer which relay it first.
+    fn on_block_connected(&mut self, block_id: HashValue, network: &NetworkServiceRef) {
+        if let Some(peer_id) = self.relayed_blocks.on_connected(block_id) {
+            // the peer is disconnected before the block is connected.
+            if self.negotiated_peers.contains(&peer_id) {
+                self.on_block_relayed_first(peer_id, block_id, network);
+            }
+        }
+    }
+
+    /// The peer relay a new block to us first, move it to the front of the high bandwidth peers,
+    /// and ask the peer out of the high bandwidth peers to switch to low bandwidth mode, like BIP152.
+    fn on_block_relayed_first(
+        &mut self,
+        peer_id: PeerId,
+        block_id: HashValue,
+        network: &NetworkServiceRef,
+    ) {
+        if let Some(index) = self.high_bandwidth_peers.iter().position(|p| p == &peer_id) {
+            self.high_bandwidth_peers.remove(index);
+            self.high_bandwidth_peers.push_front(peer_id);
+            return;
+        }
+        Self::request_relay_mode(
+            network,
+            peer_id.clone(),
+            AnnouncementType::CompactBlockHighBandwidth,
+            block_id,
+        );
+        self.negotiated_peers.insert(peer_id.clone());
+        self.high_bandwidth_peers.push_front(peer_id);
+        while self.high_bandwidth_peers.len() > HIGH_BANDWIDTH_PEERS {
+            if let Some(low_bandwidth_peer) = self.high_bandwidth_peers.pop_back() {
+                Self::request_relay_mode(
+                    network,
+                    low_bandwidth_peer,
+                    AnnouncementType::CompactBlockLowBandwidth,
+                    block_id,
+                );
+            }
+        }
+        if let Some(metrics) = self.metrics.as_ref() {
+            metrics
+                .high_bandwidth_peers
+                .set(self.high_bandwidth_peers.len() as u64);
+        }
+    }
+
+    fn request_relay_mode(
+        network: &NetworkServiceRef,
+        peer_id: PeerId,
+        mode: AnnouncementType,
+        block_id: HashValue,
+    ) {
+        debug!(
+            "[block-relay] request peer {} to relay compact block in {:?} mode",
+            peer_id, mode
+        );
+        network.send_peer_message(PeerMessage::new_announcement(
+            peer_id,
+            Announcement::new(mode, vec![block_id]),
+        ));
+    }
+
     fn handle_block_event(
-        &self,
+        &mut self,
         compact_block_msg: PeerCompactBlockMessage,
         ctx: &mut ServiceContext<BlockRelayer>,
     ) -> Result<()> {
         let network = ctx.get_shared::<NetworkServiceRef>()?;
         let block_connector_service = ctx.service_ref::<BlockConnectorService>()?.clone();
+        let relayer = ctx.self_ref();
+        let txpool = self.txpool.clone();
+        let metrics = self.metrics.clone();
+        let compact_block = compact_block_msg.message.compact_block;
+        let peer_id = compact_block_msg.peer_id;
+        let block_id = compact_block.header.id();
+        debug!("Receive peer compact block event from peer id:{}", peer_id);
+        if self
+            .relayed_blocks
+            .filter_new_blocks(vec![block_id])
+            .is_empty()
+        {
+            debug!(
+                "[block-relay] Ignore compact block {} from peer {}, it is already received",
+                block_id, peer_id
+            );
+            return Ok(());
+        }
+        let fut = BlockRelayer::process_compact_block(
+            txpool,
+            network,
+            relayer,
+            block_connector_service,
+            compact_block,
+            peer_id,
+            metrics,
+        );
+        ctx.spawn(fut.then(|result: Result<()>| async move {
+            if let Err(e) = result {
+                error!("[block-relay] process PeerCmpctBlockEvent error {:?}", e);
+            }
+        }));
+        Ok(())
+    }
+
+    fn handle_block_announcement(
+        &mut self,
+        announcement_msg: PeerAnnouncementMessage,
+        ctx: &mut ServiceContext<BlockRelayer>,
+    ) -> Result<()> {
+        let network = ctx.get_shared::<NetworkServiceRef>()?;
+        let block_connector_service = ctx.service_ref::<BlockConnectorService>()?.clone();
+        let relayer = ctx.self_ref();
         let txpool = self.txpool.clone();
         let metrics = self.metrics.clone();
+        let peer_id = announcement_msg.peer_id;
+        let block_ids = announcement_msg.message.ids();
+        debug!(
+            "Receive peer block announcement {:?} from peer id:{}",
+            block_ids, peer_id
+        );
+        let block_ids = self.relayed_blocks.filter_new_blocks(block_ids);
+        if block_ids.is_empty() {
+            return Ok(());
+        }
         let fut = async move {
-            let compact_block = compact_block_msg.message.compact_block;
-            let peer_id = compact_block_msg.peer_id;
-            debug!("Receive peer compact block event from peer id:{}", peer_id);
-            let block_id = compact_block.header.id();
-            if let Ok(Some((_, _, _, version))) =
-                txpool.get_store().get_failed_block_by_id(block_id)
-            {
-                if version == *G_CRATE_VERSION {
-                    warn!("Block is failed block : {:?}", block_id);
-                }
-            } else {
-                let peer = network.get_peer(peer_id.clone()).await?.ok_or_else(|| {
-                    format_err!(
-                        "CompatBlockMessage's peer {} is not connected",
-                        peer_id.clone()
-                    )
-                })?;
-                let peer_selector = PeerSelector::new(vec![peer], PeerStrategy::default(), None);
-                let rpc_client = VerifiedRpcClient::new(peer_selector, network);
-                let _timer = metrics
-                    .as_ref()
-                    .map(|metrics| metrics.txns_filled_time.start_timer());
-                let block = BlockRelayer::fill_compact_block(
+            let peer = network.get_peer(peer_id.clone()).await?.ok_or_else(|| {
+                format_err!("Block announcement's peer {} is not connected", peer_id)
+            })?;
+            let peer_selector = PeerSelector::new(vec![peer], PeerStrategy::default(), None);
+            let rpc_client = VerifiedRpcClient::new(peer_selector, network.clone());
+            for block_id in block_ids {
+                let (compact_block, _block_info) = rpc_client
+                    .get_compact_block(peer_id.clone(), block_id)
+                    .await?
+                    .ok_or_else(|| {
+                        format_err!(
+                            "Can not get compact block {} from peer {}",
+                            block_id,
+                            peer_id
+                        )
+                    })?;
+                BlockRelayer::process_compact_block(
                     txpool.clone(),
-                    rpc_client,
+                    network.clone(),
+                    relayer.clone(),
+                    block_connector_service.clone(),
                     compact_block,
                     peer_id.clone(),
-                    metrics,
+                    metrics.clone(),
                 )
                 .await?;
-
-                block_connector_service.notify(PeerNewBlock::new(peer_id, block))?;
             }
             Ok(())
         };
         ctx.spawn(fut.then(|result: Result<()>| async move {
             if let Err(e) = result {
-                error!("[block-relay] process PeerCmpctBlockEvent error {:?}", e);
+                error!("[block-relay] process block announcement error {:?}", e);
             }
         }));
         Ok(())
@@ -256,6 +582,7 @@ impl ActorService for BlockRelayer {
         ctx.subscribe::<SyncStatusChangeEvent>();
         ctx.subscribe::<NewHeadBlock>();
         ctx.subscribe::<NewBranch>();
+        ctx.subscribe::<PeerEvent>();
         Ok(())
     }
 
@@ -263,10 +590,47 @@ impl ActorService for BlockRelayer {
         ctx.unsubscribe::<SyncStatusChangeEvent>();
         ctx.unsubscribe::<NewHeadBlock>();
         ctx.unsubscribe::<NewBranch>();
+        ctx.unsubscribe::<PeerEvent>();
         Ok(())
     }
 }
 
+impl EventHandler<Self, PeerEvent> for BlockRelayer {
+    fn handle_event(&mut self, msg: PeerEvent, ctx: &mut ServiceContext<BlockRelayer>) {
+        match msg {
+            PeerEvent::Open(peer_id, chain_info) => {
+                // The new peer relay compact block in low bandwidth mode until it relay a new block first.
+                if self.negotiated_peers.contains(&peer_id) {
+                    return;
+                }
+                let network = match ctx.get_shared::<NetworkServiceRef>() {
+                    Ok(network) => network,
+                    Err(e) => {
+                        error!("Get network service error: {:?}", e);
+                        return;
+                    }
+                };
+                Self::request_relay_mode(
+                    &network,
+                    peer_id.clone(),
+                    AnnouncementType::CompactBlockLowBandwidth,
+                    chain_info.head().id(),
+                );
+                self.negotiated_peers.insert(peer_id);
+            }
+            PeerEvent::Close(peer_id) => {
+                self.negotiated_peers.remove(&peer_id);
+                self.high_bandwidth_peers.retain(|p| p != &peer_id);
+                if let Some(metrics) = self.metrics.as_ref() {
+                    metrics
+                        .high_bandwidth_peers
+                        .set(self.high_bandwidth_peers.len() as u64);
+                }
+            }
+        }
+    }
+}
+
 impl EventHandler<Self, SyncStatusChangeEvent> for BlockRelayer {
     fn handle_event(&mut self, msg: SyncStatusChangeEvent, _ctx: &mut ServiceContext<Self>) {
         self.sync_status = Some(msg.0);
@@ -286,6 +650,7 @@ impl EventHandler<Self, NewHeadBlock> for BlockRelayer {
                 return;
             }
         };
+        self.on_block_connected(event.0.block().id(), &network);
         self.broadcast_compact_block(network, event.0);
     }
 }
@@ -303,10 +668,17 @@ impl EventHandler<Self, NewBranch> for BlockRelayer {
                 return;
             }
         };
+        self.on_block_connected(event.0.block().id(), &network);
         self.broadcast_compact_block(network, event.0);
     }
 }
 
+impl EventHandler<Self, CompactBlockFilled> for BlockRelayer {
+    fn handle_event(&mut self, msg: CompactBlockFilled, _ctx: &mut ServiceContext<BlockRelayer>) {
+        self.relayed_blocks.on_filled(msg.block_id, msg.peer_id);
+    }
+}
+
 impl EventHandler<Self, PeerCompactBlockMessage> for BlockRelayer {
     fn handle_event(
         &mut self,
@@ -339,3 +711,18 @@ impl EventHandler<Self, PeerCompactBlockMessage> for BlockRelayer {
         }
     }
 }
+
+impl EventHandler<Self, PeerAnnouncementMessage> for BlockRelayer {
+    fn handle_event(
+        &mut self,
+        announcement_msg: PeerAnnouncementMessage,
+        ctx: &mut ServiceContext<BlockRelayer>,
+    ) {
+        if !announcement_msg.message.is_block() {
+            return;
+        }
+        if let Err(e) = self.handle_block_announcement(announcement_msg, ctx) {
+            error!("[block-relay] handle block announcement error: {:?}", e);
+        }
+    }
+}
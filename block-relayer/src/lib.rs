@@ -3,4 +3,7 @@
 
 mod block_relayer;
 mod metrics;
+#[cfg(test)]
+mod tests;
+
 pub use block_relayer::BlockRelayer;
@@ -3,7 +3,7 @@
 
 use starcoin_metrics::{
     register, Histogram, HistogramOpts, Opts, PrometheusError, Registry, UIntCounter,
-    UIntCounterVec,
+    UIntCounterVec, UIntGauge,
 };
 
 #[derive(Clone)]
@@ -12,6 +12,8 @@ pub struct BlockRelayerMetrics {
     pub txns_filled_time: Histogram,
     pub block_relay_time: Histogram,
     pub txns_filled_failed_total: UIntCounter,
+    pub compact_block_reconstruct_total: UIntCounterVec,
+    pub high_bandwidth_peers: UIntGauge,
 }
 
 impl BlockRelayerMetrics {
@@ -47,11 +49,32 @@ impl BlockRelayerMetrics {
             registry,
         )?;
 
+        let compact_block_reconstruct_total = register(
+            UIntCounterVec::new(
+                Opts::new(
+                    "compact_block_reconstruct_total",
+                    "Count of compact block reconstruction, hit|round_trip|failed, hit means all txns are filled locally",
+                ),
+                &["result"],
+            )?,
+            registry,
+        )?;
+
+        let high_bandwidth_peers = register(
+            UIntGauge::with_opts(Opts::new(
+                "high_bandwidth_peers",
+                "count of peers which relay compact block in high bandwidth mode",
+            ))?,
+            registry,
+        )?;
+
         Ok(Self {
             txns_filled_total,
             txns_filled_time,
             block_relay_time,
             txns_filled_failed_total,
+            compact_block_reconstruct_total,
+            high_bandwidth_peers,
         })
     }
 }
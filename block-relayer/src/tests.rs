@@ -0,0 +1,238 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::block_relayer::RelayedBlocks;
+use crate::BlockRelayer;
+use anyhow::Result;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use network_api::{PeerId, PeerSelector, PeerStrategy};
+use network_rpc_core::{NetRpcError, RawRpcClient};
+use starcoin_crypto::HashValue;
+use starcoin_network_rpc_api::{GetBlockTxns, GetTxnsWithHash};
+use starcoin_sync::verified_rpc_client::VerifiedRpcClient;
+use starcoin_txpool_mock_service::MockTxPoolService;
+use starcoin_types::block::{Block, BlockBody, BlockHeaderBuilder};
+use starcoin_types::compact_block::{CompactBlock, PrefilledTxn};
+use starcoin_types::transaction::{SignedUserTransaction, Transaction};
+use std::borrow::Cow;
+use std::sync::{Arc, Mutex};
+
+/// Serve the txns of a block to fill its compact block, and record the rpc calls.
+#[derive(Clone)]
+struct BlockTxnsRpcClient {
+    block: Block,
+    /// Whether the peer supports to request the block txns by index.
+    support_index: bool,
+    /// Return a wrong txn when request the block txns by index.
+    wrong_txn: bool,
+    calls: Arc<Mutex<Vec<String>>>,
+}
+
+impl BlockTxnsRpcClient {
+    fn new(block: Block) -> Self {
+        Self {
+            block,
+            support_index: true,
+            wrong_txn: false,
+            calls: Arc::new(Mutex::new(vec![])),
+        }
+    }
+
+    fn calls(&self) -> Vec<String> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    fn handle(&self, rpc_path: &str, message: &[u8]) -> Result<Vec<u8>, NetRpcError> {
+        let txns = self.block.transactions();
+        match rpc_path {
+            "get_block_txns" if self.support_index => {
+                let req: GetBlockTxns = bcs_ext::from_bytes(message)?;
+                let mut fetched = req
+                    .indexes
+                    .iter()
+                    .map(|index| txns[*index as usize].clone())
+                    .collect::<Vec<_>>();
+                if self.wrong_txn {
+                    fetched[0] = SignedUserTransaction::mock();
+                }
+                Ok(bcs_ext::to_bytes(&Some(fetched))?)
+            }
+            "get_txns" => {
+                let req: GetTxnsWithHash = bcs_ext::from_bytes(message)?;
+                let fetched = req
+                    .ids
+                    .iter()
+                    .map(|id| {
+                        txns.iter()
+                            .find(|txn| txn.id() == *id)
+                            .cloned()
+                            .map(Transaction::UserTransaction)
+                    })
+                    .collect::<Vec<_>>();
+                Ok(bcs_ext::to_bytes(&fetched)?)
+            }
+            _ => Err(NetRpcError::method_not_fount(Cow::Owned(
+                rpc_path.to_string(),
+            ))),
+        }
+    }
+}
+
+impl RawRpcClient for BlockTxnsRpcClient {
+    fn send_raw_request(
+        &self,
+        _peer_id: PeerId,
+        rpc_path: Cow<'static, str>,
+        message: Vec<u8>,
+    ) -> BoxFuture<Result<Vec<u8>>> {
+        self.calls.lock().unwrap().push(rpc_path.to_string());
+        let result = self.handle(rpc_path.as_ref(), message.as_slice());
+        async move { bcs_ext::to_bytes(&result) }.boxed()
+    }
+}
+
+fn new_block(txn_count: usize) -> Block {
+    let body = BlockBody::new(
+        (0..txn_count)
+            .map(|_| SignedUserTransaction::mock())
+            .collect(),
+        None,
+    );
+    let header = BlockHeaderBuilder::random()
+        .with_body_hash(body.hash())
+        .build();
+    Block::new(header, body)
+}
+
+async fn fill(
+    txpool: MockTxPoolService,
+    rpc_client: &BlockTxnsRpcClient,
+    compact_block: CompactBlock,
+) -> Result<Block> {
+    let peer_selector = PeerSelector::new(vec![], PeerStrategy::default(), None);
+    BlockRelayer::fill_compact_block(
+        txpool,
+        VerifiedRpcClient::new(peer_selector, rpc_client.clone()),
+        compact_block,
+        PeerId::random(),
+        None,
+    )
+    .await
+}
+
+#[stest::test]
+async fn test_fill_compact_block_from_txpool_and_prefilled() {
+    let block = new_block(4);
+    let txns = block.transactions().to_vec();
+    let mut compact_block = CompactBlock::from(block.clone());
+    compact_block.prefilled_txn = vec![PrefilledTxn {
+        index: 3,
+        tx: txns[3].clone(),
+    }];
+    let txpool = MockTxPoolService::new_with_txns(txns[..3].to_vec());
+    let rpc_client = BlockTxnsRpcClient::new(block.clone());
+
+    let filled = fill(txpool, &rpc_client, compact_block).await.unwrap();
+    assert_eq!(filled, block);
+    assert!(rpc_client.calls().is_empty());
+}
+
+#[stest::test]
+async fn test_fill_compact_block_by_index() {
+    let block = new_block(4);
+    let txns = block.transactions().to_vec();
+    let txpool = MockTxPoolService::new_with_txns(vec![txns[0].clone(), txns[2].clone()]);
+    let rpc_client = BlockTxnsRpcClient::new(block.clone());
+
+    let filled = fill(txpool, &rpc_client, block.clone().into())
+        .await
+        .unwrap();
+    assert_eq!(filled, block);
+    assert_eq!(rpc_client.calls(), vec!["get_block_txns".to_string()]);
+}
+
+#[stest::test]
+async fn test_fill_compact_block_fallback_to_hash() {
+    let block = new_block(3);
+    let txpool = MockTxPoolService::new();
+    let mut rpc_client = BlockTxnsRpcClient::new(block.clone());
+    rpc_client.support_index = false;
+
+    let filled = fill(txpool, &rpc_client, block.clone().into())
+        .await
+        .unwrap();
+    assert_eq!(filled, block);
+    assert_eq!(
+        rpc_client.calls(),
+        vec!["get_block_txns".to_string(), "get_txns".to_string()]
+    );
+}
+
+#[stest::test]
+async fn test_fill_compact_block_with_wrong_txn() {
+    let block = new_block(2);
+    let txpool = MockTxPoolService::new();
+    let mut rpc_client = BlockTxnsRpcClient::new(block.clone());
+    rpc_client.wrong_txn = true;
+
+    assert!(fill(txpool, &rpc_client, block.into()).await.is_err());
+}
+
+#[stest::test]
+async fn test_fill_compact_block_with_wrong_body() {
+    let block = new_block(2);
+    // The header commits to the txns of the block, but the compact block relays other txns.
+    let other_block = new_block(2);
+    let mut compact_block = CompactBlock::from(other_block.clone());
+    compact_block.header = block.header().clone();
+    let txpool = MockTxPoolService::new_with_txns(other_block.transactions().to_vec());
+    let rpc_client = BlockTxnsRpcClient::new(block);
+
+    assert!(fill(txpool, &rpc_client, compact_block).await.is_err());
+}
+
+#[test]
+fn test_filter_new_blocks() {
+    let mut relayed_blocks = RelayedBlocks::new(2);
+    let peer = PeerId::random();
+    let block1 = HashValue::random();
+    let block2 = HashValue::random();
+    let block3 = HashValue::random();
+
+    // The same block announced twice in one message is fetched once.
+    assert_eq!(
+        relayed_blocks.filter_new_blocks(vec![block1, block2, block1]),
+        vec![block1, block2]
+    );
+    // The block is fetched again from other peers until it is filled.
+    assert_eq!(relayed_blocks.filter_new_blocks(vec![block2]), vec![block2]);
+    relayed_blocks.on_filled(block2, peer.clone());
+    assert_eq!(
+        relayed_blocks.filter_new_blocks(vec![block2, block3]),
+        vec![block3]
+    );
+    relayed_blocks.on_connected(block3);
+    assert!(relayed_blocks.filter_new_blocks(vec![block3]).is_empty());
+    // The oldest block is evicted.
+    relayed_blocks.on_filled(block1, peer);
+    assert_eq!(relayed_blocks.filter_new_blocks(vec![block2]), vec![block2]);
+}
+
+#[test]
+fn test_promote_first_relayer_after_connected() {
+    let mut relayed_blocks = RelayedBlocks::new(8);
+    let peer1 = PeerId::random();
+    let peer2 = PeerId::random();
+    let block1 = HashValue::random();
+    let block2 = HashValue::random();
+
+    relayed_blocks.on_filled(block1, peer1.clone());
+    relayed_blocks.on_filled(block1, peer2.clone());
+    // The block which is filled but not connected, such as an invalid block, promotes nobody.
+    relayed_blocks.on_filled(block2, peer2);
+    assert_eq!(relayed_blocks.on_connected(block1), Some(peer1));
+    assert_eq!(relayed_blocks.on_connected(block1), None);
+    // The block mined or synced by ourselves.
+    assert_eq!(relayed_blocks.on_connected(HashValue::random()), None);
+}
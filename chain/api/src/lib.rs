@@ -6,6 +6,7 @@ use anyhow::{bail, format_err, Result};
 use serde::{Deserialize, Serialize};
 use starcoin_accumulator::proof::AccumulatorProof;
 use starcoin_state_api::StateWithProof;
+use starcoin_vm_types::on_chain_resource::EpochInfo;
 use starcoin_vm_types::transaction::{RichTransactionInfo, SignedUserTransaction};
 
 mod chain;
@@ -19,6 +20,51 @@ pub struct ExcludedTxns {
     pub untouched_txns: Vec<SignedUserTransaction>,
 }
 
+/// The uncle and orphan statistics of an epoch, the rates are in per mille.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct EpochUncleStats {
+    pub epoch: u64,
+    pub start_block_number: u64,
+    pub end_block_number: u64,
+    pub block_time_target: u64,
+    pub max_uncles_per_block: u64,
+    /// The main chain blocks of the epoch, less than the epoch length if the epoch is not finished.
+    pub blocks: u64,
+    /// The uncles included by the main chain blocks of the epoch.
+    pub uncles: u64,
+    /// The branch blocks of the epoch saved in the node storage.
+    pub branch_blocks: u64,
+    /// The branch blocks which are not included as uncle by the main chain.
+    pub orphans: u64,
+    pub uncle_rate: u64,
+    pub orphan_rate: u64,
+}
+
+impl EpochUncleStats {
+    pub fn new(epoch_info: &EpochInfo, blocks: u64, branch_blocks: u64, orphans: u64) -> Self {
+        let uncles = epoch_info.uncles();
+        let rate = |count: u64| {
+            count
+                .saturating_mul(1000)
+                .checked_div(blocks)
+                .unwrap_or_default()
+        };
+        Self {
+            epoch: epoch_info.number(),
+            start_block_number: epoch_info.start_block_number(),
+            end_block_number: epoch_info.end_block_number(),
+            block_time_target: epoch_info.block_time_target(),
+            max_uncles_per_block: epoch_info.epoch().max_uncles_per_block(),
+            blocks,
+            uncles,
+            branch_blocks,
+            orphans,
+            uncle_rate: rate(uncles),
+            orphan_rate: rate(orphans),
+        }
+    }
+}
+
 pub use chain::{Chain, ChainReader, ChainWriter, ExecutedBlock, MintedUncleNumber, VerifiedBlock};
 pub use errors::*;
 pub use service::{ChainAsyncService, ReadableChainService, WriteableChainService};
@@ -1,10 +1,11 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2
 
-use crate::TransactionInfoWithProof;
+use crate::{EpochUncleStats, TransactionInfoWithProof};
 use anyhow::Result;
 use starcoin_crypto::HashValue;
 use starcoin_service_registry::ServiceRequest;
+use starcoin_types::startup_info::ReorgRecord;
 use starcoin_types::transaction::RichTransactionInfo;
 use starcoin_types::{
     block::{Block, BlockHeader, BlockInfo, BlockNumber},
@@ -60,6 +61,11 @@ pub enum ChainRequest {
         access_path: Option<AccessPath>,
     },
     GetBlockInfos(Vec<HashValue>),
+    GetEpochUncleStats(u64),
+    GetReorgs {
+        from: Option<u64>,
+        count: u64,
+    },
 }
 
 impl ServiceRequest for ChainRequest {
@@ -88,4 +94,6 @@ pub enum ChainResponse {
     HashVec(Vec<HashValue>),
     TransactionProof(Box<Option<TransactionInfoWithProof>>),
     BlockInfoVec(Box<Vec<Option<BlockInfo>>>),
+    EpochUncleStats(Vec<EpochUncleStats>),
+    ReorgRecords(Vec<ReorgRecord>),
 }
@@ -2,13 +2,13 @@
 // SPDX-License-Identifier: Apache-2
 
 use crate::message::{ChainRequest, ChainResponse};
-use crate::TransactionInfoWithProof;
+use crate::{EpochUncleStats, TransactionInfoWithProof};
 use anyhow::{bail, Result};
 use starcoin_crypto::HashValue;
 use starcoin_service_registry::{ActorService, ServiceHandler, ServiceRef};
 use starcoin_types::contract_event::{ContractEvent, ContractEventInfo};
 use starcoin_types::filter::Filter;
-use starcoin_types::startup_info::ChainStatus;
+use starcoin_types::startup_info::{ChainStatus, ReorgRecord};
 use starcoin_types::transaction::{RichTransactionInfo, Transaction};
 use starcoin_types::{
     block::{Block, BlockHeader, BlockInfo, BlockNumber},
@@ -72,6 +72,13 @@ pub trait ReadableChainService {
     ) -> Result<Option<TransactionInfoWithProof>>;
 
     fn get_block_infos(&self, ids: Vec<HashValue>) -> Result<Vec<Option<BlockInfo>>>;
+
+    /// Get the uncle statistics of the latest `count` epochs, the current epoch is first.
+    fn get_epoch_uncle_stats(&self, count: u64) -> Result<Vec<EpochUncleStats>>;
+
+    /// Get the `count` records of the reorg journal in reverse order, start from the index `from`,
+    /// or the latest record if `from` is None.
+    fn get_reorgs(&self, from: Option<u64>, count: u64) -> Result<Vec<ReorgRecord>>;
 }
 
 /// Writeable block chain service trait
@@ -139,6 +146,10 @@ pub trait ChainAsyncService:
     ) -> Result<Option<TransactionInfoWithProof>>;
 
     async fn get_block_infos(&self, hashes: Vec<HashValue>) -> Result<Vec<Option<BlockInfo>>>;
+
+    async fn get_epoch_uncle_stats(&self, count: u64) -> Result<Vec<EpochUncleStats>>;
+
+    async fn get_reorgs(&self, from: Option<u64>, count: u64) -> Result<Vec<ReorgRecord>>;
 }
 
 #[async_trait::async_trait]
@@ -436,4 +447,22 @@ where
             bail!("get block_infos error")
         }
     }
+
+    async fn get_epoch_uncle_stats(&self, count: u64) -> Result<Vec<EpochUncleStats>> {
+        let response = self.send(ChainRequest::GetEpochUncleStats(count)).await??;
+        if let ChainResponse::EpochUncleStats(stats) = response {
+            Ok(stats)
+        } else {
+            bail!("get epoch uncle stats error")
+        }
+    }
+
+    async fn get_reorgs(&self, from: Option<u64>, count: u64) -> Result<Vec<ReorgRecord>> {
+        let response = self.send(ChainRequest::GetReorgs { from, count }).await??;
+        if let ChainResponse::ReorgRecords(records) = response {
+            Ok(records)
+        } else {
+            bail!("get reorgs error")
+        }
+    }
 }
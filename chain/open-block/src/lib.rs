@@ -251,14 +251,20 @@ impl OpenedBlock {
 
     /// Construct a block template for mining.
     pub fn finalize(self) -> Result<BlockTemplate> {
+        self.block_template()
+    }
+
+    /// Construct a block template of the txns included so far, the block stays open,
+    /// so more txns can be pushed and a newer template constructed later.
+    pub fn block_template(&self) -> Result<BlockTemplate> {
         let accumulator_root = self.txn_accumulator.root_hash();
         let state_root = self.state.state_root();
         let uncles = if !self.uncles.is_empty() {
-            Some(self.uncles)
+            Some(self.uncles.clone())
         } else {
             None
         };
-        let body = BlockBody::new(self.included_user_txns, uncles);
+        let body = BlockBody::new(self.included_user_txns.clone(), uncles);
         let block_template = BlockTemplate::new(
             self.previous_block_info
                 .block_accumulator_info
@@ -270,7 +276,7 @@ impl OpenedBlock {
             self.chain_id,
             self.difficulty,
             self.strategy,
-            self.block_meta,
+            self.block_meta.clone(),
         );
         Ok(block_template)
     }
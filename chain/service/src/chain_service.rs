@@ -5,7 +5,7 @@ use anyhow::{format_err, Error, Result};
 use starcoin_chain::BlockChain;
 use starcoin_chain_api::message::{ChainRequest, ChainResponse};
 use starcoin_chain_api::{
-    ChainReader, ChainWriter, ReadableChainService, TransactionInfoWithProof,
+    ChainReader, ChainWriter, EpochUncleStats, ReadableChainService, TransactionInfoWithProof,
 };
 use starcoin_config::NodeConfig;
 use starcoin_crypto::HashValue;
@@ -13,6 +13,9 @@ use starcoin_logger::prelude::*;
 use starcoin_service_registry::{
     ActorService, EventHandler, ServiceContext, ServiceFactory, ServiceHandler,
 };
+use starcoin_state_api::StateReaderExt;
+use starcoin_statedb::ChainStateDB;
+use starcoin_storage::reorg::ReorgStore;
 use starcoin_storage::{BlockStore, Storage, Store};
 use starcoin_types::block::ExecutedBlock;
 use starcoin_types::contract_event::ContractEventInfo;
@@ -22,11 +25,12 @@ use starcoin_types::transaction::RichTransactionInfo;
 use starcoin_types::{
     block::{Block, BlockHeader, BlockInfo, BlockNumber},
     contract_event::ContractEvent,
-    startup_info::StartupInfo,
+    startup_info::{ReorgRecord, StartupInfo},
     transaction::Transaction,
 };
 use starcoin_vm_runtime::metrics::VMMetrics;
 use starcoin_vm_types::access_path::AccessPath;
+use std::collections::HashSet;
 use std::sync::Arc;
 
 /// A Chain reader service to provider Reader API.
@@ -232,6 +236,12 @@ impl ServiceHandler<Self, ChainRequest> for ChainReaderService {
             ChainRequest::GetBlockInfos(ids) => Ok(ChainResponse::BlockInfoVec(Box::new(
                 self.inner.get_block_infos(ids)?,
             ))),
+            ChainRequest::GetEpochUncleStats(count) => Ok(ChainResponse::EpochUncleStats(
+                self.inner.get_epoch_uncle_stats(count)?,
+            )),
+            ChainRequest::GetReorgs { from, count } => Ok(ChainResponse::ReorgRecords(
+                self.inner.get_reorgs(from, count)?,
+            )),
         }
     }
 }
@@ -286,6 +296,34 @@ impl ChainReaderServiceInner {
         )?;
         Ok(())
     }
+
+    /// Count the branch blocks and the orphan blocks in range [start, end) from storage.
+    /// The uncle must be in the same epoch as the main block which includes it,
+    /// so a branch block of the epoch is an orphan if no main block of the epoch includes it.
+    fn count_branches(&self, start: BlockNumber, end: BlockNumber) -> Result<(u64, u64)> {
+        let mut branch_ids = vec![];
+        let mut uncle_ids = HashSet::new();
+        for number in start..end {
+            let main_block = self
+                .main
+                .get_block_by_number(number)?
+                .ok_or_else(|| format_err!("Can not find block by number {}", number))?;
+            if let Some(uncles) = main_block.uncles() {
+                uncle_ids.extend(uncles.iter().map(|uncle| uncle.id()));
+            }
+            branch_ids.extend(
+                self.storage
+                    .get_block_ids_by_number(number)?
+                    .into_iter()
+                    .filter(|id| *id != main_block.id()),
+            );
+        }
+        let orphans = branch_ids
+            .iter()
+            .filter(|id| !uncle_ids.contains(*id))
+            .count();
+        Ok((branch_ids.len() as u64, orphans as u64))
+    }
 }
 
 impl ReadableChainService for ChainReaderServiceInner {
@@ -416,6 +454,52 @@ impl ReadableChainService for ChainReaderServiceInner {
     fn get_block_infos(&self, ids: Vec<HashValue>) -> Result<Vec<Option<BlockInfo>>> {
         self.storage.get_block_infos(ids)
     }
+
+    fn get_epoch_uncle_stats(&self, count: u64) -> Result<Vec<EpochUncleStats>> {
+        let mut epoch_info = self.main.chain_state_reader().get_epoch_info()?;
+        let mut end = self.main.current_header().number() + 1;
+        let mut stats = vec![];
+        for _ in 0..count {
+            let start = epoch_info.start_block_number();
+            let (branch_blocks, orphans) = self.count_branches(start, end)?;
+            stats.push(EpochUncleStats::new(
+                &epoch_info,
+                end.saturating_sub(start),
+                branch_blocks,
+                orphans,
+            ));
+            if start == 0 {
+                break;
+            }
+            // The epoch of the previous block of the epoch start is the previous epoch.
+            let header = self
+                .main
+                .get_header_by_number(start - 1)?
+                .ok_or_else(|| format_err!("Can not find header by number {}", start - 1))?;
+            let state = ChainStateDB::new(
+                self.storage.clone().into_super_arc(),
+                Some(header.state_root()),
+            );
+            epoch_info = state.get_epoch_info()?;
+            end = start;
+        }
+        Ok(stats)
+    }
+
+    fn get_reorgs(&self, from: Option<u64>, count: u64) -> Result<Vec<ReorgRecord>> {
+        let reorg_count = self.storage.get_reorg_count()?;
+        let end = match from {
+            Some(from) => from.saturating_add(1).min(reorg_count),
+            None => reorg_count,
+        };
+        let mut records = vec![];
+        for index in (end.saturating_sub(count)..end).rev() {
+            if let Some(record) = self.storage.get_reorg(index)? {
+                records.push(record);
+            }
+        }
+        Ok(records)
+    }
 }
 
 #[cfg(test)]
@@ -424,6 +508,7 @@ mod tests {
     use starcoin_chain_api::ChainAsyncService;
     use starcoin_config::NodeConfig;
     use starcoin_service_registry::{RegistryAsyncService, RegistryService};
+    use starcoin_types::block::{BlockBody, BlockHeaderBuilder};
 
     #[stest::test]
     async fn test_actor_launch() -> Result<()> {
@@ -437,4 +522,25 @@ mod tests {
         assert_eq!(&chain_status, chain_info.status());
         Ok(())
     }
+    #[stest::test]
+    async fn test_epoch_uncle_stats() -> Result<()> {
+        let config = Arc::new(NodeConfig::random_for_test());
+        let (storage, _, _) = test_helper::Genesis::init_storage_for_test(config.net())?;
+        // A branch block saved before the service started is counted from storage.
+        let branch_header = BlockHeaderBuilder::random().with_number(0).build();
+        storage.commit_block(Block::new(branch_header, BlockBody::new_empty()))?;
+        let registry = RegistryService::launch();
+        registry.put_shared(config).await?;
+        registry.put_shared(storage).await?;
+        let service_ref = registry.register::<ChainReaderService>().await?;
+        let stats = service_ref.get_epoch_uncle_stats(3).await?;
+        // Only the genesis epoch exists.
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].epoch, 0);
+        assert_eq!(stats[0].blocks, 1);
+        assert_eq!(stats[0].uncles, 0);
+        assert_eq!(stats[0].branch_blocks, 1);
+        assert_eq!(stats[0].orphans, 1);
+        Ok(())
+    }
 }
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 #![deny(clippy::integer_arithmetic)]
 mod chain;
+pub mod replay;
 pub mod verifier;
 pub use chain::BlockChain;
 pub use starcoin_chain_api::{ChainReader, ChainWriter};
@@ -0,0 +1,217 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Re-execute a stored block with the current VM and compare the result with the recorded one,
+//! which is used to validate a VM upgrade against the chain history.
+
+use anyhow::{ensure, format_err, Result};
+use starcoin_crypto::HashValue;
+use starcoin_executor::VMMetrics;
+use starcoin_state_api::{ChainStateReader, ChainStateWriter, StateReaderExt, StateView};
+use starcoin_statedb::ChainStateDB;
+use starcoin_storage::Store;
+use starcoin_types::block::BlockNumber;
+use starcoin_types::transaction::{Transaction, TransactionInfo, TransactionStatus};
+use starcoin_vm_types::state_store::state_key::StateKey;
+use starcoin_vm_types::vm_status::StatusCode;
+use starcoin_vm_types::write_set::WriteSet;
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+/// A state entry written by the replayed or the recorded transaction whose value differs
+/// between the recorded state and the replayed state.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WriteSetEntryDiff {
+    pub state_key: StateKey,
+    /// The value in the recorded state after the transaction, None if it does not exist.
+    pub recorded: Option<Vec<u8>>,
+    /// The value in the replayed state after the transaction, None if it does not exist.
+    pub replayed: Option<Vec<u8>>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TransactionReplayResult {
+    pub txn_hash: HashValue,
+    pub recorded: TransactionInfo,
+    /// None if the replayed transaction is discarded or not executed.
+    pub replayed: Option<TransactionInfo>,
+    /// The status code if the replayed transaction is discarded.
+    pub discarded: Option<StatusCode>,
+    /// Only computed when the state root of the transaction mismatches.
+    pub write_set_diffs: Vec<WriteSetEntryDiff>,
+}
+
+impl TransactionReplayResult {
+    pub fn is_matched(&self) -> bool {
+        self.replayed.as_ref() == Some(&self.recorded)
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BlockReplayReport {
+    pub block_id: HashValue,
+    pub block_number: BlockNumber,
+    pub recorded_state_root: HashValue,
+    pub replayed_state_root: HashValue,
+    pub txns: Vec<TransactionReplayResult>,
+}
+
+impl BlockReplayReport {
+    pub fn is_matched(&self) -> bool {
+        self.recorded_state_root == self.replayed_state_root
+            && self.txns.iter().all(|txn| txn.is_matched())
+    }
+
+    pub fn mismatched_txns(&self) -> impl Iterator<Item = &TransactionReplayResult> {
+        self.txns.iter().filter(|txn| !txn.is_matched())
+    }
+}
+
+/// Re-execute the block against the state of its parent block, and compare every transaction
+/// info with the recorded one. The replayed state is never flushed to the storage.
+pub fn replay_block(
+    storage: Arc<dyn Store>,
+    block_id: HashValue,
+    vm_metrics: Option<VMMetrics>,
+) -> Result<BlockReplayReport> {
+    let block = storage
+        .get_block(block_id)?
+        .ok_or_else(|| format_err!("Can not find block by id: {}", block_id))?;
+    let header = block.header();
+    ensure!(!header.is_genesis(), "Can not replay the genesis block");
+    let parent_header = storage
+        .get_block_header_by_hash(header.parent_hash())?
+        .ok_or_else(|| format_err!("Can not find parent block header of block {}", block_id))?;
+    let recorded_txn_infos = storage
+        .get_block_txn_info_ids(block_id)?
+        .into_iter()
+        .map(|id| {
+            storage
+                .get_transaction_info(id)?
+                .map(|txn_info| txn_info.transaction_info)
+                .ok_or_else(|| format_err!("Can not find txn info by id: {}", id))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut transactions = vec![Transaction::BlockMetadata(
+        block.to_metadata(parent_header.gas_used()),
+    )];
+    transactions.extend(
+        block
+            .transactions()
+            .iter()
+            .cloned()
+            .map(Transaction::UserTransaction),
+    );
+    ensure!(
+        transactions.len() == recorded_txn_infos.len(),
+        "Txn num {} of block {} mismatches the recorded txn info num {}",
+        transactions.len(),
+        block_id,
+        recorded_txn_infos.len()
+    );
+
+    let statedb = ChainStateDB::new(
+        storage.clone().into_super_arc(),
+        Some(parent_header.state_root()),
+    );
+    let epoch = statedb.get_epoch()?;
+    let outputs = starcoin_executor::execute_block_transactions(
+        &statedb,
+        transactions.clone(),
+        epoch.block_gas_limit(),
+        vm_metrics,
+    )?;
+    let mut outputs = outputs.into_iter();
+
+    let mut txns = Vec::with_capacity(transactions.len());
+    let mut recorded_pre_state_root = parent_header.state_root();
+    for (txn, recorded) in transactions.iter().zip(recorded_txn_infos) {
+        let txn_hash = txn.id();
+        let recorded_post_state_root = recorded.state_root_hash();
+        let mut result = TransactionReplayResult {
+            txn_hash,
+            recorded,
+            replayed: None,
+            discarded: None,
+            write_set_diffs: vec![],
+        };
+        if let Some(output) = outputs.next() {
+            let (write_set, events, gas_used, status) = output.into_inner();
+            match status {
+                TransactionStatus::Discard(status) => {
+                    result.discarded = Some(status);
+                }
+                TransactionStatus::Keep(status) => {
+                    statedb.apply_write_set(write_set.clone())?;
+                    let state_root = statedb.commit()?;
+                    if state_root != recorded_post_state_root {
+                        result.write_set_diffs = diff_write_set(
+                            &storage,
+                            &statedb,
+                            write_set,
+                            recorded_pre_state_root,
+                            recorded_post_state_root,
+                        )?;
+                    }
+                    result.replayed = Some(TransactionInfo::new(
+                        txn_hash,
+                        state_root,
+                        events.as_slice(),
+                        gas_used,
+                        status,
+                    ));
+                }
+            }
+        }
+        txns.push(result);
+        recorded_pre_state_root = recorded_post_state_root;
+    }
+
+    Ok(BlockReplayReport {
+        block_id,
+        block_number: header.number(),
+        recorded_state_root: header.state_root(),
+        replayed_state_root: statedb.state_root(),
+        txns,
+    })
+}
+
+/// Diff the recorded state and the replayed state over the union of the keys written by the
+/// replayed transaction and the keys changed by the recorded transaction, so a write missing on
+/// either side is reported too.
+fn diff_write_set(
+    storage: &Arc<dyn Store>,
+    replayed_state: &ChainStateDB,
+    write_set: WriteSet,
+    recorded_pre_state_root: HashValue,
+    recorded_post_state_root: HashValue,
+) -> Result<Vec<WriteSetEntryDiff>> {
+    let recorded_state = ChainStateDB::new(
+        storage.clone().into_super_arc(),
+        Some(recorded_post_state_root),
+    );
+    let mut state_keys: BTreeSet<StateKey> = write_set
+        .into_iter()
+        .map(|(state_key, _)| state_key)
+        .collect();
+    state_keys.extend(
+        recorded_state
+            .diff(recorded_pre_state_root)?
+            .into_iter()
+            .map(|(state_key, _, _)| state_key),
+    );
+    let mut diffs = vec![];
+    for state_key in state_keys {
+        let recorded = recorded_state.get_state_value(&state_key)?;
+        let replayed = replayed_state.get_state_value(&state_key)?;
+        if recorded != replayed {
+            diffs.push(WriteSetEntryDiff {
+                state_key,
+                recorded,
+                replayed,
+            });
+        }
+    }
+    Ok(diffs)
+}
@@ -4,6 +4,7 @@
 use anyhow::Result;
 use starcoin_account_api::AccountInfo;
 use starcoin_accumulator::Accumulator;
+use starcoin_chain::replay::{replay_block, WriteSetEntryDiff};
 use starcoin_chain::BlockChain;
 use starcoin_chain::{ChainReader, ChainWriter};
 use starcoin_chain_mock::MockChain;
@@ -11,7 +12,10 @@ use starcoin_config::NodeConfig;
 use starcoin_config::{BuiltinNetworkID, ChainNetwork};
 use starcoin_consensus::Consensus;
 use starcoin_crypto::{ed25519::Ed25519PrivateKey, Genesis, PrivateKey};
+use starcoin_state_api::ChainStateWriter;
+use starcoin_statedb::ChainStateDB;
 use starcoin_transaction_builder::{build_transfer_from_association, DEFAULT_EXPIRATION_TIME};
+use starcoin_types::access_path::AccessPath;
 use starcoin_types::account_address;
 use starcoin_types::block::{Block, BlockHeader};
 use starcoin_types::filter::Filter;
@@ -19,6 +23,8 @@ use starcoin_types::identifier::Identifier;
 use starcoin_types::language_storage::TypeTag;
 use starcoin_vm_types::account_config::genesis_address;
 use starcoin_vm_types::language_storage::StructTag;
+use starcoin_vm_types::state_store::state_key::StateKey;
+use starcoin_vm_types::write_set::{WriteOp, WriteSetMut};
 use std::str::FromStr;
 use std::sync::Arc;
 
@@ -514,3 +520,110 @@ fn test_get_blocks_by_number() -> Result<()> {
 
     Ok(())
 }
+
+#[stest::test]
+fn test_replay_block() -> Result<()> {
+    let config = Arc::new(NodeConfig::random_for_test());
+    let mut block_chain = test_helper::gen_blockchain_for_test(config.net())?;
+    let miner_account = AccountInfo::random();
+    let signed_txn = build_transfer_from_association(
+        *miner_account.address(),
+        0,
+        10000,
+        config.net().time_service().now_secs() + DEFAULT_EXPIRATION_TIME,
+        config.net(),
+    )
+    .as_signed_user_txn()?
+    .clone();
+    let (template, excluded) = block_chain.create_block_template(
+        *miner_account.address(),
+        None,
+        vec![signed_txn],
+        vec![],
+        None,
+    )?;
+    assert!(excluded.discarded_txns.is_empty(), "txn is discarded.");
+    let block = block_chain
+        .consensus()
+        .create_block(template, config.net().time_service().as_ref())?;
+    block_chain.apply(block.clone())?;
+
+    let report = replay_block(block_chain.get_storage(), block.id(), None)?;
+    assert!(
+        report.is_matched(),
+        "replay report mismatched: {:?}",
+        report
+    );
+    assert_eq!(report.block_number, block.header().number());
+    assert_eq!(report.replayed_state_root, block.header().state_root());
+    // the block metadata txn and the user txn.
+    assert_eq!(report.txns.len(), 2);
+    assert!(report.txns.iter().all(|txn| txn.write_set_diffs.is_empty()));
+
+    let genesis_id = block.header().parent_hash();
+    assert!(replay_block(block_chain.get_storage(), genesis_id, None).is_err());
+    Ok(())
+}
+
+#[stest::test]
+fn test_replay_block_missing_write() -> Result<()> {
+    let config = Arc::new(NodeConfig::random_for_test());
+    let mut block_chain = test_helper::gen_blockchain_for_test(config.net())?;
+    let miner_account = AccountInfo::random();
+    let signed_txn = build_transfer_from_association(
+        *miner_account.address(),
+        0,
+        10000,
+        config.net().time_service().now_secs() + DEFAULT_EXPIRATION_TIME,
+        config.net(),
+    )
+    .as_signed_user_txn()?
+    .clone();
+    let (template, _) = block_chain.create_block_template(
+        *miner_account.address(),
+        None,
+        vec![signed_txn],
+        vec![],
+        None,
+    )?;
+    let block = block_chain
+        .consensus()
+        .create_block(template, config.net().time_service().as_ref())?;
+    block_chain.apply(block.clone())?;
+
+    // Tamper the recorded state of the user txn with an extra write, which the replayed txn
+    // misses.
+    let storage = block_chain.get_storage();
+    let txn_info_ids = storage.get_block_txn_info_ids(block.id())?;
+    let mut user_txn_info = storage
+        .get_transaction_info(txn_info_ids[1])?
+        .expect("txn info must exist.");
+    let recorded_state = ChainStateDB::new(
+        storage.clone().into_super_arc(),
+        Some(user_txn_info.transaction_info.state_root_hash),
+    );
+    let extra_key = StateKey::AccessPath(AccessPath::random_resource());
+    recorded_state.apply_write_set(
+        WriteSetMut::new(vec![(extra_key.clone(), WriteOp::Value(vec![1]))]).freeze()?,
+    )?;
+    user_txn_info.transaction_info.state_root_hash = recorded_state.commit()?;
+    recorded_state.flush()?;
+    let tampered_txn_info_ids = vec![txn_info_ids[0], user_txn_info.transaction_info.id()];
+    storage.save_transaction_infos(vec![user_txn_info])?;
+    storage.save_block_txn_info_ids(block.id(), tampered_txn_info_ids)?;
+
+    let report = replay_block(storage, block.id(), None)?;
+    assert!(!report.is_matched());
+    assert!(report.txns[0].is_matched());
+    let user_txn = &report.txns[1];
+    assert!(!user_txn.is_matched());
+    assert_eq!(
+        user_txn.write_set_diffs,
+        vec![WriteSetEntryDiff {
+            state_key: extra_key,
+            recorded: Some(vec![1]),
+            replayed: None,
+        }]
+    );
+    Ok(())
+}
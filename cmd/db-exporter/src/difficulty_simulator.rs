@@ -0,0 +1,198 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{ensure, format_err, Result};
+use clap::Parser;
+use serde::Serialize;
+use starcoin_chain::{BlockChain, ChainReader};
+use starcoin_config::{BuiltinNetworkID, ChainNetwork};
+use starcoin_consensus::simulator::{
+    DifficultySimulator, HashrateCurve, HistoryBlock, SimulationReport, SimulatorConfig,
+};
+use starcoin_genesis::Genesis;
+use starcoin_storage::cache_storage::CacheStorage;
+use starcoin_storage::db_storage::DBStorage;
+use starcoin_storage::storage::StorageInstance;
+use starcoin_storage::{Storage, StorageVersion};
+use starcoin_types::block::BlockNumber;
+use starcoin_types::U256;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Parser)]
+#[clap(
+    name = "difficulty-simulate",
+    about = "simulate the difficulty adjustment with alternative consensus config"
+)]
+pub struct DifficultySimulateOptions {
+    #[clap(long, short = 'n', default_value = "main")]
+    /// Chain Network, the base consensus config is the genesis config of the network.
+    pub net: BuiltinNetworkID,
+    #[clap(long, short = 'i', parse(from_os_str))]
+    /// starcoin node db path. like ~/.starcoin/main, replay the hashrate of the history blocks if present.
+    pub db_path: Option<PathBuf>,
+    #[clap(long, short = 's')]
+    /// the start block number of the history blocks.
+    pub start: Option<BlockNumber>,
+    #[clap(long, short = 'e')]
+    /// the end block number of the history blocks, default is the head block.
+    pub end: Option<BlockNumber>,
+    #[clap(long)]
+    /// the synthesized hashrate curve, `time_secs:hashes_per_second` pairs like `0:1000,3600:2000`.
+    pub hashrate: Option<HashrateCurve>,
+    #[clap(long, short = 'b')]
+    /// the blocks to simulate, default is the count of the history blocks or 2 epochs.
+    pub blocks: Option<u64>,
+    #[clap(long)]
+    pub uncle_rate_target: Option<u64>,
+    #[clap(long)]
+    /// the block time target of the first epoch in milliseconds.
+    pub block_time_target: Option<u64>,
+    #[clap(long)]
+    pub epoch_block_count: Option<u64>,
+    #[clap(long)]
+    pub difficulty_window: Option<u64>,
+    #[clap(long)]
+    pub min_block_time_target: Option<u64>,
+    #[clap(long)]
+    pub max_block_time_target: Option<u64>,
+    #[clap(long, default_value = "1000")]
+    /// the block propagation delay in milliseconds, used to estimate the uncle rate.
+    pub propagation_delay: u64,
+    #[clap(long)]
+    pub initial_difficulty: Option<u64>,
+    #[clap(long, default_value = "0")]
+    /// the seed of the random block time.
+    pub seed: u64,
+    #[clap(long, short = 'o', parse(from_os_str))]
+    /// output the simulated blocks to the csv file.
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Serialize)]
+struct DifficultySimulateResult {
+    history: Option<SimulationReport>,
+    simulation: SimulationReport,
+}
+
+pub fn simulate_difficulty(option: DifficultySimulateOptions) -> Result<()> {
+    let net = ChainNetwork::new_builtin(option.net);
+    let mut consensus = net.genesis_config().consensus_config.clone();
+    if let Some(uncle_rate_target) = option.uncle_rate_target {
+        consensus.uncle_rate_target = uncle_rate_target;
+    }
+    if let Some(block_time_target) = option.block_time_target {
+        consensus.base_block_time_target = block_time_target;
+    }
+    if let Some(epoch_block_count) = option.epoch_block_count {
+        consensus.epoch_block_count = epoch_block_count;
+    }
+    if let Some(difficulty_window) = option.difficulty_window {
+        consensus.base_block_difficulty_window = difficulty_window;
+    }
+    if let Some(min_block_time_target) = option.min_block_time_target {
+        consensus.min_block_time_target = min_block_time_target;
+    }
+    if let Some(max_block_time_target) = option.max_block_time_target {
+        consensus.max_block_time_target = max_block_time_target;
+    }
+
+    let history = match &option.db_path {
+        Some(db_path) => Some(load_history(
+            db_path,
+            &net,
+            option.start.unwrap_or_default(),
+            option.end,
+        )?),
+        None => None,
+    };
+    let mut config = SimulatorConfig::new(consensus.clone());
+    config.propagation_delay = option.propagation_delay;
+    config.seed = option.seed;
+    config.initial_difficulty = option.initial_difficulty.map(U256::from);
+    let (hashrate, blocks) = match (option.hashrate, &history) {
+        (Some(hashrate), _) => (
+            hashrate,
+            option
+                .blocks
+                .unwrap_or_else(|| consensus.epoch_block_count.saturating_mul(2)),
+        ),
+        (None, Some(history)) => {
+            if config.initial_difficulty.is_none() {
+                config.initial_difficulty = history.first().map(|block| block.difficulty);
+            }
+            (
+                HashrateCurve::from_history(
+                    history,
+                    consensus.base_block_difficulty_window as usize,
+                )?,
+                option
+                    .blocks
+                    .unwrap_or_else(|| history.len().saturating_sub(1) as u64),
+            )
+        }
+        (None, None) => {
+            return Err(format_err!(
+                "either the hashrate curve or the db path is required"
+            ))
+        }
+    };
+    let (simulated, report) = DifficultySimulator::new(config, hashrate)?.run(blocks)?;
+    if let Some(output) = option.output {
+        let mut writer = csv::Writer::from_path(output)?;
+        for block in simulated {
+            writer.serialize(block)?;
+        }
+        writer.flush()?;
+    }
+    let result = DifficultySimulateResult {
+        history: history
+            .map(|history| SimulationReport::from_history(&history, consensus.epoch_block_count)),
+        simulation: report,
+    };
+    println!("{}", serde_json::to_string_pretty(&result)?);
+    Ok(())
+}
+
+fn load_history(
+    from_dir: &Path,
+    net: &ChainNetwork,
+    start: BlockNumber,
+    end: Option<BlockNumber>,
+) -> Result<Vec<HistoryBlock>> {
+    let db_storage = DBStorage::open_with_cfs(
+        from_dir.join("starcoindb/db/starcoindb"),
+        StorageVersion::current_version()
+            .get_column_family_names()
+            .to_vec(),
+        true,
+        Default::default(),
+        None,
+    )?;
+    let storage = Arc::new(Storage::new(StorageInstance::new_cache_and_db_instance(
+        CacheStorage::new(None),
+        db_storage,
+    ))?);
+    let (chain_info, _) = Genesis::init_and_check_storage(net, storage.clone(), from_dir)?;
+    let chain = BlockChain::new(net.time_service(), chain_info.head().id(), storage, None)?;
+    let end = end
+        .unwrap_or_else(|| chain.current_header().number())
+        .min(chain.current_header().number());
+    ensure!(start < end, "start {} must be less than end {}", start, end);
+    (start..=end)
+        .map(|number| {
+            let block = chain
+                .get_block_by_number(number)?
+                .ok_or_else(|| format_err!("Can not find block by number {}", number))?;
+            Ok(HistoryBlock {
+                number,
+                timestamp: block.header().timestamp(),
+                difficulty: block.header().difficulty(),
+                uncles: block
+                    .uncles()
+                    .map(|uncles| uncles.len())
+                    .unwrap_or_default() as u64,
+            })
+        })
+        .collect()
+}
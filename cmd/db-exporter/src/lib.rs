@@ -2,5 +2,6 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod cmd_batch_execution;
+pub mod difficulty_simulator;
 pub mod verify_header;
 pub mod verify_module;
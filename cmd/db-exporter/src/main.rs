@@ -7,6 +7,7 @@ use bcs_ext::Sample;
 use clap::IntoApp;
 use clap::Parser;
 use csv::Writer;
+use db_exporter::difficulty_simulator::{simulate_difficulty, DifficultySimulateOptions};
 use db_exporter::verify_header::{verify_header_via_export_file, VerifyHeaderOptions};
 use db_exporter::verify_module::{verify_modules_via_export_file, VerifyModuleOptions};
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
@@ -15,6 +16,7 @@ use serde::{Serialize, Serializer};
 use starcoin_account_api::AccountInfo;
 use starcoin_accumulator::node::AccumulatorStoreType;
 use starcoin_accumulator::{Accumulator, MerkleAccumulator};
+use starcoin_chain::replay::replay_block;
 use starcoin_chain::verifier::{
     BasicVerifier, ConsensusVerifier, FullVerifier, NoneVerifier, Verifier,
 };
@@ -235,6 +237,8 @@ enum Cmd {
     ExportResource(ExportResourceOptions),
     VerifyModules(VerifyModuleOptions),
     VerifyHeader(VerifyHeaderOptions),
+    DifficultySimulate(DifficultySimulateOptions),
+    ReplayBlock(ReplayBlockOptions),
 }
 
 #[derive(Debug, Clone, Parser)]
@@ -417,6 +421,25 @@ pub struct ExportResourceOptions {
     pub fields: Vec<String>,
 }
 
+#[derive(Debug, Clone, Parser)]
+#[clap(
+    name = "replay-block",
+    about = "re-execute the block range with the current vm and diff the txn infos"
+)]
+pub struct ReplayBlockOptions {
+    #[clap(long, short = 'n')]
+    /// Chain Network, like main, proxima
+    pub net: BuiltinNetworkID,
+    #[clap(long, short = 'i', parse(from_os_str))]
+    /// starcoin node db path. like ~/.starcoin/main
+    pub db_path: PathBuf,
+    #[clap(long, short = 's')]
+    pub start: BlockNumber,
+    #[clap(long, short = 'e')]
+    /// end block number, default is the start block number.
+    pub end: Option<BlockNumber>,
+}
+
 #[tokio::main(flavor = "multi_thread")]
 async fn main() -> anyhow::Result<()> {
     let opt = Opt::parse();
@@ -571,6 +594,13 @@ async fn main() -> anyhow::Result<()> {
         Cmd::VerifyHeader(option) => {
             return verify_header_via_export_file(option.input_path, option.batch_size);
         }
+        Cmd::DifficultySimulate(option) => {
+            return simulate_difficulty(option);
+        }
+        Cmd::ReplayBlock(option) => {
+            let end = option.end.unwrap_or(option.start);
+            return replay_block_range(option.db_path, option.net, option.start, end);
+        }
     }
     Ok(())
 }
@@ -661,6 +691,90 @@ pub fn export_block_range(
     Ok(())
 }
 
+pub fn replay_block_range(
+    from_dir: PathBuf,
+    network: BuiltinNetworkID,
+    start: BlockNumber,
+    end: BlockNumber,
+) -> anyhow::Result<()> {
+    let net = ChainNetwork::new_builtin(network);
+    let db_storage = DBStorage::open_with_cfs(
+        from_dir.join("starcoindb/db/starcoindb"),
+        StorageVersion::current_version()
+            .get_column_family_names()
+            .to_vec(),
+        true,
+        Default::default(),
+        None,
+    )?;
+    let storage = Arc::new(Storage::new(StorageInstance::new_cache_and_db_instance(
+        CacheStorage::new(None),
+        db_storage,
+    ))?);
+    let (chain_info, _) =
+        Genesis::init_and_check_storage(&net, storage.clone(), from_dir.as_ref())?;
+    let chain = BlockChain::new(
+        net.time_service(),
+        chain_info.head().id(),
+        storage.clone(),
+        None,
+    )
+    .expect("create block chain should success.");
+    let cur_num = chain.status().head().number();
+    if start == 0 || start > end || end > cur_num {
+        return Err(format_err!(
+            "cur_num {} start {} end {} illegal",
+            cur_num,
+            start,
+            end
+        ));
+    }
+    let bar = ProgressBar::new(end - start + 1);
+    bar.set_style(
+        ProgressStyle::default_bar()
+            .template("[{elapsed_precise}] {bar:100.cyan/blue} {percent}% {msg}"),
+    );
+    let mut mismatched_blocks = 0u64;
+    for number in start..=end {
+        let block_id = chain
+            .get_hash_by_number(number)?
+            .ok_or_else(|| format_err!("{} get block error", number))?;
+        let report = replay_block(storage.clone(), block_id, None)?;
+        if !report.is_matched() {
+            mismatched_blocks += 1;
+            bar.println(format!(
+                "block {}({}) mismatched, state root recorded: {}, replayed: {}",
+                report.block_number,
+                report.block_id,
+                report.recorded_state_root,
+                report.replayed_state_root
+            ));
+            for txn in report.mismatched_txns() {
+                bar.println(format!(
+                    "  txn {}, recorded: {:?}, replayed: {:?}, discarded: {:?}",
+                    txn.txn_hash, txn.recorded, txn.replayed, txn.discarded
+                ));
+                for diff in &txn.write_set_diffs {
+                    bar.println(format!(
+                        "    {:?}, recorded: {}, replayed: {}",
+                        diff.state_key,
+                        diff.recorded.as_ref().map(hex::encode).unwrap_or_default(),
+                        diff.replayed.as_ref().map(hex::encode).unwrap_or_default()
+                    ));
+                }
+            }
+        }
+        bar.set_message(format!("replay block {}", number));
+        bar.inc(1);
+    }
+    bar.finish();
+    println!(
+        "replay block range [{}..{}], mismatched blocks: {}",
+        start, end, mismatched_blocks
+    );
+    Ok(())
+}
+
 pub fn apply_block(
     to_dir: PathBuf,
     input_path: PathBuf,
@@ -0,0 +1,260 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Benchmark and self-test of the solver, feed the solver with synthetic mint events,
+//! verify the found seals by consensus, and estimate the hashrate without connecting to a node.
+
+use crate::solver::create_solver;
+use crate::SealEvent;
+use anyhow::{format_err, Result};
+use clap::Parser;
+use futures::channel::mpsc::unbounded;
+use futures::executor::block_on;
+use futures::future::{select, Either};
+use futures::StreamExt;
+use futures_timer::Delay;
+use starcoin_config::{MinerClientConfig, TimeService};
+use starcoin_consensus::Consensus;
+use starcoin_crypto::HashValue;
+use starcoin_logger::prelude::*;
+use starcoin_miner_client_api::Solver;
+use starcoin_time_service::RealTimeService;
+use starcoin_types::block::{BlockHeaderBuilder, BlockHeaderExtra};
+use starcoin_types::genesis_config::ConsensusStrategy;
+use starcoin_types::system_events::{MintBlockEvent, MintEventExtra};
+use starcoin_types::U256;
+use std::fmt;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Parser)]
+pub struct BenchmarkOpt {
+    /// The consensus strategies to benchmark, default to all the pow strategies.
+    #[clap(long, short = 's', multiple_values(true), multiple_occurrences(true))]
+    pub strategy: Vec<ConsensusStrategy>,
+    /// The mint events of every strategy.
+    #[clap(long, short = 'r', default_value = "5")]
+    pub rounds: u32,
+    /// The difficulty of the mint events.
+    #[clap(long, short = 'd', default_value = "1000")]
+    pub difficulty: u64,
+    /// The max seconds to wait for a seal of a mint event.
+    #[clap(long, default_value = "60")]
+    pub timeout: u64,
+}
+
+impl BenchmarkOpt {
+    fn strategies(&self) -> Vec<ConsensusStrategy> {
+        if self.strategy.is_empty() {
+            vec![
+                ConsensusStrategy::Argon,
+                ConsensusStrategy::Keccak,
+                ConsensusStrategy::CryptoNight,
+            ]
+        } else {
+            self.strategy.clone()
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BenchmarkReport {
+    pub strategy: ConsensusStrategy,
+    pub threads: u16,
+    pub rounds: u32,
+    /// The seals pass the consensus verification.
+    pub verified: u32,
+    /// The seals fail the consensus verification.
+    pub invalid: u32,
+    /// The mint events without seal before timeout.
+    pub timeout: u32,
+    pub elapsed: Duration,
+    /// The time of the rounds which found a seal, the timeout rounds are not counted.
+    pub solve_time: Duration,
+    /// The estimated hashes per second of all threads.
+    pub hashrate: f64,
+}
+
+impl BenchmarkReport {
+    pub fn hashrate_per_thread(&self) -> f64 {
+        self.hashrate / self.threads.max(1) as f64
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.invalid == 0 && self.timeout == 0
+    }
+}
+
+impl fmt::Display for BenchmarkReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:<12} threads: {:>3}, verified: {:>3}/{}, invalid: {}, timeout: {}, elapsed: {:>8.3}s, hashrate: {:>12.3}, hashrate per thread: {:>12.3}",
+            self.strategy.to_string(),
+            self.threads,
+            self.verified,
+            self.rounds,
+            self.invalid,
+            self.timeout,
+            self.elapsed.as_secs_f64(),
+            self.hashrate,
+            self.hashrate_per_thread(),
+        )
+    }
+}
+
+/// Run the benchmark with the solver of `config`, the cpu solver or the plugin.
+pub fn run_benchmark(
+    config: MinerClientConfig,
+    opt: &BenchmarkOpt,
+) -> Result<Vec<BenchmarkReport>> {
+    let time_service: Arc<dyn TimeService> = Arc::new(RealTimeService::new());
+    let difficulty = U256::from(opt.difficulty.max(1));
+    opt.strategies()
+        .into_iter()
+        .map(|strategy| {
+            let mut report = BenchmarkReport {
+                strategy,
+                threads: config.miner_thread(),
+                rounds: opt.rounds,
+                verified: 0,
+                invalid: 0,
+                timeout: 0,
+                elapsed: Duration::default(),
+                solve_time: Duration::default(),
+                hashrate: 0f64,
+            };
+            for round in 0..opt.rounds {
+                let solver = create_solver(config.clone(), Some(time_service.clone()))?;
+                let started = Instant::now();
+                let result = bench_round(solver, strategy, difficulty, round, opt.timeout)?;
+                let elapsed = started.elapsed();
+                match result {
+                    Some(true) => report.verified += 1,
+                    Some(false) => report.invalid += 1,
+                    None => report.timeout += 1,
+                }
+                if result.is_some() {
+                    report.solve_time += elapsed;
+                }
+                report.elapsed += elapsed;
+            }
+            report.hashrate = estimate_hashrate(
+                opt.difficulty.max(1),
+                report.verified + report.invalid,
+                report.solve_time,
+            );
+            info!("[miner-client-benchmark] {}", report);
+            Ok(report)
+        })
+        .collect()
+}
+
+/// A seal of difficulty `d` takes `d` hashes in expectation, so the hashrate is the expected hashes
+/// of the seals divided by the time to find them.
+fn estimate_hashrate(difficulty: u64, seals: u32, solve_time: Duration) -> f64 {
+    let solve_time = solve_time.as_secs_f64();
+    if solve_time > 0f64 {
+        difficulty as f64 * seals as f64 / solve_time
+    } else {
+        0f64
+    }
+}
+
+/// Solve a synthetic mint event, return whether the seal is valid, or None if timeout.
+fn bench_round(
+    mut solver: Box<dyn Solver>,
+    strategy: ConsensusStrategy,
+    difficulty: U256,
+    round: u32,
+    timeout: u64,
+) -> Result<Option<bool>> {
+    let header = BlockHeaderBuilder::random()
+        .with_number(round as u64)
+        .with_difficulty(difficulty)
+        .build();
+    let extra = BlockHeaderExtra::new(rand::random());
+    let event = MintBlockEvent::new(
+        header.parent_hash(),
+        strategy,
+        header.as_pow_header_blob(),
+        difficulty,
+        header.number(),
+        Some(MintEventExtra {
+            worker_id: "benchmark".to_string(),
+            job_id: HashValue::random().to_hex(),
+            extra,
+        }),
+    );
+    let (nonce_tx, mut nonce_rx) = unbounded::<SealEvent>();
+    let (stop_tx, stop_rx) = unbounded();
+    let handle = thread::spawn(move || solver.solve(event, nonce_tx, stop_rx));
+    let seal = block_on(async {
+        match select(nonce_rx.next(), Delay::new(Duration::from_secs(timeout))).await {
+            Either::Left((seal, _)) => seal,
+            Either::Right(_) => None,
+        }
+    });
+    let _ = stop_tx.unbounded_send(true);
+    handle
+        .join()
+        .map_err(|_| format_err!("The solver of {} panicked", strategy))?;
+    let seal = match seal {
+        Some(seal) => seal,
+        None => {
+            warn!(
+                "[miner-client-benchmark] {} no seal found in {}s",
+                strategy, timeout
+            );
+            return Ok(None);
+        }
+    };
+    let sealed_extra = seal.extra.map(|extra| extra.extra).unwrap_or(extra);
+    let sealed_header = header
+        .as_builder()
+        .with_nonce(seal.nonce)
+        .with_extra(sealed_extra)
+        .build();
+    match strategy.verify_header_difficulty(difficulty, &sealed_header) {
+        Ok(()) => Ok(Some(true)),
+        Err(e) => {
+            error!(
+                "[miner-client-benchmark] {} invalid seal, nonce: {}, error: {}",
+                strategy, seal.nonce, e
+            );
+            Ok(Some(false))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cpu_solver_benchmark() {
+        let config = MinerClientConfig {
+            miner_thread: 2,
+            ..Default::default()
+        };
+        let opt = BenchmarkOpt {
+            strategy: vec![ConsensusStrategy::Keccak],
+            rounds: 2,
+            difficulty: 10,
+            timeout: 60,
+        };
+        let reports = run_benchmark(config, &opt).unwrap();
+        assert_eq!(reports.len(), 1);
+        assert!(reports[0].is_ok(), "{}", reports[0]);
+        assert_eq!(reports[0].verified, 2);
+        assert_eq!(reports[0].solve_time, reports[0].elapsed);
+    }
+
+    #[test]
+    fn test_estimate_hashrate() {
+        assert_eq!(estimate_hashrate(1000, 2, Duration::from_secs(4)), 500f64);
+        // no seal found, the timeout rounds do not count.
+        assert_eq!(estimate_hashrate(1000, 0, Duration::default()), 0f64);
+    }
+}
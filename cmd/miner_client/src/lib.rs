@@ -1,5 +1,6 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
+pub mod benchmark;
 mod cpu_solver;
 pub mod job_bus_client;
 pub mod job_client;
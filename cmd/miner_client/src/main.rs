@@ -4,6 +4,7 @@ use actix::System;
 use clap::Parser;
 use starcoin_config::MinerClientConfig;
 use starcoin_logger::prelude::*;
+use starcoin_miner_client::benchmark::{run_benchmark, BenchmarkOpt};
 use starcoin_miner_client::miner::MinerClientService;
 use starcoin_miner_client::stratum_client::StratumJobClient;
 use starcoin_miner_client::stratum_client_service::{
@@ -15,16 +16,28 @@ use starcoin_time_service::RealTimeService;
 use std::sync::Arc;
 
 #[derive(Debug, Clone, Parser, Default)]
-#[clap(name = "starcoin-miner", about = "Starcoin Miner")]
+#[clap(
+    name = "starcoin-miner",
+    about = "Starcoin Miner",
+    subcommand_negates_reqs = true
+)]
 pub struct StarcoinOpt {
     #[clap(long, short = 'a', default_value = "127.0.0.1:9880")]
     pub server: String,
-    #[clap(long, short = 'u')]
-    pub user: String,
+    #[clap(long, short = 'u', required = true)]
+    pub user: Option<String>,
     #[clap(long, short = 'n', default_value = "1")]
     pub thread_num: u16,
     #[clap(long, short = 'p')]
     pub plugin_path: Option<String>,
+    #[clap(subcommand)]
+    pub cmd: Option<MinerCommand>,
+}
+
+#[derive(Debug, Clone, Parser)]
+pub enum MinerCommand {
+    /// Benchmark and self-test the solver with synthetic mint events, without connecting to a node.
+    Benchmark(BenchmarkOpt),
 }
 
 fn main() {
@@ -38,7 +51,22 @@ fn main() {
             enable_stderr: true,
         }
     };
-    let user = opts.user;
+    if let Some(MinerCommand::Benchmark(opt)) = opts.cmd {
+        match run_benchmark(config, &opt) {
+            Ok(reports) => {
+                for report in &reports {
+                    println!("{}", report);
+                }
+                if reports.iter().all(|report| report.is_ok()) {
+                    return;
+                }
+                error!("Miner benchmark self-test failed");
+            }
+            Err(err) => error!("Failed to run miner benchmark:{}", err),
+        }
+        std::process::exit(1);
+    }
+    let user = opts.user.expect("user is required without subcommand");
     let system = System::with_tokio_rt(|| {
         tokio::runtime::Builder::new_multi_thread()
             .enable_all()
@@ -59,6 +87,7 @@ fn main() {
             pass: user,
             agent: "stc-miner".into(),
             algo: None,
+            extranonce: None,
         };
 
         let stratum_job_client = StratumJobClient::new(stratum_cli_srv, time_srv, login);
@@ -162,7 +162,7 @@ impl CommandAction for GenerateMultisigTxnCommand {
             .get_account(sender)?
             .ok_or_else(|| anyhow::anyhow!("cannot find multisig address {}", sender))?;
         let account_public_key = match &account.public_key {
-            AccountPublicKey::Single(_) => {
+            AccountPublicKey::Single(_) | AccountPublicKey::Secp256r1(_) => {
                 bail!("sender {} is not a multisig address", sender);
             }
             AccountPublicKey::Multi(m) => m.clone(),
@@ -216,9 +216,32 @@ impl CliState {
             //TODO figure out more graceful method to handle future transaction.
             bail!("there is transaction from sender({}) in the txpool, please wait it to been executed or use sequence_number({}) to replace it.",raw_txn.sender(), raw_txn.sequence_number()-1);
         }
+        if let Some(gas_profile) = txn_opts.gas_profile.as_ref() {
+            self.write_gas_profile(&raw_txn, gas_profile)?;
+        }
         self.execute_transaction(raw_txn, txn_opts.dry_run, txn_opts.blocking)
     }
 
+    fn write_gas_profile(&self, raw_txn: &RawUserTransaction, path: &Path) -> Result<()> {
+        let sender = self.get_account(raw_txn.sender())?;
+        let state_reader = self.client().state_reader(StateRootOption::Latest)?;
+        let (_vm_status, _output, trace) = playground::dry_run_with_trace(
+            &state_reader,
+            DryRunTransaction {
+                public_key: sender.public_key,
+                raw_txn: raw_txn.clone(),
+            },
+            None,
+        )?;
+        std::fs::write(path, trace.to_folded_stacks())?;
+        eprintln!(
+            "gas profile of txn written to {}, total internal gas: {}",
+            path.display(),
+            trace.total_gas()
+        );
+        Ok(())
+    }
+
     fn build_transaction(
         &self,
         sender: Option<AccountAddress>,
@@ -307,7 +330,7 @@ impl CliState {
         let signed_txn = self.account_client.sign_txn(raw_txn, sender.address)?;
 
         let multisig_public_key = match &public_key {
-            AccountPublicKey::Single(_) => {
+            AccountPublicKey::Single(_) | AccountPublicKey::Secp256r1(_) => {
                 let signed_txn_hex = hex::encode(signed_txn.encode()?);
                 let txn_hash = self.client.submit_hex_transaction(signed_txn_hex)?;
                 eprintln!("txn {} submitted.", txn_hash);
@@ -0,0 +1,42 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::StarcoinOpt;
+use anyhow::{bail, Result};
+use clap::Parser;
+use scmd::{CommandAction, ExecContext};
+
+/// Advance the node clock without triggering a new block, only available in test or dev chain.
+/// Return the new time in milliseconds.
+#[derive(Debug, Parser)]
+#[clap(name = "advance-time")]
+pub struct AdvanceTimeOpt {
+    #[clap(name = "time", help = "the time to advance in milliseconds.")]
+    time: u64,
+}
+
+pub struct AdvanceTimeCommand;
+
+impl CommandAction for AdvanceTimeCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = AdvanceTimeOpt;
+    type ReturnItem = u64;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt = ctx.opt();
+        let client = ctx.state().client();
+        let net = ctx.state().net();
+        if !net.is_test_or_dev() {
+            bail!(
+                "This command only available in test or dev network, current network is: {}",
+                net
+            );
+        }
+        client.debug_advance_time(opt.time)
+    }
+}
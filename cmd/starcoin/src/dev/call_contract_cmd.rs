@@ -7,7 +7,11 @@ use anyhow::Result;
 use clap::Parser;
 use scmd::{CommandAction, ExecContext};
 use starcoin_abi_decoder::DecodedMoveValue;
+use starcoin_dev::playground;
+use starcoin_resource_viewer::MoveValueAnnotator;
 use starcoin_rpc_api::types::{ContractCall, FunctionIdView, TransactionArgumentView, TypeTagView};
+use starcoin_rpc_client::StateRootOption;
+use std::path::{Path, PathBuf};
 
 /// Call Contract command
 ///  Some examples:
@@ -37,6 +41,20 @@ pub struct CallContractOpt {
         help = "can specify multi arg"
     )]
     args: Option<Vec<TransactionArgumentView>>,
+
+    #[clap(long = "gas-profile", name = "gas-profile-file")]
+    /// call the function locally with a gas tracer, and write the gas profile in folded stacks format to the file.
+    gas_profile: Option<PathBuf>,
+
+    #[clap(
+        long = "coverage",
+        name = "package-dir",
+        conflicts_with("gas-profile-file")
+    )]
+    /// call the function locally, and add the coverage of the call to the coverage map of the Move package in the dir.
+    /// Use `mpm coverage-report` in the package to report the coverage.
+    /// Only supported by the starcoin built with the `coverage` feature, and started with `MOVE_VM_TRACE` set to the trace file.
+    coverage: Option<PathBuf>,
 }
 
 pub struct CallContractCommand;
@@ -59,7 +77,79 @@ impl CommandAction for CallContractCommand {
             args: opt.args.clone().unwrap_or_default(),
         };
 
-        let result = ctx.state().client().contract_call(call)?;
-        Ok(result)
+        if let Some(package_dir) = opt.coverage.as_ref() {
+            return call_with_coverage(ctx, call, package_dir);
+        }
+        let gas_profile = match opt.gas_profile.as_ref() {
+            Some(gas_profile) => gas_profile,
+            None => return ctx.state().client().contract_call(call),
+        };
+        let state_reader = ctx.state().client().state_reader(StateRootOption::Latest)?;
+        let (output, trace) = playground::call_contract_with_trace(
+            &state_reader,
+            call.function_id.0.module,
+            call.function_id.0.function.as_str(),
+            call.type_args.into_iter().map(|v| v.0).collect(),
+            call.args.into_iter().map(|v| v.0).collect(),
+            None,
+        )?;
+        std::fs::write(gas_profile, trace.to_folded_stacks())?;
+        eprintln!(
+            "gas profile of call written to {}, total internal gas: {}",
+            gas_profile.display(),
+            trace.total_gas()
+        );
+        let annotator = MoveValueAnnotator::new(&state_reader);
+        output
+            .into_iter()
+            .map(|(ty, v)| annotator.view_value(&ty, &v).map(Into::into))
+            .collect()
     }
 }
+
+#[cfg(feature = "coverage")]
+fn call_with_coverage(
+    ctx: &ExecContext<CliState, StarcoinOpt, CallContractOpt>,
+    call: ContractCall,
+    package_dir: &Path,
+) -> Result<Vec<DecodedMoveValue>> {
+    use move_coverage::coverage_map::{output_map_to_file, CoverageMap};
+    use starcoin_move_coverage::trace::TraceRecorder;
+    use starcoin_move_coverage::COVERAGE_MAP_NAME;
+
+    let coverage_map_path = package_dir.join(COVERAGE_MAP_NAME);
+    let base = if coverage_map_path.exists() {
+        Some(CoverageMap::from_binary_file(&coverage_map_path)?)
+    } else {
+        None
+    };
+    let state_reader = ctx.state().client().state_reader(StateRootOption::Latest)?;
+    let trace_recorder = TraceRecorder::start()?;
+    let output = playground::call_contract(
+        &state_reader,
+        call.function_id.0.module,
+        call.function_id.0.function.as_str(),
+        call.type_args.into_iter().map(|v| v.0).collect(),
+        call.args.into_iter().map(|v| v.0).collect(),
+        None,
+    )?;
+    let coverage_map = trace_recorder.finish(base)?;
+    output_map_to_file(&coverage_map_path, &coverage_map)?;
+    eprintln!("coverage of call added to {}", coverage_map_path.display());
+    let annotator = MoveValueAnnotator::new(&state_reader);
+    output
+        .into_iter()
+        .map(|(ty, v)| annotator.view_value(&ty, &v).map(Into::into))
+        .collect()
+}
+
+/// The Move VM traces the executed instructions only with the `debugging` feature of
+/// `move-vm-runtime`, which is not enabled in the node by default.
+#[cfg(not(feature = "coverage"))]
+fn call_with_coverage(
+    _ctx: &ExecContext<CliState, StarcoinOpt, CallContractOpt>,
+    _call: ContractCall,
+    _package_dir: &Path,
+) -> Result<Vec<DecodedMoveValue>> {
+    anyhow::bail!("the coverage is not supported, build starcoin with the `coverage` feature")
+}
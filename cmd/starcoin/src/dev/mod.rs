@@ -13,6 +13,7 @@ pub use upgrade_module_proposal_cmd::*;
 pub use upgrade_module_queue_cmd::*;
 pub use upgrade_vm_config_proposal_cmd::*;
 
+pub(crate) mod advance_time_cmd;
 pub(crate) mod call_api_cmd;
 mod call_contract_cmd;
 mod compile_cmd;
@@ -25,6 +26,8 @@ pub(crate) mod move_explain;
 mod package_cmd;
 pub(crate) mod panic_cmd;
 pub(crate) mod resolve_cmd;
+pub(crate) mod seal_block_cmd;
+pub(crate) mod set_time_cmd;
 pub(crate) mod sign_txn_helper;
 pub(crate) mod sleep_cmd;
 mod subscribe_cmd;
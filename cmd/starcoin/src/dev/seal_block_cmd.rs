@@ -0,0 +1,37 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::StarcoinOpt;
+use anyhow::{bail, Result};
+use clap::Parser;
+use scmd::{CommandAction, ExecContext};
+
+/// Trigger the miner to seal a new block even if no txn in the txpool, only available in test or dev chain.
+#[derive(Debug, Parser)]
+#[clap(name = "seal-block")]
+pub struct SealBlockOpt {}
+
+pub struct SealBlockCommand;
+
+impl CommandAction for SealBlockCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = SealBlockOpt;
+    type ReturnItem = ();
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let client = ctx.state().client();
+        let net = ctx.state().net();
+        if !net.is_test_or_dev() {
+            bail!(
+                "This command only available in test or dev network, current network is: {}",
+                net
+            );
+        }
+        client.debug_seal_block()
+    }
+}
@@ -0,0 +1,45 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::StarcoinOpt;
+use anyhow::{bail, Result};
+use clap::Parser;
+use scmd::{CommandAction, ExecContext};
+
+/// Set the node clock, only available in test or dev chain.
+/// The new time takes effect on the timestamp of the next block.
+#[derive(Debug, Parser)]
+#[clap(name = "set-time")]
+pub struct SetTimeOpt {
+    #[clap(
+        name = "time",
+        help = "the new time in milliseconds, can not go backward."
+    )]
+    time: u64,
+}
+
+pub struct SetTimeCommand;
+
+impl CommandAction for SetTimeCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = SetTimeOpt;
+    type ReturnItem = u64;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let opt = ctx.opt();
+        let client = ctx.state().client();
+        let net = ctx.state().net();
+        if !net.is_test_or_dev() {
+            bail!(
+                "This command only available in test or dev network, current network is: {}",
+                net
+            );
+        }
+        client.debug_set_time(opt.time)
+    }
+}
@@ -87,7 +87,9 @@ pub fn add_command(
                         .subcommand(node::network::AddPeerCommand)
                         .subcommand(node::network::CallPeerCommand)
                         .subcommand(node::network::SetPeerReputation)
-                        .subcommand(node::network::BanPeerCommand),
+                        .subcommand(node::network::BanPeerCommand)
+                        .subcommand(node::network::AllowedPeersCommand)
+                        .subcommand(node::network::SetPeerAllowedCommand),
                 ),
         )
         .command(
@@ -140,7 +142,10 @@ pub fn add_command(
                 )
                 .subcommand(dev::panic_cmd::PanicCommand)
                 .subcommand(dev::sleep_cmd::SleepCommand)
-                .subcommand(dev::gen_block_cmd::GenBlockCommand),
+                .subcommand(dev::gen_block_cmd::GenBlockCommand)
+                .subcommand(dev::seal_block_cmd::SealBlockCommand)
+                .subcommand(dev::set_time_cmd::SetTimeCommand)
+                .subcommand(dev::advance_time_cmd::AdvanceTimeCommand),
         )
         .command(CustomCommand::with_name("contract").subcommand(contract::GetContractDataCommand))
 }
@@ -97,7 +97,7 @@ pub fn read_multisig_existing_signatures(file_input: &Path) -> Result<RawTxnMult
     let txn: SignedUserTransaction = bcs_ext::from_bytes(&std::fs::read(file_input)?)?;
 
     let existing_signatures = match txn.authenticator() {
-        TransactionAuthenticator::Ed25519 { .. } => {
+        TransactionAuthenticator::Ed25519 { .. } | TransactionAuthenticator::WebAuthn { .. } => {
             bail!("expect a multisig txn in file {}", file_input.display());
         }
         TransactionAuthenticator::MultiEd25519 {
@@ -0,0 +1,31 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::StarcoinOpt;
+use anyhow::Result;
+use clap::Parser;
+use network_types::peer_info::PeerId;
+use scmd::{CommandAction, ExecContext};
+
+#[derive(Debug, Parser, Default)]
+#[clap(name = "allowed_peers")]
+/// Show the members of the permissioned network.
+pub struct AllowedPeersOpt {}
+
+pub struct AllowedPeersCommand;
+
+impl CommandAction for AllowedPeersCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = AllowedPeersOpt;
+    type ReturnItem = Option<Vec<PeerId>>;
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let client = ctx.state().client();
+        client.network_allowed_peers()
+    }
+}
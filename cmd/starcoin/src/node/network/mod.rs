@@ -2,16 +2,20 @@
 // Copyright (c) The Starcoin Core Contributors
 
 mod add_peer_cmd;
+mod allowed_peers_cmd;
 mod ban_peer_cmd;
 mod call_peer_cmd;
 mod get_address_cmd;
 mod known_peers_cmd;
+mod set_peer_allowed_cmd;
 mod set_peer_reputation;
 mod state_cmd;
 pub use add_peer_cmd::*;
+pub use allowed_peers_cmd::*;
 pub use ban_peer_cmd::*;
 pub use call_peer_cmd::*;
 pub use get_address_cmd::*;
 pub use known_peers_cmd::*;
+pub use set_peer_allowed_cmd::*;
 pub use set_peer_reputation::*;
 pub use state_cmd::*;
@@ -0,0 +1,38 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli_state::CliState;
+use crate::StarcoinOpt;
+use anyhow::Result;
+use clap::Parser;
+use scmd::{CommandAction, ExecContext};
+
+#[derive(Debug, Parser)]
+#[clap(name = "set_peer_allowed")]
+/// Add or remove a member of the permissioned network.
+pub struct SetPeerAllowedOpt {
+    #[clap(name = "peer-id")]
+    /// the peer id of the member
+    peer_id: String,
+    #[clap(name = "allowed", long = "allowed")]
+    /// whether allow the peer to connect, default is true.
+    allowed: Option<bool>,
+}
+
+pub struct SetPeerAllowedCommand;
+
+impl CommandAction for SetPeerAllowedCommand {
+    type State = CliState;
+    type GlobalOpt = StarcoinOpt;
+    type Opt = SetPeerAllowedOpt;
+    type ReturnItem = ();
+
+    fn run(
+        &self,
+        ctx: &ExecContext<Self::State, Self::GlobalOpt, Self::Opt>,
+    ) -> Result<Self::ReturnItem> {
+        let client = ctx.state().client();
+        let opt = ctx.opt();
+        client.network_set_peer_allowed(opt.peer_id.clone(), opt.allowed.unwrap_or(true))
+    }
+}
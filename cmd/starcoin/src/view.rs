@@ -63,6 +63,11 @@ pub struct TransactionOptions {
     #[clap(name = "gas-token", long = "gas-token", name = "token code of gas")]
     /// "token code of gas to pay, for example: 0x1::STC::STC, default is STC."
     pub gas_token: Option<String>,
+
+    #[clap(long = "gas-profile", name = "gas-profile-file")]
+    /// dry run the transaction with a gas tracer, and write the gas profile in folded stacks format to the file,
+    /// which can be rendered as a flamegraph by inferno-flamegraph or flamegraph.pl.
+    pub gas_profile: Option<PathBuf>,
 }
 
 #[derive(Debug, Clone)]
@@ -1063,9 +1063,9 @@ mod tests {
     };
     use starcoin_vm_types::on_chain_config::{
         instruction_gas_schedule_v1, instruction_gas_schedule_v2, instruction_table_v1,
-        native_gas_schedule_v1, native_gas_schedule_v2, native_gas_schedule_v4, native_table_v1,
-        native_table_v2, txn_gas_schedule_test, txn_gas_schedule_v1, txn_gas_schedule_v2,
-        txn_gas_schedule_v3, GasSchedule, VMConfig,
+        native_gas_schedule_v1, native_gas_schedule_v2, native_gas_schedule_v5,
+        native_gas_schedule_v5_delta, native_table_v1, native_table_v2, txn_gas_schedule_test,
+        txn_gas_schedule_v1, txn_gas_schedule_v2, txn_gas_schedule_v3, GasSchedule, VMConfig,
     };
 
     fn config_entries(
@@ -1082,6 +1082,11 @@ mod tests {
         entries.push(("instr.cast_u16".to_string(), 3));
         entries.push(("instr.cast_u32".to_string(), 2));
         entries.push(("instr.cast_u256".to_string(), 3));
+        for (key, cost) in native_gas_schedule_v5_delta() {
+            if !natives.iter().any(|(native_key, _)| native_key == &key) {
+                natives.push((key, cost));
+            }
+        }
         entries.append(&mut natives);
         // native_table don't have these
         entries.push(("nursery.debug.print.base_cost".to_string(), 1));
@@ -1112,7 +1117,7 @@ mod tests {
 
         let entries = config_entries(
             instruction_gas_schedule_v2(),
-            native_gas_schedule_v4(),
+            native_gas_schedule_v5(),
             txn_gas_schedule_test(),
         );
 
@@ -1134,7 +1139,7 @@ mod tests {
 
         let entries = config_entries(
             instruction_gas_schedule_v2(),
-            native_gas_schedule_v4(),
+            native_gas_schedule_v5(),
             txn_gas_schedule_v3(),
         );
 
@@ -1156,7 +1161,7 @@ mod tests {
 
         let entries = config_entries(
             instruction_gas_schedule_v2(),
-            native_gas_schedule_v4(),
+            native_gas_schedule_v5(),
             txn_gas_schedule_v3(),
         );
         let gas_schedule = GasSchedule::from(&vm_config);
@@ -1213,10 +1218,15 @@ mod tests {
         let gas_schedule = GasSchedule::from(&vm_config);
         assert_eq!(entries, gas_schedule.entries);
         let gas_params =
-            StarcoinGasParameters::from_on_chain_gas_schedule(&gas_schedule.to_btree_map());
-        assert_eq!(
-            gas_params.unwrap().natives.nursery.debug.print.base_cost,
-            1.into()
-        );
+            StarcoinGasParameters::from_on_chain_gas_schedule(&gas_schedule.to_btree_map())
+                .unwrap();
+        assert_eq!(gas_params.natives.nursery.debug.print.base_cost, 1.into());
+        // the main native_table don't have the v5 natives, they must not be free.
+        let signature = &gas_params.natives.starcoin_natives.signature;
+        assert_eq!(signature.bls12381_verify.base, 48001.into());
+        assert_eq!(signature.bls12381_verify.per_byte, 22.into());
+        assert_eq!(signature.bls12381_verify_pop.base, 48001.into());
+        assert_eq!(signature.secp256r1_verify.base, 4001.into());
+        assert_eq!(signature.secp256r1_verify.per_byte, 22.into());
     }
 }
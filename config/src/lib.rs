@@ -46,7 +46,6 @@ mod txpool_config;
 use thiserror::Error;
 
 use crate::account_provider_config::AccountProviderConfig;
-use crate::stratum_config::StratumConfig;
 pub use api_config::{Api, ApiSet};
 pub use api_quota::{ApiQuotaConfig, QuotaDuration};
 pub use available_port::{
@@ -59,8 +58,10 @@ pub use genesis_config::{
 };
 pub use logger_config::LoggerConfig;
 pub use metrics_config::MetricsConfig;
-pub use miner_config::{MinerClientConfig, MinerConfig};
-pub use network_config::{NetworkConfig, NetworkRpcQuotaConfiguration};
+pub use miner_config::{
+    MinerClientConfig, MinerConfig, TxnSelectionPolicyType, UncleSelectionOrder,
+};
+pub use network_config::{NetworkConfig, NetworkRpcQuotaConfiguration, TxnPropagateMode};
 pub use rpc_config::{
     ApiQuotaConfiguration, HttpConfiguration, IpcConfiguration, RpcConfig, TcpConfiguration,
     WsConfiguration,
@@ -68,6 +69,8 @@ pub use rpc_config::{
 pub use starcoin_crypto::ed25519::genesis_key_pair;
 pub use starcoin_time_service::{MockTimeService, RealTimeService, TimeService};
 pub use storage_config::{RocksdbConfig, StorageConfig, DEFAULT_CACHE_SIZE};
+pub use stratum_config::{PoolPayoutMode, StratumConfig};
+pub use sync_config::ReorgDepthAction;
 pub use txpool_config::TxPoolConfig;
 
 pub static G_CRATE_VERSION: &str = clap::crate_version!();
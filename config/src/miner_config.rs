@@ -2,11 +2,105 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{BaseConfig, ConfigModule, StarcoinOpt};
-use anyhow::Result;
+use anyhow::{bail, Result};
 use clap::Parser;
 use serde::{Deserialize, Serialize};
+use starcoin_types::account_address::AccountAddress;
+use std::str::FromStr;
 use std::sync::Arc;
 
+/// The policy of selecting the txns from txpool into the block template.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TxnSelectionPolicyType {
+    /// Keep the order of the txpool.
+    TxPool,
+    /// The txns with the highest gas price first, keep the sequence number order of each sender.
+    GasPrice,
+    /// Pick the txns of each sender in turn, and limit the txns count of each sender.
+    SenderFairness,
+    /// The txns of the priority addresses first.
+    Priority,
+    /// Keep trying the smaller txns which fit the left gas after the block nears its gas limit.
+    GasPacking,
+}
+
+impl Default for TxnSelectionPolicyType {
+    fn default() -> Self {
+        TxnSelectionPolicyType::TxPool
+    }
+}
+
+impl std::fmt::Display for TxnSelectionPolicyType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            TxnSelectionPolicyType::TxPool => "txpool",
+            TxnSelectionPolicyType::GasPrice => "gas-price",
+            TxnSelectionPolicyType::SenderFairness => "sender-fairness",
+            TxnSelectionPolicyType::Priority => "priority",
+            TxnSelectionPolicyType::GasPacking => "gas-packing",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl FromStr for TxnSelectionPolicyType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "txpool" => TxnSelectionPolicyType::TxPool,
+            "gas-price" => TxnSelectionPolicyType::GasPrice,
+            "sender-fairness" => TxnSelectionPolicyType::SenderFairness,
+            "priority" => TxnSelectionPolicyType::Priority,
+            "gas-packing" => TxnSelectionPolicyType::GasPacking,
+            _ => bail!("Unknown txn selection policy: {}", s),
+        })
+    }
+}
+
+/// The order of selecting the uncles into the block template.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum UncleSelectionOrder {
+    /// No preference, pick any uncle which can be included.
+    Any,
+    /// The uncles with the highest difficulty first.
+    Difficulty,
+    /// The uncles nearest to the head first.
+    Nearest,
+}
+
+impl Default for UncleSelectionOrder {
+    fn default() -> Self {
+        UncleSelectionOrder::Any
+    }
+}
+
+impl std::fmt::Display for UncleSelectionOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            UncleSelectionOrder::Any => "any",
+            UncleSelectionOrder::Difficulty => "difficulty",
+            UncleSelectionOrder::Nearest => "nearest",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl FromStr for UncleSelectionOrder {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "any" => UncleSelectionOrder::Any,
+            "difficulty" => UncleSelectionOrder::Difficulty,
+            "nearest" => UncleSelectionOrder::Nearest,
+            _ => bail!("Unknown uncle selection order: {}", s),
+        })
+    }
+}
+
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize, Parser)]
 #[serde(deny_unknown_fields)]
 pub struct MinerConfig {
@@ -31,6 +125,57 @@ pub struct MinerConfig {
     /// Miner client thread number, not work for dev network, default is 1
     pub miner_thread: Option<u16>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long = "txn-selection-policy")]
+    /// The policy of selecting txns into the block template,
+    /// txpool|gas-price|sender-fairness|priority|gas-packing, default is txpool.
+    pub txn_selection_policy: Option<TxnSelectionPolicyType>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long = "max-txns-per-sender")]
+    /// Max txns count of each sender in a block, only work for the sender-fairness policy, default is 10.
+    pub max_txns_per_sender: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long = "priority-addresses", use_value_delimiter = true)]
+    /// The txns of these addresses are selected first, only work for the priority policy.
+    /// Multi address should use ',' as delimiter.
+    pub priority_addresses: Option<Vec<AccountAddress>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long = "max-uncle-depth")]
+    /// Max distance from the new block to the uncle, the deeper uncles are not included and pruned.
+    /// Default is no limit in the epoch.
+    pub max_uncle_depth: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long = "max-uncles-per-block")]
+    /// Node local max uncles per block, use min(config.max_uncles_per_block, onchain.max_uncles_per_block)
+    pub max_uncles_per_block: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long = "uncle-selection-order")]
+    /// The order of selecting uncles into the block template, any|difficulty|nearest, default is any.
+    pub uncle_selection_order: Option<UncleSelectionOrder>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long = "warm-block-template")]
+    /// Keep an opened block on the current head and push the new pending txns into it incrementally,
+    /// so the block template is ready without re-executing all txns. Default is false.
+    pub warm_block_template: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long = "warm-block-template-max-age")]
+    /// Max seconds of the warm opened block, the older one is rebuilt to keep the block timestamp fresh.
+    /// Default is 10.
+    pub warm_block_template_max_age: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long = "instant-seal")]
+    /// Seal the block template immediately in node, without the miner client, once txns enter the txpool
+    /// or a block is requested by rpc, only work for dev and test network. Default is false.
+    pub instant_seal: Option<bool>,
+
     #[serde(skip)]
     #[clap(skip)]
     base: Option<Arc<BaseConfig>>,
@@ -49,6 +194,33 @@ impl MinerConfig {
         self.disable_mint_empty_block
             .unwrap_or_else(|| self.base().net().is_dev())
     }
+    pub fn txn_selection_policy(&self) -> TxnSelectionPolicyType {
+        self.txn_selection_policy.unwrap_or_default()
+    }
+    pub fn max_txns_per_sender(&self) -> u64 {
+        self.max_txns_per_sender.unwrap_or(10)
+    }
+    pub fn priority_addresses(&self) -> Vec<AccountAddress> {
+        self.priority_addresses.clone().unwrap_or_default()
+    }
+    pub fn max_uncle_depth(&self) -> Option<u64> {
+        self.max_uncle_depth
+    }
+    pub fn max_uncles_per_block(&self) -> Option<u64> {
+        self.max_uncles_per_block
+    }
+    pub fn uncle_selection_order(&self) -> UncleSelectionOrder {
+        self.uncle_selection_order.unwrap_or_default()
+    }
+    pub fn warm_block_template(&self) -> bool {
+        self.warm_block_template.unwrap_or(false)
+    }
+    pub fn warm_block_template_max_age(&self) -> u64 {
+        self.warm_block_template_max_age.unwrap_or(10)
+    }
+    pub fn is_instant_seal(&self) -> bool {
+        self.instant_seal.unwrap_or(false) && self.base().net().is_test_or_dev()
+    }
     pub fn miner_client_config(&self) -> Option<MinerClientConfig> {
         if self.disable_miner_client() {
             return None;
@@ -102,6 +274,33 @@ impl ConfigModule for MinerConfig {
         if opt.miner.block_gas_limit.is_some() {
             self.block_gas_limit = opt.miner.block_gas_limit;
         }
+        if opt.miner.txn_selection_policy.is_some() {
+            self.txn_selection_policy = opt.miner.txn_selection_policy;
+        }
+        if opt.miner.max_txns_per_sender.is_some() {
+            self.max_txns_per_sender = opt.miner.max_txns_per_sender;
+        }
+        if opt.miner.priority_addresses.is_some() {
+            self.priority_addresses = opt.miner.priority_addresses.clone();
+        }
+        if opt.miner.max_uncle_depth.is_some() {
+            self.max_uncle_depth = opt.miner.max_uncle_depth;
+        }
+        if opt.miner.max_uncles_per_block.is_some() {
+            self.max_uncles_per_block = opt.miner.max_uncles_per_block;
+        }
+        if opt.miner.uncle_selection_order.is_some() {
+            self.uncle_selection_order = opt.miner.uncle_selection_order;
+        }
+        if opt.miner.warm_block_template.is_some() {
+            self.warm_block_template = opt.miner.warm_block_template;
+        }
+        if opt.miner.warm_block_template_max_age.is_some() {
+            self.warm_block_template_max_age = opt.miner.warm_block_template_max_age;
+        }
+        if opt.miner.instant_seal.is_some() {
+            self.instant_seal = opt.miner.instant_seal;
+        }
 
         Ok(())
     }
@@ -161,6 +161,49 @@ impl From<Vec<MultiaddrWithPeerId>> for Seeds {
         Seeds(seeds)
     }
 }
+/// How the new transactions are propagated to peers.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TxnPropagateMode {
+    /// Push the full transactions to all peers.
+    Push,
+    /// Only announce the transaction hashes, peers pull the unknown transactions by rpc.
+    /// Fallback to push when the connected peers is not more than `min_peers_to_propagate`.
+    Announce,
+    /// Push the full transactions to sqrt(peers) peers, and announce the hashes to the others.
+    Hybrid,
+}
+
+impl Default for TxnPropagateMode {
+    fn default() -> Self {
+        TxnPropagateMode::Hybrid
+    }
+}
+
+impl std::fmt::Display for TxnPropagateMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            TxnPropagateMode::Push => "push",
+            TxnPropagateMode::Announce => "announce",
+            TxnPropagateMode::Hybrid => "hybrid",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl FromStr for TxnPropagateMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.to_lowercase().as_str() {
+            "push" => TxnPropagateMode::Push,
+            "announce" => TxnPropagateMode::Announce,
+            "hybrid" => TxnPropagateMode::Hybrid,
+            mode => anyhow::bail!("Unknown txn propagate mode: {}", mode),
+        })
+    }
+}
+
 #[derive(Default, Clone, Debug, Deserialize, PartialEq, Serialize, Parser)]
 #[serde(deny_unknown_fields)]
 pub struct NetworkConfig {
@@ -211,6 +254,11 @@ pub struct NetworkConfig {
     ///max peers to propagate new block and new transactions. Default 128.
     max_peers_to_propagate: Option<u32>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long)]
+    /// how the new transactions are propagated, push|announce|hybrid. Default hybrid.
+    txn_propagate_mode: Option<TxnPropagateMode>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     #[clap(long)]
     ///max count for incoming peers. Default 25.
@@ -227,6 +275,16 @@ pub struct NetworkConfig {
     /// p2p network listen address, Default is /ip4/0.0.0.0/tcp/9840
     listen: Option<Multiaddr>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long)]
+    /// max bytes per second sent by the p2p network, shared half and half by the p2p notifications and the p2p rpc. Default is unlimited.
+    max_upload_rate: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long)]
+    /// max bytes per second received by the p2p network, shared half and half by the p2p notifications and the p2p rpc. Default is unlimited.
+    max_download_rate: Option<u64>,
+
     #[serde(skip)]
     #[clap(skip)]
     base: Option<Arc<BaseConfig>>,
@@ -242,6 +300,12 @@ pub struct NetworkConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[clap(name = "unsupported-protocols", long, use_value_delimiter = true)]
     pub unsupported_protocols: Option<Vec<String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(name = "allowed-peers", long, use_value_delimiter = true)]
+    /// Enable the permissioned network mode, only the given peers, the seeds and the reserved peers can connect to this node.
+    /// Multi peer id should use ',' as delimiter.
+    pub allowed_peers: Option<Vec<PeerId>>,
 }
 
 impl NetworkConfig {
@@ -314,6 +378,10 @@ impl NetworkConfig {
         self.min_peers_to_propagate.unwrap_or(8)
     }
 
+    pub fn txn_propagate_mode(&self) -> TxnPropagateMode {
+        self.txn_propagate_mode.unwrap_or_default()
+    }
+
     pub fn max_incoming_peers(&self) -> u32 {
         self.max_incoming_peers.unwrap_or(25)
     }
@@ -322,6 +390,14 @@ impl NetworkConfig {
         self.max_outgoing_peers.unwrap_or(75)
     }
 
+    pub fn max_upload_rate(&self) -> Option<u64> {
+        self.max_upload_rate
+    }
+
+    pub fn max_download_rate(&self) -> Option<u64> {
+        self.max_download_rate
+    }
+
     pub fn node_name(&self) -> String {
         self.node_name.clone().unwrap_or_else(generate_node_name)
     }
@@ -382,6 +458,11 @@ impl NetworkConfig {
         }
     }
 
+    /// The members of the permissioned network, `None` if the network is permissionless.
+    pub fn allowed_peers(&self) -> Option<Vec<PeerId>> {
+        self.allowed_peers.clone()
+    }
+
     pub fn supported_network_protocols(&self) -> Vec<Cow<'static, str>> {
         let protocols = NotificationMessage::protocols();
         if let Some(unsupported_protocols) = &self.unsupported_protocols {
@@ -431,6 +512,9 @@ impl ConfigModule for NetworkConfig {
         if let Some(m) = opt.network.min_peers_to_propagate {
             self.min_peers_to_propagate = Some(m);
         }
+        if opt.network.txn_propagate_mode.is_some() {
+            self.txn_propagate_mode = opt.network.txn_propagate_mode;
+        }
         if opt.network.discover_local.is_some() {
             self.discover_local = opt.network.discover_local;
         }
@@ -441,6 +525,12 @@ impl ConfigModule for NetworkConfig {
         if opt.network.max_outgoing_peers.is_some() {
             self.max_outgoing_peers = opt.network.max_outgoing_peers;
         }
+        if opt.network.max_upload_rate.is_some() {
+            self.max_upload_rate = opt.network.max_upload_rate;
+        }
+        if opt.network.max_download_rate.is_some() {
+            self.max_download_rate = opt.network.max_download_rate;
+        }
 
         if opt.network.unsupported_protocols.is_some() {
             let mut protocols: HashSet<String> = self
@@ -464,6 +554,17 @@ impl ConfigModule for NetworkConfig {
             );
         }
 
+        if let Some(allowed_peers) = opt.network.allowed_peers.as_ref() {
+            let mut peers: HashSet<PeerId> = self
+                .allowed_peers
+                .clone()
+                .unwrap_or_default()
+                .into_iter()
+                .collect();
+            peers.extend(allowed_peers.iter().cloned());
+            self.allowed_peers = Some(peers.into_iter().collect());
+        }
+
         self.load_or_generate_keypair()?;
         self.generate_listen_address();
         Ok(())
@@ -2,15 +2,57 @@ use crate::{
     get_available_port_from, get_random_available_port, BaseConfig, ConfigModule, Parser,
     StarcoinOpt,
 };
-use anyhow::Result;
+use anyhow::{bail, Result};
 use serde::{Deserialize, Serialize};
 use starcoin_logger::prelude::*;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
 
 const DEFAULT_STRATUM_PORT: u16 = 9880;
 // UNSPECIFIED is 0.0.0.0
 const DEFAULT_STRATUM_ADDRESS: IpAddr = IpAddr::V4(Ipv4Addr::UNSPECIFIED);
+const DEFAULT_SHARE_INTERVAL_SECS: u64 = 10;
+const DEFAULT_RETARGET_INTERVAL_SECS: u64 = 60;
+const DEFAULT_MIN_SHARE_DIFFICULTY: u64 = 1;
+const DEFAULT_POOL_FEE: u64 = 100;
+const DEFAULT_PPLNS_WINDOW: u64 = 10000;
+// 1 STC
+const DEFAULT_POOL_PAYOUT_THRESHOLD: u128 = 1_000_000_000;
+const DEFAULT_POOL_PAYOUT_INTERVAL_SECS: u64 = 3600;
+
+/// How the mining pool pays the workers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PoolPayoutMode {
+    /// Pay last N shares, split the reward of a block by the shares before it was found.
+    Pplns,
+    /// Pay per share, credit the expected reward of every share immediately.
+    Pps,
+}
+
+impl std::fmt::Display for PoolPayoutMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            PoolPayoutMode::Pplns => "pplns",
+            PoolPayoutMode::Pps => "pps",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl FromStr for PoolPayoutMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "pplns" => PoolPayoutMode::Pplns,
+            "pps" => PoolPayoutMode::Pps,
+            _ => bail!("Unknown pool payout mode: {}", s),
+        })
+    }
+}
 
 #[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize, Parser)]
 pub struct StratumConfig {
@@ -28,6 +70,48 @@ pub struct StratumConfig {
     /// Stratum address, default is 0.0.0.0
     pub address: Option<IpAddr>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long = "stratum-share-interval")]
+    /// The expected seconds between two shares of a worker, the share difficulty of every worker
+    /// is adjusted to reach it. Default is 10.
+    pub share_interval: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long = "stratum-retarget-interval")]
+    /// The seconds between two share difficulty adjustments of a worker. Default is 60.
+    pub retarget_interval: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long = "stratum-min-share-difficulty")]
+    /// The minimum share difficulty. Default is 1.
+    pub min_share_difficulty: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long = "stratum-pool-payout")]
+    /// Start the mining pool service which pays the stratum workers by `pplns` or `pps`,
+    /// the worker login should be the payout account address. Default is disabled.
+    pub pool_payout: Option<PoolPayoutMode>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long = "stratum-pool-fee")]
+    /// The fee of the mining pool in basis points. Default is 100, it means 1%.
+    pub pool_fee: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long = "stratum-pplns-window")]
+    /// The count of the last shares which split the block reward in PPLNS mode. Default is 10000.
+    pub pplns_window: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long = "stratum-pool-payout-threshold")]
+    /// The minimum balance in nanoSTC of a worker to be paid. Default is 1 STC.
+    pub pool_payout_threshold: Option<u128>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(long = "stratum-pool-payout-interval")]
+    /// The seconds between two payouts. Default is 3600.
+    pub pool_payout_interval: Option<u64>,
+
     #[clap(skip)]
     #[serde(skip)]
     base: Option<Arc<BaseConfig>>,
@@ -54,6 +138,58 @@ impl StratumConfig {
         });
         format!("{}:{}", address, port).parse::<SocketAddr>().ok()
     }
+
+    pub fn share_interval(&self) -> Duration {
+        Duration::from_secs(
+            self.share_interval
+                .unwrap_or(DEFAULT_SHARE_INTERVAL_SECS)
+                .max(1),
+        )
+    }
+
+    pub fn retarget_interval(&self) -> Duration {
+        Duration::from_secs(
+            self.retarget_interval
+                .unwrap_or(DEFAULT_RETARGET_INTERVAL_SECS)
+                .max(1),
+        )
+    }
+
+    pub fn min_share_difficulty(&self) -> u64 {
+        self.min_share_difficulty
+            .unwrap_or(DEFAULT_MIN_SHARE_DIFFICULTY)
+            .max(1)
+    }
+
+    /// The payout mode of the mining pool, None if the pool service is disabled.
+    pub fn pool_payout(&self) -> Option<PoolPayoutMode> {
+        if self.disable {
+            None
+        } else {
+            self.pool_payout
+        }
+    }
+
+    pub fn pool_fee(&self) -> u64 {
+        self.pool_fee.unwrap_or(DEFAULT_POOL_FEE).min(10000)
+    }
+
+    pub fn pplns_window(&self) -> u64 {
+        self.pplns_window.unwrap_or(DEFAULT_PPLNS_WINDOW).max(1)
+    }
+
+    pub fn pool_payout_threshold(&self) -> u128 {
+        self.pool_payout_threshold
+            .unwrap_or(DEFAULT_POOL_PAYOUT_THRESHOLD)
+    }
+
+    pub fn pool_payout_interval(&self) -> Duration {
+        Duration::from_secs(
+            self.pool_payout_interval
+                .unwrap_or(DEFAULT_POOL_PAYOUT_INTERVAL_SECS)
+                .max(1),
+        )
+    }
 }
 
 impl ConfigModule for StratumConfig {
@@ -68,6 +204,30 @@ impl ConfigModule for StratumConfig {
         if opt.stratum.port.is_some() {
             self.port = opt.stratum.port;
         }
+        if opt.stratum.share_interval.is_some() {
+            self.share_interval = opt.stratum.share_interval;
+        }
+        if opt.stratum.retarget_interval.is_some() {
+            self.retarget_interval = opt.stratum.retarget_interval;
+        }
+        if opt.stratum.min_share_difficulty.is_some() {
+            self.min_share_difficulty = opt.stratum.min_share_difficulty;
+        }
+        if opt.stratum.pool_payout.is_some() {
+            self.pool_payout = opt.stratum.pool_payout;
+        }
+        if opt.stratum.pool_fee.is_some() {
+            self.pool_fee = opt.stratum.pool_fee;
+        }
+        if opt.stratum.pplns_window.is_some() {
+            self.pplns_window = opt.stratum.pplns_window;
+        }
+        if opt.stratum.pool_payout_threshold.is_some() {
+            self.pool_payout_threshold = opt.stratum.pool_payout_threshold;
+        }
+        if opt.stratum.pool_payout_interval.is_some() {
+            self.pool_payout_interval = opt.stratum.pool_payout_interval;
+        }
         info!(
             "Stratum listen address: {:?}, port:{:?}",
             self.address, self.port
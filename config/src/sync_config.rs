@@ -2,12 +2,51 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{BaseConfig, ConfigModule, StarcoinOpt};
-use anyhow::Result;
+use anyhow::{bail, Result};
 use clap::Parser;
 use network_api::PeerStrategy;
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 use std::sync::Arc;
 
+/// The action when the depth of a chain reorganization exceeds the max reorg depth.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ReorgDepthAction {
+    /// Switch to the new branch and broadcast an alert event.
+    Alert,
+    /// Refuse to switch to the new branch, keep it as a branch and broadcast an alert event.
+    Refuse,
+}
+
+impl Default for ReorgDepthAction {
+    fn default() -> Self {
+        ReorgDepthAction::Alert
+    }
+}
+
+impl std::fmt::Display for ReorgDepthAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            ReorgDepthAction::Alert => "alert",
+            ReorgDepthAction::Refuse => "refuse",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl FromStr for ReorgDepthAction {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "alert" => ReorgDepthAction::Alert,
+            "refuse" => ReorgDepthAction::Refuse,
+            _ => bail!("Unknown reorg depth action: {}", s),
+        })
+    }
+}
+
 #[derive(Clone, Default, Debug, Deserialize, PartialEq, Eq, Serialize, Parser)]
 #[serde(deny_unknown_fields)]
 pub struct SyncConfig {
@@ -28,6 +67,24 @@ pub struct SyncConfig {
         help = "max retry times once sync block failed, default 15."
     )]
     max_retry_times: Option<u64>,
+
+    /// max depth of chain reorganization, the retracted blocks of main chain.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(
+        name = "max-reorg-depth",
+        long,
+        help = "max depth of chain reorganization, a deeper reorg triggers the reorg depth action, default no limit."
+    )]
+    max_reorg_depth: Option<u64>,
+
+    /// the action when the reorg depth exceeds the max reorg depth.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(
+        name = "reorg-depth-action",
+        long,
+        help = "the action when the reorg depth exceeds max-reorg-depth, alert or refuse, default alert."
+    )]
+    reorg_depth_action: Option<ReorgDepthAction>,
 }
 
 impl SyncConfig {
@@ -38,6 +95,22 @@ impl SyncConfig {
     pub fn max_retry_times(&self) -> u64 {
         self.max_retry_times.unwrap_or(15)
     }
+
+    pub fn set_max_reorg_depth(&mut self, max_reorg_depth: u64) {
+        self.max_reorg_depth = Some(max_reorg_depth);
+    }
+
+    pub fn max_reorg_depth(&self) -> Option<u64> {
+        self.max_reorg_depth
+    }
+
+    pub fn set_reorg_depth_action(&mut self, reorg_depth_action: ReorgDepthAction) {
+        self.reorg_depth_action = Some(reorg_depth_action);
+    }
+
+    pub fn reorg_depth_action(&self) -> ReorgDepthAction {
+        self.reorg_depth_action.unwrap_or_default()
+    }
 }
 
 impl ConfigModule for SyncConfig {
@@ -50,6 +123,14 @@ impl ConfigModule for SyncConfig {
             self.max_retry_times = opt.sync.max_retry_times;
         }
 
+        if opt.sync.max_reorg_depth.is_some() {
+            self.max_reorg_depth = opt.sync.max_reorg_depth;
+        }
+
+        if opt.sync.reorg_depth_action.is_some() {
+            self.reorg_depth_action = opt.sync.reorg_depth_action;
+        }
+
         Ok(())
     }
 }
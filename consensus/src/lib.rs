@@ -26,6 +26,7 @@ mod consensus_test;
 pub mod difficulty;
 pub mod dummy;
 pub mod keccak;
+pub mod simulator;
 
 pub use consensus::{Consensus, ConsensusVerifyError};
 pub use starcoin_time_service::duration_since_epoch;
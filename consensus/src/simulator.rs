@@ -0,0 +1,469 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Difficulty adjustment simulator, mine blocks against a hashrate curve with the difficulty
+//! algorithm of `get_next_target_helper` and the epoch adjustment of the on chain `Epoch` module,
+//! to evaluate the chain behaviour of a `ConsensusConfig` without a live network.
+
+use crate::difficulty::{get_next_target_helper, BlockDiffInfo};
+use crate::{difficult_to_target, target_to_difficulty};
+use anyhow::{bail, ensure, format_err, Result};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use starcoin_types::U256;
+use starcoin_vm_types::on_chain_config::ConsensusConfig;
+use std::collections::VecDeque;
+use std::str::FromStr;
+
+/// The uncle rate is in per mille, same as `ConsensusConfig::uncle_rate_target`.
+const THOUSAND: u64 = 1000;
+
+/// A piecewise constant hashrate curve, the points are (milliseconds since the simulation start, hashes per second).
+#[derive(Clone, Debug, PartialEq)]
+pub struct HashrateCurve {
+    points: Vec<(u64, f64)>,
+}
+
+impl HashrateCurve {
+    pub fn new(mut points: Vec<(u64, f64)>) -> Result<Self> {
+        ensure!(!points.is_empty(), "hashrate curve is empty");
+        ensure!(
+            points.iter().all(|(_, hashrate)| *hashrate > 0f64),
+            "hashrate must be positive"
+        );
+        points.sort_by_key(|(time, _)| *time);
+        Ok(Self { points })
+    }
+
+    pub fn constant(hashrate: f64) -> Result<Self> {
+        Self::new(vec![(0, hashrate)])
+    }
+
+    /// Estimate the hashrate from the history blocks in order of block number,
+    /// the hashrate of a block is the sum of the difficulties divided by the time of the last `window` blocks.
+    pub fn from_history(blocks: &[HistoryBlock], window: usize) -> Result<Self> {
+        ensure!(blocks.len() >= 2, "at least 2 history blocks are required");
+        let window = window.max(1);
+        let start = blocks[0].timestamp;
+        let mut points = vec![];
+        for end in 1..blocks.len() {
+            let begin = end.saturating_sub(window);
+            let elapsed = blocks[end]
+                .timestamp
+                .saturating_sub(blocks[begin].timestamp);
+            let hashes = blocks[begin.saturating_add(1)..=end]
+                .iter()
+                .map(|block| u256_to_f64(block.difficulty))
+                .sum::<f64>();
+            let hashrate = hashes * 1000f64 / elapsed.max(1) as f64;
+            points.push((blocks[end].timestamp.saturating_sub(start), hashrate));
+        }
+        Self::new(points)
+    }
+
+    pub fn hashrate_at(&self, time: u64) -> f64 {
+        self.points
+            .iter()
+            .take_while(|(start, _)| *start <= time)
+            .last()
+            .unwrap_or(&self.points[0])
+            .1
+    }
+}
+
+/// Parse the curve from `time_secs:hashrate` pairs separated by comma, like `0:1000,3600:2000`.
+impl FromStr for HashrateCurve {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let points = s
+            .split(',')
+            .map(|point| {
+                let (time, hashrate) = point
+                    .split_once(':')
+                    .ok_or_else(|| format_err!("invalid hashrate point: {}", point))?;
+                let time = u64::from_str(time.trim())?;
+                let hashrate = f64::from_str(hashrate.trim())?;
+                Ok((time.saturating_mul(1000), hashrate))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Self::new(points)
+    }
+}
+
+/// A block of the chain history.
+#[derive(Clone, Debug)]
+pub struct HistoryBlock {
+    pub number: u64,
+    pub timestamp: u64,
+    pub difficulty: U256,
+    pub uncles: u64,
+}
+
+#[derive(Clone, Debug)]
+pub struct SimulatorConfig {
+    pub consensus: ConsensusConfig,
+    /// The block time target of the first epoch in milliseconds.
+    pub block_time_target: u64,
+    /// The difficulty of the first block, estimate from the hashrate if it is none.
+    pub initial_difficulty: Option<U256>,
+    /// The block propagation delay in milliseconds, the blocks mined in the delay become uncles.
+    pub propagation_delay: u64,
+    pub seed: u64,
+}
+
+impl SimulatorConfig {
+    pub fn new(consensus: ConsensusConfig) -> Self {
+        Self {
+            block_time_target: consensus.base_block_time_target,
+            consensus,
+            initial_difficulty: None,
+            propagation_delay: 1000,
+            seed: 0,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SimulatedBlock {
+    pub number: u64,
+    pub timestamp: u64,
+    pub block_time: u64,
+    pub difficulty: U256,
+    pub uncles: u64,
+    pub block_time_target: u64,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct EpochReport {
+    pub start_block_number: u64,
+    pub end_block_number: u64,
+    pub block_time_target: u64,
+    pub avg_block_time: f64,
+    /// The uncle rate in per mille.
+    pub uncle_rate: u64,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SimulationReport {
+    pub blocks: u64,
+    /// The average block time in milliseconds.
+    pub avg_block_time: f64,
+    pub block_time_stddev: f64,
+    pub avg_difficulty: f64,
+    /// The average relative difficulty change between the adjacent blocks.
+    pub difficulty_oscillation: f64,
+    /// The max relative difficulty change between the adjacent blocks.
+    pub max_difficulty_change: f64,
+    /// The uncle rate in per mille.
+    pub uncle_rate: u64,
+    pub epochs: Vec<EpochReport>,
+}
+
+impl SimulationReport {
+    pub fn new(blocks: &[SimulatedBlock], epochs: Vec<EpochReport>) -> Self {
+        if blocks.is_empty() {
+            return Self::default();
+        }
+        let count = blocks.len() as f64;
+        let avg_block_time = blocks.iter().map(|b| b.block_time as f64).sum::<f64>() / count;
+        let block_time_stddev = (blocks
+            .iter()
+            .map(|b| (b.block_time as f64 - avg_block_time).powi(2))
+            .sum::<f64>()
+            / count)
+            .sqrt();
+        let difficulties = blocks
+            .iter()
+            .map(|b| u256_to_f64(b.difficulty))
+            .collect::<Vec<_>>();
+        let avg_difficulty = difficulties.iter().sum::<f64>() / count;
+        let changes = difficulties
+            .windows(2)
+            .map(|pair| (pair[1] - pair[0]).abs() / pair[0].max(1f64))
+            .collect::<Vec<_>>();
+        let difficulty_oscillation = if changes.is_empty() {
+            0f64
+        } else {
+            changes.iter().sum::<f64>() / changes.len() as f64
+        };
+        let max_difficulty_change = changes.iter().copied().fold(0f64, f64::max);
+        let uncles = blocks
+            .iter()
+            .fold(0u64, |total, b| total.saturating_add(b.uncles));
+        Self {
+            blocks: blocks.len() as u64,
+            avg_block_time,
+            block_time_stddev,
+            avg_difficulty,
+            difficulty_oscillation,
+            max_difficulty_change,
+            uncle_rate: uncle_rate(uncles, blocks.len() as u64),
+            epochs,
+        }
+    }
+
+    /// Report the history blocks, the epochs are split by `epoch_block_count`.
+    pub fn from_history(blocks: &[HistoryBlock], epoch_block_count: u64) -> Self {
+        let blocks = blocks
+            .windows(2)
+            .map(|pair| SimulatedBlock {
+                number: pair[1].number,
+                timestamp: pair[1].timestamp,
+                block_time: pair[1].timestamp.saturating_sub(pair[0].timestamp),
+                difficulty: pair[1].difficulty,
+                uncles: pair[1].uncles,
+                block_time_target: 0,
+            })
+            .collect::<Vec<_>>();
+        let epochs = blocks
+            .chunks(epoch_block_count.max(1) as usize)
+            .map(EpochReport::new)
+            .collect();
+        Self::new(&blocks, epochs)
+    }
+}
+
+impl EpochReport {
+    fn new(blocks: &[SimulatedBlock]) -> Self {
+        let count = blocks.len() as u64;
+        let uncles = blocks
+            .iter()
+            .fold(0u64, |total, b| total.saturating_add(b.uncles));
+        Self {
+            start_block_number: blocks.first().map(|b| b.number).unwrap_or_default(),
+            end_block_number: blocks.last().map(|b| b.number).unwrap_or_default(),
+            block_time_target: blocks
+                .first()
+                .map(|b| b.block_time_target)
+                .unwrap_or_default(),
+            avg_block_time: blocks.iter().map(|b| b.block_time as f64).sum::<f64>()
+                / count.max(1) as f64,
+            uncle_rate: uncle_rate(uncles, count),
+        }
+    }
+}
+
+pub struct DifficultySimulator {
+    config: SimulatorConfig,
+    hashrate: HashrateCurve,
+    rng: StdRng,
+}
+
+impl DifficultySimulator {
+    pub fn new(config: SimulatorConfig, hashrate: HashrateCurve) -> Result<Self> {
+        if config.consensus.epoch_block_count == 0 {
+            bail!("epoch_block_count must be positive");
+        }
+        let rng = StdRng::seed_from_u64(config.seed);
+        Ok(Self {
+            config,
+            hashrate,
+            rng,
+        })
+    }
+
+    /// Mine `count` blocks after the genesis block.
+    pub fn run(&mut self, count: u64) -> Result<(Vec<SimulatedBlock>, SimulationReport)> {
+        let consensus = self.config.consensus.clone();
+        let window = consensus.base_block_difficulty_window.max(1) as usize;
+        let mut block_time_target = self.config.block_time_target.max(1);
+        let initial_difficulty = match self.config.initial_difficulty {
+            Some(difficulty) => difficulty,
+            None => f64_to_u256(self.hashrate.hashrate_at(0) * block_time_target as f64 / 1000f64),
+        };
+        // The window of the latest blocks, the newest is at front.
+        let mut diff_infos = VecDeque::with_capacity(window);
+        diff_infos.push_front(BlockDiffInfo::new(
+            0,
+            difficult_to_target(initial_difficulty),
+        ));
+        let mut now = 0u64;
+        let mut blocks = vec![];
+        let mut epochs = vec![];
+        let mut epoch_start = 0usize;
+        for number in 1..=count {
+            let target =
+                get_next_target_helper(diff_infos.iter().cloned().collect(), block_time_target)?;
+            let difficulty = target_to_difficulty(target);
+            let expected_time = u256_to_f64(difficulty) * 1000f64 / self.hashrate.hashrate_at(now);
+            // The block time of pow is exponential distribution.
+            let sample: f64 = self.rng.gen_range(f64::EPSILON..1f64);
+            let block_time = ((-sample.ln() * expected_time) as u64).max(1);
+            // The probability of a competing block found in the propagation delay.
+            let uncle_probability =
+                1f64 - (-(self.config.propagation_delay as f64) / expected_time.max(1f64)).exp();
+            let uncles = u64::from(self.rng.gen_bool(uncle_probability.clamp(0f64, 1f64)));
+            now = now.saturating_add(block_time);
+            diff_infos.push_front(BlockDiffInfo::new(now, target));
+            diff_infos.truncate(window);
+            blocks.push(SimulatedBlock {
+                number,
+                timestamp: now,
+                block_time,
+                difficulty,
+                uncles,
+                block_time_target,
+            });
+            if number.checked_rem(consensus.epoch_block_count) == Some(0) {
+                let epoch = EpochReport::new(&blocks[epoch_start..]);
+                block_time_target = next_block_time_target(
+                    &consensus,
+                    block_time_target,
+                    blocks[epoch_start..]
+                        .iter()
+                        .fold(0u64, |total, b| total.saturating_add(b.block_time)),
+                    blocks.len().saturating_sub(epoch_start) as u64,
+                    blocks[epoch_start..]
+                        .iter()
+                        .fold(0u64, |total, b| total.saturating_add(b.uncles)),
+                );
+                epochs.push(epoch);
+                epoch_start = blocks.len();
+            }
+        }
+        if epoch_start < blocks.len() {
+            epochs.push(EpochReport::new(&blocks[epoch_start..]));
+        }
+        let report = SimulationReport::new(&blocks, epochs);
+        Ok((blocks, report))
+    }
+}
+
+/// The block time target of the next epoch, same as `Epoch::compute_next_block_time_target` on chain.
+pub fn next_block_time_target(
+    config: &ConsensusConfig,
+    last_block_time_target: u64,
+    total_time: u64,
+    blocks: u64,
+    uncles: u64,
+) -> u64 {
+    let avg_block_time = total_time.checked_div(blocks).unwrap_or_default();
+    let uncle_rate = uncle_rate(uncles, blocks);
+    let mut target = THOUSAND
+        .saturating_add(uncle_rate)
+        .saturating_mul(avg_block_time)
+        .checked_div(config.uncle_rate_target.saturating_add(THOUSAND))
+        .unwrap_or_default();
+    if target > last_block_time_target.saturating_mul(2) {
+        target = last_block_time_target.saturating_mul(2);
+    }
+    let min_target = last_block_time_target.checked_div(2).unwrap_or_default();
+    if target < min_target {
+        target = min_target;
+    }
+    target.clamp(
+        config.min_block_time_target,
+        config
+            .max_block_time_target
+            .max(config.min_block_time_target),
+    )
+}
+
+fn uncle_rate(uncles: u64, blocks: u64) -> u64 {
+    uncles
+        .saturating_mul(THOUSAND)
+        .checked_div(blocks)
+        .unwrap_or_default()
+}
+
+pub fn u256_to_f64(value: U256) -> f64 {
+    value.0.iter().rev().fold(0f64, |total, word| {
+        total * 18_446_744_073_709_551_616f64 + *word as f64
+    })
+}
+
+fn f64_to_u256(value: f64) -> U256 {
+    if value < 1f64 {
+        U256::one()
+    } else if value >= u128::max_value() as f64 {
+        U256::from(u128::max_value())
+    } else {
+        U256::from(value as u128)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn consensus_config() -> ConsensusConfig {
+        ConsensusConfig {
+            uncle_rate_target: 80,
+            base_block_time_target: 10000,
+            base_reward_per_block: 0,
+            base_reward_per_uncle_percent: 0,
+            epoch_block_count: 240,
+            base_block_difficulty_window: 24,
+            min_block_time_target: 5000,
+            max_block_time_target: 60000,
+            base_max_uncles_per_block: 2,
+            base_block_gas_limit: 0,
+            strategy: 0,
+        }
+    }
+
+    #[test]
+    fn test_hashrate_curve() {
+        let curve = HashrateCurve::from_str("0:100, 10:200").unwrap();
+        assert_eq!(curve.hashrate_at(0), 100f64);
+        assert_eq!(curve.hashrate_at(9999), 100f64);
+        assert_eq!(curve.hashrate_at(10000), 200f64);
+        assert!(HashrateCurve::from_str("0:0").is_err());
+
+        let history = (0..10)
+            .map(|n| HistoryBlock {
+                number: n,
+                timestamp: n * 2000,
+                difficulty: 1000.into(),
+                uncles: 0,
+            })
+            .collect::<Vec<_>>();
+        let curve = HashrateCurve::from_history(&history, 4).unwrap();
+        assert_eq!(curve.hashrate_at(18000), 500f64);
+    }
+
+    #[test]
+    fn test_next_block_time_target() {
+        let config = consensus_config();
+        assert_eq!(
+            next_block_time_target(&config, 10000, 2_400_000, 240, 0),
+            9259
+        );
+        assert_eq!(
+            next_block_time_target(&config, 10000, 2_592_000, 240, 20),
+            10830
+        );
+        assert_eq!(
+            next_block_time_target(&config, 10000, 24_000_000, 240, 0),
+            20000
+        );
+        assert_eq!(
+            next_block_time_target(&config, 10000, 240_000, 240, 0),
+            5000
+        );
+    }
+
+    #[test]
+    fn test_simulate_converge() {
+        let mut config = SimulatorConfig::new(consensus_config());
+        // start from a difficulty too low for the hashrate.
+        config.initial_difficulty = Some(1000.into());
+        config.propagation_delay = 0;
+        let curve = HashrateCurve::constant(10000f64).unwrap();
+        let mut simulator = DifficultySimulator::new(config, curve).unwrap();
+        let (blocks, report) = simulator.run(480).unwrap();
+        assert_eq!(blocks.len(), 480);
+        assert_eq!(report.epochs.len(), 2);
+        assert_eq!(report.uncle_rate, 0);
+        // without uncle, the block time target of the second epoch is reduced.
+        assert!(report.epochs[1].block_time_target < report.epochs[0].block_time_target);
+        let late = SimulationReport::new(&blocks[120..240], vec![]);
+        assert!(
+            (late.avg_block_time - 10000f64).abs() < 3000f64,
+            "avg block time {}",
+            late.avg_block_time
+        );
+    }
+}
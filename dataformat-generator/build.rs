@@ -1,4 +1,5 @@
 use anyhow::Result;
+use bcs_ext::Sample;
 use serde_reflection::{Error, Samples, Tracer, TracerConfig};
 use starcoin_crypto::ed25519::Ed25519PrivateKey;
 use starcoin_crypto::multi_ed25519::MultiEd25519PrivateKey;
@@ -21,6 +22,7 @@ use starcoin_types::event::EventKey;
 use starcoin_types::language_storage::TypeTag;
 use starcoin_types::sign_message::{SignedMessage, SigningMessage};
 use starcoin_types::transaction::authenticator::{AuthenticationKey, TransactionAuthenticator};
+use starcoin_types::transaction::secp256r1::{Secp256r1PublicKey, Secp256r1Signature};
 use starcoin_types::transaction::{
     Module, Package, Script, ScriptABI, SignedUserTransaction, Transaction, TransactionArgument,
     TransactionPayload,
@@ -64,6 +66,8 @@ fn generate() -> Result<(), Error> {
         tracer.trace_value(&mut samples, &pri_key.public_key())?;
         tracer.trace_value(&mut samples, &pri_key.sign(&DummyObj::default()))?;
     }
+    tracer.trace_value(&mut samples, &Secp256r1PublicKey::sample())?;
+    tracer.trace_value(&mut samples, &Secp256r1Signature::sample())?;
 
     tracer.trace_type::<BlockMetadata>(&samples)?;
 
@@ -0,0 +1,147 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use blst::min_pk::{AggregateSignature, SecretKey};
+use starcoin_dev::playground::call_contract;
+use starcoin_state_api::ChainStateWriter;
+use starcoin_statedb::ChainStateDB;
+use starcoin_types::access_path::AccessPath;
+use starcoin_types::transaction::TransactionArgument;
+use starcoin_vm_types::account_config::genesis_address;
+use starcoin_vm_types::file_format::CompiledModule;
+use starcoin_vm_types::identifier::Identifier;
+use starcoin_vm_types::language_storage::ModuleId;
+use starcoin_vm_types::state_store::state_key::StateKey;
+use starcoin_vm_types::write_set::{WriteOp, WriteSetMut};
+use test_helper::executor::prepare_genesis;
+
+const DST_SIG: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+const DST_POP: &[u8] = b"BLS_POP_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+/// The native fun declarations of the BLS12-381 natives, the natives are bound to `0x1::Signature`.
+/// The stdlib has not declared them yet, so the module is written to the state directly.
+/// The `Vector` declarations are only for compiling, the on-chain `Vector` module is linked.
+const SIGNATURE_MODULE: &str = r#"
+module 0x1::Vector {
+    native public fun empty<Element>(): vector<Element>;
+    native public fun push_back<Element>(v: &mut vector<Element>, e: Element);
+}
+
+module 0x1::Signature {
+    use 0x1::Vector;
+
+    native public fun bls12381_verify(pubkey: vector<u8>, msg: vector<u8>, signature: vector<u8>): bool;
+
+    /// The caller must ensure every public key has a verified proof-of-possession,
+    /// otherwise the aggregation is vulnerable to rogue-key attacks.
+    native public fun bls12381_aggregate_verify(pubkeys: vector<vector<u8>>, msg: vector<u8>, signature: vector<u8>): bool;
+
+    native public fun bls12381_verify_proof_of_possession(pubkey: vector<u8>, pop: vector<u8>): bool;
+
+    public fun verify(pubkey: vector<u8>, msg: vector<u8>, signature: vector<u8>): bool {
+        bls12381_verify(pubkey, msg, signature)
+    }
+
+    public fun aggregate_verify(pubkey1: vector<u8>, pubkey2: vector<u8>, msg: vector<u8>, signature: vector<u8>): bool {
+        let pubkeys = Vector::empty<vector<u8>>();
+        Vector::push_back(&mut pubkeys, pubkey1);
+        Vector::push_back(&mut pubkeys, pubkey2);
+        bls12381_aggregate_verify(pubkeys, msg, signature)
+    }
+
+    public fun verify_proof_of_possession(pubkey: vector<u8>, pop: vector<u8>): bool {
+        bls12381_verify_proof_of_possession(pubkey, pop)
+    }
+}
+"#;
+
+fn publish_signature_module(chain_state: &ChainStateDB) -> Result<()> {
+    let (_, units) =
+        starcoin_move_compiler::compile_source_string(SIGNATURE_MODULE, &[], genesis_address())?;
+    let module = units
+        .into_iter()
+        .map(|unit| unit.serialize(None))
+        .find(|code| {
+            CompiledModule::deserialize(code)
+                .map(|module| module.self_id().name().as_str() == "Signature")
+                .unwrap_or(false)
+        })
+        .expect("Signature module must exist.");
+    let key = StateKey::AccessPath(AccessPath::code_access_path(
+        genesis_address(),
+        Identifier::new("Signature")?,
+    ));
+    let write_set = WriteSetMut::new(vec![(key, WriteOp::Value(module))]).freeze()?;
+    chain_state.apply_write_set(write_set)?;
+    chain_state.commit()?;
+    Ok(())
+}
+
+fn call_bool(chain_state: &ChainStateDB, func: &str, args: Vec<Vec<u8>>) -> Result<bool> {
+    let result = call_contract(
+        chain_state,
+        ModuleId::new(genesis_address(), Identifier::new("Signature")?),
+        func,
+        vec![],
+        args.into_iter()
+            .map(TransactionArgument::U8Vector)
+            .collect(),
+        None,
+    )?;
+    Ok(bcs_ext::from_bytes(result[0].1.as_slice())?)
+}
+
+#[stest::test]
+fn test_bls12381_natives() -> Result<()> {
+    let (chain_state, _net) = prepare_genesis();
+    publish_signature_module(&chain_state)?;
+
+    let sk1 = SecretKey::key_gen(&[1u8; 32], &[]).unwrap();
+    let sk2 = SecretKey::key_gen(&[2u8; 32], &[]).unwrap();
+    let pk1 = sk1.sk_to_pk().to_bytes().to_vec();
+    let pk2 = sk2.sk_to_pk().to_bytes().to_vec();
+    let msg = b"starcoin".to_vec();
+
+    let sig1 = sk1.sign(&msg, DST_SIG, &[]);
+    let sig2 = sk2.sign(&msg, DST_SIG, &[]);
+    assert!(call_bool(
+        &chain_state,
+        "verify",
+        vec![pk1.clone(), msg.clone(), sig1.to_bytes().to_vec()]
+    )?);
+    assert!(!call_bool(
+        &chain_state,
+        "verify",
+        vec![pk2.clone(), msg.clone(), sig1.to_bytes().to_vec()]
+    )?);
+
+    let agg_sig = AggregateSignature::aggregate(&[&sig1, &sig2], true)
+        .unwrap()
+        .to_signature()
+        .to_bytes()
+        .to_vec();
+    assert!(call_bool(
+        &chain_state,
+        "aggregate_verify",
+        vec![pk1.clone(), pk2.clone(), msg.clone(), agg_sig.clone()]
+    )?);
+    assert!(!call_bool(
+        &chain_state,
+        "aggregate_verify",
+        vec![pk1.clone(), pk2.clone(), b"other".to_vec(), agg_sig]
+    )?);
+
+    let pop = sk1.sign(&pk1, DST_POP, &[]).to_bytes().to_vec();
+    assert!(call_bool(
+        &chain_state,
+        "verify_proof_of_possession",
+        vec![pk1, pop.clone()]
+    )?);
+    assert!(!call_bool(
+        &chain_state,
+        "verify_proof_of_possession",
+        vec![pk2, pop]
+    )?);
+    Ok(())
+}
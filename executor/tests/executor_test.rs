@@ -24,7 +24,7 @@ use starcoin_vm_types::genesis_config::ChainId;
 use starcoin_vm_types::on_chain_config::{ConsensusConfig, OnChainConfig};
 use starcoin_vm_types::state_view::StateView;
 use starcoin_vm_types::token::stc::{stc_type_tag, STCUnit};
-use starcoin_vm_types::vm_status::KeptVMStatus;
+use starcoin_vm_types::vm_status::{KeptVMStatus, VMStatus};
 use starcoin_vm_types::{transaction::Package, vm_status::StatusCode};
 use test_helper::executor::{
     account_execute, account_execute_should_success, association_execute_should_success,
@@ -903,6 +903,42 @@ fn test_gas_used() -> Result<()> {
     Ok(())
 }
 
+#[stest::test]
+fn test_trace_transaction() -> Result<()> {
+    let (chain_state, net) = prepare_genesis();
+
+    let account = Account::new();
+    let txn = create_account_txn_sent_as_association(
+        &account,
+        0,
+        STCUnit::STC.value_of(100).scaling(),
+        1,
+        &net,
+    );
+    let mut vm = StarcoinVM::new(None);
+    let (vm_status, traced_output, trace) = vm.trace_transaction(&chain_state, txn.clone())?;
+    assert_eq!(VMStatus::Executed, vm_status);
+
+    let output = execute_and_apply(&chain_state, Transaction::UserTransaction(txn));
+    assert_eq!(KeptVMStatus::Executed, output.status().status().unwrap());
+    assert_eq!(output.gas_used(), traced_output.gas_used());
+    assert_eq!(output.write_set(), traced_output.write_set());
+
+    assert!(trace.intrinsic_gas > 0);
+    assert!(!trace.execution.children.is_empty());
+    assert!(trace
+        .execution
+        .name
+        .ends_with("::create_account_with_initial_amount"));
+    assert!(trace.out_of_gas_at.is_none());
+    let folded_stacks = trace.to_folded_stacks();
+    assert!(folded_stacks.starts_with("intrinsic "));
+    assert!(folded_stacks
+        .lines()
+        .any(|line| line.starts_with(trace.execution.name.as_str())));
+    Ok(())
+}
+
 #[stest::test]
 fn test_publish_module_and_upgrade() -> Result<()> {
     let (chain_state, net) = prepare_genesis();
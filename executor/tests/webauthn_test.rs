@@ -0,0 +1,115 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use p256::ecdsa::signature::Signer;
+use p256::ecdsa::{Signature, SigningKey};
+use sha2::{Digest, Sha256};
+use starcoin_config::{BuiltinNetworkID, ChainNetwork};
+use starcoin_crypto::hash::CryptoHash;
+use starcoin_statedb::ChainStateDB;
+use starcoin_transaction_builder::{
+    encode_transfer_script_function, peer_to_peer_txn_sent_as_association, DEFAULT_EXPIRATION_TIME,
+    DEFAULT_MAX_GAS_AMOUNT,
+};
+use starcoin_types::account_address::AccountAddress;
+use starcoin_types::transaction::authenticator::{AuthenticationKey, TransactionAuthenticator};
+use starcoin_types::transaction::{
+    RawUserTransaction, SignedUserTransaction, Transaction, TransactionStatus,
+};
+use starcoin_vm_types::genesis_config::{ChainId, StdlibVersion};
+use starcoin_vm_types::transaction::secp256r1::{Secp256r1PublicKey, Secp256r1Signature};
+use starcoin_vm_types::vm_status::{KeptVMStatus, StatusCode};
+use std::convert::TryFrom;
+use test_helper::executor::{execute_and_apply, prepare_customized_genesis, prepare_genesis};
+
+/// Sign the raw txn by a WebAuthn assertion of the passkey `key`.
+fn webauthn_txn(key: &SigningKey, raw_txn: RawUserTransaction) -> SignedUserTransaction {
+    let public_key =
+        Secp256r1PublicKey::try_from(key.verifying_key().to_encoded_point(true).as_bytes())
+            .unwrap();
+    let mut authenticator_data = vec![0u8; 37];
+    // user present
+    authenticator_data[32] = 0x01;
+    let client_data_json = format!(
+        r#"{{"type":"webauthn.get","challenge":"{}","origin":"https://wallet.starcoin.org"}}"#,
+        base64::encode_config(raw_txn.hash().as_ref(), base64::URL_SAFE_NO_PAD)
+    )
+    .into_bytes();
+    let mut signed_data = authenticator_data.clone();
+    signed_data.extend_from_slice(Sha256::digest(&client_data_json).as_slice());
+    let signature: Signature = key.sign(&signed_data);
+    let signature = signature.normalize_s().unwrap_or(signature);
+    let signature = Secp256r1Signature::try_from(signature.as_ref()).unwrap();
+    SignedUserTransaction::new(
+        raw_txn,
+        TransactionAuthenticator::webauthn(
+            public_key,
+            signature,
+            authenticator_data,
+            client_data_json,
+        ),
+    )
+}
+
+/// Fund the passkey account by the association, then send a transfer signed by the passkey.
+fn send_webauthn_txn(chain_state: &ChainStateDB, net: &ChainNetwork) -> TransactionStatus {
+    let key = SigningKey::from_bytes(&[7u8; 32]).unwrap();
+    let public_key =
+        Secp256r1PublicKey::try_from(key.verifying_key().to_encoded_point(true).as_bytes())
+            .unwrap();
+    let sender = AuthenticationKey::secp256r1(&public_key).derived_address();
+    let output = execute_and_apply(
+        chain_state,
+        Transaction::UserTransaction(peer_to_peer_txn_sent_as_association(
+            sender,
+            0,
+            100_000_000,
+            net.time_service().now_secs() + DEFAULT_EXPIRATION_TIME,
+            net,
+        )),
+    );
+    assert_eq!(KeptVMStatus::Executed, output.status().status().unwrap());
+
+    let raw_txn = RawUserTransaction::new_script_function(
+        sender,
+        0,
+        encode_transfer_script_function(AccountAddress::random(), 1000),
+        DEFAULT_MAX_GAS_AMOUNT,
+        1,
+        net.time_service().now_secs() + DEFAULT_EXPIRATION_TIME,
+        net.chain_id(),
+    );
+    let output = execute_and_apply(
+        chain_state,
+        Transaction::UserTransaction(webauthn_txn(&key, raw_txn)),
+    );
+    output.status().clone()
+}
+
+#[stest::test]
+fn test_webauthn_txn_before_activation() -> Result<()> {
+    let mut genesis_config = BuiltinNetworkID::Test.genesis_config().clone();
+    genesis_config.stdlib_version = StdlibVersion::Version(11);
+    let net = ChainNetwork::new_custom(
+        "test_webauthn_txn".to_string(),
+        ChainId::new(100),
+        genesis_config,
+    )?;
+    let chain_state = prepare_customized_genesis(&net);
+    assert_eq!(
+        send_webauthn_txn(&chain_state, &net),
+        TransactionStatus::Discard(StatusCode::FEATURE_UNDER_GATING)
+    );
+    Ok(())
+}
+
+#[stest::test]
+fn test_webauthn_txn_after_activation() -> Result<()> {
+    let (chain_state, net) = prepare_genesis();
+    assert_eq!(
+        send_webauthn_txn(&chain_state, &net),
+        TransactionStatus::Keep(KeptVMStatus::Executed)
+    );
+    Ok(())
+}
@@ -1,11 +1,16 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use starcoin_metrics::{register, Opts, PrometheusError, Registry, UIntGauge};
+use starcoin_metrics::{
+    register, HistogramOpts, HistogramVec, Opts, PrometheusError, Registry, UIntCounterVec,
+    UIntGauge,
+};
 
 #[derive(Clone)]
 pub struct BlockBuilderMetrics {
     pub current_epoch_maybe_uncles: UIntGauge,
+    pub block_template_create_time: HistogramVec,
+    pub warm_block_total: UIntCounterVec,
 }
 
 impl BlockBuilderMetrics {
@@ -18,8 +23,32 @@ impl BlockBuilderMetrics {
             registry,
         )?;
 
+        let block_template_create_time = register(
+            HistogramVec::new(
+                HistogramOpts::new(
+                    "block_template_create_time",
+                    "create block template time, cold or warm.",
+                ),
+                &["type"],
+            )?,
+            registry,
+        )?;
+
+        let warm_block_total = register(
+            UIntCounterVec::new(
+                Opts::new(
+                    "warm_block_total",
+                    "warm opened block count, open, reuse, or discard.",
+                ),
+                &["type"],
+            )?,
+            registry,
+        )?;
+
         Ok(Self {
             current_epoch_maybe_uncles,
+            block_template_create_time,
+            warm_block_total,
         })
     }
 }
@@ -2,6 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::create_block_template::metrics::BlockBuilderMetrics;
+use crate::create_block_template::txn_selection::{
+    build_txn_selection_policy, TxPoolOrderPolicy, TxnSelectionPolicy,
+};
+use crate::create_block_template::uncle_selection::UnclePolicy;
 use anyhow::{format_err, Result};
 use futures::executor::block_on;
 use starcoin_account_api::{AccountAsyncService, AccountInfo, DefaultAccountChangeEvent};
@@ -18,6 +22,7 @@ use starcoin_open_block::OpenedBlock;
 use starcoin_service_registry::{
     ActorService, EventHandler, ServiceContext, ServiceFactory, ServiceHandler, ServiceRequest,
 };
+use starcoin_state_api::StateReaderExt;
 use starcoin_storage::{BlockStore, Storage, Store};
 use starcoin_txpool::TxPoolService;
 use starcoin_txpool_api::TxPoolSyncService;
@@ -27,11 +32,14 @@ use starcoin_types::{
 };
 use starcoin_vm_types::transaction::SignedUserTransaction;
 use std::cmp::min;
+use std::collections::HashSet;
 use std::{collections::HashMap, sync::Arc};
 
 mod metrics;
 #[cfg(test)]
 mod test_create_block_template;
+pub mod txn_selection;
+pub mod uncle_selection;
 
 #[derive(Debug)]
 pub struct GetHeadRequest;
@@ -88,7 +96,14 @@ impl ServiceFactory<Self> for BlockBuilderService {
             miner_account,
             metrics,
             vm_metrics,
-        )?;
+        )?
+        .with_txn_selection_policy(build_txn_selection_policy(&config.miner))
+        .with_uncle_policy(UnclePolicy::from(&config.miner));
+        let inner = if config.miner.warm_block_template() {
+            inner.with_warm_block(config.miner.warm_block_template_max_age())
+        } else {
+            inner
+        };
         Ok(Self { inner })
     }
 }
@@ -98,6 +113,9 @@ impl ActorService for BlockBuilderService {
         ctx.subscribe::<NewHeadBlock>();
         ctx.subscribe::<NewBranch>();
         ctx.subscribe::<DefaultAccountChangeEvent>();
+        if self.inner.is_warm_block_enabled() {
+            ctx.add_stream(self.inner.tx_provider.subscribe_pending_txn());
+        }
         Ok(())
     }
 
@@ -123,6 +141,19 @@ impl EventHandler<Self, NewBranch> for BlockBuilderService {
     }
 }
 
+/// Push the new pending txns into the warm opened block.
+impl EventHandler<Self, Arc<[HashValue]>> for BlockBuilderService {
+    fn handle_event(
+        &mut self,
+        txns: Arc<[HashValue]>,
+        _ctx: &mut ServiceContext<BlockBuilderService>,
+    ) {
+        if let Err(e) = self.inner.push_pending_txns(&txns) {
+            error!("Push pending txns to warm block error: {:?}", e);
+        }
+    }
+}
+
 impl EventHandler<Self, DefaultAccountChangeEvent> for BlockBuilderService {
     fn handle_event(
         &mut self,
@@ -131,6 +162,7 @@ impl EventHandler<Self, DefaultAccountChangeEvent> for BlockBuilderService {
     ) {
         info!("Miner account change to {}", msg.new_account.address);
         self.inner.miner_account = msg.new_account;
+        self.inner.discard_warm_block();
     }
 }
 
@@ -140,7 +172,7 @@ impl ServiceHandler<Self, BlockTemplateRequest> for BlockBuilderService {
         _msg: BlockTemplateRequest,
         _ctx: &mut ServiceContext<BlockBuilderService>,
     ) -> Result<BlockTemplateResponse> {
-        let template = self.inner.create_block_template();
+        let template = self.inner.get_block_template();
         self.inner.uncles_prune();
         template
     }
@@ -158,6 +190,8 @@ impl ServiceHandler<Self, GetHeadRequest> for BlockBuilderService {
 
 pub trait TemplateTxProvider {
     fn get_txns(&self, max: u64) -> Vec<SignedUserTransaction>;
+    /// Get the txns of `txn_hashes` which are still in the txpool.
+    fn get_txns_by_hash(&self, txn_hashes: &[HashValue]) -> Vec<SignedUserTransaction>;
     fn remove_invalid_txn(&self, txn_hash: HashValue);
 }
 
@@ -168,6 +202,10 @@ impl TemplateTxProvider for EmptyProvider {
         vec![]
     }
 
+    fn get_txns_by_hash(&self, _txn_hashes: &[HashValue]) -> Vec<SignedUserTransaction> {
+        vec![]
+    }
+
     fn remove_invalid_txn(&self, _txn_hash: HashValue) {}
 }
 
@@ -176,11 +214,27 @@ impl TemplateTxProvider for TxPoolService {
         self.get_pending_txns(Some(max), None)
     }
 
+    fn get_txns_by_hash(&self, txn_hashes: &[HashValue]) -> Vec<SignedUserTransaction> {
+        txn_hashes
+            .iter()
+            .filter_map(|txn_hash| self.find_txn(txn_hash))
+            .collect()
+    }
+
     fn remove_invalid_txn(&self, txn_hash: HashValue) {
         self.remove_txn(txn_hash, true);
     }
 }
 
+/// The opened block kept warm on the current head.
+struct WarmBlock {
+    parent: BlockHeader,
+    opened_block: OpenedBlock,
+    /// The pending txns whose sequence numbers are ahead of the opened block,
+    /// revisited when the new pending txns are pushed.
+    future_txns: Vec<HashValue>,
+}
+
 pub struct Inner<P> {
     storage: Arc<dyn Store>,
     chain: BlockChain,
@@ -191,6 +245,11 @@ pub struct Inner<P> {
     miner_account: AccountInfo,
     metrics: Option<BlockBuilderMetrics>,
     vm_metrics: Option<VMMetrics>,
+    txn_selection_policy: Box<dyn TxnSelectionPolicy>,
+    uncle_policy: UnclePolicy,
+    /// The max age in seconds of the warm block, None if the warm block is disabled.
+    warm_block_max_age: Option<u64>,
+    warm_block: Option<WarmBlock>,
 }
 
 impl<P> Inner<P>
@@ -224,10 +283,41 @@ where
             miner_account,
             metrics,
             vm_metrics,
+            txn_selection_policy: Box::new(TxPoolOrderPolicy),
+            uncle_policy: UnclePolicy::default(),
+            warm_block_max_age: None,
+            warm_block: None,
         })
     }
 
+    pub fn with_txn_selection_policy(
+        mut self,
+        txn_selection_policy: Box<dyn TxnSelectionPolicy>,
+    ) -> Self {
+        self.txn_selection_policy = txn_selection_policy;
+        self
+    }
+
+    pub fn with_uncle_policy(mut self, uncle_policy: UnclePolicy) -> Self {
+        self.uncle_policy = uncle_policy;
+        self
+    }
+
+    /// Keep a warm opened block on the current head, which is rebuilt after `max_age` seconds.
+    pub fn with_warm_block(mut self, max_age: u64) -> Self {
+        self.warm_block_max_age = Some(max_age);
+        self
+    }
+
+    pub fn is_warm_block_enabled(&self) -> bool {
+        self.warm_block_max_age.is_some()
+    }
+
     pub fn insert_uncle(&mut self, uncle: BlockHeader) {
+        // The warm block should be rebuilt to include the new uncle.
+        if self.warm_block.is_some() && self.chain.can_be_uncle(&uncle).unwrap_or_default() {
+            self.discard_warm_block();
+        }
         self.parent_uncle
             .entry(uncle.parent_hash())
             .or_insert_with(Vec::new)
@@ -241,6 +331,7 @@ where
     }
 
     pub fn update_chain(&mut self, block: ExecutedBlock) -> Result<()> {
+        self.discard_warm_block();
         let current_header = self.chain.current_header();
         let current_id = current_header.id();
         if self.chain.can_connect(&block) {
@@ -265,20 +356,19 @@ where
     }
 
     pub fn find_uncles(&self) -> Vec<BlockHeader> {
-        let mut new_uncle = Vec::new();
         let epoch = self.chain.epoch();
-        if epoch.end_block_number() != (self.chain.current_header().number() + 1) {
-            for maybe_uncle in self.uncles.values() {
-                if new_uncle.len() as u64 >= epoch.max_uncles_per_block() {
-                    break;
-                }
-                if self.chain.can_be_uncle(maybe_uncle).unwrap_or_default() {
-                    new_uncle.push(maybe_uncle.clone())
-                }
-            }
+        let block_number = self.chain.current_header().number() + 1;
+        if epoch.end_block_number() == block_number {
+            return vec![];
         }
-
-        new_uncle
+        let candidates = self
+            .uncles
+            .values()
+            .filter(|maybe_uncle| self.chain.can_be_uncle(maybe_uncle).unwrap_or_default())
+            .cloned()
+            .collect();
+        self.uncle_policy
+            .select(block_number, candidates, epoch.max_uncles_per_block())
     }
 
     fn uncles_prune(&mut self) {
@@ -288,6 +378,12 @@ where
             // 的倒数第二块处理时清理uncles
             if epoch.end_block_number() == (self.chain.current_header().number() + 2) {
                 self.uncles.clear();
+            } else {
+                // The uncles too deep for the next block can never be included later.
+                let block_number = self.chain.current_header().number() + 1;
+                let uncle_policy = &self.uncle_policy;
+                self.uncles
+                    .retain(|_, uncle| !uncle_policy.is_too_deep(block_number, uncle));
             }
         }
         if let Some(metrics) = self.metrics.as_ref() {
@@ -297,19 +393,198 @@ where
         }
     }
 
+    /// Create the block template from the warm block if enabled, otherwise build a fresh one.
+    pub fn get_block_template(&mut self) -> Result<BlockTemplateResponse> {
+        let warm = self.is_warm_block_enabled();
+        let _timer = self.metrics.as_ref().map(|metrics| {
+            metrics
+                .block_template_create_time
+                .with_label_values(&[if warm { "warm" } else { "cold" }])
+                .start_timer()
+        });
+        if warm {
+            self.create_warm_block_template()
+        } else {
+            self.create_block_template()
+        }
+    }
+
     pub fn create_block_template(&self) -> Result<BlockTemplateResponse> {
+        let (previous_header, mut opened_block) = self.open_block()?;
+        let txns = self.select_txns(opened_block.gas_limit());
+        self.push_txns(&mut opened_block, txns)?;
+        let template = opened_block.finalize()?;
+        Ok(BlockTemplateResponse {
+            parent: previous_header,
+            template,
+        })
+    }
+
+    /// Create the block template from the warm block, with the new ready txns of the txpool appended.
+    pub fn create_warm_block_template(&mut self) -> Result<BlockTemplateResponse> {
+        let warm_block = match self.take_fresh_warm_block() {
+            Some(warm_block) => self.refresh_warm_block(warm_block)?,
+            None => self.open_warm_block()?,
+        };
+        let response = BlockTemplateResponse {
+            parent: warm_block.parent.clone(),
+            template: warm_block.opened_block.block_template()?,
+        };
+        self.warm_block = Some(warm_block);
+        Ok(response)
+    }
+
+    /// Push the new pending txns `txn_hashes` into the warm block,
+    /// open it with the pending txns of the txpool if absent or expired.
+    pub fn push_pending_txns(&mut self, txn_hashes: &[HashValue]) -> Result<()> {
+        if !self.is_warm_block_enabled() {
+            return Ok(());
+        }
+        let warm_block = match self.take_fresh_warm_block() {
+            Some(mut warm_block) => {
+                let txns = self.tx_provider.get_txns_by_hash(txn_hashes);
+                self.push_ready_txns(&mut warm_block, txns)?;
+                warm_block
+            }
+            None => self.open_warm_block()?,
+        };
+        self.warm_block = Some(warm_block);
+        Ok(())
+    }
+
+    /// Take the warm block if it is not older than the max age, otherwise discard it.
+    fn take_fresh_warm_block(&mut self) -> Option<WarmBlock> {
+        let max_age = self.warm_block_max_age?.saturating_mul(1000);
+        let now_millis = self.chain.time_service().now_millis();
+        match self.warm_block.take() {
+            Some(warm_block)
+                if now_millis.saturating_sub(warm_block.opened_block.block_meta().timestamp())
+                    <= max_age =>
+            {
+                self.inc_warm_block_total("reuse");
+                Some(warm_block)
+            }
+            Some(_) => {
+                self.inc_warm_block_total("discard");
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Open the warm block on the current head, with the pending txns of the txpool.
+    fn open_warm_block(&self) -> Result<WarmBlock> {
+        let (parent, mut opened_block) = self.open_block()?;
+        self.inc_warm_block_total("open");
+        let txns = self.select_txns(opened_block.gas_limit());
+        self.push_txns(&mut opened_block, txns)?;
+        Ok(WarmBlock {
+            parent,
+            opened_block,
+            future_txns: vec![],
+        })
+    }
+
+    /// Rebuild the warm block if the head changed or one of its txns expired,
+    /// otherwise append the new ready txns of the txpool to it.
+    fn refresh_warm_block(&self, mut warm_block: WarmBlock) -> Result<WarmBlock> {
+        let now_secs = self.chain.time_service().now_secs();
+        let expired = warm_block
+            .opened_block
+            .included_user_txns()
+            .iter()
+            .any(|txn| txn.expiration_timestamp_secs() <= now_secs);
+        if warm_block.parent.id() != self.chain.current_header().id() || expired {
+            self.inc_warm_block_total("discard");
+            return self.open_warm_block();
+        }
+        let txns = self.select_txns(warm_block.opened_block.gas_limit());
+        self.push_ready_txns(&mut warm_block, txns)?;
+        Ok(warm_block)
+    }
+
+    /// Push the ready ones of `txns` and the revisited future txns into the warm block,
+    /// and keep the future ones to revisit later.
+    /// The opened block may be corrupted if push txns failed, so it should be dropped on error.
+    fn push_ready_txns(
+        &self,
+        warm_block: &mut WarmBlock,
+        mut txns: Vec<SignedUserTransaction>,
+    ) -> Result<()> {
+        txns.extend(
+            self.tx_provider
+                .get_txns_by_hash(&std::mem::take(&mut warm_block.future_txns)),
+        );
+        let (ready_txns, future_txns) = self.new_ready_txns(&warm_block.opened_block, txns)?;
+        warm_block.future_txns = future_txns;
+        self.push_txns(&mut warm_block.opened_block, ready_txns)
+    }
+
+    /// Split the `txns` not included by the opened block into the ready ones on its state
+    /// and the future ones, the future txns are left in the txpool instead of being discarded
+    /// by the block.
+    fn new_ready_txns(
+        &self,
+        opened_block: &OpenedBlock,
+        txns: Vec<SignedUserTransaction>,
+    ) -> Result<(Vec<SignedUserTransaction>, Vec<HashValue>)> {
+        let included: HashSet<HashValue> = opened_block
+            .included_user_txns()
+            .iter()
+            .map(|txn| txn.id())
+            .collect();
+        let mut txns: Vec<_> = txns
+            .into_iter()
+            .filter(|txn| !included.contains(&txn.id()))
+            .collect();
+        txns.sort_by_key(|txn| (txn.sender(), txn.sequence_number()));
+        txns.dedup_by_key(|txn| txn.id());
+        let mut next_sequence_numbers = HashMap::new();
+        let mut ready_txns = vec![];
+        let mut future_txns = vec![];
+        for txn in txns {
+            let sender = txn.sender();
+            let next_sequence_number = match next_sequence_numbers.get(&sender) {
+                Some(sequence_number) => *sequence_number,
+                None => opened_block
+                    .state_reader()
+                    .get_account_resource(sender)?
+                    .map(|account| account.sequence_number())
+                    .unwrap_or_default(),
+            };
+            if txn.sequence_number() == next_sequence_number {
+                next_sequence_numbers.insert(sender, next_sequence_number + 1);
+                ready_txns.push(txn);
+            } else {
+                if txn.sequence_number() > next_sequence_number {
+                    future_txns.push(txn.id());
+                }
+                next_sequence_numbers.insert(sender, next_sequence_number);
+            }
+        }
+        Ok((self.txn_selection_policy.select(ready_txns), future_txns))
+    }
+
+    pub fn discard_warm_block(&mut self) {
+        if self.warm_block.take().is_some() {
+            self.inc_warm_block_total("discard");
+        }
+    }
+
+    fn inc_warm_block_total(&self, label: &str) {
+        if let Some(metrics) = self.metrics.as_ref() {
+            metrics.warm_block_total.with_label_values(&[label]).inc();
+        }
+    }
+
+    /// Open a new block on the current head, without user txns.
+    fn open_block(&self) -> Result<(BlockHeader, OpenedBlock)> {
         let on_chain_block_gas_limit = self.chain.epoch().block_gas_limit();
         let block_gas_limit = self
             .local_block_gas_limit
             .map(|block_gas_limit| min(block_gas_limit, on_chain_block_gas_limit))
             .unwrap_or(on_chain_block_gas_limit);
 
-        //TODO use a GasConstant value to replace 200.
-        // block_gas_limit / min_gas_per_txn
-        let max_txns = (block_gas_limit / 200) * 2;
-
-        let txns = self.tx_provider.get_txns(max_txns);
-
         let author = *self.miner_account.address();
         let previous_header = self.chain.current_header();
         let uncles = self.find_uncles();
@@ -322,11 +597,9 @@ where
             now_millis = previous_header.timestamp() + 1;
         }
         info!(
-            "[CreateBlockTemplate] previous_header: {:?}, block_gas_limit: {}, max_txns: {}, txn len: {}, uncles len: {}, timestamp: {}",
+            "[CreateBlockTemplate] previous_header: {:?}, block_gas_limit: {}, uncles len: {}, timestamp: {}",
             previous_header,
             block_gas_limit,
-            max_txns,
-            txns.len(),
             uncles.len(),
             now_millis,
         );
@@ -335,7 +608,7 @@ where
         let strategy = epoch.strategy();
         let difficulty = strategy.calculate_next_difficulty(&self.chain)?;
 
-        let mut opened_block = OpenedBlock::new(
+        let opened_block = OpenedBlock::new(
             self.storage.clone(),
             previous_header.clone(),
             block_gas_limit,
@@ -346,15 +619,59 @@ where
             strategy,
             self.vm_metrics.clone(),
         )?;
+        Ok((previous_header, opened_block))
+    }
+
+    fn select_txns(&self, block_gas_limit: u64) -> Vec<SignedUserTransaction> {
+        //TODO use a GasConstant value to replace 200.
+        // block_gas_limit / min_gas_per_txn
+        let max_txns = (block_gas_limit / 200) * 2;
+
+        let txns = self
+            .txn_selection_policy
+            .select(self.tx_provider.get_txns(max_txns));
+        debug!(
+            "[CreateBlockTemplate] max_txns: {}, txn len: {}",
+            max_txns,
+            txns.len()
+        );
+        txns
+    }
+
+    /// Push the txns into the opened block, and remove the discarded txns from the txpool.
+    fn push_txns(
+        &self,
+        opened_block: &mut OpenedBlock,
+        txns: Vec<SignedUserTransaction>,
+    ) -> Result<()> {
+        if txns.is_empty() {
+            return Ok(());
+        }
         let excluded_txns = opened_block.push_txns(txns)?;
-        let template = opened_block.finalize()?;
-        for invalid_txn in excluded_txns.discarded_txns {
+        let mut discarded_txns = excluded_txns.discarded_txns;
+        if self.txn_selection_policy.pack_gas() {
+            // The block nears its gas limit, keep trying the smaller txns which fit the left gas,
+            // skip the sender once one of its txn is skipped, to keep the sequence number order.
+            let mut skipped_senders = HashSet::new();
+            for txn in excluded_txns.untouched_txns {
+                let sender = txn.sender();
+                if skipped_senders.contains(&sender) {
+                    continue;
+                }
+                if txn.max_gas_amount() > opened_block.gas_left() {
+                    skipped_senders.insert(sender);
+                    continue;
+                }
+                let excluded = opened_block.push_txns(vec![txn])?;
+                if !excluded.untouched_txns.is_empty() || !excluded.discarded_txns.is_empty() {
+                    skipped_senders.insert(sender);
+                }
+                discarded_txns.extend(excluded.discarded_txns);
+            }
+        }
+        for invalid_txn in discarded_txns {
             self.tx_provider.remove_invalid_txn(invalid_txn.id());
         }
-
-        Ok(BlockTemplateResponse {
-            parent: previous_header,
-            template,
-        })
+        Ok(())
     }
 }
@@ -1,8 +1,13 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::create_block_template::txn_selection::{
+    GasPackingPolicy, GasPricePolicy, PriorityAddressPolicy, SenderFairnessPolicy,
+    TxPoolOrderPolicy, TxnSelectionPolicy,
+};
+use crate::create_block_template::uncle_selection::UnclePolicy;
 use crate::create_block_template::{
-    BlockBuilderService, BlockTemplateRequest, EmptyProvider, Inner,
+    BlockBuilderService, BlockTemplateRequest, EmptyProvider, Inner, TemplateTxProvider,
 };
 use anyhow::Result;
 use starcoin_account_api::AccountInfo;
@@ -10,15 +15,29 @@ use starcoin_account_service::AccountService;
 use starcoin_chain::BlockChain;
 use starcoin_chain::{ChainReader, ChainWriter};
 use starcoin_config::ChainNetworkID;
+use starcoin_config::UncleSelectionOrder;
 use starcoin_config::{temp_dir, NodeConfig, StarcoinOpt};
 use starcoin_consensus::Consensus;
+use starcoin_crypto::HashValue;
 use starcoin_genesis::Genesis as StarcoinGenesis;
 use starcoin_logger::prelude::*;
 use starcoin_service_registry::{RegistryAsyncService, RegistryService};
 use starcoin_storage::BlockStore;
 use starcoin_time_service::MockTimeService;
+use starcoin_transaction_builder::{
+    build_batch_transfer_txn, create_signed_txn_with_association_account,
+    encode_create_account_script_function, encode_transfer_script_function,
+};
 use starcoin_txpool::TxPoolService;
-use std::sync::Arc;
+use starcoin_types::account::Account;
+use starcoin_types::account_address::AccountAddress;
+use starcoin_types::block::{BlockHeader, BlockHeaderBuilder};
+use starcoin_vm_types::account_config::stc_type_tag;
+use starcoin_vm_types::genesis_config::ChainId;
+use starcoin_vm_types::transaction::{
+    RawUserTransaction, Script, SignedUserTransaction, TransactionPayload,
+};
+use std::sync::{Arc, Mutex};
 
 #[stest::test]
 fn test_create_block_template() {
@@ -469,3 +488,481 @@ fn test_create_block_template_by_adjust_time() -> Result<()> {
     inner.chain.apply(block)?;
     Ok(())
 }
+
+fn mock_txn(
+    sender: AccountAddress,
+    sequence_number: u64,
+    gas_unit_price: u64,
+) -> SignedUserTransaction {
+    let (private_key, public_key) = starcoin_crypto::ed25519::genesis_key_pair();
+    RawUserTransaction::new_with_default_gas_token(
+        sender,
+        sequence_number,
+        TransactionPayload::Script(Script::new(vec![], vec![], vec![])),
+        10000,
+        gas_unit_price,
+        u64::max_value(),
+        ChainId::test(),
+    )
+    .sign(&private_key, public_key)
+    .unwrap()
+    .into_inner()
+}
+
+fn txn_keys(txns: &[SignedUserTransaction]) -> Vec<(AccountAddress, u64)> {
+    txns.iter()
+        .map(|txn| (txn.sender(), txn.sequence_number()))
+        .collect()
+}
+
+#[stest::test]
+fn test_txn_selection_policy() {
+    let alice = AccountAddress::random();
+    let bob = AccountAddress::random();
+    let carol = AccountAddress::random();
+    let txns = vec![
+        mock_txn(alice, 0, 1),
+        mock_txn(alice, 1, 100),
+        mock_txn(alice, 2, 1),
+        mock_txn(bob, 0, 10),
+        mock_txn(bob, 1, 10),
+        mock_txn(carol, 0, 5),
+    ];
+
+    let selected = TxPoolOrderPolicy.select(txns.clone());
+    assert_eq!(txn_keys(&selected), txn_keys(&txns));
+
+    // the high gas price txn of alice can not be picked before its previous txn.
+    let selected = GasPricePolicy.select(txns.clone());
+    assert_eq!(
+        txn_keys(&selected),
+        vec![
+            (bob, 0),
+            (bob, 1),
+            (carol, 0),
+            (alice, 0),
+            (alice, 1),
+            (alice, 2)
+        ]
+    );
+
+    let selected = SenderFairnessPolicy::new(1).select(txns.clone());
+    assert_eq!(txn_keys(&selected), vec![(alice, 0), (bob, 0), (carol, 0)]);
+    let selected = SenderFairnessPolicy::new(2).select(txns.clone());
+    assert_eq!(
+        txn_keys(&selected),
+        vec![(alice, 0), (bob, 0), (carol, 0), (alice, 1), (bob, 1)]
+    );
+
+    let selected = PriorityAddressPolicy::new(vec![carol, bob]).select(txns.clone());
+    assert_eq!(
+        txn_keys(&selected),
+        vec![
+            (bob, 0),
+            (bob, 1),
+            (carol, 0),
+            (alice, 0),
+            (alice, 1),
+            (alice, 2)
+        ]
+    );
+
+    let selected = GasPackingPolicy.select(txns.clone());
+    assert_eq!(txn_keys(&selected), txn_keys(&txns));
+    assert!(GasPackingPolicy.pack_gas());
+    assert!(!GasPricePolicy.pack_gas());
+}
+
+#[stest::test]
+fn test_create_block_template_with_policy() {
+    let node_config = Arc::new(NodeConfig::random_for_test());
+    let (storage, _, genesis) = StarcoinGenesis::init_storage_for_test(node_config.net())
+        .expect("init storage by genesis fail.");
+    let genesis_id = genesis.block().id();
+    let inner = Inner::new(
+        node_config.net(),
+        storage,
+        genesis_id,
+        EmptyProvider,
+        None,
+        AccountInfo::random(),
+        None,
+        None,
+    )
+    .unwrap()
+    .with_txn_selection_policy(Box::new(GasPackingPolicy));
+
+    let block_template = inner.create_block_template().unwrap().template;
+    assert_eq!(block_template.parent_hash, genesis_id);
+    assert_eq!(block_template.number, 1);
+}
+
+/// Provide the given txns, and record the txns removed as invalid.
+struct MockTxProvider {
+    txns: Arc<Mutex<Vec<SignedUserTransaction>>>,
+    removed: Arc<Mutex<Vec<HashValue>>>,
+}
+
+impl TemplateTxProvider for MockTxProvider {
+    fn get_txns(&self, max: u64) -> Vec<SignedUserTransaction> {
+        let txns = self.txns.lock().unwrap();
+        txns.iter().take(max as usize).cloned().collect()
+    }
+
+    fn get_txns_by_hash(&self, txn_hashes: &[HashValue]) -> Vec<SignedUserTransaction> {
+        let txns = self.txns.lock().unwrap();
+        txns.iter()
+            .filter(|txn| txn_hashes.contains(&txn.id()))
+            .cloned()
+            .collect()
+    }
+
+    fn remove_invalid_txn(&self, txn_hash: HashValue) {
+        self.removed.lock().unwrap().push(txn_hash);
+    }
+}
+
+#[stest::test]
+fn test_create_block_template_with_policy_and_txns() {
+    let node_config = Arc::new(NodeConfig::random_for_test());
+    let net = node_config.net();
+    let (storage, _, genesis) =
+        StarcoinGenesis::init_storage_for_test(net).expect("init storage by genesis fail.");
+    let genesis_id = genesis.block().id();
+    let expiration = net.time_service().now_secs() + 3600;
+    let association_txn = |seq_num: u64, gas_unit_price: u64| {
+        create_signed_txn_with_association_account(
+            TransactionPayload::ScriptFunction(encode_transfer_script_function(
+                AccountAddress::random(),
+                1000,
+            )),
+            seq_num,
+            10_000_000,
+            gas_unit_price,
+            expiration,
+            net,
+        )
+    };
+    // The txn of a sender which does not exist is discarded by the vm.
+    let unknown_sender = Account::new();
+    let unknown_sender_txn =
+        unknown_sender.sign_txn(RawUserTransaction::new_with_default_gas_token(
+            *unknown_sender.address(),
+            0,
+            TransactionPayload::ScriptFunction(encode_transfer_script_function(
+                AccountAddress::random(),
+                1000,
+            )),
+            10_000_000,
+            1000,
+            expiration,
+            net.chain_id(),
+        ));
+    let txns = vec![
+        association_txn(0, 1),
+        association_txn(1, 100),
+        unknown_sender_txn.clone(),
+        association_txn(2, 10),
+    ];
+    let removed = Arc::new(Mutex::new(vec![]));
+    let inner = Inner::new(
+        net,
+        storage,
+        genesis_id,
+        MockTxProvider {
+            txns: Arc::new(Mutex::new(txns.clone())),
+            removed: removed.clone(),
+        },
+        None,
+        AccountInfo::random(),
+        None,
+        None,
+    )
+    .unwrap()
+    .with_txn_selection_policy(Box::new(GasPricePolicy));
+
+    let block_template = inner.create_block_template().unwrap().template;
+    // The highest gas price first, but the txns of the same sender keep the sequence number order.
+    let packed = block_template
+        .body
+        .transactions
+        .iter()
+        .map(|txn| txn.id())
+        .collect::<Vec<_>>();
+    assert_eq!(packed, vec![txns[0].id(), txns[1].id(), txns[3].id()]);
+    assert!(block_template.gas_used > 0);
+    assert_eq!(*removed.lock().unwrap(), vec![unknown_sender_txn.id()]);
+}
+
+#[stest::test]
+fn test_gas_packing_near_block_gas_limit() -> Result<()> {
+    let node_config = Arc::new(NodeConfig::random_for_test());
+    let net = node_config.net();
+    let (storage, _, genesis) = StarcoinGenesis::init_storage_for_test(net)?;
+    let expiration = net.time_service().now_secs() + 3600;
+    let alice = Account::new();
+    let txns = Arc::new(Mutex::new(vec![
+        create_signed_txn_with_association_account(
+            TransactionPayload::ScriptFunction(encode_create_account_script_function(
+                net.stdlib_version(),
+                stc_type_tag(),
+                alice.address(),
+                alice.auth_key(),
+                1_000_000_000_000,
+            )),
+            0,
+            10_000_000,
+            1,
+            expiration,
+            net,
+        ),
+    ]));
+    let mut inner = Inner::new(
+        net,
+        storage,
+        genesis.block().id(),
+        MockTxProvider {
+            txns: txns.clone(),
+            removed: Arc::new(Mutex::new(vec![])),
+        },
+        None,
+        AccountInfo::random(),
+        None,
+        None,
+    )?
+    .with_txn_selection_policy(Box::new(GasPackingPolicy));
+    let template = inner.create_block_template()?.template;
+    assert_eq!(template.body.transactions.len(), 1);
+    let block = net
+        .genesis_config()
+        .consensus()
+        .create_block(template, net.time_service().as_ref())?;
+    inner.chain.apply(block)?;
+
+    // a small txn, the transfer to an existing account.
+    let small_txn = |seq_num: u64, max_gas_amount: u64| {
+        create_signed_txn_with_association_account(
+            TransactionPayload::ScriptFunction(encode_transfer_script_function(
+                *alice.address(),
+                1000,
+            )),
+            seq_num,
+            max_gas_amount,
+            1,
+            expiration,
+            net,
+        )
+    };
+    *txns.lock().unwrap() = vec![small_txn(1, 10_000_000)];
+    let small_gas = inner.create_block_template()?.template.gas_used;
+    assert!(small_gas > 0);
+
+    // a large txn, which creates many accounts.
+    let large_txn = alice.sign_txn(build_batch_transfer_txn(
+        *alice.address(),
+        (0..20).map(|_| AccountAddress::random()).collect(),
+        0,
+        1000,
+        1,
+        10_000_000,
+        expiration,
+        net.chain_id(),
+    ));
+    let packed_txns = vec![
+        small_txn(1, 2 * small_gas),
+        large_txn,
+        small_txn(2, 2 * small_gas),
+    ];
+    *txns.lock().unwrap() = packed_txns.clone();
+    // the large txn does not fit the gas left after the first txn, but the later small txn fits.
+    inner.local_block_gas_limit = Some(4 * small_gas);
+    let template = inner.create_block_template()?.template;
+    let packed = template
+        .body
+        .transactions
+        .iter()
+        .map(|txn| txn.id())
+        .collect::<Vec<_>>();
+    assert_eq!(packed, vec![packed_txns[0].id(), packed_txns[2].id()]);
+    assert!(template.gas_used <= 4 * small_gas);
+
+    // the txns after the large txn are not packed without the gas packing.
+    inner = inner.with_txn_selection_policy(Box::new(TxPoolOrderPolicy));
+    let template = inner.create_block_template()?.template;
+    assert_eq!(template.body.transactions.len(), 1);
+    Ok(())
+}
+
+#[stest::test]
+fn test_uncle_policy() {
+    let uncle = |number: u64, difficulty: u64| -> BlockHeader {
+        BlockHeaderBuilder::random()
+            .with_number(number)
+            .with_difficulty(difficulty.into())
+            .build()
+    };
+    let candidates = vec![uncle(10, 1), uncle(8, 3), uncle(9, 2), uncle(5, 4)];
+    let numbers = |uncles: Vec<BlockHeader>| uncles.iter().map(|u| u.number()).collect::<Vec<_>>();
+
+    let policy = UnclePolicy::default();
+    assert_eq!(policy.select(11, candidates.clone(), 2).len(), 2);
+
+    let policy = UnclePolicy::new(Some(3), None, UncleSelectionOrder::Difficulty);
+    assert!(policy.is_too_deep(11, &candidates[3]));
+    assert_eq!(
+        numbers(policy.select(11, candidates.clone(), 2)),
+        vec![8, 9]
+    );
+
+    let policy = UnclePolicy::new(None, Some(1), UncleSelectionOrder::Nearest);
+    assert_eq!(numbers(policy.select(11, candidates.clone(), 2)), vec![10]);
+    assert_eq!(numbers(policy.select(11, candidates, 0)), Vec::<u64>::new());
+}
+
+#[stest::test]
+fn test_warm_block_template() -> Result<()> {
+    let node_config = Arc::new(NodeConfig::random_for_test());
+    let (storage, _, genesis) = StarcoinGenesis::init_storage_for_test(node_config.net())?;
+    let genesis_id = genesis.block().id();
+    let mut inner = Inner::new(
+        node_config.net(),
+        storage.clone(),
+        genesis_id,
+        EmptyProvider,
+        None,
+        AccountInfo::random(),
+        None,
+        None,
+    )?
+    .with_warm_block(10);
+    assert!(inner.is_warm_block_enabled());
+
+    // the warm block is reused before the new head.
+    let first = inner.get_block_template()?;
+    let second = inner.get_block_template()?;
+    assert_eq!(first.parent.id(), genesis_id);
+    assert_eq!(first.template.timestamp, second.template.timestamp);
+    assert_eq!(
+        first.template.state_root,
+        inner.create_block_template()?.template.state_root
+    );
+
+    // the warm block is not rebuilt within the max age.
+    let time_service = node_config.net().time_service();
+    let mock_time_service = time_service
+        .as_any()
+        .downcast_ref::<MockTimeService>()
+        .unwrap();
+    mock_time_service.set(first.template.timestamp + 1000);
+    let reused = inner.get_block_template()?;
+    assert_eq!(reused.template.timestamp, first.template.timestamp);
+
+    // the warm block older than the max age is rebuilt with a fresh timestamp.
+    mock_time_service.set(first.template.timestamp + 11_000);
+    let third = inner.get_block_template()?;
+    assert_eq!(third.template.timestamp, first.template.timestamp + 11_000);
+    assert_eq!(
+        third.template.state_root,
+        inner.create_block_template()?.template.state_root
+    );
+
+    // the warm block is discarded on the new head.
+    let block = node_config
+        .net()
+        .genesis_config()
+        .consensus()
+        .create_block(third.template, time_service.as_ref())?;
+    let mut chain = BlockChain::new(time_service.clone(), genesis_id, storage, None)?;
+    let executed_block = chain.apply(block)?;
+    inner.update_chain(executed_block.clone())?;
+    let fourth = inner.get_block_template()?;
+    assert_eq!(fourth.parent.id(), executed_block.header().id());
+    assert_eq!(fourth.template.number, 2);
+    Ok(())
+}
+
+#[stest::test]
+fn test_warm_block_push_pending_txns() -> Result<()> {
+    let node_config = Arc::new(NodeConfig::random_for_test());
+    let net = node_config.net();
+    let (storage, _, genesis) = StarcoinGenesis::init_storage_for_test(net)?;
+    let expiration = net.time_service().now_secs() + 3600;
+    let association_txn = |seq_num: u64| {
+        create_signed_txn_with_association_account(
+            TransactionPayload::ScriptFunction(encode_transfer_script_function(
+                AccountAddress::random(),
+                1000,
+            )),
+            seq_num,
+            10_000_000,
+            1,
+            expiration,
+            net,
+        )
+    };
+    let pending_txns = Arc::new(Mutex::new(vec![]));
+    let removed = Arc::new(Mutex::new(vec![]));
+    let mut inner = Inner::new(
+        net,
+        storage,
+        genesis.block().id(),
+        MockTxProvider {
+            txns: pending_txns.clone(),
+            removed: removed.clone(),
+        },
+        None,
+        AccountInfo::random(),
+        None,
+        None,
+    )?
+    .with_warm_block(10);
+    let included = |inner: &Inner<MockTxProvider>| -> Vec<HashValue> {
+        inner
+            .warm_block
+            .as_ref()
+            .map(|warm_block| {
+                warm_block
+                    .opened_block
+                    .included_user_txns()
+                    .iter()
+                    .map(|txn| txn.id())
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let packed = |inner: &mut Inner<MockTxProvider>| -> Result<Vec<HashValue>> {
+        Ok(inner
+            .get_block_template()?
+            .template
+            .body
+            .transactions
+            .iter()
+            .map(|txn| txn.id())
+            .collect())
+    };
+    assert!(packed(&mut inner)?.is_empty());
+
+    let txns: Vec<_> = (0..4).map(association_txn).collect();
+    let hashes: Vec<_> = txns.iter().map(|txn| txn.id()).collect();
+    // Only the txns of the event are pushed, the future txn is left in the txpool.
+    pending_txns
+        .lock()
+        .unwrap()
+        .extend([txns[0].clone(), txns[1].clone(), txns[3].clone()]);
+    inner.push_pending_txns(&[hashes[3], hashes[1], hashes[0]])?;
+    assert_eq!(included(&inner), vec![hashes[0], hashes[1]]);
+
+    // The future txn becomes ready after the gap is filled.
+    pending_txns.lock().unwrap().push(txns[2].clone());
+    inner.push_pending_txns(&[hashes[2]])?;
+    assert_eq!(included(&inner), hashes);
+
+    // The warm block is reused by the template, and the new ready txns are appended.
+    let txn = association_txn(4);
+    pending_txns.lock().unwrap().push(txn.clone());
+    let mut expect = hashes.clone();
+    expect.push(txn.id());
+    assert_eq!(packed(&mut inner)?, expect);
+    assert!(removed.lock().unwrap().is_empty());
+    Ok(())
+}
@@ -0,0 +1,167 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The policies of selecting txns from txpool into the block template.
+//! All policies keep the sequence number order of the txns from the same sender,
+//! otherwise the later txns will be discarded by the vm.
+
+use starcoin_config::{MinerConfig, TxnSelectionPolicyType};
+use starcoin_types::account_address::AccountAddress;
+use starcoin_vm_types::transaction::SignedUserTransaction;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+
+pub trait TxnSelectionPolicy: Send + Sync {
+    /// Order and filter the candidate txns, the candidate txns is in txpool order.
+    fn select(&self, txns: Vec<SignedUserTransaction>) -> Vec<SignedUserTransaction>;
+
+    /// Keep trying the untouched txns which fit the left gas after the block nears its gas limit.
+    fn pack_gas(&self) -> bool {
+        false
+    }
+}
+
+pub fn build_txn_selection_policy(config: &MinerConfig) -> Box<dyn TxnSelectionPolicy> {
+    match config.txn_selection_policy() {
+        TxnSelectionPolicyType::TxPool => Box::new(TxPoolOrderPolicy),
+        TxnSelectionPolicyType::GasPrice => Box::new(GasPricePolicy),
+        TxnSelectionPolicyType::SenderFairness => {
+            Box::new(SenderFairnessPolicy::new(config.max_txns_per_sender()))
+        }
+        TxnSelectionPolicyType::Priority => {
+            Box::new(PriorityAddressPolicy::new(config.priority_addresses()))
+        }
+        TxnSelectionPolicyType::GasPacking => Box::new(GasPackingPolicy),
+    }
+}
+
+/// Group the txns by sender, keep the order of txns and the first appear order of senders.
+fn group_by_sender(
+    txns: Vec<SignedUserTransaction>,
+) -> Vec<(AccountAddress, VecDeque<SignedUserTransaction>)> {
+    let mut index = HashMap::new();
+    let mut groups: Vec<(AccountAddress, VecDeque<SignedUserTransaction>)> = vec![];
+    for txn in txns {
+        let sender = txn.sender();
+        let i = *index.entry(sender).or_insert_with(|| {
+            groups.push((sender, VecDeque::new()));
+            groups.len() - 1
+        });
+        groups[i].1.push_back(txn);
+    }
+    groups
+}
+
+/// Keep the order of the txpool.
+pub struct TxPoolOrderPolicy;
+
+impl TxnSelectionPolicy for TxPoolOrderPolicy {
+    fn select(&self, txns: Vec<SignedUserTransaction>) -> Vec<SignedUserTransaction> {
+        txns
+    }
+}
+
+/// Pick the txn with the highest gas price from the first txn of each sender.
+pub struct GasPricePolicy;
+
+impl TxnSelectionPolicy for GasPricePolicy {
+    fn select(&self, txns: Vec<SignedUserTransaction>) -> Vec<SignedUserTransaction> {
+        let total = txns.len();
+        let mut groups = group_by_sender(txns);
+        // (gas price, reverse group index) make the earlier group first when the gas price is same.
+        let mut heap = groups
+            .iter()
+            .enumerate()
+            .filter_map(|(i, (_, group))| {
+                group
+                    .front()
+                    .map(|txn| (txn.gas_unit_price(), std::cmp::Reverse(i)))
+            })
+            .collect::<BinaryHeap<_>>();
+        let mut selected = Vec::with_capacity(total);
+        while let Some((_, std::cmp::Reverse(i))) = heap.pop() {
+            let group = &mut groups[i].1;
+            if let Some(txn) = group.pop_front() {
+                selected.push(txn);
+            }
+            if let Some(next) = group.front() {
+                heap.push((next.gas_unit_price(), std::cmp::Reverse(i)));
+            }
+        }
+        selected
+    }
+}
+
+/// Pick the txns of each sender in turn, at most `max_txns_per_sender` txns of each sender.
+pub struct SenderFairnessPolicy {
+    max_txns_per_sender: u64,
+}
+
+impl SenderFairnessPolicy {
+    pub fn new(max_txns_per_sender: u64) -> Self {
+        Self {
+            max_txns_per_sender,
+        }
+    }
+}
+
+impl TxnSelectionPolicy for SenderFairnessPolicy {
+    fn select(&self, txns: Vec<SignedUserTransaction>) -> Vec<SignedUserTransaction> {
+        let mut groups = group_by_sender(txns)
+            .into_iter()
+            .map(|(_, mut group)| {
+                group.truncate(self.max_txns_per_sender as usize);
+                group
+            })
+            .collect::<Vec<_>>();
+        let mut selected = vec![];
+        loop {
+            let mut picked = false;
+            for group in groups.iter_mut() {
+                if let Some(txn) = group.pop_front() {
+                    selected.push(txn);
+                    picked = true;
+                }
+            }
+            if !picked {
+                break;
+            }
+        }
+        selected
+    }
+}
+
+/// The txns of the priority addresses first, then the others, both keep the txpool order.
+pub struct PriorityAddressPolicy {
+    priority_addresses: HashSet<AccountAddress>,
+}
+
+impl PriorityAddressPolicy {
+    pub fn new(priority_addresses: Vec<AccountAddress>) -> Self {
+        Self {
+            priority_addresses: priority_addresses.into_iter().collect(),
+        }
+    }
+}
+
+impl TxnSelectionPolicy for PriorityAddressPolicy {
+    fn select(&self, txns: Vec<SignedUserTransaction>) -> Vec<SignedUserTransaction> {
+        let (mut priority_txns, others): (Vec<_>, Vec<_>) = txns
+            .into_iter()
+            .partition(|txn| self.priority_addresses.contains(&txn.sender()));
+        priority_txns.extend(others);
+        priority_txns
+    }
+}
+
+/// Keep the order of the txpool, and fill the block with the smaller txns after it nears the gas limit.
+pub struct GasPackingPolicy;
+
+impl TxnSelectionPolicy for GasPackingPolicy {
+    fn select(&self, txns: Vec<SignedUserTransaction>) -> Vec<SignedUserTransaction> {
+        txns
+    }
+
+    fn pack_gas(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,73 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The policy of selecting the uncles into the block template.
+
+use starcoin_config::{MinerConfig, UncleSelectionOrder};
+use starcoin_types::block::{BlockHeader, BlockNumber};
+use std::cmp::Reverse;
+
+#[derive(Clone, Debug, Default)]
+pub struct UnclePolicy {
+    max_depth: Option<u64>,
+    max_per_block: Option<u64>,
+    order: UncleSelectionOrder,
+}
+
+impl UnclePolicy {
+    pub fn new(
+        max_depth: Option<u64>,
+        max_per_block: Option<u64>,
+        order: UncleSelectionOrder,
+    ) -> Self {
+        Self {
+            max_depth,
+            max_per_block,
+            order,
+        }
+    }
+
+    /// Whether the uncle is too deep to be included in the block `block_number` or later.
+    pub fn is_too_deep(&self, block_number: BlockNumber, uncle: &BlockHeader) -> bool {
+        match self.max_depth {
+            Some(max_depth) => block_number.saturating_sub(uncle.number()) > max_depth,
+            None => false,
+        }
+    }
+
+    /// Select the uncles for the block `block_number` from the candidates which can be uncle,
+    /// `on_chain_max` is the max uncles per block of the current epoch.
+    pub fn select(
+        &self,
+        block_number: BlockNumber,
+        mut candidates: Vec<BlockHeader>,
+        on_chain_max: u64,
+    ) -> Vec<BlockHeader> {
+        candidates.retain(|uncle| !self.is_too_deep(block_number, uncle));
+        match self.order {
+            UncleSelectionOrder::Any => {}
+            UncleSelectionOrder::Difficulty => {
+                candidates.sort_by_key(|uncle| (Reverse(uncle.difficulty()), uncle.id()))
+            }
+            UncleSelectionOrder::Nearest => {
+                candidates.sort_by_key(|uncle| (Reverse(uncle.number()), uncle.id()))
+            }
+        }
+        let max = self
+            .max_per_block
+            .map(|max| max.min(on_chain_max))
+            .unwrap_or(on_chain_max);
+        candidates.truncate(max as usize);
+        candidates
+    }
+}
+
+impl From<&MinerConfig> for UnclePolicy {
+    fn from(config: &MinerConfig) -> Self {
+        Self::new(
+            config.max_uncle_depth(),
+            config.max_uncles_per_block(),
+            config.uncle_selection_order(),
+        )
+    }
+}
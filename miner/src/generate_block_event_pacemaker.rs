@@ -32,6 +32,11 @@ impl GenerateBlockEventPacemaker {
         ctx.broadcast(GenerateBlockEvent::new_break(force));
     }
 
+    /// Instant seal never mints empty block, so it mints on demand too.
+    fn is_mint_on_demand(&self) -> bool {
+        self.config.miner.is_disable_mint_empty_block() || self.config.miner.is_instant_seal()
+    }
+
     pub fn is_synced(&self) -> bool {
         match self.sync_status.as_ref() {
             Some(sync_status) => sync_status.is_synced(),
@@ -45,7 +50,7 @@ impl ActorService for GenerateBlockEventPacemaker {
         ctx.subscribe::<SyncStatusChangeEvent>();
         ctx.subscribe::<NewHeadBlock>();
         //if mint empty block is disabled, trigger mint event for on demand mint (Dev)
-        if self.config.miner.is_disable_mint_empty_block() {
+        if self.is_mint_on_demand() {
             ctx.subscribe::<PropagateTransactions>();
         }
         Ok(())
@@ -54,7 +59,7 @@ impl ActorService for GenerateBlockEventPacemaker {
     fn stopped(&mut self, ctx: &mut ServiceContext<Self>) -> Result<()> {
         ctx.unsubscribe::<SyncStatusChangeEvent>();
         ctx.unsubscribe::<NewHeadBlock>();
-        if self.config.miner.is_disable_mint_empty_block() {
+        if self.is_mint_on_demand() {
             ctx.unsubscribe::<PropagateTransactions>();
         }
         Ok(())
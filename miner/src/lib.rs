@@ -171,7 +171,7 @@ impl MinerService {
 
         if !event.skip_empty_block_check
             && (block_template.body.transactions.is_empty()
-            && self.config.miner.is_disable_mint_empty_block()
+            && (self.config.miner.is_disable_mint_empty_block() || self.config.miner.is_instant_seal())
             //if block time gap > 3600, force create a empty block for fix https://github.com/starcoinorg/starcoin/issues/3036
             && block_time_gap < MAX_BLOCK_TIME_GAP)
         {
@@ -211,6 +211,12 @@ impl MinerService {
             );
         }
         self.current_task = Some(task);
+        if self.config.miner.is_instant_seal() {
+            // The dev and test network use the dummy consensus, any nonce is a valid seal.
+            return self
+                .finish_task(0, BlockHeaderExtra::default(), mining_blob, ctx)
+                .map(|_| ());
+        }
         ctx.broadcast(MintBlockEvent::new(
             parent_hash,
             strategy,
@@ -276,7 +282,10 @@ impl EventHandler<Self, GenerateBlockEvent> for MinerService {
             debug!("Miner has mint job so just ignore this event.");
             return;
         }
-        if self.config.miner.disable_miner_client() && self.client_subscribers_num == 0 {
+        if self.config.miner.disable_miner_client()
+            && self.client_subscribers_num == 0
+            && !self.config.miner.is_instant_seal()
+        {
             debug!("No miner client connected, ignore GenerateBlockEvent.");
             // Once Miner client connect, we should dispatch task.
             ctx.run_later(Duration::from_secs(2), |ctx| {
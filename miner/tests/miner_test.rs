@@ -1,6 +1,7 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use futures::{FutureExt, StreamExt};
 use starcoin_account_service::AccountService;
 use starcoin_config::NodeConfig;
 use starcoin_consensus::Consensus;
@@ -8,13 +9,17 @@ use starcoin_genesis::Genesis;
 use starcoin_miner::{
     BlockBuilderService, BlockHeaderExtra, BlockTemplateRequest, MinerService, SubmitSealRequest,
 };
+use starcoin_service_registry::bus::BusService;
 use starcoin_service_registry::{RegistryAsyncService, RegistryService};
 use starcoin_storage::BlockStore;
 use starcoin_txpool::TxPoolService;
-use starcoin_types::{system_events::GenerateBlockEvent, U256};
+use starcoin_types::{
+    system_events::{GenerateBlockEvent, MinedBlock},
+    U256,
+};
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::time::sleep;
+use tokio::time::{sleep, timeout};
 
 #[stest::test]
 async fn test_miner_service() {
@@ -76,3 +81,45 @@ async fn test_miner_service() {
 
     registry.shutdown_system().await.unwrap();
 }
+
+#[stest::test]
+async fn test_instant_seal() {
+    let mut config = NodeConfig::random_for_test();
+    config.miner.instant_seal = Some(true);
+    let registry = RegistryService::launch();
+    let node_config = Arc::new(config.clone());
+    registry.put_shared(node_config.clone()).await.unwrap();
+    let (storage, _chain_info, genesis) = Genesis::init_storage_for_test(config.net()).unwrap();
+    registry.put_shared(storage.clone()).await.unwrap();
+
+    let chain_header = storage
+        .get_block_header_by_hash(genesis.block().id())
+        .unwrap()
+        .unwrap();
+    let txpool = TxPoolService::new(node_config.clone(), storage.clone(), chain_header, None);
+    registry.put_shared(txpool).await.unwrap();
+    registry
+        .register_mocker(AccountService::mock().unwrap())
+        .await
+        .unwrap();
+    registry.register::<BlockBuilderService>().await.unwrap();
+
+    let bus = registry.service_ref::<BusService>().await.unwrap();
+    let mut mined_blocks = bus.channel::<MinedBlock>().await.unwrap();
+    let miner = registry.register::<MinerService>().await.unwrap();
+
+    // No txn in the txpool, the empty block is skipped.
+    miner.notify(GenerateBlockEvent::new_break(false)).unwrap();
+    sleep(Duration::from_millis(200)).await;
+    assert!(mined_blocks.next().now_or_never().is_none());
+
+    // Seal on demand, without the miner client.
+    miner.notify(GenerateBlockEvent::new(true, true)).unwrap();
+    let block = timeout(Duration::from_secs(5), mined_blocks.next())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(block.0.header().number(), 1);
+
+    registry.shutdown_system().await.unwrap();
+}
@@ -0,0 +1,78 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use libp2p::PeerId;
+use parking_lot::RwLock;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// The members of the permissioned network, shared by the notification and the request-response
+/// protocols, so both of them check the same allow-list.
+#[derive(Clone, Debug, Default)]
+pub struct AllowedPeers {
+    /// `None` if the network is permissionless.
+    members: Option<Arc<RwLock<HashSet<PeerId>>>>,
+}
+
+impl AllowedPeers {
+    pub fn new(members: Option<HashSet<PeerId>>) -> Self {
+        Self {
+            members: members.map(|members| Arc::new(RwLock::new(members))),
+        }
+    }
+
+    /// Returns true if the peer is a member of the permissioned network, or the network is
+    /// permissionless.
+    pub fn is_allowed(&self, peer_id: &PeerId) -> bool {
+        self.members
+            .as_ref()
+            .map(|members| members.read().contains(peer_id))
+            .unwrap_or(true)
+    }
+
+    /// Returns the members of the permissioned network, `None` if the network is permissionless.
+    pub fn members(&self) -> Option<HashSet<PeerId>> {
+        self.members.as_ref().map(|members| members.read().clone())
+    }
+
+    /// Add or remove a member of the permissioned network.
+    /// Return whether the members changed, or `None` if the network is permissionless.
+    pub fn set_allowed(&self, peer_id: PeerId, allowed: bool) -> Option<bool> {
+        let members = self.members.as_ref()?;
+        let mut members = members.write();
+        Some(if allowed {
+            members.insert(peer_id)
+        } else {
+            members.remove(&peer_id)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allowed_peers_shared() {
+        let permissionless = AllowedPeers::default();
+        assert!(permissionless.is_allowed(&PeerId::random()));
+        assert_eq!(permissionless.set_allowed(PeerId::random(), true), None);
+        assert!(permissionless.members().is_none());
+
+        let member = PeerId::random();
+        let allowed_peers = AllowedPeers::new(Some(vec![member].into_iter().collect()));
+        // the request-response protocol checks a clone of the allow-list of the protocol.
+        let shared = allowed_peers.clone();
+        let peer = PeerId::random();
+        assert!(shared.is_allowed(&member));
+        assert!(!shared.is_allowed(&peer));
+
+        assert_eq!(allowed_peers.set_allowed(peer, true), Some(true));
+        assert_eq!(allowed_peers.set_allowed(peer, true), Some(false));
+        assert!(shared.is_allowed(&peer));
+
+        assert_eq!(allowed_peers.set_allowed(member, false), Some(true));
+        assert!(!shared.is_allowed(&member));
+        assert_eq!(shared.members(), Some(vec![peer].into_iter().collect()));
+    }
+}
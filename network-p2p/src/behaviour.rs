@@ -18,6 +18,7 @@ use crate::discovery::DiscoveryConfig;
 use crate::protocol::generic_proto::NotificationsSink;
 use crate::protocol::{CustomMessageOutcome, Protocol};
 use crate::request_responses::{Event, IfDisconnected, RequestFailure, ResponseFailure};
+use crate::traffic::TrafficAccounting;
 use crate::{
     discovery::DiscoveryBehaviour, discovery::DiscoveryOut, peer_info, protocol::event::DhtEvent,
     request_responses,
@@ -32,6 +33,7 @@ use sc_peerset::ReputationChange;
 use starcoin_types::startup_info::ChainInfo;
 use std::borrow::Cow;
 use std::collections::HashSet;
+use std::sync::Arc;
 use std::time::Duration;
 
 /// General behaviour of the network. Combines all protocols together.
@@ -154,7 +156,9 @@ impl Behaviour {
         local_public_key: PublicKey,
         disco_config: DiscoveryConfig,
         request_response_protocols: Vec<request_responses::ProtocolConfig>,
+        traffic: Arc<TrafficAccounting>,
     ) -> Result<Self, request_responses::RegisterError> {
+        let allowed_peers = protocol.allowed_peers().clone();
         Ok(Behaviour {
             protocol,
             // debug_info: debug_info::DebugInfoBehaviour::new(user_agent, local_public_key),
@@ -162,7 +166,9 @@ impl Behaviour {
             discovery: disco_config.finish(),
             request_responses: request_responses::RequestResponsesBehaviour::new(
                 request_response_protocols.into_iter(),
-            )?,
+            )?
+            .with_traffic_accounting(traffic)
+            .with_allowed_peers(allowed_peers),
         })
     }
 
@@ -235,6 +241,19 @@ impl Behaviour {
             .send_request(target, protocol, request, pending_response, connect)
     }
 
+    /// Returns true if the peer is a member of the permissioned network, or the network is
+    /// permissionless.
+    pub fn is_peer_allowed(&self, peer_id: &PeerId) -> bool {
+        self.protocol.is_peer_allowed(peer_id)
+    }
+
+    /// Add or remove a member of the permissioned network, for both the notifications and the
+    /// request-response protocols, which share the allow-list.
+    /// Return false if the network is permissionless.
+    pub fn set_peer_allowed(&mut self, peer_id: PeerId, allowed: bool) -> bool {
+        self.protocol.set_peer_allowed(peer_id, allowed)
+    }
+
     /// Returns a shared reference to the user protocol.
     pub fn user_protocol(&self) -> &Protocol {
         &self.protocol
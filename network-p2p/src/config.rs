@@ -7,6 +7,7 @@ use libp2p::{
     core::Multiaddr,
     identity::{ed25519, Keypair},
     multiaddr::Protocol,
+    PeerId,
 };
 use prometheus::Registry;
 use std::borrow::Cow;
@@ -96,6 +97,9 @@ pub struct NetworkConfiguration {
     pub reserved_nodes: Vec<MultiaddrWithPeerId>,
     /// The non-reserved peer mode.
     pub non_reserved_mode: NonReservedPeerMode,
+    /// The members of a permissioned network, connections from other peers are rejected at
+    /// handshake. `None` means the network is permissionless.
+    pub allowed_peers: Option<Vec<PeerId>>,
     /// Client identifier. Sent over the wire for debugging purposes.
     pub client_version: String,
     /// Name of the node. Sent over the wire for debugging purposes.
@@ -110,6 +114,12 @@ pub struct NetworkConfiguration {
     /// Require iterative Kademlia DHT queries to use disjoint paths for increased resiliency in the
     /// presence of potentially adversarial nodes.
     pub kademlia_disjoint_query_paths: bool,
+    /// Maximum bytes per second sent by all the protocols, half of it is reserved for the
+    /// notification protocols and half for the request-response protocols, `None` means unlimited.
+    pub upload_rate_limit: Option<u64>,
+    /// Maximum bytes per second received by all the protocols, half of it is reserved for the
+    /// notification protocols and half for the request-response protocols, `None` means unlimited.
+    pub download_rate_limit: Option<u64>,
 }
 
 /// Configuration for the transport layer.
@@ -143,6 +153,7 @@ impl Default for NetworkConfiguration {
             out_peers: 75,
             reserved_nodes: Vec::new(),
             non_reserved_mode: NonReservedPeerMode::Accept,
+            allowed_peers: None,
             client_version: "unknown".into(),
             node_name: "unknown".into(),
             transport: TransportConfig::Normal {
@@ -153,6 +164,8 @@ impl Default for NetworkConfiguration {
             request_response_protocols: vec![],
             allow_non_globals_in_dht: false,
             kademlia_disjoint_query_paths: false,
+            upload_rate_limit: None,
+            download_rate_limit: None,
         }
     }
 }
@@ -173,6 +186,7 @@ impl NetworkConfiguration {
             out_peers: 75,
             reserved_nodes: Vec::new(),
             non_reserved_mode: NonReservedPeerMode::Accept,
+            allowed_peers: None,
             client_version: client_version.into(),
             node_name: node_name.into(),
             transport: TransportConfig::Normal {
@@ -183,6 +197,8 @@ impl NetworkConfiguration {
             request_response_protocols: vec![],
             allow_non_globals_in_dht: false,
             kademlia_disjoint_query_paths: false,
+            upload_rate_limit: None,
+            download_rate_limit: None,
         }
     }
 
@@ -16,6 +16,7 @@ pub use libp2p::{
     multiaddr as build_multiaddr,
 };
 
+mod allowed_peers;
 //TODO change to private
 pub mod behaviour;
 pub mod config;
@@ -36,6 +37,7 @@ mod request_responses;
 mod service;
 #[cfg(test)]
 mod service_test;
+mod traffic;
 mod transport;
 mod utils;
 
@@ -32,6 +32,8 @@ pub struct Metrics {
     pub requests_out_failure_total: UIntCounterVec,
     pub requests_out_success_total: HistogramVec,
     pub requests_out_started_total: UIntCounterVec,
+    pub traffic_bytes_total: UIntCounterVec,
+    pub traffic_dropped_total: UIntCounterVec,
     pub peerset_nodes: UIntGaugeVec,
 }
 
@@ -276,6 +278,26 @@ impl Metrics {
                 )?,
                 registry,
             )?,
+            traffic_bytes_total: register(
+                UIntCounterVec::new(
+                    Opts::new(
+                        "networkp2p_traffic_bytes_total",
+                        "Total bytes of notifications and request-responses, by direction and protocol",
+                    ),
+                    &["direction", "protocol"],
+                )?,
+                registry,
+            )?,
+            traffic_dropped_total: register(
+                UIntCounterVec::new(
+                    Opts::new(
+                        "networkp2p_traffic_dropped_total",
+                        "Total number of messages dropped by the rate limit, by direction and protocol",
+                    ),
+                    &["direction", "protocol"],
+                )?,
+                registry,
+            )?,
             peerset_nodes: register(
                 UIntGaugeVec::new(
                     Opts::new("networkp2p_peerset_nodes", "nodes numbers in each peer set"),
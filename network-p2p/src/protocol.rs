@@ -2,6 +2,7 @@ pub mod event;
 pub mod generic_proto;
 pub mod message;
 
+use crate::allowed_peers::AllowedPeers;
 use crate::protocol::generic_proto::{GenericProto, GenericProtoOut, NotificationsSink};
 use crate::protocol::message::generic::Status;
 use crate::utils::interval;
@@ -128,6 +129,9 @@ pub struct Protocol {
     /// Entries are removed when the corresponding "substream closed" is later received.
     bad_handshake_substreams: HashSet<(PeerId, sc_peerset::SetId)>,
     chain_info: ChainInfo,
+    /// The members of a permissioned network, the other peers are rejected without touching
+    /// their reputation.
+    allowed_peers: AllowedPeers,
 }
 
 impl NetworkBehaviour for Protocol {
@@ -278,6 +282,9 @@ impl NetworkBehaviour for Protocol {
 impl DiscoveryNetBehaviour for Protocol {
     fn add_discovered_nodes(&mut self, peer_ids: impl Iterator<Item = PeerId>) {
         for peer_id in peer_ids {
+            if !self.is_peer_allowed(&peer_id) {
+                continue;
+            }
             for (set_id, _) in self.notif_protocols.iter().enumerate() {
                 self.peerset_handle
                     .add_to_peers_set(SetId::from(set_id), peer_id);
@@ -294,6 +301,7 @@ impl Protocol {
         boot_node_ids: Arc<HashSet<PeerId>>,
         notif_protocols: Vec<Cow<'static, str>>,
         rpc_protocols: Vec<Cow<'static, str>>,
+        allowed_peers: AllowedPeers,
     ) -> errors::Result<(Protocol, sc_peerset::PeersetHandle)> {
         let mut important_peers = HashSet::new();
         important_peers.extend(boot_node_ids.iter());
@@ -338,6 +346,7 @@ impl Protocol {
             notif_protocols,
             rpc_protocols,
             bad_handshake_substreams: Default::default(),
+            allowed_peers,
         };
         Ok((protocol, peerset_handle))
     }
@@ -408,6 +417,17 @@ impl Protocol {
         notifications_sink: NotificationsSink,
     ) -> CustomMessageOutcome {
         debug!(target: "network-p2p", "New peer {} {:?}", who, status);
+        if !self.is_peer_allowed(&who) {
+            log!(
+                target: "network-p2p",
+                if self.important_peers.contains(&who) { Level::Warn } else { Level::Debug },
+                "Peer with id `{}` is not a member of the permissioned network, reject it.",
+                who,
+            );
+            self.bad_handshake_substreams.insert((who, set_id));
+            self.behaviour.disconnect_peer(&who, set_id);
+            return CustomMessageOutcome::None;
+        }
         if status.info.genesis_hash() != self.chain_info.genesis_hash() {
             if self.boot_node_ids.contains(&who) {
                 error!(
@@ -516,6 +536,40 @@ impl Protocol {
 
     fn maintain_peers(&mut self) {}
 
+    /// Returns true if the peer is allowed to connect to us.
+    pub fn is_peer_allowed(&self, peer_id: &PeerId) -> bool {
+        self.allowed_peers.is_allowed(peer_id)
+    }
+
+    /// Returns the allow-list of the permissioned network, which is shared with the other
+    /// protocols.
+    pub fn allowed_peers(&self) -> &AllowedPeers {
+        &self.allowed_peers
+    }
+
+    /// Add or remove a member of the permissioned network.
+    /// A removed member is disconnected immediately and no longer dialed, the reputation of the
+    /// peer is kept as it is.
+    /// Return false if the network is permissionless.
+    pub fn set_peer_allowed(&mut self, peer_id: PeerId, allowed: bool) -> bool {
+        let changed = match self.allowed_peers.set_allowed(peer_id, allowed) {
+            Some(changed) => changed,
+            None => return false,
+        };
+        if changed {
+            for (set_id, _) in self.notif_protocols.iter().enumerate() {
+                let set_id = SetId::from(set_id);
+                if allowed {
+                    self.peerset_handle.add_to_peers_set(set_id, peer_id);
+                } else {
+                    self.peerset_handle.remove_from_peers_set(set_id, peer_id);
+                    self.behaviour.disconnect_peer(&peer_id, set_id);
+                }
+            }
+        }
+        true
+    }
+
     /// Returns the number of peers we're connected to.
     pub fn num_connected_peers(&self) -> usize {
         self.context_data.peers.values().count()
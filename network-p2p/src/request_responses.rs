@@ -33,6 +33,8 @@
 //! is used to handle incoming requests.
 //!
 
+use crate::allowed_peers::AllowedPeers;
+use crate::traffic::{Direction, TrafficAccounting, TrafficClass};
 use futures::{
     channel::{mpsc, oneshot},
     prelude::*,
@@ -62,6 +64,7 @@ use std::{
     convert::TryFrom as _,
     io, iter,
     pin::Pin,
+    sync::Arc,
     task::{Context, Poll},
     time::Duration,
 };
@@ -199,6 +202,12 @@ pub struct RequestResponsesBehaviour {
 
     /// Whenever an incoming request arrives, the arrival [`Instant`] is recorded here.
     pending_responses_arrival_time: HashMap<ProtocolRequestId, Instant>,
+
+    /// Traffic statistics and bandwidth limits of the requests and responses.
+    traffic: Option<Arc<TrafficAccounting>>,
+
+    /// The members of the permissioned network, requests from other peers are refused.
+    allowed_peers: AllowedPeers,
 }
 
 /// Generated by the response builder and waiting to be processed.
@@ -248,9 +257,24 @@ impl RequestResponsesBehaviour {
             pending_requests: Default::default(),
             pending_responses: Default::default(),
             pending_responses_arrival_time: Default::default(),
+            traffic: None,
+            allowed_peers: AllowedPeers::default(),
         })
     }
 
+    /// Record the traffic of the requests and responses, and enforce the download rate limit
+    /// on incoming requests.
+    pub fn with_traffic_accounting(mut self, traffic: Arc<TrafficAccounting>) -> Self {
+        self.traffic = Some(traffic);
+        self
+    }
+
+    /// Only serve the requests of the members of the permissioned network.
+    pub fn with_allowed_peers(mut self, allowed_peers: AllowedPeers) -> Self {
+        self.allowed_peers = allowed_peers;
+        self
+    }
+
     /// Initiates sending a request.
     ///
     /// If there is no established connection to the target peer, the behavior is determined by the choice of `connect`.
@@ -267,6 +291,9 @@ impl RequestResponsesBehaviour {
         if let Some((protocol, _)) = self.protocols.get_mut(protocol_name) {
             if protocol.is_connected(target) || connect.should_connect() {
                 let len = request.len();
+                if let Some(traffic) = self.traffic.as_ref() {
+                    traffic.record(Direction::Out, target, protocol_name, len);
+                }
                 let request_id = protocol.send_request(target, request);
                 let prev_req_id = self.pending_requests.insert(
                     (protocol_name.to_string().into(), request_id).into(),
@@ -502,6 +529,11 @@ impl NetworkBehaviour for RequestResponsesBehaviour {
                 };
 
                 if let Ok(payload) = result {
+                    if let Some(traffic) = self.traffic.as_ref() {
+                        // the response has been charged to the upload rate limit and delayed
+                        // when it was built.
+                        traffic.record(Direction::Out, &peer, &protocol_name, payload.len());
+                    }
                     if let Some((protocol, _)) = self.protocols.get_mut(&*protocol_name) {
                         if protocol.send_response(inner_channel, Ok(payload)).is_err() {
                             // Note: Failure is handled further below when receiving
@@ -590,9 +622,49 @@ impl NetworkBehaviour for RequestResponsesBehaviour {
 
                             let (tx, rx) = oneshot::channel();
 
+                            let allowed = self.allowed_peers.is_allowed(&peer);
+                            let accepted = allowed
+                                && match self.traffic.as_ref() {
+                                    Some(traffic) => {
+                                        if traffic.try_acquire(
+                                            Direction::In,
+                                            TrafficClass::RequestResponse,
+                                            request.len(),
+                                        ) {
+                                            traffic.record(
+                                                Direction::In,
+                                                &peer,
+                                                protocol,
+                                                request.len(),
+                                            );
+                                            true
+                                        } else {
+                                            traffic.record_dropped(Direction::In, &peer, protocol);
+                                            false
+                                        }
+                                    }
+                                    None => true,
+                                };
+
                             // Submit the request to the "response builder" passed by the user at
                             // initialization.
-                            if let Some(resp_builder) = resp_builder {
+                            if !allowed {
+                                // The peer is not a member of the permissioned network, drop `tx`
+                                // to refuse the request.
+                                log::debug!(
+                                    target: "sub-libp2p",
+                                    "Request {:?} on protocol {:?} from {} refused, the peer is not allowed",
+                                    request_id, protocol, peer,
+                                );
+                            } else if !accepted {
+                                // The request is dropped by the download rate limit, drop `tx`,
+                                // it is reported as a `InboundFailure::Omission` event.
+                                log::debug!(
+                                    target: "sub-libp2p",
+                                    "Request {:?} on protocol {:?} from {} dropped by download rate limit",
+                                    request_id, protocol, peer,
+                                );
+                            } else if let Some(resp_builder) = resp_builder {
                                 // If the response builder is too busy, silently drop `tx`. This
                                 // will be reported by the corresponding `RequestResponse` through
                                 // an `InboundFailure::Omission` event.
@@ -606,11 +678,26 @@ impl NetworkBehaviour for RequestResponsesBehaviour {
                             }
 
                             let protocol = protocol.clone();
+                            let traffic = self.traffic.clone();
                             self.pending_responses.push(Box::pin(async move {
                                 // The `tx` created above can be dropped if we are not capable of
                                 // processing this request, which is reflected as a
                                 // `InboundFailure::Omission` event.
                                 if let Ok(response) = rx.await {
+                                    // Hold the response back until the upload rate limit allows
+                                    // it to be sent.
+                                    if let (Some(traffic), Ok(payload)) =
+                                        (traffic.as_ref(), response.result.as_ref())
+                                    {
+                                        let delay = traffic.acquire(
+                                            Direction::Out,
+                                            TrafficClass::RequestResponse,
+                                            payload.len(),
+                                        );
+                                        if !delay.is_zero() {
+                                            futures_timer::Delay::new(delay).await;
+                                        }
+                                    }
                                     Some(RequestProcessingOutcome {
                                         peer,
                                         request_id,
@@ -645,6 +732,22 @@ impl NetworkBehaviour for RequestResponsesBehaviour {
                                 Some((started, pending_response)) => {
                                     let response_len =
                                         response.as_ref().map(|resp| resp.len()).unwrap_or(0);
+                                    if let Some(traffic) = self.traffic.as_ref() {
+                                        // the response has been received, charge it to the
+                                        // download rate limit, the next requests are delayed
+                                        // until the debt is repaid.
+                                        let _ = traffic.acquire(
+                                            Direction::In,
+                                            TrafficClass::RequestResponse,
+                                            response_len,
+                                        );
+                                        traffic.record(
+                                            Direction::In,
+                                            &peer,
+                                            protocol,
+                                            response_len,
+                                        );
+                                    }
                                     let delivered = pending_response
                                         .send(response.map_err(|()| RequestFailure::Refused))
                                         .map_err(|_| RequestFailure::Obsolete);
@@ -931,7 +1034,7 @@ mod tests {
     use libp2p::noise;
     use libp2p::swarm::{Swarm, SwarmEvent};
     use libp2p::Multiaddr;
-    use std::{iter, time::Duration};
+    use std::{collections::HashSet, iter, time::Duration};
 
     fn build_swarm(
         list: impl Iterator<Item = ProtocolConfig>,
@@ -1323,4 +1426,84 @@ mod tests {
             );
         });
     }
+
+    #[test]
+    fn request_from_not_allowed_peer_is_refused() {
+        let protocol_name = "/test/req-resp/1";
+        let mut pool = LocalPool::new();
+
+        let protocol_config = |inbound_queue| ProtocolConfig {
+            name: From::from(protocol_name),
+            max_request_size: 1024,
+            max_response_size: 1024 * 1024,
+            request_timeout: Duration::from_secs(30),
+            inbound_queue,
+        };
+        let (mut swarm_1, _) = build_swarm(iter::once(protocol_config(None)));
+
+        // `swarm_2` is a permissioned node which doesn't allow `swarm_1`, its handler must never
+        // see the request.
+        let (tx, mut rx) = mpsc::channel::<IncomingRequest>(64);
+        let (mut swarm_2, listen_addr_2) = build_swarm(iter::once(protocol_config(Some(tx))));
+        swarm_2.behaviour_mut().allowed_peers = AllowedPeers::new(Some(HashSet::new()));
+
+        pool.spawner()
+            .spawn_obj(
+                async move {
+                    if rx.next().await.is_some() {
+                        panic!("request from a not allowed peer is served");
+                    }
+                }
+                .boxed()
+                .into(),
+            )
+            .unwrap();
+
+        pool.spawner()
+            .spawn_obj(
+                async move {
+                    loop {
+                        match swarm_2.select_next_some().await {
+                            SwarmEvent::Behaviour(Event::InboundRequest { result, .. }) => {
+                                assert!(result.is_err());
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                .boxed()
+                .into(),
+            )
+            .unwrap();
+
+        Swarm::dial(&mut swarm_1, listen_addr_2).unwrap();
+
+        pool.run_until(async move {
+            let mut response_receiver = None;
+
+            loop {
+                match swarm_1.select_next_some().await {
+                    SwarmEvent::ConnectionEstablished { peer_id, .. } => {
+                        let (sender, receiver) = oneshot::channel();
+                        swarm_1.behaviour_mut().send_request(
+                            &peer_id,
+                            protocol_name,
+                            b"this is a request".to_vec(),
+                            sender,
+                            IfDisconnected::ImmediateError,
+                        );
+                        assert!(response_receiver.is_none());
+                        response_receiver = Some(receiver);
+                    }
+                    SwarmEvent::Behaviour(Event::RequestFinished { result, .. }) => {
+                        assert!(result.is_err());
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+
+            assert!(response_receiver.unwrap().await.unwrap().is_err());
+        });
+    }
 }
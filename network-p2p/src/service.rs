@@ -33,6 +33,7 @@ use std::sync::{
 use std::task::Poll;
 use std::{borrow::Cow, collections::HashSet, io, iter};
 
+use crate::allowed_peers::AllowedPeers;
 use crate::config::{Params, TransportConfig};
 use crate::discovery::DiscoveryConfig;
 use crate::errors::Error;
@@ -44,6 +45,7 @@ use crate::protocol::event::Event;
 use crate::protocol::generic_proto::{NotificationsSink, NotifsHandlerError, Ready};
 use crate::protocol::{Protocol, HARD_CORE_PROTOCOL_ID};
 use crate::request_responses::{InboundFailure, OutboundFailure, RequestFailure, ResponseFailure};
+use crate::traffic::{Direction, TrafficAccounting, TrafficClass};
 use crate::{
     behaviour::{Behaviour, BehaviourOut},
     errors, out_events, DhtEvent,
@@ -123,6 +125,8 @@ pub struct NetworkService {
     /// Field extracted from the [`Metrics`] struct and necessary to report the
     /// notifications-related metrics.
     notifications_sizes_metric: Option<HistogramVec>,
+    /// Traffic statistics and bandwidth limits, shared with the [`NetworkWorker`].
+    traffic: Arc<TrafficAccounting>,
 }
 
 impl NetworkWorker {
@@ -218,6 +222,14 @@ impl NetworkWorker {
             });
         }
         let peerset_config = sc_peerset::PeersetConfig { sets: sets_conf };
+        let allowed_peers =
+            AllowedPeers::new(params.network_config.allowed_peers.as_ref().map(|peers| {
+                let mut allowed_peers: HashSet<PeerId> = peers.iter().cloned().collect();
+                // reserved nodes and boot nodes are always the members of the permissioned network.
+                allowed_peers.extend(s.iter().cloned());
+                allowed_peers.extend(boot_node_ids.iter().cloned());
+                allowed_peers
+            }));
 
         let (protocol, peerset_handle) = Protocol::new(
             peerset_config,
@@ -230,8 +242,19 @@ impl NetworkWorker {
                 .iter()
                 .map(|config| config.name.clone())
                 .collect(),
+            allowed_peers,
         )?;
 
+        let metrics = params
+            .metrics_registry
+            .as_ref()
+            .and_then(|registry| Metrics::register(registry).ok());
+        let traffic = Arc::new(TrafficAccounting::new(
+            params.network_config.upload_rate_limit,
+            params.network_config.download_rate_limit,
+            metrics.as_ref(),
+        ));
+
         // Build the swarm.
         let (mut swarm, bandwidth): (Swarm<Behaviour>, _) = {
             let user_agent = format!(
@@ -270,6 +293,7 @@ impl NetworkWorker {
                 local_public,
                 discovery_config,
                 params.network_config.request_response_protocols,
+                traffic.clone(),
             ) {
                 Ok(behaviour) => behaviour,
                 Err(crate::request_responses::RegisterError::DuplicateProtocol(proto)) => {
@@ -327,10 +351,6 @@ impl NetworkWorker {
         let external_addresses = Arc::new(Mutex::new(Vec::new()));
         let peers_notifications_sinks = Arc::new(Mutex::new(HashMap::new()));
 
-        let metrics = params
-            .metrics_registry
-            .as_ref()
-            .and_then(|registry| Metrics::register(registry).ok());
         let service = Arc::new(NetworkService {
             bandwidth,
             external_addresses,
@@ -343,6 +363,7 @@ impl NetworkWorker {
             notifications_sizes_metric: metrics
                 .as_ref()
                 .map(|metrics| metrics.notifications_sizes.clone()),
+            traffic,
         });
 
         Ok(NetworkWorker {
@@ -475,6 +496,7 @@ impl NetworkWorker {
                 .behaviour_mut()
                 .user_protocol_mut()
                 .peerset_debug_info(),
+            traffic: self.service.traffic.traffic(),
         }
     }
 
@@ -551,7 +573,22 @@ impl NetworkService {
         // Used later for the metrics report.
         let message_len = message.len();
 
+        if !self
+            .traffic
+            .try_acquire(Direction::Out, TrafficClass::Notification, message_len)
+        {
+            log::debug!(
+                target: "sub-libp2p",
+                "Notification dropped by upload rate limit: {}, {:?}, {} bytes",
+                target, protocol_name, message_len,
+            );
+            self.traffic
+                .record_dropped(Direction::Out, &target, &protocol_name);
+            return;
+        }
         sink.send_sync_notification(message);
+        self.traffic
+            .record(Direction::Out, &target, &protocol_name, message_len);
 
         if let Some(notifications_sizes_metric) = self.notifications_sizes_metric.as_ref() {
             notifications_sizes_metric
@@ -646,6 +683,7 @@ impl NetworkService {
         Ok(NotificationSender {
             sink,
             protocol_name: protocol_name.clone(),
+            traffic: self.traffic.clone(),
             notification_size_metric: self
                 .notifications_sizes_metric
                 .as_ref()
@@ -659,6 +697,12 @@ impl NetworkService {
         data: Vec<u8>,
     ) -> Result<(), NotificationSenderError> {
         let sender = self.notification_sender(target, protocol_name)?;
+        let delay = self
+            .traffic
+            .acquire(Direction::Out, TrafficClass::Notification, data.len());
+        if !delay.is_zero() {
+            futures_timer::Delay::new(delay).await;
+        }
         let ready_sender = sender.ready().await?;
         ready_sender.send(data)
     }
@@ -772,6 +816,17 @@ impl NetworkService {
         request: Vec<u8>,
         connect: IfDisconnected,
     ) -> Result<Vec<u8>, RequestFailure> {
+        // Wait for the upload of the request, and the download debt of the previous responses.
+        let delay = self
+            .traffic
+            .acquire(Direction::Out, TrafficClass::RequestResponse, request.len())
+            .max(
+                self.traffic
+                    .acquire(Direction::In, TrafficClass::RequestResponse, 0),
+            );
+        if !delay.is_zero() {
+            futures_timer::Delay::new(delay).await;
+        }
         let (tx, rx) = oneshot::channel();
         let _ = self.to_worker.unbounded_send(ServiceToWorkerMsg::Request {
             target,
@@ -882,6 +937,32 @@ impl NetworkService {
             .to_worker
             .unbounded_send(ServiceToWorkerMsg::BanPeer(ban, peer_id));
     }
+
+    /// Add or remove a member of the permissioned network, a removed member is banned and
+    /// disconnected.
+    /// Return false if the network is permissionless.
+    pub async fn set_peer_allowed(&self, peer_id: PeerId, allowed: bool) -> bool {
+        let (tx, rx) = oneshot::channel();
+        let _ = self
+            .to_worker
+            .unbounded_send(ServiceToWorkerMsg::SetPeerAllowed(peer_id, allowed, tx));
+        rx.await.unwrap_or(false)
+    }
+
+    /// Returns the members of the permissioned network, `None` if the network is permissionless.
+    pub async fn allowed_peers(&self) -> Option<HashSet<PeerId>> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self
+            .to_worker
+            .unbounded_send(ServiceToWorkerMsg::AllowedPeers(tx));
+        match rx.await {
+            Ok(t) => t,
+            Err(e) => {
+                debug!("sth wrong {}", e);
+                None
+            }
+        }
+    }
 }
 
 /// Trait for providing information about the local network state
@@ -913,6 +994,9 @@ pub struct NotificationSender {
     /// Name of the protocol on the wire.
     protocol_name: Cow<'static, str>,
 
+    /// Traffic statistics of the [`NetworkService`].
+    traffic: Arc<TrafficAccounting>,
+
     /// Field extracted from the [`Metrics`] struct and necessary to report the
     /// notifications-related metrics.
     notification_size_metric: Option<Histogram>,
@@ -928,6 +1012,7 @@ impl NotificationSender {
             },
             peer_id: self.sink.peer_id(),
             protocol_name: &self.protocol_name,
+            traffic: &self.traffic,
             notification_size_metric: self.notification_size_metric.clone(),
         })
     }
@@ -944,6 +1029,9 @@ pub struct NotificationSenderReady<'a> {
     /// Name of the protocol on the wire.
     protocol_name: &'a Cow<'static, str>,
 
+    /// Traffic statistics of the [`NetworkService`].
+    traffic: &'a TrafficAccounting,
+
     /// Field extracted from the [`Metrics`] struct and necessary to report the
     /// notifications-related metrics.
     notification_size_metric: Option<Histogram>,
@@ -967,9 +1055,17 @@ impl<'a> NotificationSenderReady<'a> {
         );
         trace!(target: "sub-libp2p", "Handler({:?}) <= Async notification", self.peer_id);
 
+        let notification_len = notification.len();
         self.ready
             .send(notification)
-            .map_err(|()| NotificationSenderError::Closed)
+            .map_err(|()| NotificationSenderError::Closed)?;
+        self.traffic.record(
+            Direction::Out,
+            self.peer_id,
+            self.protocol_name,
+            notification_len,
+        );
+        Ok(())
     }
 }
 
@@ -1008,6 +1104,8 @@ enum ServiceToWorkerMsg {
     UpdateChainStatus(Box<ChainStatus>),
     AddressByPeerId(PeerId, oneshot::Sender<Vec<Multiaddr>>),
     BanPeer(bool, PeerId),
+    SetPeerAllowed(PeerId, bool, oneshot::Sender<bool>),
+    AllowedPeers(oneshot::Sender<Option<HashSet<PeerId>>>),
 }
 
 /// Main network worker. Must be polled in order for the network to advance.
@@ -1111,6 +1209,25 @@ impl Future for NetworkWorker {
                         this.network_service.unban_peer_id(peer_id)
                     }
                 }
+                ServiceToWorkerMsg::SetPeerAllowed(peer_id, allowed, tx) => {
+                    let permissioned = this
+                        .network_service
+                        .behaviour_mut()
+                        .set_peer_allowed(peer_id, allowed);
+                    if permissioned && !allowed {
+                        let _ = this.network_service.disconnect_peer_id(peer_id);
+                    }
+                    let _ = tx.send(permissioned);
+                }
+                ServiceToWorkerMsg::AllowedPeers(tx) => {
+                    let _ = tx.send(
+                        this.network_service
+                            .behaviour()
+                            .user_protocol()
+                            .allowed_peers()
+                            .members(),
+                    );
+                }
             }
         }
 
@@ -1336,6 +1453,30 @@ impl Future for NetworkWorker {
                     remote,
                     messages,
                 })) => {
+                    let traffic = &this.service.traffic;
+                    let messages: Vec<_> = messages
+                        .into_iter()
+                        .filter(|(protocol, message)| {
+                            if traffic.try_acquire(
+                                Direction::In,
+                                TrafficClass::Notification,
+                                message.len(),
+                            ) {
+                                traffic.record(Direction::In, &remote, protocol, message.len());
+                                true
+                            } else {
+                                debug!(
+                                    "[network-p2p] notification from {} {} dropped by download rate limit",
+                                    remote, protocol
+                                );
+                                traffic.record_dropped(Direction::In, &remote, protocol);
+                                false
+                            }
+                        })
+                        .collect();
+                    if messages.is_empty() {
+                        continue;
+                    }
                     if let Some(metrics) = this.metrics.as_ref() {
                         for (protocol, message) in &messages {
                             info!(
@@ -1374,6 +1515,14 @@ impl Future for NetworkWorker {
                     peer_id, endpoint, ..
                 }) => {
                     trace!(target: "sub-libp2p", "Libp2p => Connected({:?})", peer_id);
+                    if !this.network_service.behaviour().is_peer_allowed(&peer_id) {
+                        log::debug!(
+                            target: "sub-libp2p",
+                            "Libp2p => Disconnect({:?}), the peer is not a member of the permissioned network",
+                            peer_id,
+                        );
+                        let _ = this.network_service.disconnect_peer_id(peer_id);
+                    }
                     if let Some(metrics) = this.metrics.as_ref() {
                         match endpoint {
                             ConnectedPoint::Dialer { .. } => metrics
@@ -1422,6 +1571,9 @@ impl Future for NetworkWorker {
                             metrics.distinct_peers_connections_closed_total.inc();
                         }
                     }
+                    if num_established == 0 {
+                        this.service.traffic.remove_peer(&peer_id);
+                    }
                 }
                 Poll::Ready(SwarmEvent::NewListenAddr { address, .. }) => {
                     trace!(target: "sub-libp2p", "Libp2p => NewListenAddr({})", address)
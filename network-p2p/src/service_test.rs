@@ -457,6 +457,56 @@ async fn test_handshake_fail() {
     assert_eq!(state2.connected_peers.len(), 0);
 }
 
+#[stest::test]
+async fn test_permissioned_network() {
+    let protocol = ProtocolId::from("starcoin");
+    let mut config1 = generate_config(vec![], vec![PROTOCOL_NAME.into()], vec![]);
+    config1.allowed_peers = Some(vec![]);
+    let chain_info = ChainInfo::random();
+    let worker1 = NetworkWorker::new(Params::new(
+        config1.clone(),
+        protocol.clone(),
+        chain_info.clone(),
+        None,
+    ))
+    .unwrap();
+    let service1 = worker1.service().clone();
+
+    let _ = tokio::task::spawn(worker1);
+
+    let seed = config::MultiaddrWithPeerId {
+        multiaddr: config1.listen_addresses[0].clone(),
+        peer_id: service1.local_peer_id(),
+    };
+
+    let config2 = generate_config(vec![seed], vec![PROTOCOL_NAME.into()], vec![]);
+    let worker2 = NetworkWorker::new(Params::new(config2, protocol, chain_info, None)).unwrap();
+    let service2 = worker2.service().clone();
+
+    let _ = tokio::task::spawn(worker2);
+    tokio::time::sleep(Duration::from_secs(1)).await;
+
+    // peer2 is not a member of the permissioned network, so the connection is rejected.
+    assert!(!service1.is_connected(service2.local_peer_id()).await);
+    assert!(!service2.is_connected(service1.local_peer_id()).await);
+
+    assert!(
+        service1
+            .set_peer_allowed(service2.local_peer_id(), true)
+            .await
+    );
+    let allowed_peers = service1.allowed_peers().await.unwrap();
+    assert!(allowed_peers.contains(&service2.local_peer_id()));
+
+    // peer2 is permissionless.
+    assert!(
+        !service2
+            .set_peer_allowed(service1.local_peer_id(), true)
+            .await
+    );
+    assert!(service2.allowed_peers().await.is_none());
+}
+
 fn generate_config(
     boot_nodes: Vec<MultiaddrWithPeerId>,
     notif_protocols: Vec<Cow<'static, str>>,
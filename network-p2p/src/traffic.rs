@@ -0,0 +1,307 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Traffic accounting and bandwidth limits of the notification and request-response protocols.
+
+use crate::metrics::Metrics;
+use crate::network_state::{NetworkTraffic, TrafficStats};
+use libp2p::PeerId;
+use parking_lot::Mutex;
+use starcoin_metrics::UIntCounterVec;
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    In,
+    Out,
+}
+
+impl Direction {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Direction::In => "in",
+            Direction::Out => "out",
+        }
+    }
+}
+
+/// The notification and the request-response protocols are limited by separate buckets, so the
+/// bulk sync requests can not starve the notifications of new blocks and transactions.
+/// The rate limit of a direction is split between the buckets, so the traffic of all the classes
+/// together never exceeds it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TrafficClass {
+    Notification,
+    RequestResponse,
+}
+
+/// A token bucket which limits the bytes per second, the burst is one second of traffic.
+struct RateLimiter {
+    rate: f64,
+    available: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    fn new(rate: u64) -> Self {
+        Self {
+            rate: rate as f64,
+            available: rate as f64,
+            last_refill: Instant::now(),
+        }
+    }
+
+    fn refill(&mut self) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.last_refill = now;
+        self.available = (self.available + elapsed * self.rate).min(self.rate);
+    }
+
+    /// Take `len` bytes if they are available.
+    /// A message bigger than the burst is allowed when the bucket is full.
+    fn try_acquire(&mut self, len: usize) -> bool {
+        self.refill();
+        let len = len as f64;
+        if self.available >= len || self.available >= self.rate {
+            self.available -= len;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Take `len` bytes even if they are not available,
+    /// return how long to wait until the debt is repaid.
+    fn acquire(&mut self, len: usize) -> Duration {
+        self.refill();
+        self.available -= len as f64;
+        if self.available >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.available / self.rate)
+        }
+    }
+}
+
+/// The rate limiters of a direction, one per traffic class, each class gets half of the rate.
+struct Limiters {
+    notification: RateLimiter,
+    request_response: RateLimiter,
+}
+
+impl Limiters {
+    fn new(rate: u64) -> Self {
+        let notification_rate = (rate / 2).max(1);
+        Self {
+            notification: RateLimiter::new(notification_rate),
+            request_response: RateLimiter::new(rate.saturating_sub(notification_rate).max(1)),
+        }
+    }
+
+    fn get_mut(&mut self, class: TrafficClass) -> &mut RateLimiter {
+        match class {
+            TrafficClass::Notification => &mut self.notification,
+            TrafficClass::RequestResponse => &mut self.request_response,
+        }
+    }
+}
+
+struct Inner {
+    traffic: NetworkTraffic,
+    upload_limiters: Option<Limiters>,
+    download_limiters: Option<Limiters>,
+}
+
+impl Inner {
+    fn limiter(&mut self, direction: Direction, class: TrafficClass) -> Option<&mut RateLimiter> {
+        let limiters = match direction {
+            Direction::In => self.download_limiters.as_mut(),
+            Direction::Out => self.upload_limiters.as_mut(),
+        };
+        limiters.map(|limiters| limiters.get_mut(class))
+    }
+
+    fn stats_mut(&mut self, peer: &PeerId, protocol: &str) -> [&mut TrafficStats; 3] {
+        let Inner { traffic, .. } = self;
+        [
+            &mut traffic.total,
+            traffic.protocols.entry(protocol.to_string()).or_default(),
+            traffic.peers.entry(peer.to_base58()).or_default(),
+        ]
+    }
+}
+
+/// Records the per-protocol and per-peer traffic, and enforces the upload and download rate limit.
+pub struct TrafficAccounting {
+    inner: Mutex<Inner>,
+    bytes_metric: Option<UIntCounterVec>,
+    dropped_metric: Option<UIntCounterVec>,
+}
+
+impl TrafficAccounting {
+    /// Create a new `TrafficAccounting`, the rate limits are in bytes per second and are shared by
+    /// the traffic classes, `None` or zero means unlimited.
+    pub fn new(
+        upload_rate_limit: Option<u64>,
+        download_rate_limit: Option<u64>,
+        metrics: Option<&Metrics>,
+    ) -> Self {
+        Self {
+            inner: Mutex::new(Inner {
+                traffic: NetworkTraffic::default(),
+                upload_limiters: upload_rate_limit
+                    .filter(|rate| *rate > 0)
+                    .map(Limiters::new),
+                download_limiters: download_rate_limit
+                    .filter(|rate| *rate > 0)
+                    .map(Limiters::new),
+            }),
+            bytes_metric: metrics.map(|metrics| metrics.traffic_bytes_total.clone()),
+            dropped_metric: metrics.map(|metrics| metrics.traffic_dropped_total.clone()),
+        }
+    }
+
+    /// Check the message against the rate limit, return false if the message should be dropped.
+    pub fn try_acquire(&self, direction: Direction, class: TrafficClass, len: usize) -> bool {
+        self.inner
+            .lock()
+            .limiter(direction, class)
+            .map(|limiter| limiter.try_acquire(len))
+            .unwrap_or(true)
+    }
+
+    /// Charge the message to the rate limit, return how long the sender should wait.
+    pub fn acquire(&self, direction: Direction, class: TrafficClass, len: usize) -> Duration {
+        self.inner
+            .lock()
+            .limiter(direction, class)
+            .map(|limiter| limiter.acquire(len))
+            .unwrap_or(Duration::ZERO)
+    }
+
+    /// Record a message received from or sent to the peer.
+    pub fn record(&self, direction: Direction, peer: &PeerId, protocol: &str, len: usize) {
+        {
+            let mut inner = self.inner.lock();
+            for stats in inner.stats_mut(peer, protocol) {
+                match direction {
+                    Direction::In => {
+                        stats.bytes_in = stats.bytes_in.saturating_add(len as u64);
+                        stats.messages_in = stats.messages_in.saturating_add(1);
+                    }
+                    Direction::Out => {
+                        stats.bytes_out = stats.bytes_out.saturating_add(len as u64);
+                        stats.messages_out = stats.messages_out.saturating_add(1);
+                    }
+                }
+            }
+        }
+        if let Some(metric) = self.bytes_metric.as_ref() {
+            metric
+                .with_label_values(&[direction.as_str(), protocol])
+                .inc_by(len as u64);
+        }
+    }
+
+    /// Record a message dropped by the rate limit.
+    pub fn record_dropped(&self, direction: Direction, peer: &PeerId, protocol: &str) {
+        {
+            let mut inner = self.inner.lock();
+            for stats in inner.stats_mut(peer, protocol) {
+                match direction {
+                    Direction::In => stats.dropped_in = stats.dropped_in.saturating_add(1),
+                    Direction::Out => stats.dropped_out = stats.dropped_out.saturating_add(1),
+                }
+            }
+        }
+        if let Some(metric) = self.dropped_metric.as_ref() {
+            metric
+                .with_label_values(&[direction.as_str(), protocol])
+                .inc();
+        }
+    }
+
+    /// Forget the statistics of a disconnected peer.
+    pub fn remove_peer(&self, peer: &PeerId) {
+        self.inner.lock().traffic.peers.remove(&peer.to_base58());
+    }
+
+    pub fn traffic(&self) -> NetworkTraffic {
+        self.inner.lock().traffic.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_traffic_record() {
+        let traffic = TrafficAccounting::new(None, None, None);
+        let peer1 = PeerId::random();
+        let peer2 = PeerId::random();
+        traffic.record(Direction::Out, &peer1, "/starcoin/txn/1", 100);
+        traffic.record(Direction::In, &peer2, "/starcoin/txn/1", 10);
+        traffic.record(Direction::In, &peer2, "/starcoin/block/1", 20);
+        traffic.record_dropped(Direction::Out, &peer1, "/starcoin/block/1");
+
+        let report = traffic.traffic();
+        assert_eq!(report.total.bytes_out, 100);
+        assert_eq!(report.total.bytes_in, 30);
+        assert_eq!(report.total.messages_in, 2);
+        assert_eq!(report.total.dropped_out, 1);
+        assert_eq!(report.protocols["/starcoin/txn/1"].bytes_in, 10);
+        assert_eq!(report.protocols["/starcoin/block/1"].bytes_in, 20);
+        assert_eq!(report.peers[&peer2.to_base58()].bytes_in, 30);
+
+        traffic.remove_peer(&peer2);
+        assert!(!traffic.traffic().peers.contains_key(&peer2.to_base58()));
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let traffic = TrafficAccounting::new(Some(1000), None, None);
+        let class = TrafficClass::Notification;
+        assert!(traffic.try_acquire(Direction::In, class, 1_000_000));
+        assert!(traffic.try_acquire(Direction::Out, class, 800));
+        assert!(!traffic.try_acquire(Direction::Out, class, 800));
+        assert!(traffic.acquire(Direction::Out, class, 1000) > Duration::ZERO);
+        assert_eq!(traffic.acquire(Direction::In, class, 1000), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_sync_traffic_does_not_starve_notifications() {
+        let traffic = TrafficAccounting::new(Some(1000), Some(1000), None);
+        // The bulk sync responses put the request-response buckets into debt.
+        for _ in 0..10 {
+            let _ = traffic.acquire(Direction::Out, TrafficClass::RequestResponse, 1000);
+            let _ = traffic.acquire(Direction::In, TrafficClass::RequestResponse, 1000);
+        }
+        assert!(traffic.acquire(Direction::Out, TrafficClass::RequestResponse, 0) > Duration::ZERO);
+        assert!(!traffic.try_acquire(Direction::In, TrafficClass::RequestResponse, 100));
+
+        // The notifications are still sent and received.
+        for _ in 0..5 {
+            assert!(traffic.try_acquire(Direction::Out, TrafficClass::Notification, 100));
+            assert!(traffic.try_acquire(Direction::In, TrafficClass::Notification, 100));
+        }
+    }
+
+    #[test]
+    fn test_rate_limit_shared_by_classes() {
+        let traffic = TrafficAccounting::new(Some(1000), None, None);
+        // Each class takes its whole bucket, the two classes together stay within the limit.
+        let mut sent = 0;
+        for class in [TrafficClass::Notification, TrafficClass::RequestResponse] {
+            while traffic.try_acquire(Direction::Out, class, 100) {
+                sent += 100;
+            }
+        }
+        assert!(sent <= 1000, "sent {} bytes", sent);
+        assert!(traffic.acquire(Direction::Out, TrafficClass::Notification, 500) > Duration::ZERO);
+        assert!(
+            traffic.acquire(Direction::Out, TrafficClass::RequestResponse, 500) > Duration::ZERO
+        );
+    }
+}
@@ -48,6 +48,39 @@ pub struct NetworkState {
     pub not_connected_peers: HashMap<String, NotConnectedPeer>,
     /// State of the peerset manager.
     pub peerset: serde_json::Value,
+    /// Traffic statistics of the notification and request-response protocols.
+    #[serde(default)]
+    pub traffic: NetworkTraffic,
+}
+
+/// Part of the `NetworkState` struct. Unstable.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TrafficStats {
+    /// Total bytes received.
+    pub bytes_in: u64,
+    /// Total bytes sent.
+    pub bytes_out: u64,
+    /// Count of messages received.
+    pub messages_in: u64,
+    /// Count of messages sent.
+    pub messages_out: u64,
+    /// Count of received messages dropped by the download rate limit.
+    pub dropped_in: u64,
+    /// Count of messages dropped by the upload rate limit.
+    pub dropped_out: u64,
+}
+
+/// Part of the `NetworkState` struct. Unstable.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkTraffic {
+    /// Traffic of all protocols and peers.
+    pub total: TrafficStats,
+    /// Traffic by protocol name.
+    pub protocols: HashMap<String, TrafficStats>,
+    /// Traffic by connected peer.
+    pub peers: HashMap<String, TrafficStats>,
 }
 
 /// Part of the `NetworkState` struct. Unstable.
@@ -17,6 +17,7 @@ use starcoin_types::access_path::AccessPath;
 use starcoin_types::account_address::AccountAddress;
 use starcoin_types::account_state::AccountState;
 use starcoin_types::block::{Block, BlockHeader, BlockInfo, BlockNumber};
+use starcoin_types::compact_block::CompactBlock;
 use starcoin_types::transaction::{SignedUserTransaction, Transaction, TransactionInfo};
 
 mod remote_chain_state;
@@ -152,6 +153,26 @@ impl RpcRequest for GetTxnsWithHash {
     }
 }
 
+/// Get the txns of a block by the index in the block body, used to fill the compact block.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetBlockTxns {
+    pub block_id: HashValue,
+    pub indexes: Vec<u64>,
+}
+
+impl RpcRequest for GetBlockTxns {
+    fn verify(&self) -> Result<()> {
+        if self.indexes.len() as u64 > MAX_TXN_REQUEST_SIZE {
+            return Err(NetRpcError::new(
+                RpcErrorCode::BadRequest,
+                format!("max_size is too big > {}", MAX_TXN_REQUEST_SIZE),
+            )
+            .into());
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct GetStateWithProof {
     pub state_root: HashValue,
@@ -285,6 +306,20 @@ pub trait NetworkRpc: Sized + Send + Sync + 'static {
         peer_id: PeerId,
         request: GetStateWithTableItemProof,
     ) -> BoxFuture<Result<StateWithTableItemProof>>;
+
+    /// Get the compact block and block info, return None if the block is not found.
+    fn get_compact_block(
+        &self,
+        peer_id: PeerId,
+        block_id: HashValue,
+    ) -> BoxFuture<Result<Option<(CompactBlock, BlockInfo)>>>;
+
+    /// Get the txns of the block by index, return None if the block is not found.
+    fn get_block_txns(
+        &self,
+        peer_id: PeerId,
+        req: GetBlockTxns,
+    ) -> BoxFuture<Result<Option<Vec<SignedUserTransaction>>>>;
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -11,8 +11,8 @@ use starcoin_chain_service::{ChainAsyncService, ChainReaderService};
 use starcoin_crypto::HashValue;
 use starcoin_network_rpc_api::{
     gen_server, BlockBody, GetAccountState, GetAccumulatorNodeByNodeHash, GetBlockHeadersByNumber,
-    GetBlockIds, GetStateWithProof, GetStateWithTableItemProof, GetTxnsWithHash, GetTxnsWithSize,
-    Ping, RpcRequest, MAX_BLOCK_HEADER_REQUEST_SIZE, MAX_BLOCK_INFO_REQUEST_SIZE,
+    GetBlockIds, GetBlockTxns, GetStateWithProof, GetStateWithTableItemProof, GetTxnsWithHash,
+    GetTxnsWithSize, Ping, RpcRequest, MAX_BLOCK_HEADER_REQUEST_SIZE, MAX_BLOCK_INFO_REQUEST_SIZE,
     MAX_BLOCK_REQUEST_SIZE, MAX_TXN_REQUEST_SIZE,
 };
 use starcoin_service_registry::ServiceRef;
@@ -23,6 +23,7 @@ use starcoin_storage::Store;
 use starcoin_txpool::TxPoolService;
 use starcoin_txpool_api::TxPoolSyncService;
 use starcoin_types::block::Block;
+use starcoin_types::compact_block::CompactBlock;
 use starcoin_types::{
     account_state::AccountState,
     block::{BlockHeader, BlockInfo, BlockNumber},
@@ -306,4 +307,52 @@ impl gen_server::NetworkRpc for NetworkRpcImpl {
         };
         Box::pin(fut)
     }
+
+    fn get_compact_block(
+        &self,
+        _peer_id: PeerId,
+        block_id: HashValue,
+    ) -> BoxFuture<Result<Option<(CompactBlock, BlockInfo)>>> {
+        let storage = self.storage.clone();
+        let fut = async move {
+            let block = match storage.get_block_by_hash(block_id)? {
+                Some(block) => block,
+                None => return Ok(None),
+            };
+            Ok(storage
+                .get_block_info(block_id)?
+                .map(|block_info| (CompactBlock::new(block), block_info)))
+        };
+        Box::pin(fut)
+    }
+
+    fn get_block_txns(
+        &self,
+        _peer_id: PeerId,
+        req: GetBlockTxns,
+    ) -> BoxFuture<Result<Option<Vec<SignedUserTransaction>>>> {
+        let storage = self.storage.clone();
+        let fut = async move {
+            req.verify()?;
+            let block = match storage.get_block_by_hash(req.block_id)? {
+                Some(block) => block,
+                None => return Ok(None),
+            };
+            let txns = block.transactions();
+            let mut result = Vec::with_capacity(req.indexes.len());
+            for index in req.indexes {
+                let txn = txns.get(index as usize).ok_or_else(|| {
+                    NetRpcError::client_err(format!(
+                        "txn index {} out of range, block {} has {} txns",
+                        index,
+                        req.block_id,
+                        txns.len()
+                    ))
+                })?;
+                result.push(txn.clone());
+            }
+            Ok(Some(result))
+        };
+        Box::pin(fut)
+    }
 }
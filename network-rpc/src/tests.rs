@@ -4,10 +4,11 @@
 use anyhow::Result;
 use futures::executor::block_on;
 use starcoin_config::*;
+use starcoin_crypto::HashValue;
 use starcoin_logger::prelude::*;
 use starcoin_network_rpc_api::{
-    gen_client as starcoin_gen_client, GetBlockHeadersByNumber, GetBlockIds, GetStateWithProof,
-    Ping,
+    gen_client as starcoin_gen_client, GetBlockHeadersByNumber, GetBlockIds, GetBlockTxns,
+    GetStateWithProof, Ping,
 };
 use starcoin_node::NodeHandle;
 use starcoin_state_api::StateWithProof;
@@ -103,12 +104,43 @@ fn test_network_rpc() {
 
     let blocks = block_on(async {
         client
-            .get_blocks(peer_id_2.clone(), block_ids)
+            .get_blocks(peer_id_2.clone(), block_ids.clone())
             .await
             .unwrap()
     });
     assert_eq!(2, blocks.len());
 
+    let (compact_block, block_info) = block_on(async {
+        client
+            .get_compact_block(peer_id_2.clone(), block_ids[1])
+            .await
+            .unwrap()
+    })
+    .unwrap();
+    assert_eq!(compact_block.header.id(), block_ids[1]);
+    assert_eq!(block_info.block_id, block_ids[1]);
+    let txns = block_on(async {
+        client
+            .get_block_txns(
+                peer_id_2.clone(),
+                GetBlockTxns {
+                    block_id: block_ids[1],
+                    indexes: (0..compact_block.txn_len() as u64).collect(),
+                },
+            )
+            .await
+            .unwrap()
+    })
+    .unwrap();
+    assert_eq!(txns.len(), compact_block.txn_len());
+    let not_exist = block_on(async {
+        client
+            .get_compact_block(peer_id_2.clone(), HashValue::random())
+            .await
+            .unwrap()
+    });
+    assert!(not_exist.is_none());
+
     handle2.stop().unwrap();
     handle1.stop().unwrap();
 }
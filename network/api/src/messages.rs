@@ -64,8 +64,15 @@ impl Sample for CompactBlockMessage {
     }
 }
 
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum AnnouncementType {
     Txn,
+    /// Announce the new block ids to the low bandwidth peers, the peer fetch the compact block by rpc.
+    Block,
+    /// Ask the peer to push the new compact blocks directly, the ids contains the block which trigger the request.
+    CompactBlockHighBandwidth,
+    /// Ask the peer to only announce the new block ids, the ids contains the block which trigger the request.
+    CompactBlockLowBandwidth,
 }
 
 #[allow(clippy::from_over_into)]
@@ -73,6 +80,9 @@ impl Into<u8> for AnnouncementType {
     fn into(self) -> u8 {
         match self {
             AnnouncementType::Txn => 1,
+            AnnouncementType::Block => 2,
+            AnnouncementType::CompactBlockHighBandwidth => 3,
+            AnnouncementType::CompactBlockLowBandwidth => 4,
         }
     }
 }
@@ -81,10 +91,13 @@ impl TryFrom<u8> for AnnouncementType {
     type Error = anyhow::Error;
 
     fn try_from(value: u8) -> Result<Self> {
-        if value == 1 {
-            return Ok(AnnouncementType::Txn);
-        }
-        Err(format_err!("Wrong announcement type : {:?}", value))
+        Ok(match value {
+            1 => AnnouncementType::Txn,
+            2 => AnnouncementType::Block,
+            3 => AnnouncementType::CompactBlockHighBandwidth,
+            4 => AnnouncementType::CompactBlockLowBandwidth,
+            _ => return Err(format_err!("Wrong announcement type : {:?}", value)),
+        })
     }
 }
 
@@ -104,6 +117,10 @@ impl Announcement {
         }
     }
 
+    pub fn announcement_type(&self) -> Result<AnnouncementType> {
+        self.announcement_type.try_into()
+    }
+
     pub fn is_txn(&self) -> bool {
         if let Ok(AnnouncementType::Txn) = self.announcement_type.try_into() {
             return true;
@@ -111,6 +128,13 @@ impl Announcement {
         false
     }
 
+    pub fn is_block(&self) -> bool {
+        if let Ok(AnnouncementType::Block) = self.announcement_type.try_into() {
+            return true;
+        }
+        false
+    }
+
     pub fn ids(self) -> Vec<HashValue> {
         self.ids
     }
@@ -0,0 +1,146 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Fault injection of the received notification messages, used by the network simulator.
+
+use network_api::PeerId;
+use parking_lot::Mutex;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LinkFault {
+    /// The delay before handle the message.
+    pub latency: Duration,
+    /// The probability of dropping the message, in range [0, 1].
+    pub drop_rate: f64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum FaultAction {
+    Deliver,
+    Delay(Duration),
+    Drop,
+}
+
+struct Inner {
+    default_fault: LinkFault,
+    peer_faults: HashMap<PeerId, LinkFault>,
+    rng: StdRng,
+}
+
+/// The network faults of a node, shared by the `NetworkActorService`.
+/// All messages are delivered immediately by default.
+#[derive(Clone)]
+pub struct NetworkFaults {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl Default for NetworkFaults {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl NetworkFaults {
+    /// Create a `NetworkFaults`, the message drops are deterministic with the same seed.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Inner {
+                default_fault: LinkFault::default(),
+                peer_faults: HashMap::new(),
+                rng: StdRng::seed_from_u64(seed),
+            })),
+        }
+    }
+
+    /// Reset the random seed of message drops.
+    pub fn reseed(&self, seed: u64) {
+        self.inner.lock().rng = StdRng::seed_from_u64(seed);
+    }
+
+    /// Set the fault of the messages received from all peers which have no peer fault.
+    pub fn set_default_fault(&self, fault: LinkFault) {
+        self.inner.lock().default_fault = fault;
+    }
+
+    /// Set the fault of the messages received from the `peer_id`.
+    pub fn set_peer_fault(&self, peer_id: PeerId, fault: LinkFault) {
+        self.inner.lock().peer_faults.insert(peer_id, fault);
+    }
+
+    /// Remove all faults.
+    pub fn clear(&self) {
+        let mut inner = self.inner.lock();
+        inner.default_fault = LinkFault::default();
+        inner.peer_faults.clear();
+    }
+
+    pub(crate) fn inspect(&self, peer_id: &PeerId) -> FaultAction {
+        let mut inner = self.inner.lock();
+        let fault = inner
+            .peer_faults
+            .get(peer_id)
+            .copied()
+            .unwrap_or(inner.default_fault);
+        if fault.drop_rate > 0.0 && inner.rng.gen_bool(fault.drop_rate.min(1.0)) {
+            FaultAction::Drop
+        } else if fault.latency > Duration::ZERO {
+            FaultAction::Delay(fault.latency)
+        } else {
+            FaultAction::Deliver
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_network_faults() {
+        let faults = NetworkFaults::new(1);
+        let peer1 = PeerId::random();
+        let peer2 = PeerId::random();
+        assert_eq!(faults.inspect(&peer1), FaultAction::Deliver);
+
+        faults.set_peer_fault(
+            peer1.clone(),
+            LinkFault {
+                latency: Duration::from_millis(100),
+                drop_rate: 0.0,
+            },
+        );
+        faults.set_default_fault(LinkFault {
+            latency: Duration::ZERO,
+            drop_rate: 1.0,
+        });
+        assert_eq!(
+            faults.inspect(&peer1),
+            FaultAction::Delay(Duration::from_millis(100))
+        );
+        assert_eq!(faults.inspect(&peer2), FaultAction::Drop);
+
+        faults.clear();
+        assert_eq!(faults.inspect(&peer2), FaultAction::Deliver);
+    }
+
+    #[test]
+    fn test_network_faults_deterministic() {
+        let peer = PeerId::random();
+        let fault = LinkFault {
+            latency: Duration::ZERO,
+            drop_rate: 0.5,
+        };
+        let faults1 = NetworkFaults::new(7);
+        let faults2 = NetworkFaults::new(7);
+        faults1.set_default_fault(fault);
+        faults2.set_default_fault(fault);
+        let actions1 = (0..100).map(|_| faults1.inspect(&peer)).collect::<Vec<_>>();
+        let actions2 = (0..100).map(|_| faults2.inspect(&peer)).collect::<Vec<_>>();
+        assert_eq!(actions1, actions2);
+    }
+}
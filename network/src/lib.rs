@@ -3,6 +3,8 @@
 
 #![deny(clippy::integer_arithmetic)]
 
+#[cfg(feature = "fault-injection")]
+pub mod fault;
 pub mod helper;
 mod network_metrics;
 mod service;
@@ -11,6 +13,8 @@ pub mod worker;
 
 pub use network_api::messages::*;
 
+#[cfg(feature = "fault-injection")]
+pub use fault::{LinkFault, NetworkFaults};
 pub use helper::{get_unix_ts, get_unix_ts_as_millis};
 pub use service::NetworkActorService;
 pub use service_ref::NetworkServiceRef;
@@ -1,6 +1,8 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+#[cfg(feature = "fault-injection")]
+use crate::fault::{FaultAction, NetworkFaults};
 use crate::network_metrics::NetworkMetrics;
 use crate::{build_network_worker, Announcement};
 use anyhow::{format_err, Result};
@@ -20,7 +22,7 @@ use network_api::{
 };
 use network_p2p::{Event, NetworkWorker};
 use rand::prelude::SliceRandom;
-use starcoin_config::NodeConfig;
+use starcoin_config::{NodeConfig, TxnPropagateMode};
 use starcoin_crypto::HashValue;
 use starcoin_logger::prelude::*;
 use starcoin_network_rpc::NetworkRpcService;
@@ -43,10 +45,21 @@ const BARNARD_HARD_FORK_VERSION: [i32; 3] = [1, 12, 9];
 pub struct NetworkActorService {
     worker: Option<NetworkWorker>,
     inner: Inner,
+    #[cfg(feature = "fault-injection")]
+    faults: NetworkFaults,
 
     network_worker_handle: Option<AbortHandle>,
 }
 
+/// A notification message delayed by the network faults.
+#[cfg(feature = "fault-injection")]
+#[derive(Debug)]
+struct DelayedNotification {
+    remote: PeerId,
+    protocol: Cow<'static, str>,
+    message: Bytes,
+}
+
 impl NetworkActor for NetworkActorService {}
 
 impl NetworkActorService {
@@ -72,6 +85,8 @@ impl NetworkActorService {
         Ok(Self {
             worker: Some(worker),
             inner,
+            #[cfg(feature = "fault-injection")]
+            faults: NetworkFaults::default(),
             network_worker_handle: None,
         })
     }
@@ -79,12 +94,54 @@ impl NetworkActorService {
     pub fn network_service(&self) -> Arc<network_p2p::NetworkService> {
         self.inner.network_service.clone()
     }
+
+    #[cfg(feature = "fault-injection")]
+    pub fn faults(&self) -> NetworkFaults {
+        self.faults.clone()
+    }
+
+    /// Apply the network faults to a received message, return the message if it should be
+    /// handled now, it is dropped or handled later otherwise.
+    #[cfg(feature = "fault-injection")]
+    fn inject_faults(
+        &self,
+        ctx: &mut ServiceContext<Self>,
+        remote: PeerId,
+        protocol: Cow<'static, str>,
+        message: Bytes,
+    ) -> Option<(Cow<'static, str>, Bytes)> {
+        match self.faults.inspect(&remote) {
+            FaultAction::Deliver => Some((protocol, message)),
+            FaultAction::Drop => {
+                debug!(
+                    "[network] drop message from {}, protocol: {} by network faults.",
+                    remote, protocol
+                );
+                None
+            }
+            FaultAction::Delay(latency) => {
+                let mut delayed = Some(DelayedNotification {
+                    remote,
+                    protocol,
+                    message,
+                });
+                ctx.run_later(latency, move |ctx| {
+                    if let Some(delayed) = delayed.take() {
+                        ctx.notify(delayed);
+                    }
+                });
+                None
+            }
+        }
+    }
 }
 
 impl ActorService for NetworkActorService {
     fn started(&mut self, ctx: &mut ServiceContext<Self>) -> Result<()> {
         ctx.subscribe::<SyncStatusChangeEvent>();
         ctx.subscribe::<PropagateTransactions>();
+        #[cfg(feature = "fault-injection")]
+        ctx.put_shared(self.faults.clone())?;
         let worker = self
             .worker
             .take()
@@ -215,6 +272,12 @@ impl EventHandler<Self, Event> for NetworkActorService {
             }
             Event::NotificationsReceived { remote, messages } => {
                 for (protocol, message) in messages {
+                    #[cfg(feature = "fault-injection")]
+                    let (protocol, message) =
+                        match self.inject_faults(ctx, remote.into(), protocol, message) {
+                            Some(message) => message,
+                            None => continue,
+                        };
                     if let Err(e) =
                         self.inner
                             .handle_network_message(remote.into(), protocol.clone(), message)
@@ -230,6 +293,30 @@ impl EventHandler<Self, Event> for NetworkActorService {
     }
 }
 
+#[cfg(feature = "fault-injection")]
+impl EventHandler<Self, DelayedNotification> for NetworkActorService {
+    fn handle_event(
+        &mut self,
+        msg: DelayedNotification,
+        _ctx: &mut ServiceContext<NetworkActorService>,
+    ) {
+        let DelayedNotification {
+            remote,
+            protocol,
+            message,
+        } = msg;
+        if let Err(e) = self
+            .inner
+            .handle_network_message(remote.clone(), protocol.clone(), message)
+        {
+            error!(
+                "Handle network message fail, remote:{}, protocol:{}, error: {:?}",
+                remote, protocol, e
+            )
+        }
+    }
+}
+
 impl EventHandler<Self, ReportReputation> for NetworkActorService {
     fn handle_event(
         &mut self,
@@ -404,12 +491,24 @@ impl ServiceHandler<Self, GetSelfPeer> for NetworkActorService {
 // (100 +1 ) * ( LRU_CACHE_SIZE * 32) *2 = 64M
 const LRU_CACHE_SIZE: usize = 10240;
 
+/// How the new compact blocks are relayed to a peer, requested by the peer.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum CompactBlockRelayMode {
+    /// The peer did not request a mode, push the compact block to random selected peers.
+    Unspecified,
+    /// Always push the compact block.
+    HighBandwidth,
+    /// Only announce the block id, the peer fetch the compact block by rpc.
+    LowBandwidth,
+}
+
 #[derive(Debug)]
 pub struct Peer {
     peer_info: PeerInfo,
     known_transactions: LruCache<HashValue, ()>,
     /// Holds a set of blocks known to this peer.
     known_blocks: LruCache<HashValue, ()>,
+    compact_block_relay_mode: CompactBlockRelayMode,
 }
 
 impl Peer {
@@ -418,6 +517,7 @@ impl Peer {
             peer_info,
             known_blocks: LruCache::new(LRU_CACHE_SIZE),
             known_transactions: LruCache::new(LRU_CACHE_SIZE),
+            compact_block_relay_mode: CompactBlockRelayMode::Unspecified,
         }
     }
 
@@ -549,13 +649,43 @@ impl Inner {
                 }
                 NotificationMessage::Announcement(announcement) => {
                     debug!("announcement ids length: {:?}", announcement.ids.len());
-                    if announcement.is_txn() {
+                    match announcement.announcement_type() {
+                        Ok(AnnouncementType::CompactBlockHighBandwidth) => {
+                            peer_info.compact_block_relay_mode =
+                                CompactBlockRelayMode::HighBandwidth;
+                        }
+                        Ok(AnnouncementType::CompactBlockLowBandwidth) => {
+                            peer_info.compact_block_relay_mode =
+                                CompactBlockRelayMode::LowBandwidth;
+                        }
+                        _ => {}
+                    }
+                    if announcement.is_block() {
+                        // Same as txn, the block is known after the compact block is fetched.
+                        let mut fresh_ids = Vec::new();
+                        for block_id in announcement.clone().ids() {
+                            peer_info.known_blocks.put(block_id, ());
+                            if !self.self_peer.known_blocks.contains(&block_id) {
+                                fresh_ids.push(block_id);
+                            }
+                        }
+                        if fresh_ids.is_empty() {
+                            None
+                        } else {
+                            Some(NotificationMessage::Announcement(Announcement::new(
+                                AnnouncementType::Block,
+                                fresh_ids,
+                            )))
+                        }
+                    } else if announcement.is_txn() {
+                        // Do not mark the announced txns as known by self here,
+                        // they are only known after pulled from the peer, otherwise a failed pull
+                        // make the txn never be fetched again.
                         let mut fresh_ids = Vec::new();
                         for txn_id in announcement.clone().ids() {
                             peer_info.known_transactions.put(txn_id, ());
 
                             if !self.self_peer.known_transactions.contains(&txn_id) {
-                                self.self_peer.known_transactions.put(txn_id, ());
                                 fresh_ids.push(txn_id);
                             };
                         }
@@ -727,15 +857,49 @@ impl Inner {
                 let peers_after_protocol_filter = filtered_peer_ids.len();
                 let peers_len = self.peers.len() as u32;
 
-                let selected_peers = select_random_peers(
+                let mut unspecified_peer_ids = vec![];
+                let mut selected_peers = vec![];
+                let mut low_bandwidth_peer_ids = vec![];
+                for peer_id in filtered_peer_ids {
+                    match self
+                        .peers
+                        .get(&peer_id)
+                        .map(|peer| peer.compact_block_relay_mode)
+                    {
+                        Some(CompactBlockRelayMode::HighBandwidth) => selected_peers.push(peer_id),
+                        Some(CompactBlockRelayMode::LowBandwidth) => {
+                            low_bandwidth_peer_ids.push(peer_id)
+                        }
+                        _ => unspecified_peer_ids.push(peer_id),
+                    }
+                }
+                selected_peers.extend(select_random_peers(
                     self.config
                         .network
                         .min_peers_to_propagate()
                         .max(peers_len / 2)
                         ..=self.config.network.max_peers_to_propagate().max(peers_len), // use max(max_peers_to_propagate,peers_len) to ensure range [min,max] , max > min.
-                    filtered_peer_ids.iter(),
-                );
+                    unspecified_peer_ids.iter(),
+                ));
                 let peers_send_message = selected_peers.len();
+                if !low_bandwidth_peer_ids.is_empty() {
+                    let (announcement_protocol, announcement) = NotificationMessage::Announcement(
+                        Announcement::new(AnnouncementType::Block, vec![id]),
+                    )
+                    .encode_notification()
+                    .expect("Encode notification Announcement message should ok");
+                    for peer_id in
+                        self.filter(low_bandwidth_peer_ids, announcement_protocol.clone())
+                    {
+                        let peer = self.peers.get_mut(&peer_id).expect("peer should exists");
+                        peer.known_blocks.put(id, ());
+                        prepare_to_broadcast.push((
+                            announcement_protocol.clone(),
+                            peer_id,
+                            announcement.clone(),
+                        ));
+                    }
+                }
                 for peer_id in &selected_peers {
                     let peer = self.peers.get_mut(peer_id).expect("peer should exists");
                     peer.known_blocks.put(id, ());
@@ -759,7 +923,8 @@ impl Inner {
                 });
                 let origin_txn_len = msg.txns.len();
                 let mut send_peer_count: usize = 0;
-                let selected_peers = select_random_peers(
+                let selected_peers = select_txn_push_peers(
+                    self.config.network.txn_propagate_mode(),
                     self.config.network.min_peers_to_propagate()
                         ..=self.config.network.max_peers_to_propagate(),
                     self.peers
@@ -862,11 +1027,35 @@ where
     peer_ids
 }
 
+/// Select the peers which the full transactions are pushed to, the other peers receive the announcement.
+fn select_txn_push_peers<'a, P>(
+    mode: TxnPropagateMode,
+    peer_num_range: RangeInclusive<u32>,
+    peers: P,
+) -> Vec<PeerId>
+where
+    P: ExactSizeIterator<Item = &'a PeerId>,
+{
+    match mode {
+        TxnPropagateMode::Push => peers.cloned().collect(),
+        TxnPropagateMode::Announce => {
+            // announce is not worth with few peers, fallback to push.
+            if peers.len() as u32 <= *peer_num_range.start() {
+                peers.cloned().collect()
+            } else {
+                vec![]
+            }
+        }
+        TxnPropagateMode::Hybrid => select_random_peers(peer_num_range, peers),
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::service::greater_barnard_fork_version;
-    use crate::service::select_random_peers;
+    use crate::service::{select_random_peers, select_txn_push_peers};
     use network_api::PeerId;
+    use starcoin_config::TxnPropagateMode;
 
     fn create_peers(n: u32) -> Vec<PeerId> {
         (0..n).map(|_| PeerId::random()).collect()
@@ -899,6 +1088,27 @@ mod test {
         assert_eq!(select_random_peers(3..=3, create_peers(3).iter()).len(), 3);
     }
 
+    #[test]
+    fn test_select_txn_push_peers() {
+        let peers = create_peers(25);
+        assert_eq!(
+            select_txn_push_peers(TxnPropagateMode::Push, 8..=128, peers.iter()).len(),
+            25
+        );
+        assert_eq!(
+            select_txn_push_peers(TxnPropagateMode::Hybrid, 8..=128, peers.iter()).len(),
+            8
+        );
+        assert!(
+            select_txn_push_peers(TxnPropagateMode::Announce, 8..=128, peers.iter()).is_empty()
+        );
+        assert_eq!(
+            select_txn_push_peers(TxnPropagateMode::Announce, 8..=128, create_peers(8).iter())
+                .len(),
+            8
+        );
+    }
+
     #[test]
     fn greater_version_test() {
         let v1 = String::from("starcoin/1.12.6 (build:v1.12.6) (kele01)");
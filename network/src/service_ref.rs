@@ -155,4 +155,23 @@ impl NetworkServiceRef {
     pub async fn is_connected(&self, peer_id: PeerId) -> bool {
         self.network_service.is_connected(peer_id.into()).await
     }
+
+    pub async fn allowed_peers(&self) -> Option<Vec<PeerId>> {
+        self.network_service
+            .allowed_peers()
+            .await
+            .map(|peers| peers.into_iter().map(|peer_id| peer_id.into()).collect())
+    }
+
+    pub async fn set_peer_allowed(&self, peer_id: PeerId, allowed: bool) -> Result<()> {
+        if self
+            .network_service
+            .set_peer_allowed(peer_id.into(), allowed)
+            .await
+        {
+            Ok(())
+        } else {
+            Err(format_err!("The network is not in permissioned mode."))
+        }
+    }
 }
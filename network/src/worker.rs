@@ -102,6 +102,11 @@ pub fn build_network_worker(
         node_name,
         client_version: starcoin_config::G_APP_NAME_WITH_VERSION.clone(),
         allow_non_globals_in_dht,
+        upload_rate_limit: network_config.max_upload_rate(),
+        download_rate_limit: network_config.max_download_rate(),
+        allowed_peers: network_config
+            .allowed_peers()
+            .map(|peers| peers.into_iter().map(|peer_id| peer_id.into()).collect()),
         ..NetworkConfiguration::default()
     };
     // protocol id is chain/{chain_id}, `RegisteredProtocol` will append `/starcoin` prefix
@@ -44,6 +44,7 @@ use starcoin_storage::errors::StorageInitError;
 use starcoin_storage::metrics::StorageMetrics;
 use starcoin_storage::storage::StorageInstance;
 use starcoin_storage::{BlockStore, Storage};
+use starcoin_stratum::pool::{PoolService, PoolServiceFactory};
 use starcoin_stratum::service::{StratumService, StratumServiceFactory};
 use starcoin_stratum::stratum::{Stratum, StratumFactory};
 use starcoin_sync::announcement::AnnouncementService;
@@ -387,6 +388,14 @@ impl NodeService {
             .register_by_factory::<Stratum, StratumFactory>()
             .await?;
 
+        if config.stratum.pool_payout().is_some() {
+            registry
+                .register_by_factory::<PoolService, PoolServiceFactory>()
+                .await?;
+        } else {
+            info!("Config.stratum.pool_payout is not set, No mining pool service.");
+        }
+
         registry.register::<GenerateBlockEventPacemaker>().await?;
 
         // wait for service init.
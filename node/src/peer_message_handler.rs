@@ -66,6 +66,21 @@ impl PeerMessageHandler for NodePeerMessageHandler {
                     }
                 }
             }
+            NotificationMessage::Announcement(message) if message.is_block() => {
+                if let Err(e) = self
+                    .block_relayer
+                    .notify(PeerAnnouncementMessage::new(peer_message.peer_id, message))
+                {
+                    match e {
+                        TrySendError::Full(_) => {
+                            warn!("Handle block PeerAnnouncementMessage error, BlockRelayer is too busy.");
+                        }
+                        TrySendError::Disconnected(_) => {
+                            error!("Handle block PeerAnnouncementMessage error, BlockRelayer is shutdown.");
+                        }
+                    }
+                }
+            }
             NotificationMessage::Announcement(message) => {
                 if let Err(e) = self
                     .announcement_service
@@ -21,6 +21,7 @@ use starcoin_rpc_server::service::RpcService;
 use starcoin_service_registry::{ServiceContext, ServiceFactory};
 use starcoin_state_service::ChainStateService;
 use starcoin_storage::Storage;
+use starcoin_stratum::stratum::Stratum;
 use starcoin_sync::sync::SyncService;
 use starcoin_txpool::TxPoolService;
 use std::sync::Arc;
@@ -43,6 +44,7 @@ impl ServiceFactory<RpcService> for RpcServiceFactory {
             .service_ref_opt::<SyncService>()?
             .map(|service_ref| SyncManagerRpcImpl::new(service_ref.clone()));
         let network_manager_api = NetworkManagerRpcImpl::new(network_service);
+        let bus = ctx.bus_ref().clone();
         let chain_api = ctx
             .service_ref_opt::<ChainReaderService>()?
             .map(|service_ref| {
@@ -51,6 +53,7 @@ impl ServiceFactory<RpcService> for RpcServiceFactory {
                     genesis.block().id(),
                     storage.clone(),
                     service_ref.clone(),
+                    bus,
                 )
             });
         let txpool_service = ctx.get_shared::<TxPoolService>()?;
@@ -71,27 +74,29 @@ impl ServiceFactory<RpcService> for RpcServiceFactory {
         });
         let pubsub_service = ctx.service_ref::<PubSubService>()?.clone();
         let pubsub_api = Some(PubSubImpl::new(pubsub_service));
+        let vm_metrics = ctx.get_shared_opt::<VMMetrics>()?;
+        let dev_playground = PlaygroudService::new(storage.clone(), vm_metrics);
         let debug_api = Some(DebugRpcImpl::new(
             config.clone(),
             log_handler,
             ctx.bus_ref().clone(),
+            ctx.service_ref_opt::<ChainReaderService>()?.cloned(),
+            dev_playground.clone(),
+            storage.clone(),
         ));
+        let stratum_service = ctx.service_ref_opt::<Stratum>()?.cloned();
         let miner_api = ctx
             .service_ref_opt::<MinerService>()?
-            .map(|service_ref| MinerRpcImpl::new(service_ref.clone()));
+            .map(|service_ref| MinerRpcImpl::new(service_ref.clone(), stratum_service));
 
-        let contract_api = {
-            let vm_metrics = ctx.get_shared_opt::<VMMetrics>()?;
-            let dev_playground = PlaygroudService::new(storage.clone(), vm_metrics);
-            ContractRpcImpl::new(
-                config.clone(),
-                account_service,
-                txpool_service,
-                chain_state_service,
-                dev_playground,
-                storage,
-            )
-        };
+        let contract_api = ContractRpcImpl::new(
+            config.clone(),
+            account_service,
+            txpool_service,
+            chain_state_service,
+            dev_playground,
+            storage,
+        );
 
         Ok(RpcService::new_with_api(
             config,
@@ -4,8 +4,9 @@
 pub use self::gen_client::Client as ChainClient;
 use crate::types::pubsub::EventFilter;
 use crate::types::{
-    BlockHeaderView, BlockInfoView, BlockView, ChainId, ChainInfoView, StrView,
-    TransactionEventResponse, TransactionInfoView, TransactionInfoWithProofView, TransactionView,
+    BlockHeaderView, BlockInfoView, BlockView, ChainId, ChainInfoView, EpochUncleStatsView,
+    ReorgRecordView, StrView, TransactionEventResponse, TransactionInfoView,
+    TransactionInfoWithProofView, TransactionView, WaitConfirmationsView,
 };
 use crate::FutureResult;
 use jsonrpc_core::Result;
@@ -122,6 +123,26 @@ pub trait ChainApi {
         event_index: Option<u64>,
         access_path: Option<StrView<AccessPath>>,
     ) -> FutureResult<Option<StrView<Vec<u8>>>>;
+
+    /// Get the uncle and orphan statistics of the latest `count` epochs, the current epoch is first.
+    /// `count` is 1 if absent.
+    #[rpc(name = "chain.uncle_stats")]
+    fn uncle_stats(&self, count: Option<u64>) -> FutureResult<Vec<EpochUncleStatsView>>;
+
+    /// Get the records of the chain reorganization journal in reverse order,
+    /// start from the index `from`, or the latest record if `from` is absent.
+    #[rpc(name = "chain.get_reorgs")]
+    fn get_reorgs(&self, from: Option<u64>, count: u64) -> FutureResult<Vec<ReorgRecordView>>;
+
+    /// Wait until the transaction reaches `confirmations` confirmations on the main chain,
+    /// or is retracted from the main chain by a reorg, or the `timeout` (in seconds, default 60, max 120) expires.
+    #[rpc(name = "chain.wait_for_confirmations")]
+    fn wait_for_confirmations(
+        &self,
+        txn_hash: HashValue,
+        confirmations: u64,
+        timeout: Option<u64>,
+    ) -> FutureResult<WaitConfirmationsView>;
 }
 
 #[derive(Copy, Clone, Default, Serialize, Deserialize, JsonSchema)]
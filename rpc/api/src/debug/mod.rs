@@ -6,7 +6,9 @@ use openrpc_derive::openrpc;
 use starcoin_logger::LogPattern;
 
 pub use self::gen_client::Client as DebugClient;
-use crate::types::FactoryAction;
+use crate::types::{BlockReplayView, FactoryAction, TransactionTraceView};
+use crate::FutureResult;
+use starcoin_crypto::HashValue;
 #[openrpc]
 pub trait DebugApi {
     /// Update log level, if logger_name is none, update global log level.
@@ -25,6 +27,30 @@ pub trait DebugApi {
     #[rpc(name = "debug.sleep")]
     fn sleep(&self, time: u64) -> Result<()>;
 
+    /// Trigger the miner to seal a new block even if no txn in the txpool, only work for dev and test network.
+    #[rpc(name = "debug.seal_block")]
+    fn seal_block(&self) -> Result<()>;
+
+    /// Set the node clock to `time` in milliseconds, the clock can not go backward.
+    /// Only work for dev and test network, take effect on the block timestamp of the next block.
+    #[rpc(name = "debug.set_time")]
+    fn set_time(&self, time: u64) -> Result<u64>;
+
+    /// Advance the node clock by `time` milliseconds without triggering a new block, return the new time.
+    /// Only work for dev and test network.
+    #[rpc(name = "debug.advance_time")]
+    fn advance_time(&self, time: u64) -> Result<u64>;
+
+    /// Re-execute a transaction on the main chain against its parent state with a gas tracer,
+    /// return the gas used by each call frame, instruction, native function and storage write.
+    #[rpc(name = "debug.trace_transaction")]
+    fn trace_transaction(&self, txn_hash: HashValue) -> FutureResult<TransactionTraceView>;
+
+    /// Re-execute a stored block with the current VM against its parent state, compare every
+    /// transaction info with the recorded one, and report the differing write set entries.
+    #[rpc(name = "debug.replay_block")]
+    fn replay_block(&self, block_hash: HashValue) -> FutureResult<BlockReplayView>;
+
     /// Get and set txn factory status.
     #[rpc(name = "txfactory.status")]
     fn txfactory_status(&self, action: FactoryAction) -> Result<bool>;
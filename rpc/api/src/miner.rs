@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2
 
 pub use self::gen_client::Client as MinerClient;
-use crate::types::MintedBlockView;
+use crate::types::{MintedBlockView, StratumStatsView};
 use crate::FutureResult;
 use openrpc_derive::openrpc;
 use starcoin_types::system_events::MintBlockEvent;
@@ -20,6 +20,9 @@ pub trait MinerApi {
     /// get current mining job
     #[rpc(name = "mining.get_job")]
     fn get_job(&self) -> FutureResult<Option<MintBlockEvent>>;
+    /// get the share and hashrate statistics of the stratum workers
+    #[rpc(name = "mining.stratum_stats")]
+    fn stratum_stats(&self) -> FutureResult<StratumStatsView>;
 }
 
 #[test]
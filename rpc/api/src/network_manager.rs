@@ -40,6 +40,14 @@ pub trait NetworkManagerApi {
     /// ban peer
     #[rpc(name = "network_manager.ban_peer")]
     fn ban_peer(&self, peer_id: String, ban: bool) -> Result<()>;
+
+    /// Get the members of the permissioned network, return None if the network is permissionless.
+    #[rpc(name = "network_manager.allowed_peers")]
+    fn allowed_peers(&self) -> FutureResult<Option<Vec<PeerId>>>;
+
+    /// Add or remove a member of the permissioned network.
+    #[rpc(name = "network_manager.set_peer_allowed")]
+    fn set_peer_allowed(&self, peer_id: String, allowed: bool) -> FutureResult<()>;
 }
 
 #[test]
@@ -19,6 +19,7 @@ use starcoin_abi_decoder::{
 use starcoin_abi_types::ModuleABI;
 use starcoin_accumulator::proof::AccumulatorProof;
 use starcoin_crypto::{CryptoMaterialError, HashValue, ValidCryptoMaterialStringExt};
+use starcoin_gas::{FrameGasTrace, GasCounter, TransactionGasTrace};
 use starcoin_resource_viewer::{AnnotatedMoveStruct, AnnotatedMoveValue};
 use starcoin_service_registry::ServiceRequest;
 use starcoin_state_api::{StateProof, StateWithProof, StateWithTableItemProof};
@@ -30,7 +31,7 @@ use starcoin_types::event::EventKey;
 use starcoin_types::genesis_config;
 use starcoin_types::language_storage::TypeTag;
 use starcoin_types::proof::SparseMerkleProof;
-use starcoin_types::startup_info::ChainInfo;
+use starcoin_types::startup_info::{ChainInfo, ChainStatus, ReorgRecord};
 use starcoin_types::transaction::authenticator::{AuthenticationKey, TransactionAuthenticator};
 use starcoin_types::transaction::{RawUserTransaction, ScriptFunction, TransactionArgument};
 use starcoin_types::vm_error::AbortLocation;
@@ -62,6 +63,112 @@ pub struct MintedBlockView {
     pub block_hash: HashValue,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct StratumWorkerView {
+    pub login: String,
+    pub agent: String,
+    /// The extranonce of the worker in hex, also used as the worker id.
+    pub extranonce: String,
+    pub online: bool,
+    #[schemars(with = "String")]
+    pub share_difficulty: U256,
+    pub accepted_shares: u64,
+    pub rejected_shares: u64,
+    pub stale_shares: u64,
+    pub blocks: u64,
+    /// The estimated hashes per second.
+    pub hashrate: StrView<u64>,
+    /// The unix timestamp in seconds of the last accepted share.
+    pub last_share_time: Option<StrView<u64>>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct StratumStatsView {
+    pub accepted_shares: u64,
+    pub rejected_shares: u64,
+    pub stale_shares: u64,
+    pub blocks: u64,
+    /// The estimated hashes per second of all workers.
+    pub hashrate: StrView<u64>,
+    pub workers: Vec<StratumWorkerView>,
+}
+
+/// The uncle and orphan statistics of an epoch, the rates are in per mille.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct EpochUncleStatsView {
+    pub epoch: StrView<u64>,
+    pub start_block_number: StrView<BlockNumber>,
+    pub end_block_number: StrView<BlockNumber>,
+    pub block_time_target: StrView<u64>,
+    pub max_uncles_per_block: u64,
+    /// The main chain blocks of the epoch.
+    pub blocks: u64,
+    /// The uncles included by the main chain blocks of the epoch.
+    pub uncles: u64,
+    /// The branch blocks of the epoch saved in the node storage.
+    pub branch_blocks: u64,
+    /// The branch blocks which are not included as uncle by the main chain.
+    pub orphans: u64,
+    pub uncle_rate: u64,
+    pub orphan_rate: u64,
+}
+
+impl From<EpochUncleStats> for EpochUncleStatsView {
+    fn from(stats: EpochUncleStats) -> Self {
+        Self {
+            epoch: stats.epoch.into(),
+            start_block_number: stats.start_block_number.into(),
+            end_block_number: stats.end_block_number.into(),
+            block_time_target: stats.block_time_target.into(),
+            max_uncles_per_block: stats.max_uncles_per_block,
+            blocks: stats.blocks,
+            uncles: stats.uncles,
+            branch_blocks: stats.branch_blocks,
+            orphans: stats.orphans,
+            uncle_rate: stats.uncle_rate,
+            orphan_rate: stats.orphan_rate,
+        }
+    }
+}
+
+/// A chain reorganization record of the reorg journal.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct ReorgRecordView {
+    pub index: StrView<u64>,
+    pub timestamp: StrView<u64>,
+    pub old_head: HashValue,
+    pub old_head_number: StrView<BlockNumber>,
+    pub new_head: HashValue,
+    pub new_head_number: StrView<BlockNumber>,
+    pub common_ancestor: HashValue,
+    pub common_ancestor_number: StrView<BlockNumber>,
+    /// The count of the retracted blocks of the old main chain.
+    pub depth: u64,
+    pub retracted_txns: Vec<HashValue>,
+    pub enacted_txns: Vec<HashValue>,
+    /// The switch to the new head is refused for exceeding the max reorg depth.
+    pub refused: bool,
+}
+
+impl From<ReorgRecord> for ReorgRecordView {
+    fn from(record: ReorgRecord) -> Self {
+        Self {
+            index: record.index.into(),
+            timestamp: record.timestamp.into(),
+            old_head: record.old_head,
+            old_head_number: record.old_head_number.into(),
+            new_head: record.new_head,
+            new_head_number: record.new_head_number.into(),
+            common_ancestor: record.common_ancestor,
+            common_ancestor_number: record.common_ancestor_number.into(),
+            depth: record.depth,
+            retracted_txns: record.retracted_txns,
+            enacted_txns: record.enacted_txns,
+            refused: record.refused,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct ResourceView {
     pub raw: StrView<Vec<u8>>,
@@ -842,6 +949,10 @@ pub struct BlockView {
 
     /// Raw block data that can be verified by block_hash and body_hash.
     pub raw: Option<RawBlockView>,
+
+    /// Confirmation of the block relative to the current head, only present for main chain blocks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confirmation: Option<ConfirmationView>,
 }
 
 impl BlockView {
@@ -870,6 +981,7 @@ impl BlockView {
                 .collect(),
             body: txns_view,
             raw: raw_block,
+            confirmation: None,
         })
     }
 }
@@ -925,6 +1037,10 @@ pub struct TransactionInfoView {
     /// failures and Move abort's receive more detailed information. But other errors are generally
     /// categorized with no status code or other information
     pub status: TransactionStatusView,
+
+    /// Confirmation of the transaction's block relative to the current head, only present for main chain transactions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confirmation: Option<ConfirmationView>,
 }
 
 impl TransactionInfoView {
@@ -939,10 +1055,95 @@ impl TransactionInfoView {
             event_root_hash: txn_info.transaction_info.event_root_hash,
             gas_used: txn_info.transaction_info.gas_used.into(),
             status: TransactionStatusView::from(txn_info.transaction_info.status),
+            confirmation: None,
         }
     }
 }
 
+/// The hash power share (in percent) assumed for an attacker when estimating the probabilistic finality.
+pub const FINALITY_ATTACKER_HASH_RATE_PERCENT: u64 = 10;
+/// The finality estimate is saturated beyond this count of head-difficulty-equivalent blocks.
+const MAX_FINALITY_BLOCKS: u64 = 1000;
+
+#[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ConfirmationView {
+    /// The count of main chain blocks from the block to the current head, the block itself included.
+    pub confirmations: StrView<u64>,
+    /// The cumulative difficulty of the block and all the main chain blocks after it.
+    #[schemars(with = "String")]
+    pub confirmed_difficulty: U256,
+    /// The estimated probability, in parts per million, that the block will not be reverted by an attacker
+    /// with `FINALITY_ATTACKER_HASH_RATE_PERCENT` of the hash power.
+    /// None if the head difficulty is zero.
+    pub finality_ppm: Option<u64>,
+}
+
+impl ConfirmationView {
+    /// Build the confirmation of a main chain `block` relative to the `head`.
+    pub fn new(block: &BlockHeader, block_info: &BlockInfo, head: &ChainStatus) -> Self {
+        let confirmations = head
+            .head
+            .number()
+            .saturating_sub(block.number())
+            .saturating_add(1);
+        let confirmed_difficulty = head
+            .info
+            .total_difficulty
+            .saturating_sub(block_info.total_difficulty)
+            .saturating_add(block.difficulty());
+        let head_difficulty = head.head.difficulty();
+        let finality_ppm = if head_difficulty.is_zero() {
+            None
+        } else {
+            let blocks = (confirmed_difficulty / head_difficulty)
+                .min(U256::from(MAX_FINALITY_BLOCKS))
+                .as_u64();
+            Some(probabilistic_finality_ppm(blocks))
+        };
+        Self {
+            confirmations: confirmations.into(),
+            confirmed_difficulty,
+            finality_ppm,
+        }
+    }
+}
+
+/// Estimate the probability, in parts per million, that an attacker can never catch up with
+/// `blocks` blocks of honest work, following the calculation of the Bitcoin whitepaper.
+pub fn probabilistic_finality_ppm(blocks: u64) -> u64 {
+    let q = FINALITY_ATTACKER_HASH_RATE_PERCENT as f64 / 100.0;
+    let p = 1.0 - q;
+    let z = blocks.min(MAX_FINALITY_BLOCKS);
+    let lambda = z as f64 * (q / p);
+    let mut poisson = (-lambda).exp();
+    let mut attack_success = 1.0;
+    for k in 0..=z {
+        if k > 0 {
+            poisson *= lambda / k as f64;
+        }
+        attack_success -= poisson * (1.0 - (q / p).powf((z - k) as f64));
+    }
+    ((1.0 - attack_success.clamp(0.0, 1.0)) * 1_000_000.0).floor() as u64
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfirmationStatus {
+    /// The transaction reached the required confirmations.
+    Confirmed,
+    /// The transaction was included in the main chain, but retracted by a reorg.
+    Retracted,
+    /// The required confirmations were not reached before the timeout.
+    Timeout,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct WaitConfirmationsView {
+    pub status: ConfirmationStatus,
+    /// The latest transaction info on the main chain, None if the transaction is not on the main chain.
+    pub txn_info: Option<TransactionInfoView>,
+}
+
 impl From<RichTransactionInfo> for TransactionInfoView {
     fn from(txn_info: RichTransactionInfo) -> Self {
         TransactionInfoView::new(txn_info)
@@ -1168,7 +1369,7 @@ impl TransactionEventView {
 use schemars::gen::SchemaGenerator;
 use schemars::schema::{InstanceType, Schema, SchemaObject};
 use starcoin_accumulator::accumulator_info::AccumulatorInfo;
-use starcoin_chain_api::{EventWithProof, TransactionInfoWithProof};
+use starcoin_chain_api::{EpochUncleStats, EventWithProof, TransactionInfoWithProof};
 use starcoin_types::account_address::AccountAddress;
 use starcoin_vm_types::move_resource::MoveResource;
 use starcoin_vm_types::state_store::state_key::{StateKey, TableItem};
@@ -1182,6 +1383,143 @@ pub struct DryRunOutputView {
     pub txn_output: TransactionOutputView,
 }
 
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct GasCounterView {
+    pub count: StrView<u64>,
+    pub gas: StrView<u64>,
+}
+
+impl From<GasCounter> for GasCounterView {
+    fn from(counter: GasCounter) -> Self {
+        Self {
+            count: counter.count.into(),
+            gas: counter.gas.into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct FrameGasTraceView {
+    pub name: String,
+    pub calls: StrView<u64>,
+    /// The gas used by the function itself, excluding its callees.
+    pub self_gas: StrView<u64>,
+    /// The gas used by the function and all its callees.
+    pub total_gas: StrView<u64>,
+    pub instructions: BTreeMap<String, GasCounterView>,
+    pub children: Vec<FrameGasTraceView>,
+}
+
+impl From<FrameGasTrace> for FrameGasTraceView {
+    fn from(frame: FrameGasTrace) -> Self {
+        Self {
+            total_gas: frame.total_gas().into(),
+            name: frame.name,
+            calls: frame.calls.into(),
+            self_gas: frame.self_gas.into(),
+            instructions: frame
+                .instructions
+                .into_iter()
+                .map(|(instruction, counter)| (instruction, counter.into()))
+                .collect(),
+            children: frame.children.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// The gas profile of a transaction, all the gas is in internal gas units.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct GasTraceView {
+    pub total_gas: StrView<u64>,
+    pub intrinsic_gas: StrView<u64>,
+    pub storage_write_gas: StrView<u64>,
+    pub execution: FrameGasTraceView,
+    pub natives: BTreeMap<String, GasCounterView>,
+    /// The call stack when the transaction ran out of gas, the entry function is first.
+    pub out_of_gas_at: Option<Vec<String>>,
+    /// The trace in folded stacks format, which can be rendered as a flamegraph.
+    pub folded_stacks: String,
+}
+
+impl From<TransactionGasTrace> for GasTraceView {
+    fn from(trace: TransactionGasTrace) -> Self {
+        Self {
+            total_gas: trace.total_gas().into(),
+            intrinsic_gas: trace.intrinsic_gas.into(),
+            storage_write_gas: trace.storage_write_gas.into(),
+            folded_stacks: trace.to_folded_stacks(),
+            execution: trace.execution.into(),
+            natives: trace
+                .natives
+                .into_iter()
+                .map(|(name, counter)| (name, counter.into()))
+                .collect(),
+            out_of_gas_at: trace.out_of_gas_at,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct TransactionTraceView {
+    pub txn_hash: HashValue,
+    pub explained_status: VmStatusExplainView,
+    pub status: TransactionStatusView,
+    pub gas_used: StrView<u64>,
+    pub gas_trace: GasTraceView,
+}
+
+/// The executed result recorded by a transaction info, without the block info.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ExecutedTransactionInfoView {
+    pub state_root_hash: HashValue,
+    pub event_root_hash: HashValue,
+    pub gas_used: StrView<u64>,
+    pub status: TransactionStatusView,
+}
+
+impl From<TransactionInfo> for ExecutedTransactionInfoView {
+    fn from(txn_info: TransactionInfo) -> Self {
+        Self {
+            state_root_hash: txn_info.state_root_hash(),
+            event_root_hash: txn_info.event_root_hash(),
+            gas_used: txn_info.gas_used().into(),
+            status: txn_info.status().clone().into(),
+        }
+    }
+}
+
+/// A state entry written by the replayed transaction whose value differs from the recorded state.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct WriteSetEntryDiffView {
+    pub state_key: StateKey,
+    /// The value in the recorded state after the transaction, None if it does not exist.
+    pub recorded: Option<StrView<Vec<u8>>>,
+    /// The value written by the replayed transaction, None if it is deleted.
+    pub replayed: Option<StrView<Vec<u8>>>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct TransactionReplayView {
+    pub txn_hash: HashValue,
+    pub matched: bool,
+    pub recorded: ExecutedTransactionInfoView,
+    /// None if the replayed transaction is discarded or not executed.
+    pub replayed: Option<ExecutedTransactionInfoView>,
+    /// The status if the replayed transaction is discarded.
+    pub discarded: Option<TransactionStatusView>,
+    pub write_set_diffs: Vec<WriteSetEntryDiffView>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct BlockReplayView {
+    pub block_hash: HashValue,
+    pub block_number: StrView<BlockNumber>,
+    pub matched: bool,
+    pub recorded_state_root: HashValue,
+    pub replayed_state_root: HashValue,
+    pub txns: Vec<TransactionReplayView>,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct TransactionOutputView {
     pub status: TransactionStatusView,
@@ -1930,7 +2268,7 @@ impl From<StateKey> for StateKeyView {
 
 #[cfg(test)]
 mod tests {
-    use crate::types::{ByteCodeOrScriptFunction, FunctionId, StrView};
+    use crate::types::{probabilistic_finality_ppm, ByteCodeOrScriptFunction, FunctionId, StrView};
     use move_core_types::u256;
     use starcoin_types::account_address::AccountAddress;
 
@@ -1953,6 +2291,16 @@ mod tests {
         assert!(matches!(bytecode, ByteCodeOrScriptFunction::ByteCode(_)));
     }
 
+    #[test]
+    fn test_probabilistic_finality() {
+        assert_eq!(probabilistic_finality_ppm(0), 0);
+        // P(attacker success) with q = 0.1 is 0.2045873 for z = 1 and 0.0000012 for z = 10.
+        assert_eq!(probabilistic_finality_ppm(1), 795_412);
+        assert_eq!(probabilistic_finality_ppm(10), 999_998);
+        assert!(probabilistic_finality_ppm(1000) >= 999_999);
+        assert!(probabilistic_finality_ppm(5) > probabilistic_finality_ppm(4));
+    }
+
     #[test]
     fn test_str_view_u256() {
         let str = "115792089237316195423570985008687907853269984665640564039457584007913129638801";
@@ -31,13 +31,14 @@ use starcoin_rpc_api::state::{
 };
 use starcoin_rpc_api::types::pubsub::EventFilter;
 use starcoin_rpc_api::types::{
-    AccountStateSetView, AnnotatedMoveStructView, BlockHeaderView, BlockInfoView, BlockView,
-    ChainId, ChainInfoView, CodeView, ContractCall, DecodedMoveValue, DryRunOutputView,
-    DryRunTransactionRequest, FactoryAction, FunctionIdView, ListCodeView, ListResourceView,
-    MintedBlockView, ModuleIdView, PeerInfoView, ResourceView, SignedMessageView,
-    SignedUserTransactionView, StateWithProofView, StateWithTableItemProofView, StrView,
-    StructTagView, TransactionEventResponse, TransactionInfoView, TransactionInfoWithProofView,
-    TransactionRequest, TransactionView,
+    AccountStateSetView, AnnotatedMoveStructView, BlockHeaderView, BlockInfoView, BlockReplayView,
+    BlockView, ChainId, ChainInfoView, CodeView, ContractCall, DecodedMoveValue, DryRunOutputView,
+    DryRunTransactionRequest, EpochUncleStatsView, FactoryAction, FunctionIdView, ListCodeView,
+    ListResourceView, MintedBlockView, ModuleIdView, PeerInfoView, ReorgRecordView, ResourceView,
+    SignedMessageView, SignedUserTransactionView, StateWithProofView, StateWithTableItemProofView,
+    StrView, StratumStatsView, StructTagView, TransactionEventResponse, TransactionInfoView,
+    TransactionInfoWithProofView, TransactionRequest, TransactionTraceView, TransactionView,
+    WaitConfirmationsView,
 };
 use starcoin_rpc_api::{
     account::AccountClient, chain::ChainClient, contract_api::ContractClient, debug::DebugClient,
@@ -722,6 +723,34 @@ impl RpcClient {
             .map_err(map_err)
     }
 
+    pub fn debug_seal_block(&self) -> anyhow::Result<()> {
+        self.call_rpc_blocking(|inner| inner.debug_client.seal_block())
+            .map_err(map_err)
+    }
+
+    pub fn debug_set_time(&self, time: u64) -> anyhow::Result<u64> {
+        self.call_rpc_blocking(|inner| inner.debug_client.set_time(time))
+            .map_err(map_err)
+    }
+
+    pub fn debug_advance_time(&self, time: u64) -> anyhow::Result<u64> {
+        self.call_rpc_blocking(|inner| inner.debug_client.advance_time(time))
+            .map_err(map_err)
+    }
+
+    pub fn debug_trace_transaction(
+        &self,
+        txn_hash: HashValue,
+    ) -> anyhow::Result<TransactionTraceView> {
+        self.call_rpc_blocking(|inner| inner.debug_client.trace_transaction(txn_hash))
+            .map_err(map_err)
+    }
+
+    pub fn debug_replay_block(&self, block_hash: HashValue) -> anyhow::Result<BlockReplayView> {
+        self.call_rpc_blocking(|inner| inner.debug_client.replay_block(block_hash))
+            .map_err(map_err)
+    }
+
     pub fn chain_id(&self) -> anyhow::Result<ChainId> {
         self.call_rpc_blocking(|inner| inner.chain_client.id())
             .map_err(map_err)
@@ -766,6 +795,37 @@ impl RpcClient {
             .map_err(map_err)
     }
 
+    pub fn chain_uncle_stats(
+        &self,
+        count: Option<u64>,
+    ) -> anyhow::Result<Vec<EpochUncleStatsView>> {
+        self.call_rpc_blocking(|inner| inner.chain_client.uncle_stats(count))
+            .map_err(map_err)
+    }
+
+    pub fn chain_get_reorgs(
+        &self,
+        from: Option<u64>,
+        count: u64,
+    ) -> anyhow::Result<Vec<ReorgRecordView>> {
+        self.call_rpc_blocking(|inner| inner.chain_client.get_reorgs(from, count))
+            .map_err(map_err)
+    }
+
+    pub fn chain_wait_for_confirmations(
+        &self,
+        txn_hash: HashValue,
+        confirmations: u64,
+        timeout: Option<u64>,
+    ) -> anyhow::Result<WaitConfirmationsView> {
+        self.call_rpc_blocking(|inner| {
+            inner
+                .chain_client
+                .wait_for_confirmations(txn_hash, confirmations, timeout)
+        })
+        .map_err(map_err)
+    }
+
     pub fn chain_get_blocks_by_number(
         &self,
         number: Option<BlockNumber>,
@@ -914,6 +974,11 @@ impl RpcClient {
             .map_err(map_err)
     }
 
+    pub fn miner_stratum_stats(&self) -> anyhow::Result<StratumStatsView> {
+        self.call_rpc_blocking(|inner| inner.miner_client.stratum_stats())
+            .map_err(map_err)
+    }
+
     pub fn txpool_status(&self) -> anyhow::Result<TxPoolStatus> {
         self.call_rpc_blocking(|inner| inner.txpool_client.state())
             .map_err(map_err)
@@ -1098,6 +1163,14 @@ impl RpcClient {
         self.call_rpc_blocking(|inner| inner.network_client.ban_peer(peer_id, ban))
             .map_err(map_err)
     }
+    pub fn network_allowed_peers(&self) -> anyhow::Result<Option<Vec<PeerId>>> {
+        self.call_rpc_blocking(|inner| inner.network_client.allowed_peers())
+            .map_err(map_err)
+    }
+    pub fn network_set_peer_allowed(&self, peer_id: String, allowed: bool) -> anyhow::Result<()> {
+        self.call_rpc_blocking(|inner| inner.network_client.set_peer_allowed(peer_id, allowed))
+            .map_err(map_err)
+    }
     pub fn close(self) {
         if let Err(e) = self.chain_watcher.try_send(chain_watcher::StopWatcher) {
             debug!("Try to stop chain watcher error: {:?}", e);
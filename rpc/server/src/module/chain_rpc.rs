@@ -2,7 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::module::map_err;
+use anyhow::bail;
 use futures::future::{FutureExt, TryFutureExt};
+use futures::StreamExt;
+use futures_timer::Delay;
 use starcoin_abi_decoder::decode_txn_payload;
 use starcoin_chain_service::ChainAsyncService;
 use starcoin_config::NodeConfig;
@@ -15,10 +18,13 @@ use starcoin_rpc_api::chain::{
 use starcoin_rpc_api::types::pubsub::EventFilter;
 use starcoin_rpc_api::types::{
     BlockHeaderView, BlockInfoView, BlockTransactionsView, BlockView, ChainId, ChainInfoView,
+    ConfirmationStatus, ConfirmationView, EpochUncleStatsView, ReorgRecordView,
     SignedUserTransactionView, StrView, TransactionEventResponse, TransactionInfoView,
-    TransactionInfoWithProofView, TransactionView,
+    TransactionInfoWithProofView, TransactionView, WaitConfirmationsView,
 };
 use starcoin_rpc_api::FutureResult;
+use starcoin_service_registry::bus::{Bus, BusService};
+use starcoin_service_registry::ServiceRef;
 use starcoin_state_api::StateView;
 use starcoin_statedb::ChainStateDB;
 use starcoin_storage::Storage;
@@ -26,8 +32,11 @@ use starcoin_types::access_path::AccessPath;
 use starcoin_types::block::BlockNumber;
 use starcoin_types::filter::Filter;
 use starcoin_types::startup_info::ChainInfo;
+use starcoin_types::system_events::NewHeadBlock;
 use std::convert::TryInto;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
 pub struct ChainRpcImpl<S>
 where
@@ -37,6 +46,9 @@ where
     genesis_hash: HashValue,
     storage: Arc<Storage>,
     service: S,
+    bus: ServiceRef<BusService>,
+    /// The count of the pending `chain.wait_for_confirmations` calls.
+    confirmation_waiters: Arc<AtomicUsize>,
 }
 
 impl<S> ChainRpcImpl<S>
@@ -48,12 +60,15 @@ where
         genesis_hash: HashValue,
         storage: Arc<Storage>,
         service: S,
+        bus: ServiceRef<BusService>,
     ) -> Self {
         Self {
             config,
             genesis_hash,
             storage,
             service,
+            bus,
+            confirmation_waiters: Arc::new(AtomicUsize::new(0)),
         }
     }
 }
@@ -89,9 +104,13 @@ where
         let storage = self.storage.clone();
         let fut = async move {
             let result = service.get_block_by_hash(hash).await?;
+            let block_id = result.as_ref().map(|b| b.id());
             let mut block: Option<BlockView> = result
                 .map(|b| BlockView::try_from_block(b, false, raw))
                 .transpose()?;
+            if let (Some(block), Some(block_id)) = (block.as_mut(), block_id) {
+                block.confirmation = main_block_confirmation(&service, block_id).await?;
+            }
             if decode {
                 let state = ChainStateDB::new(
                     storage,
@@ -120,9 +139,13 @@ where
 
         let fut = async move {
             let result = service.main_block_by_number(number).await?;
+            let block_id = result.as_ref().map(|b| b.id());
             let mut block: Option<BlockView> = result
                 .map(|b| BlockView::try_from_block(b, false, raw))
                 .transpose()?;
+            if let (Some(block), Some(block_id)) = (block.as_mut(), block_id) {
+                block.confirmation = main_block_confirmation(&service, block_id).await?;
+            }
             if decode {
                 let state = ChainStateDB::new(
                     storage,
@@ -234,10 +257,12 @@ where
     ) -> FutureResult<Option<TransactionInfoView>> {
         let service = self.service.clone();
         let fut = async move {
-            Ok(service
-                .get_transaction_info(transaction_hash)
-                .await?
-                .map(Into::into))
+            match service.get_transaction_info(transaction_hash).await? {
+                Some(txn_info) => Ok(Some(
+                    txn_info_with_confirmation(&service, txn_info.into()).await?,
+                )),
+                None => Ok(None),
+            }
         }
         .map_err(map_err);
 
@@ -247,11 +272,16 @@ where
     fn get_block_txn_infos(&self, block_hash: HashValue) -> FutureResult<Vec<TransactionInfoView>> {
         let service = self.service.clone();
         let fut = async move {
+            let confirmation = main_block_confirmation(&service, block_hash).await?;
             Ok(service
                 .get_block_txn_infos(block_hash)
                 .await?
                 .into_iter()
-                .map(Into::into)
+                .map(|txn_info| {
+                    let mut txn_info = TransactionInfoView::from(txn_info);
+                    txn_info.confirmation = confirmation.clone();
+                    txn_info
+                })
                 .collect::<Vec<_>>())
         }
         .map_err(map_err);
@@ -266,10 +296,15 @@ where
     ) -> FutureResult<Option<TransactionInfoView>> {
         let service = self.service.clone();
         let fut = async move {
-            Ok(service
+            match service
                 .get_txn_info_by_block_and_index(block_hash, idx)
                 .await?
-                .map(Into::into))
+            {
+                Some(txn_info) => Ok(Some(
+                    txn_info_with_confirmation(&service, txn_info.into()).await?,
+                )),
+                None => Ok(None),
+            }
         }
         .map_err(map_err);
 
@@ -469,6 +504,163 @@ where
 
         Box::pin(fut.boxed())
     }
+
+    fn uncle_stats(&self, count: Option<u64>) -> FutureResult<Vec<EpochUncleStatsView>> {
+        let service = self.service.clone();
+        let fut = async move {
+            let count = count.unwrap_or(1).max(1).min(MAX_UNCLE_STATS_EPOCHS);
+            let stats = service.get_epoch_uncle_stats(count).await?;
+            Ok(stats.into_iter().map(Into::into).collect())
+        }
+        .map_err(map_err);
+
+        Box::pin(fut.boxed())
+    }
+
+    fn get_reorgs(&self, from: Option<u64>, count: u64) -> FutureResult<Vec<ReorgRecordView>> {
+        let service = self.service.clone();
+        let config = self.config.clone();
+        let fut = async move {
+            let count = count.min(config.rpc.block_query_max_range());
+            let records = service.get_reorgs(from, count).await?;
+            Ok(records.into_iter().map(Into::into).collect())
+        }
+        .map_err(map_err);
+
+        Box::pin(fut.boxed())
+    }
+
+    fn wait_for_confirmations(
+        &self,
+        txn_hash: HashValue,
+        confirmations: u64,
+        timeout: Option<u64>,
+    ) -> FutureResult<WaitConfirmationsView> {
+        let service = self.service.clone();
+        let bus = self.bus.clone();
+        let confirmation_waiters = self.confirmation_waiters.clone();
+        let timeout = Duration::from_secs(
+            timeout
+                .unwrap_or(DEFAULT_WAIT_CONFIRMATIONS_TIMEOUT_SECS)
+                .min(MAX_WAIT_CONFIRMATIONS_TIMEOUT_SECS),
+        );
+        let fut = async move {
+            let _waiter = ConfirmationWaiter::acquire(confirmation_waiters)?;
+            // Subscribe the new head before the first check, to not miss any head.
+            let mut new_heads = bus.channel::<NewHeadBlock>().await?;
+            let mut deadline = Delay::new(timeout).fuse();
+            let mut included = false;
+            loop {
+                let txn_info = match service.get_transaction_info(txn_hash).await? {
+                    Some(txn_info) => {
+                        included = true;
+                        Some(txn_info_with_confirmation(&service, txn_info.into()).await?)
+                    }
+                    None => None,
+                };
+                let confirmed = txn_info
+                    .as_ref()
+                    .and_then(|txn_info| txn_info.confirmation.as_ref())
+                    .map(|confirmation| confirmation.confirmations.0 >= confirmations)
+                    .unwrap_or(false);
+                if confirmed {
+                    return Ok(WaitConfirmationsView {
+                        status: ConfirmationStatus::Confirmed,
+                        txn_info,
+                    });
+                }
+                if included && txn_info.is_none() {
+                    return Ok(WaitConfirmationsView {
+                        status: ConfirmationStatus::Retracted,
+                        txn_info,
+                    });
+                }
+                // Check again on every new head, the reorg also emits a new head.
+                futures::select! {
+                    new_head = new_heads.next() => {
+                        if new_head.is_none() {
+                            bail!("The new head block subscription is closed.");
+                        }
+                    }
+                    _ = deadline => {
+                        return Ok(WaitConfirmationsView {
+                            status: ConfirmationStatus::Timeout,
+                            txn_info,
+                        });
+                    }
+                }
+            }
+        }
+        .map_err(map_err);
+
+        Box::pin(fut.boxed())
+    }
+}
+
+/// The max epochs of the uncle stats returned by one rpc call.
+const MAX_UNCLE_STATS_EPOCHS: u64 = 32;
+/// The default and max timeout of `chain.wait_for_confirmations`, in seconds.
+const DEFAULT_WAIT_CONFIRMATIONS_TIMEOUT_SECS: u64 = 60;
+const MAX_WAIT_CONFIRMATIONS_TIMEOUT_SECS: u64 = 120;
+/// The max pending `chain.wait_for_confirmations` calls of the node.
+const MAX_CONFIRMATION_WAITERS: usize = 256;
+
+/// A pending `chain.wait_for_confirmations` call, counted until dropped.
+struct ConfirmationWaiter(Arc<AtomicUsize>);
+
+impl ConfirmationWaiter {
+    fn acquire(waiters: Arc<AtomicUsize>) -> anyhow::Result<Self> {
+        if waiters.fetch_add(1, Ordering::SeqCst) >= MAX_CONFIRMATION_WAITERS {
+            waiters.fetch_sub(1, Ordering::SeqCst);
+            bail!(
+                "Too many pending wait_for_confirmations calls, the max is {}.",
+                MAX_CONFIRMATION_WAITERS
+            );
+        }
+        Ok(Self(waiters))
+    }
+}
+
+impl Drop for ConfirmationWaiter {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Get the confirmation of the block relative to the main chain head,
+/// None if the block is not on the main chain.
+async fn main_block_confirmation<S>(
+    service: &S,
+    block_id: HashValue,
+) -> anyhow::Result<Option<ConfirmationView>>
+where
+    S: ChainAsyncService,
+{
+    let header = match service.get_header_by_hash(&block_id).await? {
+        Some(header) => header,
+        None => return Ok(None),
+    };
+    let main_header = service.main_block_header_by_number(header.number()).await?;
+    if main_header.map(|h| h.id()) != Some(block_id) {
+        return Ok(None);
+    }
+    let block_info = match service.get_block_info_by_hash(&block_id).await? {
+        Some(block_info) => block_info,
+        None => return Ok(None),
+    };
+    let head = service.main_status().await?;
+    Ok(Some(ConfirmationView::new(&header, &block_info, &head)))
+}
+
+async fn txn_info_with_confirmation<S>(
+    service: &S,
+    mut txn_info: TransactionInfoView,
+) -> anyhow::Result<TransactionInfoView>
+where
+    S: ChainAsyncService,
+{
+    txn_info.confirmation = main_block_confirmation(service, txn_info.block_hash).await?;
+    Ok(txn_info)
 }
 
 fn try_decode_block_txns(state: &dyn StateView, block: &mut BlockView) -> anyhow::Result<()> {
@@ -499,3 +691,83 @@ fn try_decode_txn_payload(
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use starcoin_chain_service::ChainReaderService;
+    use starcoin_service_registry::{RegistryAsyncService, RegistryService};
+
+    async fn chain_rpc() -> anyhow::Result<(ChainRpcImpl<ServiceRef<ChainReaderService>>, HashValue)>
+    {
+        let config = Arc::new(NodeConfig::random_for_test());
+        let (storage, _, genesis) = test_helper::Genesis::init_storage_for_test(config.net())?;
+        let registry = RegistryService::launch();
+        registry.put_shared(config.clone()).await?;
+        registry.put_shared(storage.clone()).await?;
+        let service = registry.register::<ChainReaderService>().await?;
+        let bus = registry.service_ref::<BusService>().await?;
+        let genesis_id = genesis.block().id();
+        let genesis_txn_hash = service
+            .get_block_txn_infos(genesis_id)
+            .await?
+            .first()
+            .map(|txn_info| txn_info.transaction_hash())
+            .ok_or_else(|| anyhow::format_err!("The genesis txn should exist"))?;
+        Ok((
+            ChainRpcImpl::new(config, genesis_id, storage, service, bus),
+            genesis_txn_hash,
+        ))
+    }
+
+    #[stest::test]
+    async fn test_wait_for_confirmations_confirmed() -> anyhow::Result<()> {
+        let (rpc, txn_hash) = chain_rpc().await?;
+        let result = rpc
+            .wait_for_confirmations(txn_hash, 1, Some(1))
+            .await
+            .unwrap();
+        assert_eq!(result.status, ConfirmationStatus::Confirmed);
+        assert_eq!(result.txn_info.unwrap().transaction_hash, txn_hash);
+        Ok(())
+    }
+
+    #[stest::test]
+    async fn test_wait_for_confirmations_timeout() -> anyhow::Result<()> {
+        let (rpc, txn_hash) = chain_rpc().await?;
+        let result = rpc
+            .wait_for_confirmations(txn_hash, 100, Some(1))
+            .await
+            .unwrap();
+        assert_eq!(result.status, ConfirmationStatus::Timeout);
+        let confirmation = result.txn_info.unwrap().confirmation.unwrap();
+        assert_eq!(confirmation.confirmations.0, 1);
+        assert_eq!(rpc.confirmation_waiters.load(Ordering::SeqCst), 0);
+        Ok(())
+    }
+
+    #[stest::test]
+    async fn test_wait_for_confirmations_unknown_txn() -> anyhow::Result<()> {
+        let (rpc, _) = chain_rpc().await?;
+        let result = rpc
+            .wait_for_confirmations(HashValue::random(), 1, Some(1))
+            .await
+            .unwrap();
+        assert_eq!(result.status, ConfirmationStatus::Timeout);
+        assert!(result.txn_info.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_confirmation_waiter_limit() {
+        let waiters = Arc::new(AtomicUsize::new(0));
+        let guards = (0..MAX_CONFIRMATION_WAITERS)
+            .map(|_| ConfirmationWaiter::acquire(waiters.clone()).unwrap())
+            .collect::<Vec<_>>();
+        assert!(ConfirmationWaiter::acquire(waiters.clone()).is_err());
+        assert_eq!(waiters.load(Ordering::SeqCst), MAX_CONFIRMATION_WAITERS);
+        drop(guards);
+        assert_eq!(waiters.load(Ordering::SeqCst), 0);
+        assert!(ConfirmationWaiter::acquire(waiters).is_ok());
+    }
+}
@@ -1,18 +1,33 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::module::to_invalid_param_err;
 use crate::module::txfactory_rpc::TxFactoryStatusHandle;
+use crate::module::{map_err, to_invalid_param_err};
+use anyhow::{bail, format_err};
+use futures::future::TryFutureExt;
+use futures::FutureExt;
 use jsonrpc_core::Result;
+use starcoin_chain::replay::{replay_block, BlockReplayReport};
+use starcoin_chain_service::{ChainAsyncService, ChainReaderService};
 use starcoin_config::NodeConfig;
+use starcoin_crypto::HashValue;
+use starcoin_dev::playground::PlaygroudService;
 use starcoin_logger::prelude::LevelFilter;
 use starcoin_logger::structured_log::set_slog_level;
 use starcoin_logger::{LogPattern, LoggerHandle};
 use starcoin_rpc_api::debug::DebugApi;
-use starcoin_rpc_api::types::FactoryAction;
+use starcoin_rpc_api::types::{
+    BlockReplayView, FactoryAction, StrView, TransactionReplayView, TransactionTraceView,
+    WriteSetEntryDiffView,
+};
+use starcoin_rpc_api::FutureResult;
 use starcoin_service_registry::bus::{Bus, BusService};
 use starcoin_service_registry::ServiceRef;
+use starcoin_statedb::ChainStateDB;
+use starcoin_storage::Storage;
+use starcoin_time_service::MockTimeService;
 use starcoin_types::system_events::GenerateBlockEvent;
+use starcoin_types::transaction::Transaction;
 use std::str::FromStr;
 use std::sync::Arc;
 
@@ -20,6 +35,9 @@ pub struct DebugRpcImpl {
     config: Arc<NodeConfig>,
     log_handle: Arc<LoggerHandle>,
     bus: ServiceRef<BusService>,
+    chain_service: Option<ServiceRef<ChainReaderService>>,
+    playground: PlaygroudService,
+    storage: Arc<Storage>,
 }
 
 impl DebugRpcImpl {
@@ -27,15 +45,36 @@ impl DebugRpcImpl {
         config: Arc<NodeConfig>,
         log_handle: Arc<LoggerHandle>,
         bus: ServiceRef<BusService>,
+        chain_service: Option<ServiceRef<ChainReaderService>>,
+        playground: PlaygroudService,
+        storage: Arc<Storage>,
     ) -> Self {
         Self {
             config,
             log_handle,
             bus,
+            chain_service,
+            playground,
+            storage,
         }
     }
 }
 
+impl DebugRpcImpl {
+    fn mock_time_service(&self) -> Result<MockTimeService> {
+        if !self.config.net().is_test_or_dev() {
+            return Err(jsonrpc_core::Error::invalid_request());
+        }
+        self.config
+            .net()
+            .time_service()
+            .as_any()
+            .downcast_ref::<MockTimeService>()
+            .cloned()
+            .ok_or_else(jsonrpc_core::Error::invalid_request)
+    }
+}
+
 impl DebugApi for DebugRpcImpl {
     fn set_log_level(&self, logger_name: Option<String>, level: String) -> Result<()> {
         let logger_name = logger_name.and_then(|s| {
@@ -79,7 +118,121 @@ impl DebugApi for DebugRpcImpl {
         Ok(())
     }
 
+    fn seal_block(&self) -> Result<()> {
+        if !self.config.net().is_test_or_dev() {
+            return Err(jsonrpc_core::Error::invalid_request());
+        }
+        self.bus
+            .broadcast(GenerateBlockEvent::new(true, true))
+            .map_err(|_e| jsonrpc_core::Error::internal_error())?;
+        Ok(())
+    }
+
+    fn set_time(&self, time: u64) -> Result<u64> {
+        let time_service = self.mock_time_service()?;
+        let now = time_service.now_millis();
+        if time < now {
+            return Err(jsonrpc_core::Error::invalid_params(format!(
+                "Can not set time backward, current time: {}, new time: {}",
+                now, time
+            )));
+        }
+        time_service.set(time);
+        Ok(time)
+    }
+
+    fn advance_time(&self, time: u64) -> Result<u64> {
+        let time_service = self.mock_time_service()?;
+        time_service.increment_by(time);
+        Ok(time_service.now_millis())
+    }
+
+    fn trace_transaction(&self, txn_hash: HashValue) -> FutureResult<TransactionTraceView> {
+        let service = self.chain_service.clone();
+        let playground = self.playground.clone();
+        let storage = self.storage.clone();
+        let fut = async move {
+            let service = service.ok_or_else(|| format_err!("Chain service is not available."))?;
+            let txn_info = service
+                .get_transaction_info(txn_hash)
+                .await?
+                .ok_or_else(|| format_err!("Can not find txn info of txn {}", txn_hash))?;
+            let txn = match service.get_transaction(txn_hash).await? {
+                Some(Transaction::UserTransaction(txn)) => txn,
+                Some(_) => bail!("Only user transaction can be traced, txn: {}", txn_hash),
+                None => bail!("Can not find txn {}", txn_hash),
+            };
+            // The block metadata txn is always the first txn of a block, so the parent state of
+            // a user txn is the state after the previous txn in the same block.
+            let global_index = txn_info
+                .transaction_global_index
+                .checked_sub(1)
+                .ok_or_else(|| format_err!("Invalid txn global index of txn {}", txn_hash))?;
+            let parent_txn_info = service
+                .get_transaction_infos(global_index, false, 1)
+                .await?
+                .pop()
+                .ok_or_else(|| format_err!("Can not find txn info at index {}", global_index))?;
+            let state_root = parent_txn_info.state_root_hash();
+            let (vm_status, output, trace) = playground.trace_transaction(state_root, txn)?;
+            let state_view = ChainStateDB::new(storage, Some(state_root));
+            let explained_status = vm_status_translator::explain_vm_status(&state_view, vm_status)?;
+            Ok(TransactionTraceView {
+                txn_hash,
+                explained_status,
+                status: output.status().clone().into(),
+                gas_used: output.gas_used().into(),
+                gas_trace: trace.into(),
+            })
+        }
+        .map_err(map_err);
+        Box::pin(fut.boxed())
+    }
+
+    fn replay_block(&self, block_hash: HashValue) -> FutureResult<BlockReplayView> {
+        let storage = self.storage.clone();
+        let metrics = self.playground.metrics.clone();
+        let fut = async move {
+            let report = replay_block(storage, block_hash, metrics)?;
+            Ok(to_block_replay_view(report))
+        }
+        .map_err(map_err);
+        Box::pin(fut.boxed())
+    }
+
     fn txfactory_status(&self, action: FactoryAction) -> Result<bool> {
         Ok(TxFactoryStatusHandle::handle_action(action))
     }
 }
+
+fn to_block_replay_view(report: BlockReplayReport) -> BlockReplayView {
+    let matched = report.is_matched();
+    let txns = report
+        .txns
+        .into_iter()
+        .map(|txn| TransactionReplayView {
+            matched: txn.is_matched(),
+            txn_hash: txn.txn_hash,
+            recorded: txn.recorded.into(),
+            replayed: txn.replayed.map(Into::into),
+            discarded: txn.discarded.map(Into::into),
+            write_set_diffs: txn
+                .write_set_diffs
+                .into_iter()
+                .map(|diff| WriteSetEntryDiffView {
+                    state_key: diff.state_key,
+                    recorded: diff.recorded.map(StrView),
+                    replayed: diff.replayed.map(StrView),
+                })
+                .collect(),
+        })
+        .collect();
+    BlockReplayView {
+        block_hash: report.block_id,
+        block_number: report.block_number.into(),
+        matched,
+        recorded_state_root: report.recorded_state_root,
+        replayed_state_root: report.replayed_state_root,
+        txns,
+    }
+}
@@ -5,20 +5,29 @@ use crate::module::map_err;
 use futures::{FutureExt, TryFutureExt};
 use starcoin_miner::{MinerService, SubmitSealRequest, UpdateSubscriberNumRequest};
 use starcoin_rpc_api::miner::MinerApi;
-use starcoin_rpc_api::types::MintedBlockView;
+use starcoin_rpc_api::types::{MintedBlockView, StrView, StratumStatsView, StratumWorkerView};
 use starcoin_rpc_api::FutureResult;
 use starcoin_service_registry::ServiceRef;
+use starcoin_stratum::rpc::StratumStatsRequest;
+use starcoin_stratum::stratum::Stratum;
 use starcoin_types::block::BlockHeaderExtra;
 use starcoin_types::system_events::MintBlockEvent;
 use std::convert::TryInto;
 
 pub struct MinerRpcImpl {
     miner_service: ServiceRef<MinerService>,
+    stratum: Option<ServiceRef<Stratum>>,
 }
 
 impl MinerRpcImpl {
-    pub fn new(miner_service: ServiceRef<MinerService>) -> Self {
-        Self { miner_service }
+    pub fn new(
+        miner_service: ServiceRef<MinerService>,
+        stratum: Option<ServiceRef<Stratum>>,
+    ) -> Self {
+        Self {
+            miner_service,
+            stratum,
+        }
     }
 }
 
@@ -61,4 +70,39 @@ impl MinerApi for MinerRpcImpl {
         .map_err(map_err);
         Box::pin(fut.boxed())
     }
+
+    fn stratum_stats(&self) -> FutureResult<StratumStatsView> {
+        let stratum = self.stratum.clone();
+        let fut = async move {
+            let stratum =
+                stratum.ok_or_else(|| anyhow::anyhow!("Stratum service is not started"))?;
+            let stats = stratum.send(StratumStatsRequest).await?;
+            Ok(StratumStatsView {
+                accepted_shares: stats.accepted_shares,
+                rejected_shares: stats.rejected_shares,
+                stale_shares: stats.stale_shares,
+                blocks: stats.blocks,
+                hashrate: StrView(stats.hashrate),
+                workers: stats
+                    .workers
+                    .into_iter()
+                    .map(|worker| StratumWorkerView {
+                        login: worker.login,
+                        agent: worker.agent,
+                        extranonce: worker.extranonce,
+                        online: worker.online,
+                        share_difficulty: worker.share_difficulty,
+                        accepted_shares: worker.accepted_shares,
+                        rejected_shares: worker.rejected_shares,
+                        stale_shares: worker.stale_shares,
+                        blocks: worker.blocks,
+                        hashrate: StrView(worker.hashrate),
+                        last_share_time: worker.last_share_time.map(StrView),
+                    })
+                    .collect(),
+            })
+        }
+        .map_err(map_err);
+        Box::pin(fut.boxed())
+    }
 }
@@ -110,4 +110,24 @@ impl NetworkManagerApi for NetworkManagerRpcImpl {
         service.ban_peer(peer_id, ban);
         Ok(())
     }
+
+    fn allowed_peers(&self) -> FutureResult<Option<Vec<PeerId>>> {
+        let service = self.service.clone();
+        let fut = async move {
+            let result = service.allowed_peers().await;
+            Ok(result)
+        }
+        .map_err(map_err);
+        Box::pin(fut.boxed())
+    }
+
+    fn set_peer_allowed(&self, peer_id: String, allowed: bool) -> FutureResult<()> {
+        let service = self.service.clone();
+        let fut = async move {
+            let peer_id = PeerId::from_str(peer_id.as_str())?;
+            service.set_peer_allowed(peer_id, allowed).await
+        }
+        .map_err(map_err);
+        Box::pin(fut.boxed())
+    }
 }
@@ -500,6 +500,7 @@ impl EventHandler<Notification<ThinBlock>> for NewHeadHandler {
             body: block.body.into(),
             uncles: vec![],
             raw: None,
+            confirmation: None,
         })))]
     }
 }
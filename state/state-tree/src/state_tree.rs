@@ -1,7 +1,8 @@
 use anyhow::Result;
 use forkable_jellyfish_merkle::blob::Blob;
 use forkable_jellyfish_merkle::iterator::{JellyfishMerkleIntoIterator, JellyfishMerkleIterator};
-use forkable_jellyfish_merkle::node_type::{Node, NodeKey};
+use forkable_jellyfish_merkle::nibble::Nibble;
+use forkable_jellyfish_merkle::node_type::{InternalNode, Node, NodeKey};
 use forkable_jellyfish_merkle::proof::SparseMerkleProof;
 use forkable_jellyfish_merkle::{
     JellyfishMerkleTree, RawKey, StaleNodeIndex, TreeReader, TreeUpdateBatch,
@@ -265,6 +266,25 @@ where
         Ok(iterator)
     }
 
+    /// Diff the tree with the tree at `other_root`, returns every key whose value differs, as
+    /// (key, value in this tree, value in the other tree). Subtrees with the same hash are
+    /// skipped, so the cost is proportional to the changes instead of the tree size.
+    pub fn diff(
+        &self,
+        other_root: HashValue,
+    ) -> Result<Vec<(K, Option<Vec<u8>>, Option<Vec<u8>>)>> {
+        let cur_root_hash = self.root_hash();
+        let mut cache_guard = self.cache.lock();
+        let cache = cache_guard.deref_mut();
+        let reader = CachedTreeReader {
+            store: self.storage.as_ref(),
+            cache,
+        };
+        let mut diffs = vec![];
+        diff_nodes(&reader, cur_root_hash, other_root, &mut diffs)?;
+        Ok(diffs)
+    }
+
     /// passing None value with a key means delete the key
     fn updates(&self, updates: Vec<(K, Option<Blob>)>) -> Result<HashValue> {
         let cur_root_hash = self.root_hash();
@@ -340,6 +360,69 @@ where
     }
 }
 
+fn diff_nodes<K, R>(
+    reader: &R,
+    left: NodeKey,
+    right: NodeKey,
+    diffs: &mut Vec<(K, Option<Vec<u8>>, Option<Vec<u8>>)>,
+) -> Result<()>
+where
+    K: RawKey,
+    R: TreeReader<K>,
+{
+    if left == right {
+        return Ok(());
+    }
+    let left_node = reader.get_node(&left)?;
+    let right_node = reader.get_node(&right)?;
+    if let (Node::Internal(left_node), Node::Internal(right_node)) = (&left_node, &right_node) {
+        for nibble in 0..16u8 {
+            let nibble = Nibble::from(nibble);
+            let child_hash = |node: &InternalNode| {
+                node.child(nibble)
+                    .map(|child| child.hash)
+                    .unwrap_or(*SPARSE_MERKLE_PLACEHOLDER_HASH)
+            };
+            diff_nodes(reader, child_hash(left_node), child_hash(right_node), diffs)?;
+        }
+        return Ok(());
+    }
+    // One side is a leaf or null, the subtrees are small, so compare the leaves directly.
+    let mut left_leaves = BTreeMap::new();
+    collect_leaves(reader, left_node, &mut left_leaves)?;
+    let mut right_leaves = BTreeMap::new();
+    collect_leaves(reader, right_node, &mut right_leaves)?;
+    for (key, left_blob) in left_leaves {
+        let right_blob = right_leaves.remove(&key);
+        if right_blob.as_ref() != Some(&left_blob) {
+            diffs.push((key, Some(left_blob.into()), right_blob.map(Into::into)));
+        }
+    }
+    for (key, right_blob) in right_leaves {
+        diffs.push((key, None, Some(right_blob.into())));
+    }
+    Ok(())
+}
+
+fn collect_leaves<K, R>(reader: &R, node: Node<K>, leaves: &mut BTreeMap<K, Blob>) -> Result<()>
+where
+    K: RawKey,
+    R: TreeReader<K>,
+{
+    match node {
+        Node::Null => {}
+        Node::Leaf(leaf) => {
+            leaves.insert(leaf.raw_key().clone(), leaf.blob().clone());
+        }
+        Node::Internal(internal) => {
+            for child in internal.all_child() {
+                collect_leaves(reader, reader.get_node(&child)?, leaves)?;
+            }
+        }
+    }
+    Ok(())
+}
+
 struct CachedTreeReader<'a, K: RawKey> {
     store: &'a dyn StateNodeStore,
     cache: &'a StateCache<K>,
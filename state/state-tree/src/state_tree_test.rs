@@ -368,3 +368,44 @@ pub fn test_state_multi_commit_and_flush() -> Result<()> {
     assert_eq!(state.get(&hash_value3)?, None);
     Ok(())
 }
+
+#[test]
+pub fn test_state_tree_diff() -> Result<()> {
+    let store = Arc::new(MockStateNodeStore::new());
+    let state = StateTree::<HashValueKey>::new(store.clone(), None);
+    let hash_value: HashValueKey = HashValue::random().into();
+    let updated = update_nibble(&hash_value, 0, 1);
+    let removed = update_nibble(&hash_value, 0, 2);
+    // share the first nibble with `removed`, so the subtree shape changes.
+    let added = update_nibble(&removed, 1, 3);
+    let unchanged: Vec<_> = (4..16).map(|n| update_nibble(&hash_value, 0, n)).collect();
+
+    state.put(updated, vec![1]);
+    state.put(removed, vec![2]);
+    for key in &unchanged {
+        state.put(*key, vec![0]);
+    }
+    let root1 = state.commit()?;
+    state.flush()?;
+    assert!(state.diff(root1)?.is_empty());
+
+    state.put(updated, vec![11]);
+    state.remove(&removed);
+    state.put(added, vec![3]);
+    let root2 = state.commit()?;
+    state.flush()?;
+
+    let mut diffs = state.diff(root1)?;
+    diffs.sort_by(|a, b| a.0.cmp(&b.0));
+    let mut expected = vec![
+        (updated, Some(vec![11]), Some(vec![1])),
+        (removed, None, Some(vec![2])),
+        (added, Some(vec![3]), None),
+    ];
+    expected.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(diffs, expected);
+
+    let old_state = StateTree::<HashValueKey>::new(store, Some(root1));
+    assert_eq!(old_state.diff(root2)?.len(), 3);
+    Ok(())
+}
@@ -262,6 +262,63 @@ impl ChainStateDB {
         Self::new(self.store.clone(), Some(state_root))
     }
 
+    /// Diff the state with the state at `other_root`, returns every state entry whose value
+    /// differs, as (state key, value in this state, value in the other state).
+    /// The table handle root kept in the `TABLE_PATH` is expanded to the table items.
+    /// Both states should be flushed to the store.
+    pub fn diff(
+        &self,
+        other_root: HashValue,
+    ) -> Result<Vec<(StateKey, Option<Vec<u8>>, Option<Vec<u8>>)>> {
+        let mut diffs = vec![];
+        for (address, left, right) in self.state_tree.diff(other_root)? {
+            let left = left.map(|v| AccountState::decode(&v)).transpose()?;
+            let right = right.map(|v| AccountState::decode(&v)).transpose()?;
+            let code_diffs = self.diff_tree::<ModuleName>(
+                left.as_ref().and_then(|state| state.code_root()),
+                right.as_ref().and_then(|state| state.code_root()),
+            )?;
+            for (module_name, left, right) in code_diffs {
+                let access_path = AccessPath::new(address, DataPath::Code(module_name));
+                diffs.push((StateKey::AccessPath(access_path), left, right));
+            }
+            let resource_diffs = self.diff_tree::<StructTag>(
+                left.as_ref().map(|state| state.resource_root()),
+                right.as_ref().map(|state| state.resource_root()),
+            )?;
+            for (struct_tag, left, right) in resource_diffs {
+                let data_path = DataPath::Resource(struct_tag);
+                if address == table_handle_address() && data_path == *TABLE_PATH {
+                    continue;
+                }
+                let access_path = AccessPath::new(address, data_path);
+                diffs.push((StateKey::AccessPath(access_path), left, right));
+            }
+        }
+        let other = self.fork_at(other_root);
+        let handle_diffs = self
+            .state_tree_table_handles
+            .diff(other.state_tree_table_handles.root_hash())?;
+        for (handle, left, right) in handle_diffs {
+            let left = left.map(|v| HashValue::from_slice(&v)).transpose()?;
+            let right = right.map(|v| HashValue::from_slice(&v)).transpose()?;
+            for (key, left, right) in self.diff_tree::<Vec<u8>>(left, right)? {
+                diffs.push((StateKey::table_item(handle, key), left, right));
+            }
+        }
+        Ok(diffs)
+    }
+
+    fn diff_tree<K: RawKey>(
+        &self,
+        left: Option<HashValue>,
+        right: Option<HashValue>,
+    ) -> Result<Vec<(K, Option<Vec<u8>>, Option<Vec<u8>>)>> {
+        let left = left.unwrap_or(*SPARSE_MERKLE_PLACEHOLDER_HASH);
+        let right = right.unwrap_or(*SPARSE_MERKLE_PLACEHOLDER_HASH);
+        self.new_state_tree::<K>(left).diff(right)
+    }
+
     fn new_state_tree<K: RawKey>(&self, root_hash: HashValue) -> StateTree<K> {
         StateTree::new(self.store.clone(), Some(root_hash))
     }
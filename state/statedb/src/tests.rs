@@ -294,3 +294,41 @@ fn test_state_with_table_item_proof() -> Result<()> {
     state_with_table_item_proof3.verify(&handle2, key3.as_slice())?;
     Ok(())
 }
+
+#[test]
+fn test_state_db_diff() -> Result<()> {
+    let storage = MockStateNodeStore::new();
+    let chain_state_db = ChainStateDB::new(Arc::new(storage), None);
+    let updated = StateKey::AccessPath(AccessPath::random_resource());
+    let deleted = StateKey::AccessPath(AccessPath::random_code());
+    let table_item = StateKey::table_item(TableHandle(AccountAddress::random()), random_bytes());
+    chain_state_db.apply_write_set(state_keys_to_write_set(
+        vec![updated.clone(), deleted.clone()],
+        vec![vec![1], vec![2]],
+    ))?;
+    chain_state_db.commit()?;
+    chain_state_db.flush()?;
+    let state_root = chain_state_db.state_root();
+    assert!(chain_state_db.diff(state_root)?.is_empty());
+
+    let write_set = WriteSetMut::new(vec![
+        (updated.clone(), WriteOp::Value(vec![11])),
+        (deleted.clone(), WriteOp::Deletion),
+        (table_item.clone(), WriteOp::Value(vec![3])),
+    ])
+    .freeze()?;
+    chain_state_db.apply_write_set(write_set)?;
+    chain_state_db.commit()?;
+    chain_state_db.flush()?;
+
+    let mut diffs = chain_state_db.diff(state_root)?;
+    diffs.sort_by(|a, b| a.0.cmp(&b.0));
+    let mut expected = vec![
+        (updated, Some(vec![11]), Some(vec![1])),
+        (deleted, None, Some(vec![2])),
+        (table_item, Some(vec![3]), None),
+    ];
+    expected.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(diffs, expected);
+    Ok(())
+}
@@ -3,16 +3,19 @@
 use crate::define_storage;
 use crate::storage::{CodecKVStore, StorageInstance, ValueCodec};
 use crate::{
-    BLOCK_BODY_PREFIX_NAME, BLOCK_HEADER_PREFIX_NAME, BLOCK_PREFIX_NAME,
-    BLOCK_TRANSACTIONS_PREFIX_NAME, BLOCK_TRANSACTION_INFOS_PREFIX_NAME, FAILED_BLOCK_PREFIX_NAME,
+    BLOCK_BODY_PREFIX_NAME, BLOCK_HEADER_PREFIX_NAME, BLOCK_NUMBER_INDEX_PREFIX_NAME,
+    BLOCK_PREFIX_NAME, BLOCK_TRANSACTIONS_PREFIX_NAME, BLOCK_TRANSACTION_INFOS_PREFIX_NAME,
+    FAILED_BLOCK_PREFIX_NAME,
 };
 use anyhow::{bail, Result};
 use bcs_ext::{BCSCodec, Sample};
 use network_types::peer_info::PeerId;
+use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
 use starcoin_crypto::HashValue;
 use starcoin_logger::prelude::*;
-use starcoin_types::block::{Block, BlockBody, BlockHeader};
+use starcoin_types::block::{Block, BlockBody, BlockHeader, BlockNumber};
+use std::sync::Arc;
 
 #[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
 pub struct OldFailedBlock {
@@ -100,6 +103,12 @@ define_storage!(
     Vec<HashValue>,
     BLOCK_TRANSACTION_INFOS_PREFIX_NAME
 );
+define_storage!(
+    BlockNumberIndexStorage,
+    BlockNumber,
+    Vec<HashValue>,
+    BLOCK_NUMBER_INDEX_PREFIX_NAME
+);
 define_storage!(
     FailedBlockStorage,
     HashValue,
@@ -115,6 +124,9 @@ pub struct BlockStorage {
     block_txns_store: BlockTransactionsStorage,
     block_txn_infos_store: BlockTransactionInfosStorage,
     failed_block_storage: FailedBlockStorage,
+    block_number_index_store: BlockNumberIndexStorage,
+    /// Serialize the read-modify-write of the block number index.
+    block_number_index_lock: Arc<Mutex<()>>,
 }
 
 impl ValueCodec for Block {
@@ -174,7 +186,9 @@ impl BlockStorage {
             body_store: BlockBodyStorage::new(instance.clone()),
             block_txns_store: BlockTransactionsStorage::new(instance.clone()),
             block_txn_infos_store: BlockTransactionInfosStorage::new(instance.clone()),
-            failed_block_storage: FailedBlockStorage::new(instance),
+            failed_block_storage: FailedBlockStorage::new(instance.clone()),
+            block_number_index_store: BlockNumberIndexStorage::new(instance),
+            block_number_index_lock: Arc::new(Mutex::new(())),
         }
     }
     pub fn save(&self, block: Block) -> Result<()> {
@@ -217,6 +231,7 @@ impl BlockStorage {
 
     pub fn commit_block(&self, block: Block) -> Result<()> {
         let (header, _) = block.clone().into_inner();
+        self.index_block_number(header.number(), header.id())?;
         //save header
         self.save_header(header)?;
         // save block , no need body
@@ -225,6 +240,9 @@ impl BlockStorage {
         self.save(block)
     }
     pub fn delete_block(&self, block_id: HashValue) -> Result<()> {
+        if let Some(header) = self.header_store.get(block_id)? {
+            self.unindex_block_number(header.number(), block_id)?;
+        }
         self.header_store.remove(block_id)?;
         self.body_store.remove(block_id)?;
         self.block_store.remove(block_id)?;
@@ -240,6 +258,38 @@ impl BlockStorage {
         self.get(block_id)
     }
 
+    pub fn get_block_ids_by_number(&self, number: BlockNumber) -> Result<Vec<HashValue>> {
+        Ok(self
+            .block_number_index_store
+            .get(number)?
+            .unwrap_or_default())
+    }
+
+    pub(crate) fn index_block_number(
+        &self,
+        number: BlockNumber,
+        block_id: HashValue,
+    ) -> Result<()> {
+        let _guard = self.block_number_index_lock.lock();
+        let mut ids = self.get_block_ids_by_number(number)?;
+        if !ids.contains(&block_id) {
+            ids.push(block_id);
+            self.block_number_index_store.put(number, ids)?;
+        }
+        Ok(())
+    }
+
+    fn unindex_block_number(&self, number: BlockNumber, block_id: HashValue) -> Result<()> {
+        let _guard = self.block_number_index_lock.lock();
+        let mut ids = self.get_block_ids_by_number(number)?;
+        ids.retain(|id| *id != block_id);
+        if ids.is_empty() {
+            self.block_number_index_store.remove(number)
+        } else {
+            self.block_number_index_store.put(number, ids)
+        }
+    }
+
     pub fn get_transactions(&self, block_id: HashValue) -> Result<Vec<HashValue>> {
         match self.block_txns_store.get(block_id) {
             Ok(Some(transactions)) => Ok(transactions),
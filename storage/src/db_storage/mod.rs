@@ -44,12 +44,18 @@ impl DBStorage {
 
     pub fn open_with_cfs(
         root_path: impl AsRef<Path>,
-        column_families: Vec<ColumnFamilyName>,
+        mut column_families: Vec<ColumnFamilyName>,
         readonly: bool,
         rocksdb_config: RocksdbConfig,
         metrics: Option<StorageMetrics>,
     ) -> Result<Self> {
         let path = root_path.as_ref();
+        if readonly && Self::db_exists(path) {
+            // The column families added by the later storage versions do not exist in the db
+            // which is not upgraded yet, and can not be created in readonly mode.
+            let cf_vec = Self::list_cf(path)?;
+            column_families.retain(|cf| cf_vec.iter().any(|name| name == cf));
+        }
 
         let cfs_set: HashSet<_> = column_families.iter().collect();
         {
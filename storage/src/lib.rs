@@ -8,6 +8,8 @@ use crate::block::BlockStorage;
 use crate::block_info::{BlockInfoStorage, BlockInfoStore};
 use crate::chain_info::ChainInfoStorage;
 use crate::contract_event::ContractEventStorage;
+use crate::pool::{PoolStorage, PoolStore};
+use crate::reorg::{ReorgStorage, ReorgStore};
 use crate::state_node::StateStorage;
 use crate::storage::{CodecKVStore, CodecWriteBatch, ColumnFamilyName, StorageInstance};
 //use crate::table_info::{TableInfoStorage, TableInfoStore};
@@ -22,10 +24,10 @@ use starcoin_accumulator::AccumulatorTreeStore;
 use starcoin_crypto::HashValue;
 use starcoin_state_store_api::{StateNode, StateNodeStore};
 use starcoin_types::contract_event::ContractEvent;
-use starcoin_types::startup_info::{ChainInfo, ChainStatus, SnapshotRange};
+use starcoin_types::startup_info::{ChainInfo, ChainStatus, ReorgRecord, SnapshotRange};
 use starcoin_types::transaction::{RichTransactionInfo, Transaction};
 use starcoin_types::{
-    block::{Block, BlockBody, BlockHeader, BlockInfo},
+    block::{Block, BlockBody, BlockHeader, BlockInfo, BlockNumber},
     startup_info::StartupInfo,
 };
 //use starcoin_vm_types::state_store::table::{TableHandle, TableInfo};
@@ -45,6 +47,8 @@ pub mod contract_event;
 pub mod db_storage;
 pub mod errors;
 pub mod metrics;
+pub mod pool;
+pub mod reorg;
 pub mod state_node;
 pub mod storage;
 pub mod table_info;
@@ -76,6 +80,9 @@ pub const TRANSACTION_INFO_HASH_PREFIX_NAME: ColumnFamilyName = "transaction_inf
 pub const CONTRACT_EVENT_PREFIX_NAME: ColumnFamilyName = "contract_event";
 pub const FAILED_BLOCK_PREFIX_NAME: ColumnFamilyName = "failed_block";
 pub const TABLE_INFO_PREFIX_NAME: ColumnFamilyName = "table_info";
+pub const POOL_PREFIX_NAME: ColumnFamilyName = "pool";
+pub const REORG_PREFIX_NAME: ColumnFamilyName = "reorg";
+pub const BLOCK_NUMBER_INDEX_PREFIX_NAME: ColumnFamilyName = "block_number_index";
 
 ///db storage use prefix_name vec to init
 /// Please note that adding a prefix needs to be added in vec simultaneously, remember！！
@@ -141,17 +148,44 @@ static VEC_PREFIX_NAME_V3: Lazy<Vec<ColumnFamilyName>> = Lazy::new(|| {
         // TABLE_INFO_PREFIX_NAME,
     ]
 });
+
+static VEC_PREFIX_NAME_V4: Lazy<Vec<ColumnFamilyName>> = Lazy::new(|| {
+    vec![
+        BLOCK_ACCUMULATOR_NODE_PREFIX_NAME,
+        TRANSACTION_ACCUMULATOR_NODE_PREFIX_NAME,
+        BLOCK_PREFIX_NAME,
+        BLOCK_HEADER_PREFIX_NAME,
+        BLOCK_BODY_PREFIX_NAME, // unused column
+        BLOCK_INFO_PREFIX_NAME,
+        BLOCK_TRANSACTIONS_PREFIX_NAME,
+        BLOCK_TRANSACTION_INFOS_PREFIX_NAME,
+        STATE_NODE_PREFIX_NAME,
+        CHAIN_INFO_PREFIX_NAME,
+        TRANSACTION_PREFIX_NAME,
+        TRANSACTION_INFO_PREFIX_NAME, // unused column
+        TRANSACTION_INFO_PREFIX_NAME_V2,
+        TRANSACTION_INFO_HASH_PREFIX_NAME,
+        CONTRACT_EVENT_PREFIX_NAME,
+        FAILED_BLOCK_PREFIX_NAME,
+        // TABLE_INFO_PREFIX_NAME,
+        REORG_PREFIX_NAME,
+        POOL_PREFIX_NAME,
+        BLOCK_NUMBER_INDEX_PREFIX_NAME,
+    ]
+});
+
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, IntoPrimitive, TryFromPrimitive)]
 #[repr(u8)]
 pub enum StorageVersion {
     V1 = 1,
     V2 = 2,
     V3 = 3,
+    V4 = 4,
 }
 
 impl StorageVersion {
     pub fn current_version() -> StorageVersion {
-        StorageVersion::V3
+        StorageVersion::V4
     }
 
     pub fn get_column_family_names(&self) -> &'static [ColumnFamilyName] {
@@ -159,6 +193,7 @@ impl StorageVersion {
             StorageVersion::V1 => &VEC_PREFIX_NAME_V1,
             StorageVersion::V2 => &VEC_PREFIX_NAME_V2,
             StorageVersion::V3 => &VEC_PREFIX_NAME_V3,
+            StorageVersion::V4 => &VEC_PREFIX_NAME_V4,
         }
     }
 }
@@ -188,6 +223,9 @@ pub trait BlockStore {
 
     fn get_block_by_hash(&self, block_id: HashValue) -> Result<Option<Block>>;
 
+    /// Get the ids of all the blocks with the `number` saved in storage, include the branch blocks.
+    fn get_block_ids_by_number(&self, number: BlockNumber) -> Result<Vec<HashValue>>;
+
     fn save_block_transaction_ids(
         &self,
         block_id: HashValue,
@@ -274,6 +312,8 @@ pub struct Storage {
     block_info_storage: BlockInfoStorage,
     event_storage: ContractEventStorage,
     chain_info_storage: ChainInfoStorage,
+    pool_storage: PoolStorage,
+    reorg_storage: ReorgStorage,
     // table_info_storage: TableInfoStorage,
     // instance: StorageInstance,
 }
@@ -293,7 +333,9 @@ impl Storage {
                 AccumulatorStorage::new_transaction_accumulator_storage(instance.clone()),
             block_info_storage: BlockInfoStorage::new(instance.clone()),
             event_storage: ContractEventStorage::new(instance.clone()),
-            chain_info_storage: ChainInfoStorage::new(instance),
+            chain_info_storage: ChainInfoStorage::new(instance.clone()),
+            pool_storage: PoolStorage::new(instance.clone()),
+            reorg_storage: ReorgStorage::new(instance),
             // table_info_storage: TableInfoStorage::new(instance),
             // instance,
         };
@@ -326,6 +368,30 @@ impl StateNodeStore for Storage {
     }
 }
 
+impl PoolStore for Storage {
+    fn get_pool_state(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        self.pool_storage.get_pool_state(key)
+    }
+
+    fn save_pool_state(&self, key: &str, state: Vec<u8>) -> Result<()> {
+        self.pool_storage.save_pool_state(key, state)
+    }
+}
+
+impl ReorgStore for Storage {
+    fn get_reorg_count(&self) -> Result<u64> {
+        self.reorg_storage.get_reorg_count()
+    }
+
+    fn save_reorg(&self, record: ReorgRecord) -> Result<()> {
+        self.reorg_storage.save_reorg(record)
+    }
+
+    fn get_reorg(&self, index: u64) -> Result<Option<ReorgRecord>> {
+        self.reorg_storage.get_reorg(index)
+    }
+}
+
 impl Display for Storage {
     fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
         write!(f, "{}", self.clone())
@@ -405,6 +471,10 @@ impl BlockStore for Storage {
         self.block_storage.get_block_by_hash(block_id)
     }
 
+    fn get_block_ids_by_number(&self, number: BlockNumber) -> Result<Vec<HashValue>> {
+        self.block_storage.get_block_ids_by_number(number)
+    }
+
     fn save_block_transaction_ids(
         &self,
         block_id: HashValue,
@@ -572,6 +642,7 @@ pub trait Store:
     + TransactionStore
     + BlockTransactionInfoStore
     + ContractEventStore
+    + ReorgStore
     + IntoSuper<dyn StateNodeStore>
 {
     fn get_transaction_info_by_block_and_index(
@@ -0,0 +1,24 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::storage::CodecKVStore;
+use crate::{define_storage, POOL_PREFIX_NAME};
+use anyhow::Result;
+
+define_storage!(PoolStorage, Vec<u8>, Vec<u8>, POOL_PREFIX_NAME);
+
+/// The state of the mining pool service, the value is encoded by the pool service.
+pub trait PoolStore {
+    fn get_pool_state(&self, key: &str) -> Result<Option<Vec<u8>>>;
+    fn save_pool_state(&self, key: &str, state: Vec<u8>) -> Result<()>;
+}
+
+impl PoolStore for PoolStorage {
+    fn get_pool_state(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        self.get(key.as_bytes().to_vec())
+    }
+
+    fn save_pool_state(&self, key: &str, state: Vec<u8>) -> Result<()> {
+        self.put(key.as_bytes().to_vec(), state)
+    }
+}
@@ -0,0 +1,60 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::storage::{CodecKVStore, KVStore, KeyCodec, SchemaStorage, ValueCodec, WriteBatch};
+use crate::{define_storage, REORG_PREFIX_NAME};
+use anyhow::Result;
+use bcs_ext::BCSCodec;
+use starcoin_types::startup_info::ReorgRecord;
+use std::convert::TryInto;
+
+define_storage!(ReorgStorage, u64, ReorgRecord, REORG_PREFIX_NAME);
+
+impl ValueCodec for ReorgRecord {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        self.encode()
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Self::decode(data)
+    }
+}
+
+/// The reorg journal of the main chain, the records are indexed from 0 in order.
+pub trait ReorgStore {
+    /// The count of the records in the journal, also the index of the next record.
+    fn get_reorg_count(&self) -> Result<u64>;
+    /// Save the record at `record.index` and update the count of the records in one batch.
+    fn save_reorg(&self, record: ReorgRecord) -> Result<()>;
+    fn get_reorg(&self, index: u64) -> Result<Option<ReorgRecord>>;
+}
+
+impl ReorgStorage {
+    /// The count is kept in the same column family as the records, so that they are written in
+    /// one batch, its key never collides with the 8 bytes key of a record.
+    const REORG_COUNT_KEY: &'static str = "reorg_count";
+
+    pub fn get_reorg(&self, index: u64) -> Result<Option<ReorgRecord>> {
+        self.get(index)
+    }
+
+    pub fn get_reorg_count(&self) -> Result<u64> {
+        KVStore::get(self.get_store(), Self::REORG_COUNT_KEY.as_bytes()).and_then(|bytes| {
+            match bytes {
+                Some(bytes) => Ok(u64::from_be_bytes(bytes.as_slice().try_into()?)),
+                None => Ok(0),
+            }
+        })
+    }
+
+    pub fn save_reorg(&self, record: ReorgRecord) -> Result<()> {
+        let count = record.index.saturating_add(1).max(self.get_reorg_count()?);
+        let mut batch = WriteBatch::new();
+        batch.put(record.index.encode_key()?, record.encode_value()?)?;
+        batch.put(
+            Self::REORG_COUNT_KEY.as_bytes().to_vec(),
+            count.to_be_bytes().to_vec(),
+        )?;
+        KVStore::write_batch_sync(self.get_store(), batch)
+    }
+}
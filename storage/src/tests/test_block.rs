@@ -11,7 +11,8 @@ use crate::block::{FailedBlock, OldFailedBlock};
 use crate::cache_storage::CacheStorage;
 use crate::db_storage::DBStorage;
 use crate::storage::StorageInstance;
-use crate::Storage;
+use crate::upgrade::DBUpgrade;
+use crate::{Storage, StorageVersion};
 use starcoin_config::RocksdbConfig;
 use starcoin_types::account_address::AccountAddress;
 use starcoin_types::block::{Block, BlockBody, BlockHeader, BlockHeaderExtra};
@@ -132,6 +133,103 @@ fn test_block_number() {
     assert_eq!(block1, block2.unwrap());
 }
 
+fn new_block_with_number(number: u64) -> Block {
+    let header = BlockHeader::new(
+        HashValue::random(),
+        Local::now().timestamp_nanos() as u64,
+        number,
+        AccountAddress::random(),
+        HashValue::zero(),
+        HashValue::random(),
+        HashValue::zero(),
+        0,
+        U256::zero(),
+        HashValue::random(),
+        ChainId::test(),
+        0,
+        BlockHeaderExtra::new([0u8; 4]),
+    );
+    Block::new(header, BlockBody::new(vec![], None))
+}
+
+#[test]
+fn test_block_number_index() {
+    let tmpdir = starcoin_config::temp_dir();
+    let storage = Storage::new(StorageInstance::new_cache_and_db_instance(
+        CacheStorage::new(None),
+        DBStorage::new(tmpdir.path(), RocksdbConfig::default(), None).unwrap(),
+    ))
+    .unwrap();
+    let block1 = new_block_with_number(1);
+    let block2 = new_block_with_number(1);
+    let block3 = new_block_with_number(2);
+    for block in [&block1, &block2, &block3, &block1] {
+        storage.block_storage.commit_block(block.clone()).unwrap();
+    }
+    assert_eq!(
+        storage.block_storage.get_block_ids_by_number(1).unwrap(),
+        vec![block1.id(), block2.id()]
+    );
+    assert_eq!(
+        storage.block_storage.get_block_ids_by_number(2).unwrap(),
+        vec![block3.id()]
+    );
+    assert!(storage
+        .block_storage
+        .get_block_ids_by_number(3)
+        .unwrap()
+        .is_empty());
+
+    storage.block_storage.delete_block(block1.id()).unwrap();
+    assert_eq!(
+        storage.block_storage.get_block_ids_by_number(1).unwrap(),
+        vec![block2.id()]
+    );
+    storage.block_storage.delete_block(block3.id()).unwrap();
+    assert!(storage
+        .block_storage
+        .get_block_ids_by_number(2)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn test_block_number_index_upgrade() {
+    let tmpdir = starcoin_config::temp_dir();
+    let mut instance = StorageInstance::new_cache_and_db_instance(
+        CacheStorage::new(None),
+        DBStorage::new(tmpdir.path(), RocksdbConfig::default(), None).unwrap(),
+    );
+    let storage = Storage::new(instance.clone()).unwrap();
+    let block1 = new_block_with_number(1);
+    let block2 = new_block_with_number(1);
+    let block3 = new_block_with_number(2);
+    // the blocks saved by a V3 storage, which has no block number index.
+    for block in [&block1, &block2, &block3] {
+        storage
+            .block_storage
+            .save_header(block.header().clone())
+            .unwrap();
+        storage.block_storage.save(block.clone()).unwrap();
+    }
+    assert!(storage
+        .block_storage
+        .get_block_ids_by_number(1)
+        .unwrap()
+        .is_empty());
+
+    DBUpgrade::do_upgrade(StorageVersion::V3, StorageVersion::V4, &mut instance).unwrap();
+    let mut ids = storage.block_storage.get_block_ids_by_number(1).unwrap();
+    ids.sort();
+    let mut expect = vec![block1.id(), block2.id()];
+    expect.sort();
+    assert_eq!(ids, expect);
+    assert_eq!(
+        storage.block_storage.get_block_ids_by_number(2).unwrap(),
+        vec![block3.id()]
+    );
+}
+
 #[test]
 fn test_old_failed_block_decode() {
     let dt = Local::now();
@@ -5,12 +5,14 @@ extern crate chrono;
 
 use crate::cache_storage::CacheStorage;
 use crate::db_storage::DBStorage;
+use crate::pool::PoolStore;
+use crate::reorg::ReorgStore;
 use crate::storage::{CodecKVStore, InnerStore, StorageInstance, ValueCodec};
 use crate::transaction_info::{BlockTransactionInfo, OldTransactionInfoStorage};
 use crate::{
     BlockInfoStore, BlockStore, BlockTransactionInfoStore, Storage,
     StorageVersion, /*TableInfoStore,*/
-    TransactionStore, DEFAULT_PREFIX_NAME, TRANSACTION_INFO_PREFIX_NAME,
+    TransactionStore, DEFAULT_PREFIX_NAME, POOL_PREFIX_NAME, TRANSACTION_INFO_PREFIX_NAME,
     TRANSACTION_INFO_PREFIX_NAME_V2,
 };
 use anyhow::Result;
@@ -19,7 +21,7 @@ use starcoin_config::RocksdbConfig;
 use starcoin_crypto::HashValue;
 use starcoin_types::block::{Block, BlockBody, BlockHeader, BlockInfo};
 //use starcoin_types::language_storage::TypeTag;
-use starcoin_types::startup_info::SnapshotRange;
+use starcoin_types::startup_info::{ReorgRecord, SnapshotRange};
 use starcoin_types::transaction::{
     RichTransactionInfo, SignedUserTransaction, Transaction, TransactionInfo,
 };
@@ -76,6 +78,42 @@ fn test_open_read_only() {
     assert_eq!(result, Some(value.to_vec()));
 }
 
+#[test]
+fn test_open_read_only_old_version() {
+    let tmpdir = starcoin_config::temp_dir();
+    let path = tmpdir.as_ref().join("starcoindb");
+    let key = HashValue::random();
+    let value = HashValue::zero();
+    {
+        let db = DBStorage::open_with_cfs(
+            path.clone(),
+            StorageVersion::V3.get_column_family_names().to_vec(),
+            false,
+            RocksdbConfig::default(),
+            None,
+        )
+        .unwrap();
+        db.put(DEFAULT_PREFIX_NAME, key.to_vec(), value.to_vec())
+            .unwrap();
+    }
+    // The db is not upgraded yet, the column families of the current version are skipped.
+    let db = DBStorage::open_with_cfs(
+        path,
+        StorageVersion::current_version()
+            .get_column_family_names()
+            .to_vec(),
+        true,
+        RocksdbConfig::default(),
+        None,
+    )
+    .unwrap();
+    assert_eq!(
+        db.get(DEFAULT_PREFIX_NAME, key.to_vec()).unwrap(),
+        Some(value.to_vec())
+    );
+    assert!(db.get(POOL_PREFIX_NAME, key.to_vec()).is_err());
+}
+
 #[test]
 fn test_storage() {
     let tmpdir = starcoin_config::temp_dir();
@@ -391,6 +429,71 @@ pub fn test_snapshot_range() -> Result<()> {
     Ok(())
 }
 
+#[test]
+pub fn test_pool_state() -> Result<()> {
+    let tmpdir = starcoin_config::temp_dir();
+    {
+        let instance = StorageInstance::new_cache_and_db_instance(
+            CacheStorage::new(None),
+            DBStorage::new(tmpdir.path(), RocksdbConfig::default(), None)?,
+        );
+        let storage = Storage::new(instance)?;
+        assert!(storage.get_pool_state("state")?.is_none());
+        storage.save_pool_state("state", vec![1, 2, 3])?;
+    }
+    let instance = StorageInstance::new_db_instance(DBStorage::new(
+        tmpdir.path(),
+        RocksdbConfig::default(),
+        None,
+    )?);
+    let storage = Storage::new(instance)?;
+    assert_eq!(storage.get_pool_state("state")?, Some(vec![1, 2, 3]));
+    Ok(())
+}
+
+#[test]
+pub fn test_reorg_journal() -> Result<()> {
+    let tmpdir = starcoin_config::temp_dir();
+    let record = ReorgRecord {
+        index: 0,
+        timestamp: 1000,
+        old_head: HashValue::random(),
+        old_head_number: 10,
+        new_head: HashValue::random(),
+        new_head_number: 11,
+        common_ancestor: HashValue::random(),
+        common_ancestor_number: 8,
+        depth: 2,
+        retracted_txns: vec![HashValue::random()],
+        enacted_txns: vec![],
+        refused: false,
+    };
+    {
+        let instance = StorageInstance::new_cache_and_db_instance(
+            CacheStorage::new(None),
+            DBStorage::new(tmpdir.path(), RocksdbConfig::default(), None)?,
+        );
+        let storage = Storage::new(instance)?;
+        assert_eq!(storage.get_reorg_count()?, 0);
+        storage.save_reorg(record.clone())?;
+        storage.save_reorg(ReorgRecord {
+            index: 1,
+            ..record.clone()
+        })?;
+    }
+    let instance = StorageInstance::new_db_instance(DBStorage::new(
+        tmpdir.path(),
+        RocksdbConfig::default(),
+        None,
+    )?);
+    let storage = Storage::new(instance)?;
+    assert_eq!(storage.get_reorg_count()?, 2);
+    assert_eq!(storage.get_reorg(0)?, Some(record));
+    assert_eq!(storage.get_reorg(1)?.map(|record| record.index), Some(1));
+    assert!(storage.get_reorg(2)?.is_none());
+    Ok(())
+}
+
 #[test]
 pub fn test_cache_evict_multi_get() -> Result<()> {
     let tmpdir = starcoin_config::temp_dir();
@@ -163,6 +163,23 @@ impl DBUpgrade {
         Ok(())
     }
 
+    fn db_upgrade_v3_v4(instance: &mut StorageInstance) -> Result<()> {
+        let block_storage = BlockStorage::new(instance.clone());
+        let mut iter = block_storage.header_store.iter()?;
+        iter.seek_to_first();
+        let mut processed_count = 0;
+        for item in iter {
+            let (id, block_header) = item?;
+            block_storage.index_block_number(block_header.number(), id)?;
+            processed_count += 1;
+            if processed_count % 10000 == 0 {
+                info!("index block number processed items: {}", processed_count);
+            }
+        }
+        info!("index block number of {} blocks", processed_count);
+        Ok(())
+    }
+
     pub fn do_upgrade(
         version_in_db: StorageVersion,
         version_in_code: StorageVersion,
@@ -185,6 +202,21 @@ impl DBUpgrade {
             (StorageVersion::V2, StorageVersion::V3) => {
                 Self::db_upgrade_v2_v3(instance)?;
             }
+
+            (StorageVersion::V1, StorageVersion::V4) => {
+                Self::db_upgrade_v1_v2(instance)?;
+                Self::db_upgrade_v2_v3(instance)?;
+                Self::db_upgrade_v3_v4(instance)?;
+            }
+
+            (StorageVersion::V2, StorageVersion::V4) => {
+                Self::db_upgrade_v2_v3(instance)?;
+                Self::db_upgrade_v3_v4(instance)?;
+            }
+
+            (StorageVersion::V3, StorageVersion::V4) => {
+                Self::db_upgrade_v3_v4(instance)?;
+            }
             _ => bail!(
                 "Can not upgrade db from {:?} to {:?}",
                 version_in_db,
@@ -1,8 +1,11 @@
 use starcoin_types::U256;
 
+pub mod pool;
 pub mod rpc;
 pub mod service;
+pub mod stats;
 pub mod stratum;
+pub mod vardiff;
 pub use crate::rpc::gen_client::Client as StratumRpcClient;
 pub use anyhow::Result;
 
@@ -0,0 +1,625 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The optional mining pool service, records the accepted shares of the stratum workers,
+//! credits the block rewards of the pool account to the workers by PPLNS or PPS,
+//! and pays the workers by batch transfer transactions.
+//! The block rewards and the payouts are only accounted after `CONFIRMATIONS` blocks,
+//! so a reorg of the recent blocks does not credit the rewards of the retracted blocks.
+
+use crate::stratum::ShareAcceptedEvent;
+use anyhow::{format_err, Result};
+use bcs_ext::BCSCodec;
+use futures::executor::block_on;
+use serde::{Deserialize, Serialize};
+use starcoin_account_api::AccountAsyncService;
+use starcoin_account_service::AccountService;
+use starcoin_config::{NodeConfig, PoolPayoutMode, StratumConfig};
+use starcoin_crypto::HashValue;
+use starcoin_logger::prelude::*;
+use starcoin_service_registry::{
+    ActorService, EventHandler, ServiceContext, ServiceFactory, ServiceHandler, ServiceRef,
+    ServiceRequest,
+};
+use starcoin_state_api::ChainStateAsyncService;
+use starcoin_state_service::ChainStateService;
+use starcoin_storage::pool::PoolStore;
+use starcoin_storage::{BlockStore, BlockTransactionInfoStore, ContractEventStore, Storage};
+use starcoin_transaction_builder::build_batch_script_function;
+use starcoin_txpool::TxPoolService;
+use starcoin_txpool_api::TxPoolSyncService;
+use starcoin_types::account_address::AccountAddress;
+use starcoin_types::account_config::{AccountResource, BlockRewardEvent};
+use starcoin_types::block::BlockHeader;
+use starcoin_types::system_events::NewHeadBlock;
+use starcoin_types::transaction::{RawUserTransaction, TransactionPayload};
+use starcoin_types::vm_error::KeptVMStatus;
+use starcoin_types::U256;
+use std::collections::{BTreeMap, VecDeque};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+const POOL_STATE_KEY: &str = "pool_state";
+/// The max payees of one batch transfer transaction.
+const MAX_PAYEES_PER_TXN: usize = 100;
+/// The max block headers to catch up for the reward events after a restart.
+const MAX_CATCH_UP_BLOCKS: u64 = 100;
+/// The blocks are processed after they have this many confirmations on the main chain.
+const CONFIRMATIONS: u64 = 6;
+const PERSIST_INTERVAL: Duration = Duration::from_secs(10);
+const PAYOUT_MAX_GAS_AMOUNT: u64 = 10_000_000;
+const PAYOUT_TXN_EXPIRATION_SECS: u64 = 60 * 60;
+const FEE_BASIS: u64 = 10000;
+
+#[derive(Clone, Debug)]
+pub struct PoolConfig {
+    pub payout_mode: PoolPayoutMode,
+    /// The pool fee in basis points.
+    pub fee: u64,
+    pub pplns_window: u64,
+    pub payout_threshold: u128,
+    pub payout_interval: Duration,
+}
+
+impl PoolConfig {
+    pub fn new(config: &StratumConfig) -> Option<Self> {
+        config.pool_payout().map(|payout_mode| Self {
+            payout_mode,
+            fee: config.pool_fee(),
+            pplns_window: config.pplns_window(),
+            payout_threshold: config.pool_payout_threshold(),
+            payout_interval: config.pool_payout_interval(),
+        })
+    }
+
+    fn deduct_fee(&self, amount: u128) -> u128 {
+        mul_div(
+            amount,
+            U256::from(FEE_BASIS - self.fee),
+            U256::from(FEE_BASIS),
+        )
+    }
+}
+
+/// `amount * numerator / denominator`, the result is truncated.
+fn mul_div(amount: u128, numerator: U256, denominator: U256) -> u128 {
+    if denominator.is_zero() {
+        return 0;
+    }
+    let result = U256::from(amount).saturating_mul(numerator) / denominator;
+    if result > U256::from(u128::max_value()) {
+        u128::max_value()
+    } else {
+        result.as_u128()
+    }
+}
+
+/// A payout txn submitted to the txpool, the balances are deducted after it is executed and confirmed.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingPayout {
+    pub txn_hash: HashValue,
+    /// The expiration timestamp of the txn in seconds.
+    pub expiration: u64,
+    /// The paid logins and amounts of the txn.
+    pub payees: Vec<(String, u128)>,
+}
+
+/// The persistent state of the mining pool.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PoolState {
+    /// The last shares in the PPLNS window, (login, share difficulty).
+    pub shares: VecDeque<(String, U256)>,
+    /// The PPLNS window of the blocks found by the pool but not rewarded yet, by block number.
+    pub pending_blocks: BTreeMap<u64, BTreeMap<String, U256>>,
+    /// The unpaid balance of the workers in nanoSTC.
+    pub balances: BTreeMap<String, u128>,
+    /// The total paid amount of the workers in nanoSTC.
+    pub paid: BTreeMap<String, u128>,
+    /// The payout txns which are not confirmed yet, their payees are not paid again until then.
+    pub pending_payouts: Vec<PendingPayout>,
+    /// The latest block reward on chain, used to estimate the reward of a share in PPS mode.
+    pub last_block_reward: u128,
+    /// The number of the last confirmed block whose reward events and payout txns are processed.
+    pub last_processed_block: u64,
+}
+
+impl PoolState {
+    pub fn record_share(&mut self, config: &PoolConfig, share: &ShareAcceptedEvent) {
+        match config.payout_mode {
+            PoolPayoutMode::Pplns => {
+                self.shares
+                    .push_back((share.login.clone(), share.share_difficulty));
+                while self.shares.len() as u64 > config.pplns_window {
+                    self.shares.pop_front();
+                }
+                if let Some(block_number) = share.block_number {
+                    self.pending_blocks
+                        .insert(block_number, self.window_weights());
+                }
+            }
+            PoolPayoutMode::Pps => {
+                let reward = config.deduct_fee(mul_div(
+                    self.last_block_reward,
+                    share.share_difficulty,
+                    share.network_difficulty,
+                ));
+                self.credit(&share.login, reward);
+            }
+        }
+    }
+
+    /// Credit the reward of a block mined by the pool account.
+    pub fn record_block_reward(&mut self, config: &PoolConfig, block_number: u64, reward: u128) {
+        // The rewards are in order of block number, the pending blocks before it are orphaned.
+        let pending = self.pending_blocks.remove(&block_number);
+        self.pending_blocks = self.pending_blocks.split_off(&block_number);
+        if config.payout_mode == PoolPayoutMode::Pps {
+            // The workers are paid by shares, the block reward belongs to the pool.
+            return;
+        }
+        let weights = pending.unwrap_or_else(|| self.window_weights());
+        let total_weight = weights
+            .values()
+            .fold(U256::zero(), |total, weight| total.saturating_add(*weight));
+        let reward = config.deduct_fee(reward);
+        for (login, weight) in weights {
+            self.credit(&login, mul_div(reward, weight, total_weight));
+        }
+    }
+
+    /// The balances reach the payout threshold, the logins which are not an address,
+    /// or have a pending payout, are skipped.
+    pub fn payouts(&self, config: &PoolConfig) -> Vec<(String, AccountAddress, u128)> {
+        self.balances
+            .iter()
+            .filter(|(_, balance)| **balance > 0 && **balance >= config.payout_threshold)
+            .filter(|(login, _)| {
+                !self
+                    .pending_payouts
+                    .iter()
+                    .any(|payout| payout.payees.iter().any(|(payee, _)| payee == *login))
+            })
+            .filter_map(|(login, balance)| {
+                AccountAddress::from_str(login)
+                    .ok()
+                    .map(|address| (login.clone(), address, *balance))
+            })
+            .collect()
+    }
+
+    /// Record the payout txn submitted to the txpool.
+    pub fn add_pending_payout(
+        &mut self,
+        txn_hash: HashValue,
+        expiration: u64,
+        payouts: &[(String, AccountAddress, u128)],
+    ) {
+        self.pending_payouts.push(PendingPayout {
+            txn_hash,
+            expiration,
+            payees: payouts
+                .iter()
+                .map(|(login, _, amount)| (login.clone(), *amount))
+                .collect(),
+        });
+    }
+
+    /// The txn is confirmed on the main chain, deduct the balances of the payees if it is executed.
+    /// A failed payout txn pays nothing, its payees are paid again by the next payout.
+    pub fn confirm_payout(&mut self, txn_hash: HashValue, executed: bool) {
+        let index = match self
+            .pending_payouts
+            .iter()
+            .position(|payout| payout.txn_hash == txn_hash)
+        {
+            Some(index) => index,
+            None => return,
+        };
+        let payout = self.pending_payouts.remove(index);
+        if !executed {
+            return;
+        }
+        for (login, amount) in payout.payees {
+            if let Some(balance) = self.balances.get_mut(&login) {
+                *balance = balance.saturating_sub(amount);
+                if *balance == 0 {
+                    self.balances.remove(&login);
+                }
+            }
+            let paid = self.paid.entry(login).or_default();
+            *paid = paid.saturating_add(amount);
+        }
+    }
+
+    /// Drop the pending payouts which are expired at the `timestamp` of a confirmed block,
+    /// they can not be included by the later blocks.
+    pub fn expire_payouts(&mut self, timestamp: u64) {
+        self.pending_payouts
+            .retain(|payout| payout.expiration >= timestamp);
+    }
+
+    fn credit(&mut self, login: &str, amount: u128) {
+        if amount == 0 {
+            return;
+        }
+        let balance = self.balances.entry(login.to_string()).or_default();
+        *balance = balance.saturating_add(amount);
+    }
+
+    fn window_weights(&self) -> BTreeMap<String, U256> {
+        let mut weights = BTreeMap::<String, U256>::new();
+        for (login, difficulty) in &self.shares {
+            let weight = weights.entry(login.clone()).or_default();
+            *weight = weight.saturating_add(*difficulty);
+        }
+        weights
+    }
+}
+
+#[derive(Debug)]
+struct PayoutEvent;
+
+#[derive(Debug)]
+struct PersistEvent;
+
+/// Get the state of the mining pool.
+#[derive(Debug)]
+pub struct PoolStateRequest;
+
+impl ServiceRequest for PoolStateRequest {
+    type Response = PoolState;
+}
+
+pub struct PoolService {
+    config: PoolConfig,
+    state: PoolState,
+    dirty: bool,
+    node_config: Arc<NodeConfig>,
+    storage: Arc<Storage>,
+    txpool: TxPoolService,
+    account_service: ServiceRef<AccountService>,
+    chain_state_service: ServiceRef<ChainStateService>,
+}
+
+impl PoolService {
+    fn persist(&mut self) -> Result<()> {
+        if self.dirty {
+            self.storage
+                .save_pool_state(POOL_STATE_KEY, self.state.encode()?)?;
+            self.dirty = false;
+        }
+        Ok(())
+    }
+
+    /// The pool account is the default account, which is the author of the blocks mined by stratum.
+    fn pool_address(&self) -> Result<AccountAddress> {
+        block_on(self.account_service.get_default_account())?
+            .map(|account| account.address)
+            .ok_or_else(|| format_err!("The default account of the pool does not exist."))
+    }
+
+    /// Process the reward events and the payout txns of the blocks confirmed by the `head`.
+    fn process_confirmed_blocks(&mut self, head: &BlockHeader) -> Result<()> {
+        let confirmed = head.number().saturating_sub(CONFIRMATIONS);
+        if confirmed <= self.state.last_processed_block {
+            return Ok(());
+        }
+        let pool_address = self.pool_address()?;
+        // Collect the headers not processed, include the blocks connected while the service is stopped.
+        // The headers are collected from the head backward, so only the blocks on the main chain are processed.
+        let first = confirmed
+            .saturating_sub(MAX_CATCH_UP_BLOCKS)
+            .max(self.state.last_processed_block + 1);
+        let mut headers = vec![];
+        let mut next = Some(head.clone());
+        while let Some(header) = next.take() {
+            if header.number() < first {
+                break;
+            }
+            if header.number() > first {
+                next = self
+                    .storage
+                    .get_block_header_by_hash(header.parent_hash())?;
+            }
+            if header.number() <= confirmed {
+                headers.push(header);
+            }
+        }
+        for header in headers.into_iter().rev() {
+            for txn_info_id in self.storage.get_block_txn_info_ids(header.id())? {
+                if let Some(txn_info) = self.storage.get_transaction_info(txn_info_id)? {
+                    self.state.confirm_payout(
+                        txn_info.transaction_hash(),
+                        txn_info.status() == &KeptVMStatus::Executed,
+                    );
+                }
+                let events = self
+                    .storage
+                    .get_contract_events(txn_info_id)?
+                    .unwrap_or_default();
+                for event in events {
+                    if !event.is::<BlockRewardEvent>() {
+                        continue;
+                    }
+                    let reward = event.decode_event::<BlockRewardEvent>()?;
+                    self.state.last_block_reward = reward.block_reward;
+                    if reward.miner == pool_address {
+                        let amount = reward.block_reward.saturating_add(reward.gas_fees);
+                        info!(target: "stratum", "pool credit reward {} of block {}", amount, reward.block_number);
+                        self.state
+                            .record_block_reward(&self.config, reward.block_number, amount);
+                    }
+                }
+            }
+            self.state.expire_payouts(header.timestamp() / 1000);
+            self.state.last_processed_block = header.number();
+        }
+        self.dirty = true;
+        Ok(())
+    }
+
+    fn payout(&mut self) -> Result<()> {
+        let payouts = self.state.payouts(&self.config);
+        if payouts.is_empty() {
+            return Ok(());
+        }
+        let sender = self.pool_address()?;
+        let mut sequence_number = match self.txpool.next_sequence_number(sender) {
+            Some(sequence_number) => sequence_number,
+            None => block_on(
+                self.chain_state_service
+                    .clone()
+                    .get_resource::<AccountResource>(sender),
+            )?
+            .map(|resource| resource.sequence_number())
+            .ok_or_else(|| format_err!("Can not find pool account {} on chain", sender))?,
+        };
+        for batch in payouts.chunks(MAX_PAYEES_PER_TXN) {
+            match self.submit_payout(sender, sequence_number, batch) {
+                Ok((txn_hash, expiration)) => {
+                    self.state.add_pending_payout(txn_hash, expiration, batch);
+                    self.dirty = true;
+                    sequence_number += 1;
+                }
+                Err(e) => {
+                    error!(target: "stratum", "pool submit payout txn failed: {}", e);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Submit the payout txn to the txpool, return the hash and the expiration of the txn.
+    fn submit_payout(
+        &self,
+        sender: AccountAddress,
+        sequence_number: u64,
+        batch: &[(String, AccountAddress, u128)],
+    ) -> Result<(HashValue, u64)> {
+        let (receivers, amounts) = batch
+            .iter()
+            .map(|(_, address, amount)| (*address, *amount))
+            .unzip();
+        let net = self.node_config.net();
+        let expiration = net.time_service().now_secs() + PAYOUT_TXN_EXPIRATION_SECS;
+        let raw_txn = RawUserTransaction::new_with_default_gas_token(
+            sender,
+            sequence_number,
+            TransactionPayload::ScriptFunction(build_batch_script_function(receivers, amounts)),
+            PAYOUT_MAX_GAS_AMOUNT,
+            1,
+            expiration,
+            net.chain_id(),
+        );
+        let txn = block_on(self.account_service.sign_txn(raw_txn, sender))?;
+        let txn_hash = txn.id();
+        self.txpool
+            .add_txns(vec![txn])
+            .pop()
+            .ok_or_else(|| format_err!("Add payout txn to txpool without result"))??;
+        info!(target: "stratum", "pool submit payout txn {} to {} workers", txn_hash, batch.len());
+        Ok((txn_hash, expiration))
+    }
+}
+
+impl ActorService for PoolService {
+    fn started(&mut self, ctx: &mut ServiceContext<Self>) -> Result<()> {
+        ctx.subscribe::<ShareAcceptedEvent>();
+        ctx.subscribe::<NewHeadBlock>();
+        ctx.run_interval(self.config.payout_interval, |ctx| ctx.notify(PayoutEvent));
+        ctx.run_interval(PERSIST_INTERVAL, |ctx| ctx.notify(PersistEvent));
+        Ok(())
+    }
+
+    fn stopped(&mut self, ctx: &mut ServiceContext<Self>) -> Result<()> {
+        ctx.unsubscribe::<ShareAcceptedEvent>();
+        ctx.unsubscribe::<NewHeadBlock>();
+        self.persist()
+    }
+}
+
+impl EventHandler<Self, ShareAcceptedEvent> for PoolService {
+    fn handle_event(&mut self, msg: ShareAcceptedEvent, _ctx: &mut ServiceContext<Self>) {
+        self.state.record_share(&self.config, &msg);
+        self.dirty = true;
+        if msg.block_number.is_some() {
+            if let Err(e) = self.persist() {
+                error!(target: "stratum", "pool persist state failed: {}", e);
+            }
+        }
+    }
+}
+
+impl EventHandler<Self, NewHeadBlock> for PoolService {
+    fn handle_event(&mut self, msg: NewHeadBlock, _ctx: &mut ServiceContext<Self>) {
+        if let Err(e) = self
+            .process_confirmed_blocks(msg.0.block().header())
+            .and_then(|_| self.persist())
+        {
+            error!(target: "stratum", "pool process block rewards failed: {}", e);
+        }
+    }
+}
+
+impl EventHandler<Self, PayoutEvent> for PoolService {
+    fn handle_event(&mut self, _msg: PayoutEvent, _ctx: &mut ServiceContext<Self>) {
+        if let Err(e) = self.payout().and_then(|_| self.persist()) {
+            error!(target: "stratum", "pool payout failed: {}", e);
+        }
+    }
+}
+
+impl EventHandler<Self, PersistEvent> for PoolService {
+    fn handle_event(&mut self, _msg: PersistEvent, _ctx: &mut ServiceContext<Self>) {
+        if let Err(e) = self.persist() {
+            error!(target: "stratum", "pool persist state failed: {}", e);
+        }
+    }
+}
+
+impl ServiceHandler<Self, PoolStateRequest> for PoolService {
+    fn handle(&mut self, _msg: PoolStateRequest, _ctx: &mut ServiceContext<Self>) -> PoolState {
+        self.state.clone()
+    }
+}
+
+pub struct PoolServiceFactory;
+
+impl ServiceFactory<PoolService> for PoolServiceFactory {
+    fn create(ctx: &mut ServiceContext<PoolService>) -> Result<PoolService> {
+        let node_config = ctx.get_shared::<Arc<NodeConfig>>()?;
+        let config = PoolConfig::new(&node_config.stratum)
+            .ok_or_else(|| format_err!("The pool payout mode is not configured."))?;
+        let storage = ctx.get_shared::<Arc<Storage>>()?;
+        let state = storage
+            .get_pool_state(POOL_STATE_KEY)?
+            .map(|bytes| PoolState::decode(bytes.as_slice()))
+            .transpose()?
+            .unwrap_or_default();
+        Ok(PoolService {
+            config,
+            state,
+            dirty: false,
+            node_config,
+            storage,
+            txpool: ctx.get_shared::<TxPoolService>()?,
+            account_service: ctx.service_ref::<AccountService>()?.clone(),
+            chain_state_service: ctx.service_ref::<ChainStateService>()?.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(payout_mode: PoolPayoutMode) -> PoolConfig {
+        PoolConfig {
+            payout_mode,
+            fee: 100,
+            pplns_window: 3,
+            payout_threshold: 1000,
+            payout_interval: Duration::from_secs(60),
+        }
+    }
+
+    fn share(login: &str, difficulty: u64, block_number: Option<u64>) -> ShareAcceptedEvent {
+        ShareAcceptedEvent {
+            login: login.to_string(),
+            share_difficulty: difficulty.into(),
+            network_difficulty: 100.into(),
+            block_number,
+        }
+    }
+
+    #[test]
+    fn test_pplns() {
+        let config = config(PoolPayoutMode::Pplns);
+        let mut state = PoolState::default();
+        state.record_share(&config, &share("a", 10, None));
+        state.record_share(&config, &share("a", 10, None));
+        state.record_share(&config, &share("b", 10, None));
+        state.record_share(&config, &share("b", 20, Some(5)));
+        // the first share of a is out of the window.
+        assert_eq!(state.shares.len(), 3);
+        state.record_share(&config, &share("c", 10, Some(6)));
+        state.record_share(&config, &share("c", 10, Some(7)));
+
+        state.record_block_reward(&config, 6, 10000);
+        // block 5 is orphaned, block 7 is pending.
+        assert_eq!(
+            state.pending_blocks.keys().copied().collect::<Vec<_>>(),
+            vec![7]
+        );
+        // the window of block 6 is b: 30, c: 10, shares 9900 after the fee.
+        assert!(!state.balances.contains_key("a"));
+        assert_eq!(state.balances["b"], 7425);
+        assert_eq!(state.balances["c"], 2475);
+        assert_eq!(state, PoolState::decode(&state.encode().unwrap()).unwrap());
+    }
+
+    #[test]
+    fn test_pps_and_payout() {
+        let config = config(PoolPayoutMode::Pps);
+        let address = AccountAddress::random();
+        let login = address.to_string();
+        let mut state = PoolState::default();
+        state.record_share(&config, &share(&login, 10, None));
+        assert!(
+            state.balances.is_empty(),
+            "no reward before the first block reward event"
+        );
+
+        state.last_block_reward = 100000;
+        state.record_share(&config, &share(&login, 10, None));
+        state.record_share(&config, &share("not an address", 10, None));
+        assert_eq!(state.balances[&login], 9900);
+        state.record_block_reward(&config, 1, 100000);
+        assert_eq!(state.balances[&login], 9900);
+
+        let payouts = state.payouts(&config);
+        assert_eq!(payouts, vec![(login.clone(), address, 9900)]);
+        // the balance is kept until the payout txn is confirmed, and not paid twice.
+        let txn_hash = HashValue::random();
+        state.add_pending_payout(txn_hash, 100, &payouts);
+        assert_eq!(state.balances[&login], 9900);
+        assert!(state.payouts(&config).is_empty());
+        state.record_share(&config, &share(&login, 10, None));
+        state.confirm_payout(txn_hash, true);
+        assert_eq!(state.balances[&login], 9900);
+        assert_eq!(state.paid[&login], 9900);
+        assert!(state.pending_payouts.is_empty());
+        assert_eq!(state, PoolState::decode(&state.encode().unwrap()).unwrap());
+    }
+
+    #[test]
+    fn test_failed_and_expired_payouts() {
+        let config = config(PoolPayoutMode::Pps);
+        let address = AccountAddress::random();
+        let login = address.to_string();
+        let mut state = PoolState::default();
+        state.credit(&login, 5000);
+        let payouts = state.payouts(&config);
+
+        // the failed txn pays nothing, the balance is paid by the next payout.
+        let failed_txn = HashValue::random();
+        state.add_pending_payout(failed_txn, 100, &payouts);
+        state.confirm_payout(failed_txn, false);
+        assert_eq!(state.balances[&login], 5000);
+        assert!(!state.paid.contains_key(&login));
+        assert_eq!(state.payouts(&config), payouts);
+
+        // the txn is not included before it is expired.
+        let expired_txn = HashValue::random();
+        state.add_pending_payout(expired_txn, 100, &payouts);
+        state.expire_payouts(100);
+        assert_eq!(state.pending_payouts.len(), 1);
+        state.expire_payouts(101);
+        assert!(state.pending_payouts.is_empty());
+        // the expired txn is not tracked anymore.
+        state.confirm_payout(expired_txn, true);
+        assert_eq!(state.balances[&login], 5000);
+        assert_eq!(state.payouts(&config), payouts);
+    }
+}
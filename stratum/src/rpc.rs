@@ -1,4 +1,5 @@
 use crate::difficulty_to_target_hex;
+use crate::stats::StratumStats;
 use crate::stratum::Stratum;
 use byteorder::{ByteOrder, LittleEndian, WriteBytesExt};
 use futures::FutureExt;
@@ -14,6 +15,7 @@ use starcoin_miner::SubmitSealRequest as MinerSubmitSealRequest;
 use starcoin_service_registry::{ServiceRef, ServiceRequest};
 use starcoin_types::block::BlockHeaderExtra;
 use starcoin_types::system_events::MintBlockEvent;
+use starcoin_types::U256;
 use std::borrow::BorrowMut;
 use std::convert::TryInto;
 use std::io::Write;
@@ -85,6 +87,20 @@ pub struct Status {
     pub status: String,
 }
 
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+pub struct WorkerRequest {
+    /// The worker id returned by login.
+    pub id: String,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+pub struct ExtranonceResult {
+    /// The extranonce in hex.
+    pub extranonce: String,
+    /// The byte size of the extranonce.
+    pub size: u8,
+}
+
 #[allow(clippy::needless_return)]
 #[rpc]
 pub trait StratumRpc {
@@ -95,6 +111,10 @@ pub trait StratumRpc {
     #[rpc(name = "submit", raw_params)]
     fn submit(&self, share: Params) -> BoxFuture<Result<SubmitResult>>;
 
+    /// Subscribe the extranonce of the worker, the following jobs carry the extranonce explicitly.
+    #[rpc(name = "extranonce.subscribe", raw_params)]
+    fn extranonce_subscribe(&self, worker: Params) -> BoxFuture<Result<ExtranonceResult>>;
+
     #[pubsub(subscription = "job", subscribe, name = "login", raw_params)]
     fn subscribe(
         &self,
@@ -135,6 +155,28 @@ impl ServiceRequest for SubmitShareEvent {
     type Response = anyhow::Result<()>;
 }
 
+#[derive(Debug, Clone)]
+pub(crate) struct KeepalivedEvent(pub(crate) WorkerRequest);
+
+impl ServiceRequest for KeepalivedEvent {
+    type Response = ();
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct ExtranonceSubscribeEvent(pub(crate) WorkerRequest);
+
+impl ServiceRequest for ExtranonceSubscribeEvent {
+    type Response = anyhow::Result<ExtranonceResult>;
+}
+
+/// Get the share and hashrate statistics of the stratum workers.
+#[derive(Debug, Clone)]
+pub struct StratumStatsRequest;
+
+impl ServiceRequest for StratumStatsRequest {
+    type Response = StratumStats;
+}
+
 pub struct StratumRpcImpl {
     service: ServiceRef<Stratum>,
 }
@@ -152,6 +194,9 @@ pub struct LoginRequest {
     pub agent: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub algo: Option<Vec<String>>,
+    /// The extranonce of the previous connection, used to resume the worker session after reconnect.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extranonce: Option<String>,
 }
 
 impl ServiceRequest for LoginRequest {
@@ -190,6 +235,9 @@ pub struct StratumJob {
     pub target: String,
     pub job_id: String,
     pub blob: String,
+    /// The extranonce of the worker, only sent to the workers subscribed the extranonce.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extranonce: Option<String>,
 }
 
 impl StratumJob {
@@ -204,22 +252,33 @@ impl StratumJob {
     }
 }
 
+/// The job id is derived from the minting blob, so it is same for all workers and connections.
+pub fn job_id(minting_blob: &[u8]) -> String {
+    hex::encode(&minting_blob[0..8])
+}
+
 impl StratumJobResponse {
-    pub fn from(e: &MintBlockEvent, login: Option<LoginRequest>, worker_id: [u8; 4]) -> Self {
+    pub fn from(
+        e: &MintBlockEvent,
+        login: Option<LoginRequest>,
+        worker_id: [u8; 4],
+        share_difficulty: U256,
+        extranonce_subscribed: bool,
+    ) -> Self {
         let mut minting_blob = e.minting_blob.clone();
         let _ = minting_blob[35..39].borrow_mut().write_all(&worker_id);
         let worker_id_hex = hex::encode(worker_id);
-        let job_id = hex::encode(&e.minting_blob[0..8]);
         Self {
             login,
             id: worker_id_hex.clone(),
             status: "OK".into(),
             job: StratumJob {
                 height: 0,
-                id: worker_id_hex,
-                target: difficulty_to_target_hex(e.difficulty),
-                job_id,
+                id: worker_id_hex.clone(),
+                target: difficulty_to_target_hex(share_difficulty.min(e.difficulty)),
+                job_id: job_id(&e.minting_blob),
                 blob: hex::encode(&minting_blob),
+                extranonce: extranonce_subscribed.then(|| worker_id_hex),
             },
         }
     }
@@ -228,8 +287,12 @@ impl StratumJobResponse {
 impl StratumRpc for StratumRpcImpl {
     type Metadata = Metadata;
 
-    fn keepalived(&self, _id: Params) -> Result<KeepalivedResult> {
-        //TODO: update active time for id
+    fn keepalived(&self, id: Params) -> Result<KeepalivedResult> {
+        if let Ok(worker) = id.parse::<WorkerRequest>() {
+            if let Err(e) = self.service.try_send(KeepalivedEvent(worker)) {
+                warn!(target: "stratum", "keepalived failed:{}", e);
+            }
+        }
         Ok(KeepalivedResult {
             result: Status {
                 status: "KEEPALIVED".to_string(),
@@ -256,6 +319,20 @@ impl StratumRpc for StratumRpcImpl {
         Box::pin(fut.boxed())
     }
 
+    fn extranonce_subscribe(&self, worker: Params) -> BoxFuture<Result<ExtranonceResult>> {
+        let service = self.service.clone();
+        let fut = async move {
+            let worker = worker.parse::<WorkerRequest>()?;
+            service.send(ExtranonceSubscribeEvent(worker)).await?
+        }
+        .map_err(|e: anyhow::Error| jsonrpc_core::Error {
+            code: ErrorCode::InvalidParams,
+            message: e.to_string(),
+            data: None,
+        });
+        Box::pin(fut.boxed())
+    }
+
     fn subscribe(
         &self,
         _meta: Self::Metadata,
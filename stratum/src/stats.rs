@@ -0,0 +1,158 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Share accounting and hashrate estimation of the stratum workers.
+
+use serde::{Deserialize, Serialize};
+use starcoin_types::U256;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// The window of the accepted shares used to estimate the hashrate.
+pub const HASHRATE_WINDOW: Duration = Duration::from_secs(600);
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkerStats {
+    pub login: String,
+    pub agent: String,
+    /// The extranonce of the worker in hex, also used as the worker id.
+    pub extranonce: String,
+    pub online: bool,
+    pub share_difficulty: U256,
+    pub accepted_shares: u64,
+    pub rejected_shares: u64,
+    pub stale_shares: u64,
+    pub blocks: u64,
+    /// The estimated hashes per second.
+    pub hashrate: u64,
+    /// The unix timestamp in seconds of the last accepted share.
+    pub last_share_time: Option<u64>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StratumStats {
+    pub accepted_shares: u64,
+    pub rejected_shares: u64,
+    pub stale_shares: u64,
+    pub blocks: u64,
+    pub hashrate: u64,
+    pub workers: Vec<WorkerStats>,
+}
+
+impl StratumStats {
+    pub fn new(workers: Vec<WorkerStats>) -> Self {
+        let mut stats = Self::default();
+        for worker in &workers {
+            stats.accepted_shares = stats.accepted_shares.saturating_add(worker.accepted_shares);
+            stats.rejected_shares = stats.rejected_shares.saturating_add(worker.rejected_shares);
+            stats.stale_shares = stats.stale_shares.saturating_add(worker.stale_shares);
+            stats.blocks = stats.blocks.saturating_add(worker.blocks);
+            stats.hashrate = stats.hashrate.saturating_add(worker.hashrate);
+        }
+        stats.workers = workers;
+        stats
+    }
+}
+
+/// The share counters of a worker.
+#[derive(Clone, Debug, Default)]
+pub struct ShareAccount {
+    accepted: u64,
+    rejected: u64,
+    stale: u64,
+    blocks: u64,
+    last_share_time: Option<u64>,
+    /// The accept time and difficulty of the shares in the hashrate window.
+    recent_shares: VecDeque<(Instant, U256)>,
+}
+
+impl ShareAccount {
+    pub fn record_accepted(&mut self, difficulty: U256, now: Instant) {
+        self.accepted = self.accepted.saturating_add(1);
+        self.last_share_time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .ok()
+            .map(|duration| duration.as_secs());
+        self.recent_shares.push_back((now, difficulty));
+        self.prune(now);
+    }
+
+    pub fn record_rejected(&mut self) {
+        self.rejected = self.rejected.saturating_add(1);
+    }
+
+    pub fn record_stale(&mut self) {
+        self.stale = self.stale.saturating_add(1);
+    }
+
+    pub fn record_block(&mut self) {
+        self.blocks = self.blocks.saturating_add(1);
+    }
+
+    /// A share of difficulty `d` takes `d` hashes in expectation,
+    /// so the hashrate is the sum of the share difficulties divided by the window.
+    pub fn hashrate(&mut self, now: Instant) -> u64 {
+        self.prune(now);
+        let total = self
+            .recent_shares
+            .iter()
+            .fold(U256::zero(), |total, (_, difficulty)| {
+                total.saturating_add(*difficulty)
+            });
+        let hashrate = total / U256::from(HASHRATE_WINDOW.as_secs());
+        if hashrate > U256::from(u64::max_value()) {
+            u64::max_value()
+        } else {
+            hashrate.low_u64()
+        }
+    }
+
+    pub fn fill_stats(&mut self, stats: &mut WorkerStats, now: Instant) {
+        stats.accepted_shares = self.accepted;
+        stats.rejected_shares = self.rejected;
+        stats.stale_shares = self.stale;
+        stats.blocks = self.blocks;
+        stats.hashrate = self.hashrate(now);
+        stats.last_share_time = self.last_share_time;
+    }
+
+    fn prune(&mut self, now: Instant) {
+        while let Some((time, _)) = self.recent_shares.front() {
+            if now.saturating_duration_since(*time) > HASHRATE_WINDOW {
+                self.recent_shares.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_share_account() {
+        let start = Instant::now();
+        let mut account = ShareAccount::default();
+        account.record_accepted(60_000.into(), start);
+        account.record_accepted(60_000.into(), start + Duration::from_secs(10));
+        account.record_rejected();
+        account.record_stale();
+        account.record_block();
+        assert_eq!(account.hashrate(start + Duration::from_secs(20)), 200);
+
+        let mut stats = WorkerStats::default();
+        account.fill_stats(&mut stats, start + Duration::from_secs(605));
+        assert_eq!(stats.accepted_shares, 2);
+        assert_eq!(stats.rejected_shares, 1);
+        assert_eq!(stats.stale_shares, 1);
+        assert_eq!(stats.blocks, 1);
+        assert_eq!(stats.hashrate, 100);
+        assert!(stats.last_share_time.is_some());
+
+        let total = StratumStats::new(vec![stats.clone(), stats]);
+        assert_eq!(total.accepted_shares, 4);
+        assert_eq!(total.hashrate, 200);
+    }
+}
@@ -1,8 +1,12 @@
 use crate::rpc::*;
-use anyhow::Result;
+use crate::stats::{ShareAccount, StratumStats, WorkerStats};
+use crate::vardiff::{VarDiff, VarDiffConfig};
+use anyhow::{bail, format_err, Result};
 use futures::channel::mpsc;
 use futures::StreamExt;
 use jsonrpc_pubsub::SubscriptionId;
+use starcoin_config::NodeConfig;
+use starcoin_consensus::{difficult_to_target, Consensus};
 use starcoin_logger::prelude::*;
 use starcoin_miner::{
     MinerService, SubmitSealRequest as MinerSubmitSealRequest, UpdateSubscriberNumRequest,
@@ -11,53 +15,298 @@ use starcoin_service_registry::{
     ActorService, EventHandler, ServiceContext, ServiceFactory, ServiceHandler, ServiceRef,
 };
 use starcoin_types::system_events::MintBlockEvent;
-use std::collections::HashMap;
+use starcoin_types::U256;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::convert::{TryFrom, TryInto};
-use std::sync::atomic;
+use std::sync::{atomic, Arc};
+use std::time::{Duration, Instant};
+
+/// The count of recent jobs which accept shares, the shares of older jobs are stale.
+const RECENT_JOBS: usize = 8;
+/// The disconnected session is kept for this duration, so the worker can resume it after reconnect.
+const SESSION_EXPIRE: Duration = Duration::from_secs(600);
+
+/// Broadcast when a share of a worker is accepted.
+#[derive(Clone, Debug)]
+pub struct ShareAcceptedEvent {
+    pub login: String,
+    pub share_difficulty: U256,
+    pub network_difficulty: U256,
+    /// The number of the block if the share reaches the network difficulty.
+    pub block_number: Option<u64>,
+}
+
+/// A recent job, and the shares submitted to it.
+struct Job {
+    id: String,
+    event: MintBlockEvent,
+    /// The nonce and extranonce of the accepted shares, a repeated share is rejected.
+    shares: HashSet<(u32, [u8; 4])>,
+}
+
+/// The session of a worker, keyed by the extranonce which is unique among the sessions.
+struct WorkerSession {
+    login: LoginRequest,
+    extranonce: [u8; 4],
+    /// The job sender of the current connection, None if the worker is disconnected.
+    sender: Option<mpsc::UnboundedSender<StratumJobResponse>>,
+    extranonce_subscribed: bool,
+    vardiff: VarDiff,
+    account: ShareAccount,
+    last_active: Instant,
+}
+
+impl WorkerSession {
+    fn job_response(
+        &self,
+        event: &MintBlockEvent,
+        login: Option<LoginRequest>,
+    ) -> StratumJobResponse {
+        StratumJobResponse::from(
+            event,
+            login,
+            self.extranonce,
+            self.vardiff.difficulty(),
+            self.extranonce_subscribed,
+        )
+    }
+
+    fn send_job(&mut self, event: &MintBlockEvent) {
+        let job = self.job_response(event, None);
+        if let Some(sender) = self.sender.as_ref() {
+            if let Err(err) = sender.unbounded_send(job) {
+                if err.is_disconnected() {
+                    self.sender = None;
+                } else if err.is_full() {
+                    error!(target: "stratum", "worker {} fail to new messages, channel is full", hex::encode(self.extranonce));
+                }
+            }
+        }
+    }
+
+    fn stats(&mut self, now: Instant) -> WorkerStats {
+        let mut stats = WorkerStats {
+            login: self.login.login.clone(),
+            agent: self.login.agent.clone(),
+            extranonce: hex::encode(self.extranonce),
+            online: self.sender.is_some(),
+            share_difficulty: self.vardiff.difficulty(),
+            ..Default::default()
+        };
+        self.account.fill_stats(&mut stats, now);
+        stats
+    }
+}
 
 pub struct Stratum {
     uid: atomic::AtomicU32,
-    mint_block_subscribers: HashMap<u32, (mpsc::UnboundedSender<StratumJobResponse>, LoginRequest)>,
+    sessions: HashMap<[u8; 4], WorkerSession>,
+    /// The subscription id of the connections to the extranonce of the session.
+    subscriptions: HashMap<u32, [u8; 4]>,
+    /// The recent jobs, the latest is at the back.
+    jobs: VecDeque<Job>,
+    vardiff_config: VarDiffConfig,
     miner_service: ServiceRef<MinerService>,
 }
 
 impl Stratum {
-    fn new(miner_service: ServiceRef<MinerService>) -> Self {
+    fn new(miner_service: ServiceRef<MinerService>, vardiff_config: VarDiffConfig) -> Self {
         Self {
             miner_service,
             uid: atomic::AtomicU32::new(1),
-            mint_block_subscribers: Default::default(),
+            sessions: Default::default(),
+            subscriptions: Default::default(),
+            jobs: Default::default(),
+            vardiff_config,
         }
     }
     fn next_id(&self) -> u32 {
         self.uid.fetch_add(1, atomic::Ordering::SeqCst)
     }
-    fn sync_current_job(&mut self) -> Result<Option<MintBlockEvent>> {
+    fn sync_current_job(&mut self, subscribers_num: usize) -> Result<Option<MintBlockEvent>> {
         let service = self.miner_service.clone();
-        let subscribers_num = self.mint_block_subscribers.len() as u32;
-        futures::executor::block_on(service.send(UpdateSubscriberNumRequest {
-            number: Some(subscribers_num),
-        }))
+        let event = futures::executor::block_on(service.send(UpdateSubscriberNumRequest {
+            number: Some(subscribers_num as u32),
+        }))?;
+        if let Some(event) = event.as_ref() {
+            self.add_job(event.clone());
+        }
+        Ok(event)
+    }
+    fn add_job(&mut self, event: MintBlockEvent) {
+        let job_id = job_id(&event.minting_blob);
+        if self.jobs.iter().any(|job| job.id == job_id) {
+            return;
+        }
+        self.jobs.push_back(Job {
+            id: job_id,
+            event,
+            shares: HashSet::new(),
+        });
+        while self.jobs.len() > RECENT_JOBS {
+            self.jobs.pop_front();
+        }
+    }
+    fn current_job(&self) -> Option<&MintBlockEvent> {
+        self.jobs.back().map(|job| &job.event)
     }
     fn send_to_all(&mut self, event: MintBlockEvent) {
-        let mut remove_outdated = vec![];
-        for (id, (ch, login)) in self.mint_block_subscribers.iter() {
-            let worker_id = login.get_worker_id(*id);
-            let job = StratumJobResponse::from(&event, None, worker_id);
-            if let Err(err) = ch.unbounded_send(job) {
-                if err.is_disconnected() {
-                    remove_outdated.push(*id);
-                } else if err.is_full() {
-                    error!(target: "stratum", "subscription {:?} fail to new messages, channel is full", id);
-                }
+        let now = Instant::now();
+        for session in self.sessions.values_mut() {
+            if session.sender.is_none() {
+                continue;
+            }
+            session
+                .vardiff
+                .retarget(&self.vardiff_config, event.difficulty, now);
+            session.send_job(&event);
+        }
+        self.remove_outdated(now);
+    }
+    fn remove_outdated(&mut self, now: Instant) {
+        let subscriptions = &mut self.subscriptions;
+        self.sessions.retain(|extranonce, session| {
+            if session.sender.is_none() {
+                subscriptions.retain(|_, e| e != extranonce);
+                now.saturating_duration_since(session.last_active) < SESSION_EXPIRE
+            } else {
+                true
+            }
+        });
+    }
+    /// Resume the session of the extranonce in login request,
+    /// or allocate a new session with an unused extranonce.
+    fn login(
+        &mut self,
+        login: &LoginRequest,
+        sender: mpsc::UnboundedSender<StratumJobResponse>,
+        now: Instant,
+    ) -> [u8; 4] {
+        let resumed = login
+            .extranonce
+            .as_ref()
+            .and_then(|extranonce| parse_extranonce(extranonce).ok())
+            .filter(|extranonce| {
+                self.sessions
+                    .get(extranonce)
+                    .map(|session| session.login.login == login.login)
+                    .unwrap_or(false)
+            });
+        if let Some(extranonce) = resumed {
+            info!(target: "stratum", "worker {} resume session {}", login.login, hex::encode(extranonce));
+            let session = self
+                .sessions
+                .get_mut(&extranonce)
+                .expect("Resumed session must exist");
+            session.login = login.clone();
+            session.sender = Some(sender);
+            session.last_active = now;
+            return extranonce;
+        }
+        let extranonce = loop {
+            let extranonce = login.get_worker_id(self.next_id());
+            if !self.sessions.contains_key(&extranonce) {
+                break extranonce;
+            }
+        };
+        let initial_difficulty = self
+            .current_job()
+            .map(|event| event.difficulty)
+            .unwrap_or(self.vardiff_config.min_difficulty)
+            .max(self.vardiff_config.min_difficulty);
+        self.sessions.insert(
+            extranonce,
+            WorkerSession {
+                login: login.clone(),
+                extranonce,
+                sender: Some(sender),
+                extranonce_subscribed: false,
+                vardiff: VarDiff::new(initial_difficulty, now),
+                account: ShareAccount::default(),
+                last_active: now,
+            },
+        );
+        extranonce
+    }
+    fn submit_share(&mut self, share: ShareRequest) -> Result<ShareAcceptedEvent> {
+        let now = Instant::now();
+        let extranonce = parse_extranonce(&share.id)?;
+        let job_index = self.jobs.iter().position(|job| job.id == share.job_id);
+        let session = self
+            .sessions
+            .get_mut(&extranonce)
+            .ok_or_else(|| format_err!("Unknown worker {}", share.id))?;
+        session.last_active = now;
+        let job_index = match job_index {
+            Some(job_index) => job_index,
+            None => {
+                session.account.record_stale();
+                bail!("Stale share of job {}", share.job_id);
+            }
+        };
+        let mut seal: MinerSubmitSealRequest = share.try_into()?;
+        if self.jobs[job_index]
+            .shares
+            .contains(&(seal.nonce, extranonce))
+        {
+            session.account.record_rejected();
+            bail!("Duplicate share of job {}", self.jobs[job_index].id);
+        }
+        let job = self.jobs[job_index].event.clone();
+        let pow_hash: U256 = job
+            .strategy
+            .calculate_pow_hash(&job.minting_blob, seal.nonce, &seal.extra)?
+            .into();
+        let share_difficulty = session.vardiff.difficulty().min(job.difficulty);
+        if pow_hash > difficult_to_target(share_difficulty) {
+            session.account.record_rejected();
+            bail!("Low difficulty share");
+        }
+        self.jobs[job_index].shares.insert((seal.nonce, extranonce));
+        session.account.record_accepted(share_difficulty, now);
+        session.vardiff.record_share();
+        if session
+            .vardiff
+            .retarget(&self.vardiff_config, job.difficulty, now)
+            .is_some()
+        {
+            if let Some(current) = self.jobs.back() {
+                session.send_job(&current.event);
             }
         }
-        for id in remove_outdated {
-            self.mint_block_subscribers.remove(&id);
+        let mut accepted = ShareAcceptedEvent {
+            login: session.login.login.clone(),
+            share_difficulty,
+            network_difficulty: job.difficulty,
+            block_number: None,
+        };
+        if pow_hash <= difficult_to_target(job.difficulty) {
+            info!(target: "stratum", "worker {} found block of job {}", hex::encode(extranonce), job_id(&job.minting_blob));
+            session.account.record_block();
+            accepted.block_number = Some(job.block_number);
+            seal.minting_blob = job.minting_blob;
+            self.miner_service.try_send(seal)?;
         }
+        Ok(accepted)
+    }
+    fn stats(&mut self) -> StratumStats {
+        let now = Instant::now();
+        let mut workers = self
+            .sessions
+            .values_mut()
+            .map(|session| session.stats(now))
+            .collect::<Vec<_>>();
+        workers.sort_by(|a, b| a.login.cmp(&b.login).then(a.extranonce.cmp(&b.extranonce)));
+        StratumStats::new(workers)
     }
 }
 
+fn parse_extranonce(extranonce: &str) -> Result<[u8; 4]> {
+    hex::decode(extranonce)?
+        .try_into()
+        .map_err(|_| format_err!("Invalid extranonce {}", extranonce))
+}
+
 impl ActorService for Stratum {
     fn started(&mut self, ctx: &mut ServiceContext<Self>) -> Result<()> {
         ctx.set_mailbox_capacity(1024);
@@ -73,6 +322,7 @@ impl ActorService for Stratum {
 
 impl EventHandler<Self, MintBlockEvent> for Stratum {
     fn handle_event(&mut self, event: MintBlockEvent, _ctx: &mut ServiceContext<Stratum>) {
+        self.add_job(event.clone());
         self.send_to_all(event);
     }
 }
@@ -84,12 +334,17 @@ impl ServiceHandler<Self, Unsubscribe> for Stratum {
                 if self
                     .miner_service
                     .try_send(UpdateSubscriberNumRequest {
-                        number: Some(self.mint_block_subscribers.len() as u32 - 1),
+                        number: Some(self.subscriptions.len().saturating_sub(1) as u32),
                     })
                     .is_ok()
                 {
-                    self.mint_block_subscribers.remove(&id);
-                    self.uid.fetch_sub(1, atomic::Ordering::SeqCst);
+                    // Keep the session, so the worker can resume it by the extranonce.
+                    if let Some(extranonce) = self.subscriptions.remove(&id) {
+                        if let Some(session) = self.sessions.get_mut(&extranonce) {
+                            session.sender = None;
+                            session.last_active = Instant::now();
+                        }
+                    }
                     return;
                 }
             }
@@ -104,8 +359,11 @@ impl ServiceHandler<Self, SubscribeJobEvent> for Stratum {
         let SubscribeJobEvent(subscriber, login) = msg;
         let (sender, receiver) = mpsc::unbounded();
         let sub_id = self.next_id();
-        self.mint_block_subscribers
-            .insert(sub_id, (sender.clone(), login.clone()));
+        let current_job = self.sync_current_job(self.subscriptions.len() + 1);
+        let extranonce = self.login(&login, sender.clone(), Instant::now());
+        // The previous connection of a resumed session is replaced.
+        self.subscriptions.retain(|_, e| *e != extranonce);
+        self.subscriptions.insert(sub_id, extranonce);
         ctx.spawn(async move {
             if let Ok(sink) = subscriber
                 .assign_id_async(SubscriptionId::Number(sub_id as u64))
@@ -127,10 +385,13 @@ impl ServiceHandler<Self, SubscribeJobEvent> for Stratum {
                 error!(target: "stratum", "Subscriber assign is failed");
             }
         });
-        if let Ok(Some(event)) = self.sync_current_job() {
+        if let Ok(Some(event)) = current_job {
+            let stratum_result = self
+                .sessions
+                .get(&extranonce)
+                .expect("Session must exist after login")
+                .job_response(&event, Some(login));
             ctx.spawn(async move {
-                let worker_id = login.get_worker_id(sub_id);
-                let stratum_result = StratumJobResponse::from(&event, Some(login), worker_id);
                 if let Err(err) = sender.unbounded_send(stratum_result) {
                     error!(target: "stratum", "Failed to send MintBlockEvent: {}", err);
                 }
@@ -142,29 +403,190 @@ impl ServiceHandler<Self, SubscribeJobEvent> for Stratum {
 }
 
 impl ServiceHandler<Self, SubmitShareEvent> for Stratum {
-    fn handle(&mut self, msg: SubmitShareEvent, _ctx: &mut ServiceContext<Self>) -> Result<()> {
+    fn handle(&mut self, msg: SubmitShareEvent, ctx: &mut ServiceContext<Self>) -> Result<()> {
         info!(target: "stratum", "received submit share event:{:?}", &msg.0);
-        if let Some(current_mint_event) = self.sync_current_job()? {
-            let job_id = hex::encode(&current_mint_event.minting_blob[0..8]);
-            let submit_job_id = msg.0.job_id.clone();
-            if submit_job_id != job_id {
-                warn!(target: "stratum", "received job mismatch with current job,{},{}", submit_job_id, job_id);
-                return Ok(());
-            };
-            let mut seal: MinerSubmitSealRequest = msg.0.try_into()?;
-
-            seal.minting_blob = current_mint_event.minting_blob;
-            self.miner_service.try_send(seal)?;
+        if self.current_job().is_none() {
+            self.sync_current_job(self.subscriptions.len())?;
         }
+        let accepted = self.submit_share(msg.0)?;
+        ctx.broadcast(accepted);
         Ok(())
     }
 }
 
+impl ServiceHandler<Self, KeepalivedEvent> for Stratum {
+    fn handle(&mut self, msg: KeepalivedEvent, _ctx: &mut ServiceContext<Self>) {
+        if let Ok(extranonce) = parse_extranonce(&msg.0.id) {
+            if let Some(session) = self.sessions.get_mut(&extranonce) {
+                session.last_active = Instant::now();
+            }
+        }
+    }
+}
+
+impl ServiceHandler<Self, ExtranonceSubscribeEvent> for Stratum {
+    fn handle(
+        &mut self,
+        msg: ExtranonceSubscribeEvent,
+        _ctx: &mut ServiceContext<Self>,
+    ) -> Result<ExtranonceResult> {
+        let extranonce = parse_extranonce(&msg.0.id)?;
+        let session = self
+            .sessions
+            .get_mut(&extranonce)
+            .ok_or_else(|| format_err!("Unknown worker {}", msg.0.id))?;
+        session.extranonce_subscribed = true;
+        Ok(ExtranonceResult {
+            extranonce: hex::encode(extranonce),
+            size: extranonce.len() as u8,
+        })
+    }
+}
+
+impl ServiceHandler<Self, StratumStatsRequest> for Stratum {
+    fn handle(
+        &mut self,
+        _msg: StratumStatsRequest,
+        _ctx: &mut ServiceContext<Self>,
+    ) -> StratumStats {
+        self.stats()
+    }
+}
+
 pub struct StratumFactory;
 
 impl ServiceFactory<Stratum> for StratumFactory {
     fn create(ctx: &mut ServiceContext<Stratum>) -> Result<Stratum> {
+        let config = ctx.get_shared::<Arc<NodeConfig>>()?;
         let miner_service = ctx.service_ref::<MinerService>()?.clone();
-        Ok(Stratum::new(miner_service))
+        Ok(Stratum::new(
+            miner_service,
+            VarDiffConfig::from(&config.stratum),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use starcoin_crypto::HashValue;
+    use starcoin_service_registry::mocker::mock;
+    use starcoin_service_registry::{RegistryAsyncService, RegistryService};
+    use starcoin_types::genesis_config::ConsensusStrategy;
+
+    /// The stratum with a mocked miner service, the registry must be kept until the test ends.
+    async fn new_stratum() -> (ServiceRef<RegistryService>, Stratum) {
+        let registry = RegistryService::launch();
+        let miner_service = registry
+            .register_mocker(mock::<MinerService, _>(|_request, _ctx| {
+                Box::new(Ok::<HashValue, anyhow::Error>(HashValue::zero()))
+            }))
+            .await
+            .unwrap();
+        let stratum = Stratum::new(
+            miner_service,
+            VarDiffConfig {
+                share_interval: Duration::from_secs(10),
+                retarget_interval: Duration::from_secs(60),
+                min_difficulty: 1.into(),
+            },
+        );
+        (registry, stratum)
+    }
+
+    fn mint_event(strategy: ConsensusStrategy, difficulty: U256, number: u64) -> MintBlockEvent {
+        let mut minting_blob = vec![0u8; 76];
+        minting_blob[0..8].copy_from_slice(&number.to_le_bytes());
+        MintBlockEvent::new(
+            HashValue::random(),
+            strategy,
+            minting_blob,
+            difficulty,
+            number,
+            None,
+        )
+    }
+
+    fn login_request(login: &str, extranonce: Option<[u8; 4]>) -> LoginRequest {
+        LoginRequest {
+            login: login.to_string(),
+            pass: String::new(),
+            agent: "test".to_string(),
+            algo: None,
+            extranonce: extranonce.map(hex::encode),
+        }
+    }
+
+    fn share(extranonce: [u8; 4], event: &MintBlockEvent, nonce: u32) -> ShareRequest {
+        ShareRequest {
+            id: hex::encode(extranonce),
+            job_id: job_id(&event.minting_blob),
+            nonce: format!("{:08x}", nonce),
+            result: String::new(),
+        }
+    }
+
+    #[stest::test]
+    async fn test_login_and_resume() {
+        let (_registry, mut stratum) = new_stratum().await;
+        stratum.add_job(mint_event(ConsensusStrategy::Dummy, 1.into(), 1));
+        let now = Instant::now();
+
+        let (sender, _receiver) = mpsc::unbounded();
+        let alice = stratum.login(&login_request("alice", None), sender.clone(), now);
+        // every connection of the same login gets its own extranonce.
+        let alice2 = stratum.login(&login_request("alice", None), sender, now);
+        assert_ne!(alice, alice2);
+
+        // the disconnected worker resumes its session by the extranonce.
+        stratum.sessions.get_mut(&alice).unwrap().sender = None;
+        let (sender, mut receiver) = mpsc::unbounded();
+        let resumed = stratum.login(&login_request("alice", Some(alice)), sender, now);
+        assert_eq!(resumed, alice);
+        stratum.send_to_all(mint_event(ConsensusStrategy::Dummy, 1.into(), 2));
+        assert_eq!(receiver.try_next().unwrap().unwrap().id, hex::encode(alice));
+
+        // other login can not take over the session.
+        let (sender, _receiver) = mpsc::unbounded();
+        let bob = stratum.login(&login_request("bob", Some(alice)), sender, now);
+        assert_ne!(bob, alice);
+        assert_eq!(stratum.stats().workers.len(), 3);
+    }
+
+    #[stest::test]
+    async fn test_submit_share() {
+        let (_registry, mut stratum) = new_stratum().await;
+        let job = mint_event(ConsensusStrategy::Dummy, 1.into(), 1);
+        stratum.add_job(job.clone());
+        let (sender, _receiver) = mpsc::unbounded();
+        let alice = stratum.login(&login_request("alice", None), sender, Instant::now());
+
+        let accepted = stratum.submit_share(share(alice, &job, 1)).unwrap();
+        assert_eq!(accepted.login, "alice");
+        assert_eq!(accepted.block_number, Some(1));
+
+        // the same nonce of the same worker is rejected, other nonces are still accepted.
+        let err = stratum.submit_share(share(alice, &job, 1)).unwrap_err();
+        assert!(err.to_string().contains("Duplicate"), "{}", err);
+        assert!(stratum.submit_share(share(alice, &job, 2)).is_ok());
+
+        // the share of a job which is not in the recent jobs is stale.
+        let old_job = mint_event(ConsensusStrategy::Dummy, 1.into(), 0);
+        let err = stratum.submit_share(share(alice, &old_job, 3)).unwrap_err();
+        assert!(err.to_string().contains("Stale"), "{}", err);
+
+        // the share whose pow hash does not reach the share difficulty is rejected.
+        let hard_job = mint_event(ConsensusStrategy::Argon, u64::max_value().into(), 2);
+        stratum.add_job(hard_job.clone());
+        let (sender, _receiver) = mpsc::unbounded();
+        let bob = stratum.login(&login_request("bob", None), sender, Instant::now());
+        let err = stratum.submit_share(share(bob, &hard_job, 1)).unwrap_err();
+        assert!(err.to_string().contains("Low difficulty"), "{}", err);
+
+        let stats = stratum.stats();
+        assert_eq!(stats.accepted_shares, 2);
+        assert_eq!(stats.rejected_shares, 2);
+        assert_eq!(stats.stale_shares, 1);
+        assert_eq!(stats.blocks, 2);
     }
 }
@@ -0,0 +1,152 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Variable share difficulty of a worker, adjust the difficulty to make the worker submit
+//! a share every `share_interval`.
+
+use starcoin_config::StratumConfig;
+use starcoin_types::U256;
+use std::time::{Duration, Instant};
+
+/// The max factor of one adjustment.
+const MAX_ADJUST_FACTOR: u64 = 4;
+
+#[derive(Clone, Debug)]
+pub struct VarDiffConfig {
+    pub share_interval: Duration,
+    pub retarget_interval: Duration,
+    pub min_difficulty: U256,
+}
+
+impl From<&StratumConfig> for VarDiffConfig {
+    fn from(config: &StratumConfig) -> Self {
+        Self {
+            share_interval: config.share_interval(),
+            retarget_interval: config.retarget_interval(),
+            min_difficulty: config.min_share_difficulty().into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct VarDiff {
+    difficulty: U256,
+    shares: u64,
+    last_retarget: Instant,
+}
+
+impl VarDiff {
+    pub fn new(difficulty: U256, now: Instant) -> Self {
+        Self {
+            difficulty,
+            shares: 0,
+            last_retarget: now,
+        }
+    }
+
+    pub fn difficulty(&self) -> U256 {
+        self.difficulty
+    }
+
+    pub fn record_share(&mut self) {
+        self.shares = self.shares.saturating_add(1);
+    }
+
+    /// Adjust the difficulty by the shares since the last retarget if the retarget interval elapsed,
+    /// the difficulty is in range [min_difficulty, network_difficulty].
+    /// Return the new difficulty if it is changed.
+    pub fn retarget(
+        &mut self,
+        config: &VarDiffConfig,
+        network_difficulty: U256,
+        now: Instant,
+    ) -> Option<U256> {
+        let elapsed = now.saturating_duration_since(self.last_retarget);
+        if elapsed < config.retarget_interval {
+            return None;
+        }
+        let expected_shares =
+            (elapsed.as_millis() / config.share_interval.as_millis().max(1)).max(1) as u64;
+        let factor = U256::from(MAX_ADJUST_FACTOR);
+        let lower = (self.difficulty / factor).max(U256::one());
+        let upper = self.difficulty.saturating_mul(factor);
+        let adjusted = (self.difficulty.saturating_mul(self.shares.into()) / expected_shares)
+            .max(lower)
+            .min(upper)
+            .min(network_difficulty)
+            .max(config.min_difficulty);
+        self.shares = 0;
+        self.last_retarget = now;
+        if adjusted == self.difficulty {
+            None
+        } else {
+            self.difficulty = adjusted;
+            Some(adjusted)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> VarDiffConfig {
+        VarDiffConfig {
+            share_interval: Duration::from_secs(10),
+            retarget_interval: Duration::from_secs(60),
+            min_difficulty: 10.into(),
+        }
+    }
+
+    #[test]
+    fn test_vardiff_retarget() {
+        let config = config();
+        let start = Instant::now();
+        let mut vardiff = VarDiff::new(1000.into(), start);
+        vardiff.record_share();
+        assert_eq!(
+            vardiff.retarget(&config, 1_000_000.into(), start + Duration::from_secs(30)),
+            None
+        );
+
+        // 12 shares in 60 seconds, expect 6 shares.
+        (0..11).for_each(|_| vardiff.record_share());
+        let now = start + Duration::from_secs(60);
+        assert_eq!(
+            vardiff.retarget(&config, 1_000_000.into(), now),
+            Some(2000.into())
+        );
+
+        // too many shares, limited by the max adjust factor.
+        (0..100).for_each(|_| vardiff.record_share());
+        let now = now + Duration::from_secs(60);
+        assert_eq!(
+            vardiff.retarget(&config, 1_000_000.into(), now),
+            Some(8000.into())
+        );
+
+        // no share, decrease the difficulty.
+        let now = now + Duration::from_secs(60);
+        assert_eq!(
+            vardiff.retarget(&config, 1_000_000.into(), now),
+            Some(2000.into())
+        );
+    }
+
+    #[test]
+    fn test_vardiff_bounds() {
+        let config = config();
+        let start = Instant::now();
+        let mut vardiff = VarDiff::new(20.into(), start);
+        let now = start + Duration::from_secs(60);
+        assert_eq!(
+            vardiff.retarget(&config, 1_000_000.into(), now),
+            Some(10.into())
+        );
+
+        (0..100).for_each(|_| vardiff.record_share());
+        let now = now + Duration::from_secs(60);
+        assert_eq!(vardiff.retarget(&config, 30.into(), now), Some(30.into()));
+        assert_eq!(vardiff.difficulty(), 30.into());
+    }
+}
@@ -3,6 +3,7 @@ use anyhow::Result;
 use async_std::sync::Arc;
 use network_api::messages::PeerAnnouncementMessage;
 use network_api::{PeerProvider, PeerSelector, PeerStrategy, ReputationChange};
+use parking_lot::Mutex;
 use starcoin_crypto::HashValue;
 use starcoin_logger::prelude::*;
 use starcoin_network::NetworkServiceRef;
@@ -12,16 +13,23 @@ use starcoin_storage::{BlockTransactionInfoStore, Storage};
 use starcoin_txpool::TxPoolService;
 use starcoin_txpool_api::TxPoolSyncService;
 use starcoin_types::transaction::TransactionError;
+use std::collections::HashSet;
 
 /// Service which handle Announcement message
 pub struct AnnouncementService {
     storage: Arc<Storage>,
     txpool: TxPoolService,
+    /// The txns which are pulling from peers, avoid pulling the same txn from multi peers.
+    pulling_txns: Arc<Mutex<HashSet<HashValue>>>,
 }
 
 impl AnnouncementService {
     fn new(storage: Arc<Storage>, txpool: TxPoolService) -> Self {
-        AnnouncementService { storage, txpool }
+        AnnouncementService {
+            storage,
+            txpool,
+            pulling_txns: Arc::new(Mutex::new(HashSet::new())),
+        }
     }
 }
 
@@ -44,6 +52,7 @@ impl EventHandler<Self, PeerAnnouncementMessage> for AnnouncementService {
     ) {
         let txpool = self.txpool.clone();
         let storage = self.storage.clone();
+        let pulling_txns = self.pulling_txns.clone();
         let network = ctx
             .get_shared::<NetworkServiceRef>()
             .expect("NetworkServiceRef not exist.");
@@ -60,6 +69,13 @@ impl EventHandler<Self, PeerAnnouncementMessage> for AnnouncementService {
                     }
                     false
                 }).collect::<Vec<HashValue>>();
+                let fresh_ids = {
+                    let mut pulling_txns = pulling_txns.lock();
+                    fresh_ids
+                        .into_iter()
+                        .filter(|txn_id| pulling_txns.insert(*txn_id))
+                        .collect::<Vec<HashValue>>()
+                };
 
                 if !fresh_ids.is_empty() {
                     let peer_selector =
@@ -68,7 +84,7 @@ impl EventHandler<Self, PeerAnnouncementMessage> for AnnouncementService {
                         peer_selector,
                         network.clone(),
                     );
-                    match rpc_client.get_txns_with_hash_from_pool(Some(peer_id.clone()), GetTxnsWithHash { ids:fresh_ids }).await {
+                    match rpc_client.get_txns_with_hash_from_pool(Some(peer_id.clone()), GetTxnsWithHash { ids:fresh_ids.clone() }).await {
                         Err(err) => error!(
                             "[sync] handle announcement msg result error: {:?}, peer_id:{:?} ",
                             err, peer_id
@@ -95,6 +111,11 @@ impl EventHandler<Self, PeerAnnouncementMessage> for AnnouncementService {
                             }
                         }
                     }
+                    // the txns are in the pool now or failed to pull, can be pulled again if failed.
+                    let mut pulling_txns = pulling_txns.lock();
+                    fresh_ids.iter().for_each(|txn_id| {
+                        pulling_txns.remove(txn_id);
+                    });
                 }
             }
         });
@@ -14,6 +14,8 @@ pub struct ChainMetrics {
     pub chain_rollback_block_total: UIntCounter,
     pub chain_block_num: UIntGauge,
     pub chain_txn_num: UIntGauge,
+    pub chain_reorg_depth: Histogram,
+    pub chain_reorg_refused_total: UIntCounter,
 }
 
 impl ChainMetrics {
@@ -66,6 +68,22 @@ impl ChainMetrics {
             registry,
         )?;
 
+        let chain_reorg_depth = register(
+            Histogram::with_opts(
+                HistogramOpts::new("chain_reorg_depth", "the depth of chain reorganization")
+                    .buckets(vec![1.0, 2.0, 3.0, 5.0, 8.0, 13.0, 21.0, 34.0, 55.0, 100.0]),
+            )?,
+            registry,
+        )?;
+
+        let chain_reorg_refused_total = register(
+            UIntCounter::with_opts(Opts::new(
+                "chain_reorg_refused_total",
+                "total chain reorganization refused for exceeding the max reorg depth",
+            ))?,
+            registry,
+        )?;
+
         Ok(Self {
             chain_block_connect_total,
             chain_select_head_total,
@@ -73,6 +91,8 @@ impl ChainMetrics {
             chain_rollback_block_total,
             chain_block_num,
             chain_txn_num,
+            chain_reorg_depth,
+            chain_reorg_refused_total,
         })
     }
 }
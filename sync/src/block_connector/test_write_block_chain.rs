@@ -5,11 +5,12 @@ use crate::block_connector::WriteBlockChainService;
 use starcoin_account_api::AccountInfo;
 use starcoin_chain::{BlockChain, ChainReader};
 use starcoin_chain_service::WriteableChainService;
-use starcoin_config::NodeConfig;
+use starcoin_config::{NodeConfig, ReorgDepthAction};
 use starcoin_consensus::Consensus;
 use starcoin_genesis::Genesis as StarcoinGenesis;
 use starcoin_service_registry::bus::BusService;
 use starcoin_service_registry::{RegistryAsyncService, RegistryService};
+use starcoin_storage::reorg::ReorgStore;
 use starcoin_storage::Store;
 use starcoin_time_service::TimeService;
 use starcoin_txpool_mock_service::MockTxPoolService;
@@ -22,7 +23,16 @@ pub async fn create_writeable_block_chain() -> (
     Arc<NodeConfig>,
     Arc<dyn Store>,
 ) {
-    let node_config = NodeConfig::random_for_test();
+    create_writeable_block_chain_with_config(NodeConfig::random_for_test()).await
+}
+
+pub async fn create_writeable_block_chain_with_config(
+    node_config: NodeConfig,
+) -> (
+    WriteBlockChainService<MockTxPoolService>,
+    Arc<NodeConfig>,
+    Arc<dyn Store>,
+) {
     let node_config = Arc::new(node_config);
 
     let (storage, chain_info, _) = StarcoinGenesis::init_storage_for_test(node_config.net())
@@ -206,6 +216,74 @@ async fn test_block_chain_switch_main() {
     );
 }
 
+#[stest::test(timeout = 120)]
+async fn test_block_chain_reorg_journal() {
+    let times = 10;
+    let (mut writeable_block_chain_service, node_config, storage) =
+        create_writeable_block_chain().await;
+    let net = node_config.net();
+    gen_blocks(
+        times,
+        &mut writeable_block_chain_service,
+        net.time_service().as_ref(),
+    );
+    gen_fork_block_chain(
+        0,
+        node_config,
+        2 * times,
+        &mut writeable_block_chain_service,
+    );
+
+    assert_eq!(storage.get_reorg_count().unwrap(), 1);
+    let record = storage.get_reorg(0).unwrap().unwrap();
+    assert_eq!(record.common_ancestor_number, 0);
+    assert_eq!(record.old_head_number, times);
+    assert_eq!(record.depth, times);
+    assert!(!record.refused);
+}
+
+#[stest::test(timeout = 120)]
+async fn test_block_chain_reorg_refused() {
+    let times = 5;
+    let mut config = NodeConfig::random_for_test();
+    config.sync.set_max_reorg_depth(2);
+    config.sync.set_reorg_depth_action(ReorgDepthAction::Refuse);
+    let (mut writeable_block_chain_service, node_config, storage) =
+        create_writeable_block_chain_with_config(config).await;
+    let net = node_config.net();
+    gen_blocks(
+        times,
+        &mut writeable_block_chain_service,
+        net.time_service().as_ref(),
+    );
+    let main_head = writeable_block_chain_service
+        .get_main()
+        .current_header()
+        .id();
+
+    gen_fork_block_chain(
+        0,
+        node_config,
+        4 * times,
+        &mut writeable_block_chain_service,
+    );
+
+    // The main chain is kept, and the refused branch is recorded only once.
+    assert_eq!(
+        writeable_block_chain_service
+            .get_main()
+            .current_header()
+            .id(),
+        main_head
+    );
+    assert_eq!(storage.get_reorg_count().unwrap(), 1);
+    let record = storage.get_reorg(0).unwrap().unwrap();
+    assert_eq!(record.common_ancestor_number, 0);
+    assert_eq!(record.old_head, main_head);
+    assert_eq!(record.depth, times);
+    assert!(record.refused);
+}
+
 #[stest::test]
 async fn test_block_chain_reset() -> anyhow::Result<()> {
     let times = 10;
@@ -3,26 +3,32 @@
 
 use crate::block_connector::metrics::ChainMetrics;
 use anyhow::{format_err, Result};
+use lru::LruCache;
 use starcoin_chain::BlockChain;
 use starcoin_chain_api::{ChainReader, ChainWriter, ConnectBlockError, WriteableChainService};
-use starcoin_config::NodeConfig;
+use starcoin_config::{NodeConfig, ReorgDepthAction};
 use starcoin_crypto::HashValue;
 use starcoin_executor::VMMetrics;
 use starcoin_logger::prelude::*;
 use starcoin_service_registry::bus::{Bus, BusService};
 use starcoin_service_registry::ServiceRef;
+use starcoin_storage::reorg::ReorgStore;
 use starcoin_storage::Store;
 use starcoin_txpool_api::TxPoolSyncService;
-use starcoin_types::block::BlockInfo;
+use starcoin_types::block::{BlockIdAndNumber, BlockInfo};
 use starcoin_types::{
     block::{Block, BlockHeader, ExecutedBlock},
-    startup_info::StartupInfo,
-    system_events::{NewBranch, NewHeadBlock},
+    startup_info::{ReorgRecord, StartupInfo},
+    system_events::{NewBranch, NewHeadBlock, ReorgDepthAlert},
 };
 use std::fmt::Formatter;
 use std::sync::Arc;
 
 const MAX_ROLL_BACK_BLOCK: usize = 10;
+/// The max count of the retracted or enacted txns kept in a reorg record.
+const MAX_REORG_RECORD_TXNS: usize = 1000;
+/// The max count of the refused branches remembered.
+const MAX_REFUSED_BRANCHES: usize = 64;
 
 pub struct WriteBlockChainService<P>
 where
@@ -36,6 +42,9 @@ where
     bus: ServiceRef<BusService>,
     metrics: Option<ChainMetrics>,
     vm_metrics: Option<VMMetrics>,
+    /// The first block ids after the common ancestor of the branches refused to switch to,
+    /// the later blocks of a refused branch are kept as branch blocks without a new reorg record.
+    refused_branches: LruCache<HashValue, ()>,
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -126,6 +135,7 @@ where
             bus,
             metrics,
             vm_metrics,
+            refused_branches: LruCache::new(MAX_REFUSED_BRANCHES),
         })
     }
 
@@ -178,7 +188,49 @@ where
         if branch_total_difficulty > main_total_difficulty {
             let (enacted_count, enacted_blocks, retracted_count, retracted_blocks) =
                 if !parent_is_main_head {
-                    self.find_ancestors_from_accumulator(&new_branch)?
+                    let (
+                        ancestor,
+                        enacted_count,
+                        enacted_blocks,
+                        retracted_count,
+                        retracted_blocks,
+                    ) = self.find_ancestors_from_accumulator(&new_branch)?;
+                    if retracted_count > 0 {
+                        let branch_root = new_branch
+                            .get_hash_by_number(ancestor.number + 1)?
+                            .ok_or_else(|| {
+                                format_err!(
+                                    "Can not find branch block by number {}",
+                                    ancestor.number + 1
+                                )
+                            })?;
+                        if self.refused_branches.contains(&branch_root) {
+                            debug!(
+                                "[chain] Keep block {} of the refused branch {} as branch.",
+                                executed_block.header().id(),
+                                branch_root
+                            );
+                            self.broadcast_new_branch(executed_block);
+                            return Ok(());
+                        }
+                        if !self.record_reorg(
+                            &new_branch,
+                            ancestor,
+                            retracted_count,
+                            &enacted_blocks,
+                            &retracted_blocks,
+                        )? {
+                            self.refused_branches.put(branch_root, ());
+                            self.broadcast_new_branch(executed_block);
+                            return Ok(());
+                        }
+                    }
+                    (
+                        enacted_count,
+                        enacted_blocks,
+                        retracted_count,
+                        retracted_blocks,
+                    )
                 } else {
                     (1, vec![executed_block.block.clone()], 0, vec![])
                 };
@@ -304,10 +356,76 @@ where
         }
     }
 
+    /// Record the reorg to the journal, and check the reorg depth.
+    /// Return false if the switch to the new branch is refused.
+    fn record_reorg(
+        &self,
+        new_branch: &BlockChain,
+        ancestor: BlockIdAndNumber,
+        depth: u64,
+        enacted_blocks: &[Block],
+        retracted_blocks: &[Block],
+    ) -> Result<bool> {
+        let sync_config = &self.config.sync;
+        let exceeded = sync_config
+            .max_reorg_depth()
+            .map(|max_reorg_depth| depth > max_reorg_depth)
+            .unwrap_or(false);
+        let refused = exceeded && sync_config.reorg_depth_action() == ReorgDepthAction::Refuse;
+        if let Some(metrics) = self.metrics.as_ref() {
+            metrics.chain_reorg_depth.observe(depth as f64);
+            if refused {
+                metrics.chain_reorg_refused_total.inc();
+            }
+        }
+        let txn_hashes = |blocks: &[Block]| {
+            let mut hashes = blocks
+                .iter()
+                .flat_map(|block| block.transactions().iter().map(|txn| txn.id()))
+                .collect::<Vec<_>>();
+            // keep the txns of the latest blocks.
+            let excess = hashes.len().saturating_sub(MAX_REORG_RECORD_TXNS);
+            hashes.drain(..excess);
+            hashes
+        };
+        let old_head = self.main.current_header();
+        let new_head = new_branch.current_header();
+        let index = self.storage.get_reorg_count()?;
+        let record = ReorgRecord {
+            index,
+            timestamp: self.config.net().time_service().now_millis(),
+            old_head: old_head.id(),
+            old_head_number: old_head.number(),
+            new_head: new_head.id(),
+            new_head_number: new_head.number(),
+            common_ancestor: ancestor.id,
+            common_ancestor_number: ancestor.number,
+            depth,
+            retracted_txns: txn_hashes(retracted_blocks),
+            enacted_txns: txn_hashes(enacted_blocks),
+            refused,
+        };
+        if let Err(e) = self.storage.save_reorg(record.clone()) {
+            error!("Save reorg record error: {:?}", e);
+        }
+        if exceeded {
+            warn!(
+                "[chain] Reorg depth {} exceeds the max reorg depth, {}",
+                depth, record
+            );
+            if let Err(e) = self.bus.broadcast(ReorgDepthAlert(Arc::new(record))) {
+                error!("Broadcast ReorgDepthAlert error: {:?}", e);
+            }
+        } else {
+            info!("[chain] {}", record);
+        }
+        Ok(!refused)
+    }
+
     fn find_ancestors_from_accumulator(
         &self,
         new_branch: &BlockChain,
-    ) -> Result<(u64, Vec<Block>, u64, Vec<Block>)> {
+    ) -> Result<(BlockIdAndNumber, u64, Vec<Block>, u64, Vec<Block>)> {
         let ancestor = self.main.find_ancestor(new_branch)?.ok_or_else(|| {
             format_err!(
                 "Can not find ancestors between main chain: {:?} and branch: {:?}",
@@ -343,7 +461,7 @@ where
             "Commit block count:{}, rollback block count:{}",
             enacted_count, retracted_count,
         );
-        Ok((enacted_count, enacted, retracted_count, retracted))
+        Ok((ancestor, enacted_count, enacted, retracted_count, retracted))
     }
 
     fn find_blocks_until(
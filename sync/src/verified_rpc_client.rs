@@ -12,10 +12,11 @@ use starcoin_crypto::hash::HashValue;
 use starcoin_logger::prelude::*;
 use starcoin_network_rpc_api::{
     gen_client::NetworkRpcClient, BlockBody, GetAccumulatorNodeByNodeHash, GetBlockHeadersByNumber,
-    GetBlockIds, GetTxnsWithHash, RawRpcClient,
+    GetBlockIds, GetBlockTxns, GetTxnsWithHash, RawRpcClient,
 };
 use starcoin_state_tree::StateNode;
 use starcoin_types::block::Block;
+use starcoin_types::compact_block::CompactBlock;
 use starcoin_types::transaction::{SignedUserTransaction, Transaction};
 use starcoin_types::{
     block::{BlockHeader, BlockInfo, BlockNumber},
@@ -195,6 +196,58 @@ impl VerifiedRpcClient {
         }
     }
 
+    pub async fn get_compact_block(
+        &self,
+        peer_id: PeerId,
+        block_id: HashValue,
+    ) -> Result<Option<(CompactBlock, BlockInfo)>> {
+        let data = self
+            .client
+            .get_compact_block(peer_id.clone(), block_id)
+            .await?;
+        if let Some((compact_block, block_info)) = data.as_ref() {
+            let actual_id = compact_block.header.id();
+            if actual_id != block_id || block_info.block_id != block_id {
+                return Err(RpcVerifyError::new(
+                    peer_id.clone(),
+                    format!(
+                        "request compact block with id: {} from peer {}, but got block {}",
+                        block_id, peer_id, actual_id
+                    ),
+                )
+                .into());
+            }
+        }
+        Ok(data)
+    }
+
+    /// Get the txns of the block by index, the caller should check the txns with the compact block.
+    pub async fn get_block_txns(
+        &self,
+        peer_id: PeerId,
+        req: GetBlockTxns,
+    ) -> Result<Option<Vec<SignedUserTransaction>>> {
+        let data = self
+            .client
+            .get_block_txns(peer_id.clone(), req.clone())
+            .await?;
+        if let Some(txns) = data.as_ref() {
+            if txns.len() != req.indexes.len() {
+                return Err(RpcVerifyError::new(
+                    peer_id.clone(),
+                    format!(
+                        "Txn len mismatch {:?} : {:?} from peer : {:?}.",
+                        txns.len(),
+                        req.indexes.len(),
+                        peer_id
+                    ),
+                )
+                .into());
+            }
+        }
+        Ok(data)
+    }
+
     pub async fn get_txns(
         &self,
         peer_id: Option<PeerId>,
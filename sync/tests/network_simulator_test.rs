@@ -0,0 +1,68 @@
+use starcoin_network::LinkFault;
+use std::time::Duration;
+use test_helper::{NetworkSimulator, SimulatorAction};
+
+#[stest::test(timeout = 240)]
+fn test_sync_after_partition() {
+    let simulator = NetworkSimulator::start(3, 1).unwrap();
+    simulator
+        .run(vec![
+            SimulatorAction::Mine { node: 0, count: 2 },
+            SimulatorAction::Sleep(Duration::from_secs(2)),
+            SimulatorAction::Partition(vec![vec![0], vec![1, 2]]),
+            SimulatorAction::Mine { node: 0, count: 3 },
+            SimulatorAction::Mine { node: 1, count: 5 },
+        ])
+        .unwrap();
+    let heads = simulator.heads().unwrap();
+    assert_ne!(
+        heads[0].id(),
+        heads[1].id(),
+        "partitioned nodes should fork."
+    );
+
+    simulator.run(vec![SimulatorAction::Heal]).unwrap();
+    let head = simulator.wait_synced(Duration::from_secs(60)).unwrap();
+    assert_eq!(head.number(), 7);
+    simulator.stop().unwrap();
+}
+
+#[stest::test(timeout = 240)]
+fn test_sync_with_latency_and_drops() {
+    let simulator = NetworkSimulator::start(3, 2).unwrap();
+    simulator
+        .run(vec![
+            SimulatorAction::SetLinkFault {
+                node: 1,
+                from: None,
+                fault: LinkFault {
+                    latency: Duration::from_millis(300),
+                    drop_rate: 0.0,
+                },
+            },
+            SimulatorAction::SetLinkFault {
+                node: 2,
+                from: Some(0),
+                fault: LinkFault {
+                    latency: Duration::ZERO,
+                    drop_rate: 0.5,
+                },
+            },
+            SimulatorAction::BroadcastInvalidBlock { node: 2 },
+            SimulatorAction::Mine { node: 0, count: 5 },
+        ])
+        .unwrap();
+    let head = simulator.wait_synced(Duration::from_secs(60)).unwrap();
+    assert_eq!(head.number(), 5);
+    // the invalid block is dropped, and the peers which received it punish the sender.
+    for node in [0, 1] {
+        let reputation = simulator.reputation(node, 2).unwrap();
+        assert!(
+            matches!(reputation, Some(reputation) if reputation < 0),
+            "node {} should reduce the reputation of node 2, got: {:?}",
+            node,
+            reputation
+        );
+    }
+    simulator.stop().unwrap();
+}
@@ -8,6 +8,7 @@ pub mod executor;
 pub mod network;
 pub mod node;
 pub mod protest;
+pub mod simulator;
 pub mod starcoin_dao;
 pub mod txn;
 pub mod txpool;
@@ -16,6 +17,7 @@ pub use chain::gen_blockchain_for_test;
 pub use dummy_network_service::DummyNetworkService;
 pub use network::{build_network, build_network_cluster, build_network_pair};
 pub use node::{run_node_by_config, run_test_node};
+pub use simulator::{NetworkSimulator, SimulatorAction};
 pub use starcoin_genesis::Genesis;
 pub use starcoin_node::NodeHandle;
 pub use starcoin_types::account::Account;
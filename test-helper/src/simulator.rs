@@ -0,0 +1,309 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! An in-process network simulator, start multi nodes with the memory transport,
+//! and run scripted mining, partitions, latency, message drops and malicious peers.
+
+use crate::node::run_node_by_config;
+use anyhow::{bail, format_err, Result};
+use futures::executor::block_on;
+use network_api::messages::{CompactBlockMessage, NotificationMessage};
+use network_api::{NetworkService, PeerId, PeerProvider};
+use starcoin_config::NodeConfig;
+use starcoin_crypto::HashValue;
+use starcoin_logger::prelude::*;
+use starcoin_network::{LinkFault, NetworkFaults};
+use starcoin_node::NodeHandle;
+use starcoin_storage::BlockStore;
+use starcoin_types::block::{AccumulatorInfo, Block, BlockBody, BlockHeader, BlockInfo};
+use starcoin_types::compact_block::CompactBlock;
+use starcoin_types::U256;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// A step of the simulator script.
+#[derive(Clone, Debug)]
+pub enum SimulatorAction {
+    /// Mine `count` blocks on the node.
+    Mine { node: usize, count: u64 },
+    /// Split the nodes into groups, the nodes in different groups can not connect to each other.
+    /// The nodes not in any group are isolated.
+    Partition(Vec<Vec<usize>>),
+    /// Reconnect all nodes.
+    Heal,
+    /// Set the fault of the messages received by `node` from `from`, or from all peers if `from` is None.
+    SetLinkFault {
+        node: usize,
+        from: Option<usize>,
+        fault: LinkFault,
+    },
+    /// Remove all link faults.
+    ClearLinkFaults,
+    /// The node broadcast an invalid block with a huge total difficulty to its peers.
+    BroadcastInvalidBlock { node: usize },
+    /// Wait a while, let the messages propagate.
+    Sleep(Duration),
+}
+
+pub struct SimulatorNode {
+    handle: NodeHandle,
+    faults: NetworkFaults,
+}
+
+impl SimulatorNode {
+    pub fn handle(&self) -> &NodeHandle {
+        &self.handle
+    }
+
+    pub fn config(&self) -> Arc<NodeConfig> {
+        self.handle.config()
+    }
+
+    pub fn peer_id(&self) -> PeerId {
+        self.handle.config().network.self_peer_id()
+    }
+
+    pub fn faults(&self) -> &NetworkFaults {
+        &self.faults
+    }
+
+    pub fn head(&self) -> Result<BlockHeader> {
+        let storage = self.handle.storage();
+        let startup_info = storage
+            .get_startup_info()?
+            .ok_or_else(|| format_err!("Startup info should exist."))?;
+        storage
+            .get_block_header_by_hash(startup_info.main)?
+            .ok_or_else(|| format_err!("Can not find block header by {}", startup_info.main))
+    }
+}
+
+/// The nodes of the simulator run in permissioned network mode and only allow each other,
+/// the partitions are made by removing the peers from the allowed list.
+pub struct NetworkSimulator {
+    nodes: Vec<SimulatorNode>,
+}
+
+impl NetworkSimulator {
+    /// Start `node_count` nodes, every node use the nodes started before it as seeds.
+    /// The `seed` makes the message drops deterministic.
+    pub fn start(node_count: usize, seed: u64) -> Result<Self> {
+        if node_count == 0 {
+            bail!("The simulator needs at least one node.");
+        }
+        let mut configs = (0..node_count)
+            .map(|_| NodeConfig::random_for_test())
+            .collect::<Vec<_>>();
+        let peer_ids = configs
+            .iter()
+            .map(|config| config.network.self_peer_id())
+            .collect::<Vec<_>>();
+        let addresses = configs
+            .iter()
+            .map(|config| config.network.self_address())
+            .collect::<Vec<_>>();
+        for (i, config) in configs.iter_mut().enumerate() {
+            config.network.allowed_peers = Some(peer_ids.clone());
+            config.network.seeds = addresses[..i].to_vec().into();
+        }
+
+        let mut nodes = vec![];
+        for (i, config) in configs.into_iter().enumerate() {
+            info!(
+                "[simulator] start node {}, peer id: {}",
+                i,
+                config.network.self_peer_id()
+            );
+            let handle = run_node_by_config(Arc::new(config))?;
+            let faults = handle.registry().get_shared_sync::<NetworkFaults>()?;
+            faults.reseed(seed.wrapping_add(i as u64));
+            nodes.push(SimulatorNode { handle, faults });
+        }
+        let simulator = Self { nodes };
+        simulator.wait_connected(Duration::from_secs(10))?;
+        Ok(simulator)
+    }
+
+    pub fn nodes(&self) -> &[SimulatorNode] {
+        &self.nodes
+    }
+
+    pub fn node(&self, index: usize) -> &SimulatorNode {
+        &self.nodes[index]
+    }
+
+    pub fn run(&self, script: Vec<SimulatorAction>) -> Result<()> {
+        for action in script {
+            debug!("[simulator] run action: {:?}", action);
+            match action {
+                SimulatorAction::Mine { node, count } => {
+                    self.mine(node, count)?;
+                }
+                SimulatorAction::Partition(groups) => self.partition(groups)?,
+                SimulatorAction::Heal => self.heal()?,
+                SimulatorAction::SetLinkFault { node, from, fault } => {
+                    self.set_link_fault(node, from, fault)
+                }
+                SimulatorAction::ClearLinkFaults => self.clear_link_faults(),
+                SimulatorAction::BroadcastInvalidBlock { node } => {
+                    self.broadcast_invalid_block(node)
+                }
+                SimulatorAction::Sleep(duration) => std::thread::sleep(duration),
+            }
+        }
+        Ok(())
+    }
+
+    pub fn mine(&self, node: usize, count: u64) -> Result<Vec<Block>> {
+        (0..count)
+            .map(|_| self.node(node).handle.generate_block())
+            .collect()
+    }
+
+    pub fn partition(&self, groups: Vec<Vec<usize>>) -> Result<()> {
+        let group_of = |node: usize| groups.iter().position(|group| group.contains(&node));
+        for i in 0..self.nodes.len() {
+            for j in 0..self.nodes.len() {
+                if i == j {
+                    continue;
+                }
+                let connected = match (group_of(i), group_of(j)) {
+                    (Some(group_i), Some(group_j)) => group_i == group_j,
+                    _ => false,
+                };
+                if !connected {
+                    self.set_peer_allowed(i, j, false)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn heal(&self) -> Result<()> {
+        for i in 0..self.nodes.len() {
+            for j in 0..self.nodes.len() {
+                if i != j {
+                    self.set_peer_allowed(i, j, true)?;
+                }
+            }
+        }
+        for i in 1..self.nodes.len() {
+            let network = self.node(i).handle.network();
+            for j in 0..i {
+                let address = self.node(j).config().network.self_address();
+                network.add_peer(address.to_string())?;
+            }
+        }
+        self.wait_connected(Duration::from_secs(10))
+    }
+
+    pub fn set_link_fault(&self, node: usize, from: Option<usize>, fault: LinkFault) {
+        let faults = self.node(node).faults();
+        match from {
+            Some(from) => faults.set_peer_fault(self.node(from).peer_id(), fault),
+            None => faults.set_default_fault(fault),
+        }
+    }
+
+    pub fn clear_link_faults(&self) {
+        self.nodes.iter().for_each(|node| node.faults.clear());
+    }
+
+    pub fn broadcast_invalid_block(&self, node: usize) {
+        let header = BlockHeader::random();
+        let block_info = BlockInfo::new(
+            header.id(),
+            U256::max_value(),
+            AccumulatorInfo::default(),
+            AccumulatorInfo::default(),
+        );
+        let block = Block::new(header, BlockBody::new_empty());
+        info!(
+            "[simulator] node {} broadcast invalid block {}",
+            node,
+            block.id()
+        );
+        self.node(node)
+            .handle
+            .network()
+            .broadcast(NotificationMessage::CompactBlock(Box::new(
+                CompactBlockMessage::new(CompactBlock::new(block), block_info),
+            )));
+    }
+
+    /// The reputation of `peer` on `node`, None if the node does not know the peer.
+    pub fn reputation(&self, node: usize, peer: usize) -> Result<Option<i32>> {
+        let network = self.node(node).handle.network();
+        let peer_id = self.node(peer).peer_id();
+        let reputations = block_on(async {
+            network
+                .reputations(i32::min_value())
+                .await?
+                .await
+                .map_err(|e| format_err!("Get reputations error: {:?}", e))
+        })?;
+        Ok(reputations
+            .into_iter()
+            .find(|(id, _)| id == &peer_id)
+            .map(|(_, reputation)| reputation))
+    }
+
+    pub fn heads(&self) -> Result<Vec<BlockHeader>> {
+        self.nodes.iter().map(|node| node.head()).collect()
+    }
+
+    /// Wait until all nodes have the same head, return the head.
+    pub fn wait_synced(&self, timeout: Duration) -> Result<BlockHeader> {
+        let start = Instant::now();
+        loop {
+            let heads = self.heads()?;
+            if heads.iter().all(|head| head.id() == heads[0].id()) {
+                return Ok(heads[0].clone());
+            }
+            if start.elapsed() > timeout {
+                bail!(
+                    "Nodes are not synced after {:?}, heads: {:?}",
+                    timeout,
+                    heads
+                        .iter()
+                        .map(|head| (head.number(), head.id()))
+                        .collect::<Vec<(u64, HashValue)>>()
+                );
+            }
+            std::thread::sleep(Duration::from_millis(500));
+        }
+    }
+
+    /// Wait until every node is connected to all other nodes.
+    pub fn wait_connected(&self, timeout: Duration) -> Result<()> {
+        let start = Instant::now();
+        loop {
+            let connected = self.nodes.iter().all(|node| {
+                let network = node.handle.network();
+                self.nodes
+                    .iter()
+                    .filter(|other| other.peer_id() != node.peer_id())
+                    .all(|other| block_on(network.is_connected(other.peer_id())))
+            });
+            if connected {
+                return Ok(());
+            }
+            if start.elapsed() > timeout {
+                bail!("Nodes are not connected after {:?}", timeout);
+            }
+            std::thread::sleep(Duration::from_millis(200));
+        }
+    }
+
+    pub fn stop(self) -> Result<()> {
+        for node in self.nodes {
+            node.handle.stop()?;
+        }
+        Ok(())
+    }
+
+    fn set_peer_allowed(&self, node: usize, peer: usize, allowed: bool) -> Result<()> {
+        let network = self.node(node).handle.network();
+        block_on(network.set_peer_allowed(self.node(peer).peer_id(), allowed))
+    }
+}
@@ -90,8 +90,13 @@ impl TxPoolSyncService for MockTxPoolService {
         unimplemented!()
     }
 
-    fn find_txn(&self, _hash: &HashValue) -> Option<SignedUserTransaction> {
-        unimplemented!()
+    fn find_txn(&self, hash: &HashValue) -> Option<SignedUserTransaction> {
+        self.pool
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|txn| txn.id() == *hash)
+            .cloned()
     }
 
     fn txns_of_sender(
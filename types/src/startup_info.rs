@@ -271,3 +271,44 @@ impl TryInto<Vec<u8>> for BarnardHardFork {
         self.encode()
     }
 }
+
+/// A chain reorganization of the main chain, recorded in the reorg journal.
+#[derive(Eq, PartialEq, Hash, Deserialize, Serialize, Clone, Debug)]
+pub struct ReorgRecord {
+    /// The index of the record in the reorg journal.
+    pub index: u64,
+    /// The timestamp in milliseconds when the reorg happened.
+    pub timestamp: u64,
+    pub old_head: HashValue,
+    pub old_head_number: BlockNumber,
+    pub new_head: HashValue,
+    pub new_head_number: BlockNumber,
+    pub common_ancestor: HashValue,
+    pub common_ancestor_number: BlockNumber,
+    /// The count of the retracted blocks of the old main chain.
+    pub depth: u64,
+    /// The txns of the latest retracted blocks, at most 10 blocks and 1000 txns are collected.
+    pub retracted_txns: Vec<HashValue>,
+    /// The txns of the latest enacted blocks, at most 10 blocks and 1000 txns are collected.
+    pub enacted_txns: Vec<HashValue>,
+    /// The reorg exceeds the max reorg depth and the switch is refused.
+    pub refused: bool,
+}
+
+impl fmt::Display for ReorgRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Reorg #{} [old_head: {}({}), new_head: {}({}), ancestor: {}({}), depth: {}, refused: {}]",
+            self.index,
+            self.old_head,
+            self.old_head_number,
+            self.new_head,
+            self.new_head_number,
+            self.common_ancestor,
+            self.common_ancestor_number,
+            self.depth,
+            self.refused
+        )
+    }
+}
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::block::{Block, BlockHeaderExtra, ExecutedBlock};
+use crate::startup_info::ReorgRecord;
 use crate::sync_status::SyncStatus;
 use crate::U256;
 use schemars::JsonSchema;
@@ -16,6 +17,10 @@ pub struct NewHeadBlock(pub Arc<ExecutedBlock>);
 #[derive(Clone, Debug)]
 pub struct NewBranch(pub Arc<ExecutedBlock>);
 
+/// Fire this event when the depth of a chain reorganization exceeds the max reorg depth.
+#[derive(Clone, Debug)]
+pub struct ReorgDepthAlert(pub Arc<ReorgRecord>);
+
 #[derive(Clone, Debug)]
 pub struct MinedBlock(pub Arc<Block>);
 
@@ -6,6 +6,7 @@ use starcoin_abi_decoder::decode_move_value;
 use starcoin_abi_resolver::ABIResolver;
 use starcoin_abi_types::TypeInstantiation;
 use starcoin_crypto::HashValue;
+use starcoin_gas::TransactionGasTrace;
 use starcoin_resource_viewer::module_cache::ModuleCache;
 use starcoin_resource_viewer::{AnnotatedMoveStruct, AnnotatedMoveValue, MoveValueAnnotator};
 use starcoin_rpc_api::types::{DryRunOutputView, TransactionOutputView, WriteOpValueView};
@@ -17,7 +18,9 @@ use starcoin_vm_types::file_format::CompiledModule;
 use starcoin_vm_types::identifier::{IdentStr, Identifier};
 use starcoin_vm_types::language_storage::{ModuleId, StructTag, TypeTag};
 use starcoin_vm_types::state_view::StateView;
-use starcoin_vm_types::transaction::{DryRunTransaction, TransactionOutput, TransactionPayload};
+use starcoin_vm_types::transaction::{
+    DryRunTransaction, SignedUserTransaction, TransactionOutput, TransactionPayload,
+};
 use starcoin_vm_types::transaction_argument::convert_txn_args;
 use starcoin_vm_types::transaction_argument::TransactionArgument;
 use starcoin_vm_types::vm_status::VMStatus;
@@ -48,6 +51,24 @@ impl PlaygroudService {
         dry_run(&state_view, txn, self.metrics.clone())
     }
 
+    pub fn dry_run_with_trace(
+        &self,
+        state_root: HashValue,
+        txn: DryRunTransaction,
+    ) -> Result<(VMStatus, TransactionOutput, TransactionGasTrace)> {
+        let state_view = ChainStateDB::new(self.state.clone(), Some(state_root));
+        dry_run_with_trace(&state_view, txn, self.metrics.clone())
+    }
+
+    pub fn trace_transaction(
+        &self,
+        state_root: HashValue,
+        txn: SignedUserTransaction,
+    ) -> Result<(VMStatus, TransactionOutput, TransactionGasTrace)> {
+        let state_view = ChainStateDB::new(self.state.clone(), Some(state_root));
+        trace_transaction(&state_view, txn, self.metrics.clone())
+    }
+
     pub fn call_contract(
         &self,
         state_root: HashValue,
@@ -150,6 +171,25 @@ pub fn dry_run_explain<S: StateView>(
     })
 }
 
+pub fn dry_run_with_trace<S: StateView>(
+    state_view: &S,
+    txn: DryRunTransaction,
+    metrics: Option<VMMetrics>,
+) -> Result<(VMStatus, TransactionOutput, TransactionGasTrace)> {
+    let mut vm = StarcoinVM::new(metrics);
+    vm.dry_run_transaction_with_trace(state_view, txn)
+}
+
+/// Re-execute the user transaction against the given state with a gas tracer.
+pub fn trace_transaction<S: StateView>(
+    state_view: &S,
+    txn: SignedUserTransaction,
+    metrics: Option<VMMetrics>,
+) -> Result<(VMStatus, TransactionOutput, TransactionGasTrace)> {
+    let mut vm = StarcoinVM::new(metrics);
+    vm.trace_transaction(state_view, txn)
+}
+
 pub fn call_contract<S: StateView>(
     state_view: &S,
     module_id: ModuleId,
@@ -159,8 +199,50 @@ pub fn call_contract<S: StateView>(
     metrics: Option<VMMetrics>,
 ) -> Result<Vec<(TypeTag, Vec<u8>)>> {
     let function_name = IdentStr::new(func)?;
+    let ret_tys = resolve_return_types(state_view, &module_id, function_name, &type_args, &args)?;
+    let mut vm = StarcoinVM::new(metrics);
+    let rets = vm.execute_readonly_function(
+        state_view,
+        &module_id,
+        function_name,
+        type_args,
+        convert_txn_args(&args),
+    )?;
+    zip_return_values(ret_tys, rets)
+}
+
+/// Call the contract like `call_contract`, and return the gas profile of the call.
+pub fn call_contract_with_trace<S: StateView>(
+    state_view: &S,
+    module_id: ModuleId,
+    func: &str,
+    type_args: Vec<TypeTag>,
+    args: Vec<TransactionArgument>,
+    metrics: Option<VMMetrics>,
+) -> Result<(Vec<(TypeTag, Vec<u8>)>, TransactionGasTrace)> {
+    let function_name = IdentStr::new(func)?;
+    let ret_tys = resolve_return_types(state_view, &module_id, function_name, &type_args, &args)?;
+    let mut vm = StarcoinVM::new(metrics);
+    let (rets, trace) = vm.execute_readonly_function_with_trace(
+        state_view,
+        &module_id,
+        function_name,
+        type_args,
+        convert_txn_args(&args),
+    )?;
+    Ok((zip_return_values(ret_tys, rets)?, trace))
+}
+
+/// Validate the type args and args of the function call, and return the return types.
+fn resolve_return_types<S: StateView>(
+    state_view: &S,
+    module_id: &ModuleId,
+    function_name: &IdentStr,
+    type_args: &[TypeTag],
+    args: &[TransactionArgument],
+) -> Result<Vec<TypeTag>> {
     let abi_resolver = ABIResolver::new(state_view);
-    let func_abi = abi_resolver.resolve_function(&module_id, function_name)?;
+    let func_abi = abi_resolver.resolve_function(module_id, function_name)?;
 
     // validate params
     {
@@ -180,7 +262,6 @@ pub fn call_contract<S: StateView>(
     }
 
     let ty_tags_abi = type_args
-        .as_slice()
         .iter()
         .map(|t| abi_resolver.resolve_type_tag(t))
         .collect::<Result<Vec<_>>>()?;
@@ -188,8 +269,8 @@ pub fn call_contract<S: StateView>(
 
     // after instantiate the function, we check the arg types.
     {
-        for (i, (abi, v)) in func_instantiation.args().iter().zip(&args).enumerate() {
-            match (abi.type_abi(), &v) {
+        for (i, (abi, v)) in func_instantiation.args().iter().zip(args).enumerate() {
+            match (abi.type_abi(), v) {
                 (TypeInstantiation::U8, TransactionArgument::U8(_))
                 | (TypeInstantiation::U64, TransactionArgument::U64(_))
                 | (TypeInstantiation::U128, TransactionArgument::U128(_))
@@ -213,20 +294,14 @@ pub fn call_contract<S: StateView>(
         }
     }
 
-    let mut vm = StarcoinVM::new(metrics);
-    let rets = vm.execute_readonly_function(
-        state_view,
-        &module_id,
-        function_name,
-        type_args,
-        convert_txn_args(&args),
-    )?;
-
-    let ret_tys = func_instantiation
+    func_instantiation
         .returns()
         .iter()
         .map(|r| r.type_tag())
-        .collect::<Result<Vec<_>>>()?;
+        .collect::<Result<Vec<_>>>()
+}
+
+fn zip_return_values(ret_tys: Vec<TypeTag>, rets: Vec<Vec<u8>>) -> Result<Vec<(TypeTag, Vec<u8>)>> {
     anyhow::ensure!(
         ret_tys.len() == rets.len(),
         "length of return values mismatch, expect: {}, got: {}",
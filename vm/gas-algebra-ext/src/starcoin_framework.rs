@@ -33,4 +33,15 @@ crate::natives::define_gas_parameters_for_natives!(GasParameters, "starcoin_nati
     [.u256.div.base,  optional "u256.div.base",  (10 + 1) * MUL],
     [.u256.rem.base,  optional "u256.rem.base",  (4 + 1) * MUL],
     [.u256.pow.base,  optional "u256.pow.base",  (8 + 1) * MUL],
+
+    // required, the older on-chain native_table is completed by GasSchedule::from(&VMConfig).
+    [.signature.bls12381_verify.base, "signature.bls12381_verify.base", (48000 + 1) * MUL],
+    [.signature.bls12381_aggregate_verify.base, "signature.bls12381_aggregate_verify.base", (48000 + 1) * MUL],
+    [.signature.bls12381_aggregate_verify.per_pubkey, "signature.bls12381_aggregate_verify.per_pubkey", (1600 + 1) * MUL],
+    [.signature.bls12381_verify_pop.base, "signature.bls12381_verify_pop.base", (48000 + 1) * MUL],
+    [.signature.secp256r1_verify.base, "signature.secp256r1_verify.base", (4000 + 1) * MUL],
+    [.signature.secp256r1_verify.per_byte, "signature.secp256r1_verify.per_byte", (21 + 1) * MUL],
+    [.signature.bls12381_verify.per_byte, "signature.bls12381_verify.per_byte", (21 + 1) * MUL],
+    [.signature.bls12381_aggregate_verify.per_byte, "signature.bls12381_aggregate_verify.per_byte", (21 + 1) * MUL],
+    [.signature.bls12381_verify_pop.per_byte, "signature.bls12381_verify_pop.per_byte", (21 + 1) * MUL],
 ], allow_unmapped = 3 /* signature */ + 2 /* hash */ + 1 /* u256 */);
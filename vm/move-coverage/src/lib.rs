@@ -0,0 +1,16 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Collect the coverage of the Move code executed by the Starcoin VM, and report it against the
+//! Move source of the compiled package.
+
+#![forbid(unsafe_code)]
+
+pub mod report;
+pub mod trace;
+
+/// The coverage map of a package, at the package root. It is the file read by `package coverage`.
+pub const COVERAGE_MAP_NAME: &str = ".coverage_map.mvcov";
+
+/// The lcov report of a package, at the package root.
+pub const LCOV_REPORT_NAME: &str = "lcov.info";
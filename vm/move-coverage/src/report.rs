@@ -0,0 +1,212 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{format_err, Result};
+use move_binary_format::access::ModuleAccess;
+use move_binary_format::file_format::{CompiledModule, FunctionDefinitionIndex};
+use move_bytecode_source_map::source_map::SourceMap;
+use move_coverage::coverage_map::ExecCoverageMap;
+use move_coverage::summary::summarize_inst_cov;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// A module of the compiled package, with its source map and source file.
+pub struct SourceModule<'a> {
+    pub module: &'a CompiledModule,
+    pub source_map: &'a SourceMap,
+    pub source_path: &'a Path,
+}
+
+pub struct FunctionCoverage {
+    pub name: String,
+    /// The line of the function definition.
+    pub line: usize,
+    /// How many times the function is called.
+    pub hits: u64,
+}
+
+/// The coverage of the functions and the lines of a source file.
+pub struct SourceFileCoverage {
+    pub source_path: PathBuf,
+    pub functions: Vec<FunctionCoverage>,
+    /// The hits of every line which has some instructions.
+    pub lines: BTreeMap<usize, u64>,
+}
+
+impl SourceFileCoverage {
+    /// Map the coverage of the instructions of the module to the lines of its source, a line is
+    /// hit as many times as its most executed instruction.
+    pub fn new(source: &SourceModule, coverage_map: &ExecCoverageMap) -> Result<Self> {
+        let source_text = fs::read_to_string(source.source_path)?;
+        let line_starts: Vec<usize> = std::iter::once(0)
+            .chain(source_text.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        let line_of = |offset: u32| line_starts.partition_point(|start| *start <= offset as usize);
+
+        let module_id = source.module.self_id();
+        let module_coverage = coverage_map
+            .module_maps
+            .get(&(*module_id.address(), module_id.name().to_owned()));
+
+        let mut functions = vec![];
+        let mut lines = BTreeMap::new();
+        for (idx, function_def) in source.module.function_defs().iter().enumerate() {
+            let code = match &function_def.code {
+                Some(code) => code,
+                None => continue,
+            };
+            let function_name = source
+                .module
+                .identifier_at(source.module.function_handle_at(function_def.function).name);
+            let function_map = source
+                .source_map
+                .get_function_source_map(FunctionDefinitionIndex(idx as u16))?;
+            let pc_hits = module_coverage.and_then(|m| m.function_maps.get(function_name));
+            let hits_at = |pc: u64| {
+                pc_hits
+                    .and_then(|pc_hits| pc_hits.get(&pc))
+                    .copied()
+                    .unwrap_or(0)
+            };
+
+            functions.push(FunctionCoverage {
+                name: format!("{}::{}", module_id.name(), function_name),
+                line: line_of(function_map.definition_location.start()),
+                hits: hits_at(0),
+            });
+            for pc in 0..code.code.len() {
+                let loc = function_map.get_code_location(pc as u16).ok_or_else(|| {
+                    format_err!(
+                        "cannot find the source location of {}::{} at {}",
+                        module_id,
+                        function_name,
+                        pc
+                    )
+                })?;
+                let line_hits = lines.entry(line_of(loc.start())).or_insert(0);
+                *line_hits = (*line_hits).max(hits_at(pc as u64));
+            }
+        }
+        Ok(Self {
+            source_path: source.source_path.to_path_buf(),
+            functions,
+            lines,
+        })
+    }
+}
+
+/// Write the coverage in the lcov tracefile format, which most coverage tools can read.
+pub fn write_lcov<W: Write>(writer: &mut W, files: &[SourceFileCoverage]) -> Result<()> {
+    writeln!(writer, "TN:")?;
+    for file in files {
+        writeln!(writer, "SF:{}", file.source_path.display())?;
+        for function in &file.functions {
+            writeln!(writer, "FN:{},{}", function.line, function.name)?;
+        }
+        for function in &file.functions {
+            writeln!(writer, "FNDA:{},{}", function.hits, function.name)?;
+        }
+        writeln!(writer, "FNF:{}", file.functions.len())?;
+        writeln!(
+            writer,
+            "FNH:{}",
+            file.functions.iter().filter(|f| f.hits > 0).count()
+        )?;
+        for (line, hits) in &file.lines {
+            writeln!(writer, "DA:{},{}", line, hits)?;
+        }
+        writeln!(writer, "LF:{}", file.lines.len())?;
+        writeln!(
+            writer,
+            "LH:{}",
+            file.lines.values().filter(|hits| **hits > 0).count()
+        )?;
+        writeln!(writer, "end_of_record")?;
+    }
+    Ok(())
+}
+
+/// Write the instruction coverage table of the modules, the same as `package coverage summary`.
+pub fn write_summary<W: Write>(
+    writer: &mut W,
+    modules: &[&CompiledModule],
+    coverage_map: &ExecCoverageMap,
+    summarize_functions: bool,
+) -> Result<()> {
+    let mut total_instructions = 0;
+    let mut total_covered = 0;
+    for module in modules {
+        let (instructions, covered) = summarize_inst_cov(module, coverage_map)
+            .summarize_human(writer, summarize_functions)?;
+        total_instructions += instructions;
+        total_covered += covered;
+    }
+    let percentage = if total_instructions == 0 {
+        0f64
+    } else {
+        total_covered as f64 / total_instructions as f64 * 100f64
+    };
+    writeln!(writer, "+-------------------------+")?;
+    writeln!(writer, "| % Move Coverage: {:.2}  |", percentage)?;
+    writeln!(writer, "+-------------------------+")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_lcov() {
+        let files = vec![
+            SourceFileCoverage {
+                source_path: PathBuf::from("sources/M.move"),
+                functions: vec![
+                    FunctionCoverage {
+                        name: "M::f".to_string(),
+                        line: 3,
+                        hits: 2,
+                    },
+                    FunctionCoverage {
+                        name: "M::g".to_string(),
+                        line: 7,
+                        hits: 0,
+                    },
+                ],
+                lines: [(4, 2), (5, 1), (8, 0)].into_iter().collect(),
+            },
+            SourceFileCoverage {
+                source_path: PathBuf::from("sources/N.move"),
+                functions: vec![],
+                lines: BTreeMap::new(),
+            },
+        ];
+        let mut output = vec![];
+        write_lcov(&mut output, &files).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "TN:\n\
+            SF:sources/M.move\n\
+            FN:3,M::f\n\
+            FN:7,M::g\n\
+            FNDA:2,M::f\n\
+            FNDA:0,M::g\n\
+            FNF:2\n\
+            FNH:1\n\
+            DA:4,2\n\
+            DA:5,1\n\
+            DA:8,0\n\
+            LF:3\n\
+            LH:2\n\
+            end_of_record\n\
+            SF:sources/N.move\n\
+            FNF:0\n\
+            FNH:0\n\
+            LF:0\n\
+            LH:0\n\
+            end_of_record\n"
+        );
+    }
+}
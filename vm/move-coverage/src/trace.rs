@@ -0,0 +1,195 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{format_err, Result};
+use move_coverage::coverage_map::CoverageMap;
+use std::fs::{self, File};
+use std::io::{self, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+/// The env var of the file the Move VM appends the trace of every executed instruction to.
+pub const MOVE_VM_TRACE: &str = "MOVE_VM_TRACE";
+
+/// Enable the trace of the Move VM for the process, `MOVE_VM_TRACE` is set to a temp file if it
+/// is not set yet.
+///
+/// The Move VM reads `MOVE_VM_TRACE` once, when it executes the first instruction of the process,
+/// and the env of the process must not be changed when other threads may read it, so it must be
+/// called at the start of `main`, before any thread is spawned and any Move code runs.
+pub fn enable_trace() -> Result<PathBuf> {
+    if let Some(trace_path) = std::env::var_os(MOVE_VM_TRACE) {
+        return Ok(PathBuf::from(trace_path));
+    }
+    let trace_path = std::env::temp_dir().join(format!("move-vm-{}.trace", std::process::id()));
+    if trace_path.exists() {
+        fs::remove_file(&trace_path)?;
+    }
+    std::env::set_var(MOVE_VM_TRACE, &trace_path);
+    Ok(trace_path)
+}
+
+/// Record the trace of the Move code executed in the process, from `start` to `finish`.
+///
+/// All the recorders of the process share the trace file of the Move VM, every recorder only
+/// reads the part of the trace appended after it is started.
+pub struct TraceRecorder {
+    trace_path: PathBuf,
+    offset: u64,
+}
+
+impl TraceRecorder {
+    /// Start to record the trace of the Move VM, which must be enabled when the process starts,
+    /// by `MOVE_VM_TRACE` or `enable_trace`.
+    pub fn start() -> Result<Self> {
+        let trace_path = std::env::var_os(MOVE_VM_TRACE).ok_or_else(|| {
+            format_err!(
+                "the Move VM trace is not enabled, set `{}` to the trace file when starting the \
+                process",
+                MOVE_VM_TRACE
+            )
+        })?;
+        Self::new(PathBuf::from(trace_path))
+    }
+
+    /// Record the trace appended to the `trace_path` from now on.
+    pub fn new(trace_path: PathBuf) -> Result<Self> {
+        let offset = match fs::metadata(&trace_path) {
+            Ok(metadata) => metadata.len(),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => 0,
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self { trace_path, offset })
+    }
+
+    pub fn trace_path(&self) -> &Path {
+        self.trace_path.as_path()
+    }
+
+    /// The coverage of the code executed since `start`, added to the `base` coverage if any.
+    pub fn finish(self, base: Option<CoverageMap>) -> Result<CoverageMap> {
+        let mut trace_file = File::open(&self.trace_path).map_err(|e| {
+            format_err!(
+                "cannot read the Move VM trace at {}, it is only recorded when the VM is started \
+                after `{}` is set: {}",
+                self.trace_path.display(),
+                MOVE_VM_TRACE,
+                e
+            )
+        })?;
+        trace_file.seek(SeekFrom::Start(self.offset))?;
+        let mut trace = tempfile::NamedTempFile::new()?;
+        io::copy(&mut trace_file, &mut trace)?;
+        Ok(match base {
+            Some(base) => base.update_coverage_from_trace_file(trace.path()),
+            None => CoverageMap::from_trace_file(trace.path()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_coverage::coverage_map::ExecCoverageMap;
+    use std::collections::BTreeMap;
+    use std::io::Write;
+
+    fn append(trace_path: &Path, lines: &[&str]) {
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(trace_path)
+            .unwrap();
+        for line in lines {
+            writeln!(file, "{}", line).unwrap();
+        }
+    }
+
+    /// The hits of every pc of the functions of the coverage.
+    fn hits(coverage_map: &CoverageMap) -> BTreeMap<(String, String), BTreeMap<u64, u64>> {
+        let exec_map: ExecCoverageMap = coverage_map.to_unified_exec_map();
+        exec_map
+            .module_maps
+            .into_iter()
+            .flat_map(|((_, module), module_map)| {
+                module_map
+                    .function_maps
+                    .into_iter()
+                    .map(move |(function, pc_hits)| {
+                        ((module.to_string(), function.to_string()), pc_hits)
+                    })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_record_from_offset() {
+        let dir = tempfile::tempdir().unwrap();
+        let trace_path = dir.path().join("move-vm.trace");
+        append(&trace_path, &["0x1::M::f,0,LdU64(1)", "0x1::M::f,1,Ret"]);
+
+        let recorder = TraceRecorder::new(trace_path.clone()).unwrap();
+        append(
+            &trace_path,
+            &[
+                "0x1::M::g,0,LdTrue",
+                "0x1::M::g,1,Ret",
+                "0x1::M::g,0,LdTrue",
+            ],
+        );
+        let coverage_map = recorder.finish(None).unwrap();
+
+        // the trace before the recorder is started is not counted.
+        let expected: BTreeMap<_, _> = [(
+            ("M".to_string(), "g".to_string()),
+            [(0, 2), (1, 1)].into_iter().collect(),
+        )]
+        .into_iter()
+        .collect();
+        assert_eq!(hits(&coverage_map), expected);
+    }
+
+    #[test]
+    fn test_record_without_trace() {
+        let dir = tempfile::tempdir().unwrap();
+        let trace_path = dir.path().join("move-vm.trace");
+        let recorder = TraceRecorder::new(trace_path.clone()).unwrap();
+        assert!(recorder.finish(None).is_err());
+
+        // the trace file is created by the VM after the recorder is started.
+        let recorder = TraceRecorder::new(trace_path.clone()).unwrap();
+        append(&trace_path, &["0x1::M::f,0,Ret"]);
+        let coverage_map = recorder.finish(None).unwrap();
+        assert_eq!(
+            hits(&coverage_map)[&("M".to_string(), "f".to_string())],
+            [(0, 1)].into_iter().collect()
+        );
+    }
+
+    #[test]
+    fn test_merge_into_base() {
+        let dir = tempfile::tempdir().unwrap();
+        let trace_path = dir.path().join("move-vm.trace");
+
+        let recorder = TraceRecorder::new(trace_path.clone()).unwrap();
+        append(&trace_path, &["0x1::M::f,0,LdU64(1)", "0x1::M::f,1,Ret"]);
+        let base = recorder.finish(None).unwrap();
+
+        let recorder = TraceRecorder::new(trace_path.clone()).unwrap();
+        append(&trace_path, &["0x1::M::f,0,LdU64(1)", "0x1::N::h,0,Ret"]);
+        let coverage_map = recorder.finish(Some(base)).unwrap();
+
+        let expected: BTreeMap<_, _> = [
+            (
+                ("M".to_string(), "f".to_string()),
+                [(0, 2), (1, 1)].into_iter().collect(),
+            ),
+            (
+                ("N".to_string(), "h".to_string()),
+                [(0, 1)].into_iter().collect(),
+            ),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(hits(&coverage_map), expected);
+    }
+}
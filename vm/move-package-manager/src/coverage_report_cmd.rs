@@ -0,0 +1,86 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use clap::Parser;
+use move_cli::sandbox::utils::PackageContext;
+use move_cli::Move;
+use move_compiler::compiled_unit::{CompiledUnit, NamedCompiledModule};
+use move_coverage::coverage_map::CoverageMap;
+use move_package::compilation::compiled_package::CompiledPackage;
+use move_package::source_package::layout::SourcePackageLayout;
+use starcoin_move_coverage::report::{write_lcov, write_summary, SourceFileCoverage, SourceModule};
+use starcoin_move_coverage::{COVERAGE_MAP_NAME, LCOV_REPORT_NAME};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+#[derive(Parser)]
+pub struct CoverageReportCommand {
+    #[clap(long = "summarize-functions")]
+    /// Whether function coverage summaries should be displayed.
+    summarize_functions: bool,
+
+    #[clap(long = "lcov", parse(from_os_str))]
+    /// Path to write the lcov report, default to `lcov.info` in the package root.
+    lcov: Option<PathBuf>,
+}
+
+/// Report the coverage collected by `integration-test --coverage` or `dev call --coverage`.
+pub fn handle_coverage_report(move_args: &Move, cmd: CoverageReportCommand) -> Result<()> {
+    let package_path = match move_args.package_path.as_ref() {
+        Some(path) => path.clone(),
+        None => std::env::current_dir()?,
+    };
+    let package_root = SourcePackageLayout::try_find_root(&package_path.canonicalize()?)?;
+    let coverage_map = CoverageMap::from_binary_file(package_root.join(COVERAGE_MAP_NAME))?;
+    let pkg_ctx = PackageContext::new(&Some(package_root.clone()), &move_args.build_config)?;
+    let lcov_path = cmd
+        .lcov
+        .unwrap_or_else(|| package_root.join(LCOV_REPORT_NAME));
+    report_coverage(
+        pkg_ctx.package(),
+        &coverage_map,
+        lcov_path.as_path(),
+        cmd.summarize_functions,
+    )
+}
+
+/// Write the lcov report of the modules of the package to `lcov_path`, and print the summary.
+pub fn report_coverage(
+    package: &CompiledPackage,
+    coverage_map: &CoverageMap,
+    lcov_path: &Path,
+    summarize_functions: bool,
+) -> Result<()> {
+    let coverage_map = coverage_map.to_unified_exec_map();
+    let sources: Vec<_> = package
+        .root_compiled_units
+        .iter()
+        .filter_map(|unit| match &unit.unit {
+            CompiledUnit::Module(NamedCompiledModule {
+                module, source_map, ..
+            }) => Some(SourceModule {
+                module,
+                source_map,
+                source_path: unit.source_path.as_path(),
+            }),
+            CompiledUnit::Script(_) => None,
+        })
+        .collect();
+
+    let files = sources
+        .iter()
+        .map(|source| SourceFileCoverage::new(source, &coverage_map))
+        .collect::<Result<Vec<_>>>()?;
+    write_lcov(&mut File::create(lcov_path)?, &files)?;
+
+    let modules: Vec<_> = sources.iter().map(|source| source.module).collect();
+    write_summary(
+        &mut std::io::stdout(),
+        &modules,
+        &coverage_map,
+        summarize_functions,
+    )?;
+    println!("lcov report written to {}", lcov_path.display());
+    Ok(())
+}
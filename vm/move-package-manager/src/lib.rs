@@ -1,6 +1,7 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::coverage_report_cmd::report_coverage;
 use anyhow::Result;
 use clap::{Args, Parser};
 use move_cli::Move;
@@ -12,9 +13,12 @@ use move_compiler::shared::NamedAddressMaps;
 use move_compiler::{
     cfgir, expansion, hlir, naming, parser, typing, Compiler, FullyCompiledProgram,
 };
+use move_coverage::coverage_map::output_map_to_file;
 use move_package::compilation::build_plan::BuildPlan;
 use move_package::source_package::layout::SourcePackageLayout;
 use once_cell::sync::Lazy;
+use starcoin_move_coverage::trace::TraceRecorder;
+use starcoin_move_coverage::{COVERAGE_MAP_NAME, LCOV_REPORT_NAME};
 use std::fmt::Display;
 use std::num::NonZeroUsize;
 use std::path::PathBuf;
@@ -22,6 +26,7 @@ use std::str::FromStr;
 use std::sync::Mutex;
 
 pub mod compatibility_check_cmd;
+pub mod coverage_report_cmd;
 pub mod deployment;
 pub mod package;
 pub mod release;
@@ -122,6 +127,12 @@ pub struct IntegrationTestCommand {
     /// If current project is the framework project,
     /// load these modules as stdlib and replace the default stdlib.
     current_as_stdlib: bool,
+
+    #[clap(long)]
+    /// Collect the coverage of the Move code executed by the tests, and report the coverage of
+    /// the package modules when the tests pass. The coverage map is saved in the package root for
+    /// `package coverage` and `coverage-report`.
+    pub coverage: bool,
 }
 
 static G_PRE_COMPILED_LIB: Lazy<Mutex<Option<FullyCompiledProgram>>> =
@@ -141,7 +152,7 @@ pub fn run_integration_test(move_arg: Move, cmd: IntegrationTestCommand) -> Resu
         std::env::set_current_dir(rooted_path).unwrap();
         PathBuf::from(".")
     };
-    let (pre_compiled_lib, _compiled_package) = {
+    let (pre_compiled_lib, compiled_package) = {
         // force move to rebuild all packages, so that we can use compile_driver to generate the full compiled program.
         let mut build_config = move_arg.build_config;
         build_config.force_recompilation = true;
@@ -316,6 +327,23 @@ pub fn run_integration_test(move_arg: Move, cmd: IntegrationTestCommand) -> Resu
     }
 
     let test_opts = datatest_stable::TestOpts::try_parse_from(test_args.as_slice())?;
+    // the trace is enabled at the start of the process, before the tests run any Move code.
+    let trace_recorder = if cmd.coverage {
+        Some(TraceRecorder::start()?)
+    } else {
+        None
+    };
     datatest_stable::runner_with_opts(&[requirements], test_opts);
+
+    if let Some(trace_recorder) = trace_recorder {
+        let coverage_map = trace_recorder.finish(None)?;
+        output_map_to_file(rerooted_path.join(COVERAGE_MAP_NAME), &coverage_map)?;
+        report_coverage(
+            &compiled_package,
+            &coverage_map,
+            rerooted_path.join(LCOV_REPORT_NAME).as_path(),
+            false,
+        )?;
+    }
     Ok(())
 }
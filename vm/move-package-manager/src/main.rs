@@ -9,12 +9,14 @@ use move_core_types::errmap::ErrorMapping;
 use move_package_manager::compatibility_check_cmd::{
     handle_compatibility_check, CompatibilityCheckCommand,
 };
+use move_package_manager::coverage_report_cmd::{handle_coverage_report, CoverageReportCommand};
 use move_package_manager::deployment::{handle_deployment, DeploymentCommand};
 use move_package_manager::package::{handle_package_commands, PackageCommand};
 use move_package_manager::release::{handle_release, Release};
 use move_package_manager::{run_integration_test, IntegrationTestCommand};
 use move_vm_test_utils::gas_schedule::CostTable;
 use starcoin_config::genesis_config::G_LATEST_GAS_PARAMS;
+use starcoin_move_coverage::trace::enable_trace;
 use starcoin_vm_runtime::natives::starcoin_natives;
 use starcoin_vm_types::on_chain_config::G_LATEST_INSTRUCTION_TABLE;
 use std::path::PathBuf;
@@ -72,12 +74,23 @@ pub enum Commands {
     /// Deploy package to chain
     #[clap(name = "deploy")]
     Deploy(DeploymentCommand),
+
+    /// Report the coverage collected by `integration-test --coverage` or `dev call --coverage`,
+    /// in lcov format and as a summary table.
+    #[clap(name = "coverage-report")]
+    CoverageReport(CoverageReportCommand),
 }
 
 fn main() -> Result<()> {
     let error_descriptions: ErrorMapping =
         bcs_ext::from_bytes(stdlib::ERROR_DESCRIPTIONS).expect("Decode err map failed");
     let args: CliOptions = CliOptions::parse();
+    // the Move VM reads the trace env once, so it is set before anything runs.
+    if let Commands::IntegrationTest(cmd) = &args.cmd {
+        if cmd.coverage {
+            enable_trace()?;
+        }
+    }
 
     let move_args = &args.move_args;
     let gas_params = G_LATEST_GAS_PARAMS.clone();
@@ -99,5 +112,6 @@ fn main() -> Result<()> {
         Commands::Release(release) => handle_release(move_args, release),
         Commands::CompatibilityCheck(cmd) => handle_compatibility_check(move_args, cmd),
         Commands::Deploy(cmd) => handle_deployment(move_args, cmd),
+        Commands::CoverageReport(cmd) => handle_coverage_report(move_args, cmd),
     }
 }
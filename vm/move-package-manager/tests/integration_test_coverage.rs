@@ -0,0 +1,91 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{ensure, Result};
+use starcoin_move_coverage::{COVERAGE_MAP_NAME, LCOV_REPORT_NAME};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+fn write_package(package_dir: &Path) -> Result<()> {
+    fs::create_dir_all(package_dir.join("sources"))?;
+    fs::create_dir_all(package_dir.join("integration-tests"))?;
+    fs::write(
+        package_dir.join("Move.toml"),
+        r#"[package]
+name = "Coverage"
+version = "0.0.1"
+
+[addresses]
+Coverage = "0x42"
+"#,
+    )?;
+    fs::write(
+        package_dir.join("sources/Math.move"),
+        r#"module Coverage::Math {
+    public fun add(a: u64, b: u64): u64 {
+        a + b
+    }
+
+    public fun unused(): u64 {
+        0
+    }
+}
+"#,
+    )?;
+    fs::write(
+        package_dir.join("integration-tests/math.move"),
+        r#"//# init -n dev
+
+//# call 0x42::Math::add --args 1u64 --args 2u64
+"#,
+    )?;
+    fs::write(
+        package_dir.join("integration-tests/math.exp"),
+        r#"processed 2 tasks
+
+task 1 'call'. lines 3-3:
+3
+"#,
+    )?;
+    Ok(())
+}
+
+#[test]
+fn test_integration_test_coverage() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let package_dir = dir.path().join("coverage");
+    write_package(&package_dir)?;
+
+    let output = Command::new(env!("CARGO_BIN_EXE_mpm"))
+        .current_dir(&package_dir)
+        .env("MOVE_VM_TRACE", dir.path().join("move-vm.trace"))
+        .args(["integration-test", "--coverage"])
+        .output()?;
+    ensure!(
+        output.status.success(),
+        "mpm integration-test --coverage failed: {}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let coverage_map = package_dir.join(COVERAGE_MAP_NAME);
+    ensure!(
+        coverage_map.is_file() && fs::metadata(&coverage_map)?.len() > 0,
+        "the coverage map {} is not saved",
+        coverage_map.display()
+    );
+
+    // only the function called by the tests is covered.
+    let lcov = fs::read_to_string(package_dir.join(LCOV_REPORT_NAME))?;
+    let lines: Vec<_> = lcov.lines().collect();
+    assert!(lines
+        .iter()
+        .any(|line| line.starts_with("SF:") && line.ends_with("Math.move")));
+    assert!(lines.contains(&"FNDA:1,Math::add"));
+    assert!(lines.contains(&"FNDA:0,Math::unused"));
+    assert!(lines.contains(&"FNF:2"));
+    assert!(lines.contains(&"FNH:1"));
+    assert!(lines.contains(&"end_of_record"));
+    Ok(())
+}
@@ -0,0 +1,270 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! BLS12-381 signatures in the minimal-pubkey-size variant of the proof-of-possession scheme:
+//! public keys are 48 bytes compressed G1 points and signatures are 96 bytes compressed G2 points.
+
+use blst::min_pk::{PublicKey, Signature};
+use blst::BLST_ERROR;
+use log::debug;
+use move_binary_format::errors::PartialVMResult;
+use move_core_types::gas_algebra::{
+    InternalGas, InternalGasPerArg, InternalGasPerByte, NumArgs, NumBytes,
+};
+use move_vm_runtime::native_functions::{NativeContext, NativeFunction};
+use move_vm_types::{
+    loaded_data::runtime_types::Type, natives::function::NativeResult, pop_arg, values::Value,
+};
+use smallvec::smallvec;
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+/// Domain separation tag for signing messages.
+pub const DST_SIG: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+/// Domain separation tag for signing the proof-of-possession of a public key.
+pub const DST_POP: &[u8] = b"BLS_POP_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+/// Deserialize the public key, and check it is not the identity and is in the prime order subgroup.
+fn validate_pubkey(bytes: &[u8]) -> Option<PublicKey> {
+    PublicKey::key_validate(bytes)
+        .map_err(|e| debug!("invalid bls12381 public key: {:?}", e))
+        .ok()
+}
+
+fn parse_signature(bytes: &[u8]) -> Option<Signature> {
+    Signature::from_bytes(bytes)
+        .map_err(|e| debug!("invalid bls12381 signature: {:?}", e))
+        .ok()
+}
+
+fn verify(pubkey: &[u8], msg: &[u8], signature: &[u8]) -> bool {
+    match (validate_pubkey(pubkey), parse_signature(signature)) {
+        (Some(pk), Some(sig)) => {
+            sig.verify(true, msg, DST_SIG, &[], &pk, false) == BLST_ERROR::BLST_SUCCESS
+        }
+        _ => false,
+    }
+}
+
+fn aggregate_verify(pubkeys: &[Vec<u8>], msg: &[u8], signature: &[u8]) -> bool {
+    if pubkeys.is_empty() {
+        return false;
+    }
+    let pks = match pubkeys
+        .iter()
+        .map(|pk| validate_pubkey(pk.as_slice()))
+        .collect::<Option<Vec<_>>>()
+    {
+        Some(pks) => pks,
+        None => return false,
+    };
+    let sig = match parse_signature(signature) {
+        Some(sig) => sig,
+        None => return false,
+    };
+    let pk_refs = pks.iter().collect::<Vec<_>>();
+    sig.fast_aggregate_verify(true, msg, DST_SIG, pk_refs.as_slice()) == BLST_ERROR::BLST_SUCCESS
+}
+
+fn verify_proof_of_possession(pubkey: &[u8], pop: &[u8]) -> bool {
+    match (validate_pubkey(pubkey), parse_signature(pop)) {
+        (Some(pk), Some(pop)) => {
+            pop.verify(true, pubkey, DST_POP, &[], &pk, false) == BLST_ERROR::BLST_SUCCESS
+        }
+        _ => false,
+    }
+}
+
+/***************************************************************************************************
+ * native fun bls12381_verify
+ *
+ *   gas cost: base_cost + unit_cost * msg_length
+ *
+ **************************************************************************************************/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bls12381VerifyGasParameters {
+    pub base: InternalGas,
+    pub per_byte: InternalGasPerByte,
+}
+
+pub fn native_bls12381_verify(
+    gas_params: &Bls12381VerifyGasParameters,
+    _context: &mut NativeContext,
+    _ty_args: Vec<Type>,
+    mut arguments: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(_ty_args.is_empty());
+    debug_assert!(arguments.len() == 3);
+
+    let signature = pop_arg!(arguments, Vec<u8>);
+    let msg = pop_arg!(arguments, Vec<u8>);
+    let pubkey = pop_arg!(arguments, Vec<u8>);
+
+    let cost = gas_params.base + gas_params.per_byte * NumBytes::new(msg.len() as u64);
+
+    let valid = verify(pubkey.as_slice(), msg.as_slice(), signature.as_slice());
+    Ok(NativeResult::ok(cost, smallvec![Value::bool(valid)]))
+}
+
+pub fn make_native_bls12381_verify(gas_params: Bls12381VerifyGasParameters) -> NativeFunction {
+    Arc::new(move |context, ty_args, args| {
+        native_bls12381_verify(&gas_params, context, ty_args, args)
+    })
+}
+
+/***************************************************************************************************
+ * native fun bls12381_aggregate_verify
+ *
+ *   gas cost: base_cost + unit_cost * pubkey_num + unit_cost * msg_length
+ *
+ *   The public keys must have verified proofs-of-possession by
+ *   `bls12381_verify_proof_of_possession`, to prevent rogue-key attacks.
+ *
+ **************************************************************************************************/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bls12381AggregateVerifyGasParameters {
+    pub base: InternalGas,
+    pub per_pubkey: InternalGasPerArg,
+    pub per_byte: InternalGasPerByte,
+}
+
+/// Verify an aggregate signature of the same message signed by all the public keys.
+/// The caller must ensure every public key has a verified proof-of-possession,
+/// otherwise the aggregation is vulnerable to rogue-key attacks.
+pub fn native_bls12381_aggregate_verify(
+    gas_params: &Bls12381AggregateVerifyGasParameters,
+    _context: &mut NativeContext,
+    _ty_args: Vec<Type>,
+    mut arguments: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(_ty_args.is_empty());
+    debug_assert!(arguments.len() == 3);
+
+    let signature = pop_arg!(arguments, Vec<u8>);
+    let msg = pop_arg!(arguments, Vec<u8>);
+    let pubkeys = pop_arg!(arguments, Vec<Value>)
+        .into_iter()
+        .map(|pk| pk.value_as::<Vec<u8>>())
+        .collect::<PartialVMResult<Vec<_>>>()?;
+
+    let cost = gas_params.base
+        + gas_params.per_pubkey * NumArgs::new(pubkeys.len() as u64)
+        + gas_params.per_byte * NumBytes::new(msg.len() as u64);
+
+    let valid = aggregate_verify(pubkeys.as_slice(), msg.as_slice(), signature.as_slice());
+    Ok(NativeResult::ok(cost, smallvec![Value::bool(valid)]))
+}
+
+pub fn make_native_bls12381_aggregate_verify(
+    gas_params: Bls12381AggregateVerifyGasParameters,
+) -> NativeFunction {
+    Arc::new(move |context, ty_args, args| {
+        native_bls12381_aggregate_verify(&gas_params, context, ty_args, args)
+    })
+}
+
+/***************************************************************************************************
+ * native fun bls12381_verify_proof_of_possession
+ *
+ *   gas cost: base_cost + unit_cost * pubkey_length
+ *
+ **************************************************************************************************/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bls12381VerifyPopGasParameters {
+    pub base: InternalGas,
+    /// The proof-of-possession signs the public key, so it is charged by the public key length.
+    pub per_byte: InternalGasPerByte,
+}
+
+pub fn native_bls12381_verify_proof_of_possession(
+    gas_params: &Bls12381VerifyPopGasParameters,
+    _context: &mut NativeContext,
+    _ty_args: Vec<Type>,
+    mut arguments: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(_ty_args.is_empty());
+    debug_assert!(arguments.len() == 2);
+
+    let pop = pop_arg!(arguments, Vec<u8>);
+    let pubkey = pop_arg!(arguments, Vec<u8>);
+
+    let cost = gas_params.base + gas_params.per_byte * NumBytes::new(pubkey.len() as u64);
+
+    let valid = verify_proof_of_possession(pubkey.as_slice(), pop.as_slice());
+    Ok(NativeResult::ok(cost, smallvec![Value::bool(valid)]))
+}
+
+pub fn make_native_bls12381_verify_proof_of_possession(
+    gas_params: Bls12381VerifyPopGasParameters,
+) -> NativeFunction {
+    Arc::new(move |context, ty_args, args| {
+        native_bls12381_verify_proof_of_possession(&gas_params, context, ty_args, args)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use blst::min_pk::{AggregateSignature, SecretKey};
+    use rand::RngCore;
+
+    fn random_key() -> SecretKey {
+        let mut ikm = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut ikm);
+        SecretKey::key_gen(&ikm, &[]).unwrap()
+    }
+
+    #[test]
+    fn test_verify() {
+        let sk = random_key();
+        let pk = sk.sk_to_pk().to_bytes();
+        let msg = b"starcoin";
+        let sig = sk.sign(msg, DST_SIG, &[]).to_bytes();
+
+        assert!(verify(&pk, msg, &sig));
+        assert!(!verify(&pk, b"starcoin2", &sig));
+        // a signature with the proof-of-possession tag is not a message signature.
+        let pop_tagged = sk.sign(msg, DST_POP, &[]).to_bytes();
+        assert!(!verify(&pk, msg, &pop_tagged));
+        assert!(!verify(&[0u8; 48], msg, &sig));
+        assert!(!verify(&pk, msg, &[0u8; 96]));
+    }
+
+    #[test]
+    fn test_aggregate_verify() {
+        let msg = b"validator set";
+        let sks = (0..4).map(|_| random_key()).collect::<Vec<_>>();
+        let pks = sks
+            .iter()
+            .map(|sk| sk.sk_to_pk().to_bytes().to_vec())
+            .collect::<Vec<_>>();
+        let sigs = sks
+            .iter()
+            .map(|sk| sk.sign(msg, DST_SIG, &[]))
+            .collect::<Vec<_>>();
+        let sig_refs = sigs.iter().collect::<Vec<_>>();
+        let agg_sig = AggregateSignature::aggregate(sig_refs.as_slice(), true)
+            .unwrap()
+            .to_signature()
+            .to_bytes();
+
+        assert!(aggregate_verify(pks.as_slice(), msg, &agg_sig));
+        assert!(!aggregate_verify(&pks[1..], msg, &agg_sig));
+        assert!(!aggregate_verify(pks.as_slice(), b"other", &agg_sig));
+        assert!(!aggregate_verify(&[], msg, &agg_sig));
+    }
+
+    #[test]
+    fn test_verify_proof_of_possession() {
+        let sk = random_key();
+        let pk = sk.sk_to_pk().to_bytes();
+        let pop = sk.sign(&pk, DST_POP, &[]).to_bytes();
+        assert!(verify_proof_of_possession(&pk, &pop));
+
+        let msg_sig = sk.sign(&pk, DST_SIG, &[]).to_bytes();
+        assert!(!verify_proof_of_possession(&pk, &msg_sig));
+
+        let other = random_key().sk_to_pk().to_bytes();
+        assert!(!verify_proof_of_possession(&other, &pop));
+    }
+}
@@ -2,7 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod account;
+pub mod bls12381;
 pub mod hash;
+pub mod secp256r1;
 pub mod signature;
 pub mod token;
 pub mod u256;
@@ -51,6 +53,23 @@ impl GasParameters {
                     base: 0.into(),
                     per_byte: 0.into(),
                 },
+                bls12381_verify: bls12381::Bls12381VerifyGasParameters {
+                    base: 0.into(),
+                    per_byte: 0.into(),
+                },
+                bls12381_aggregate_verify: bls12381::Bls12381AggregateVerifyGasParameters {
+                    base: 0.into(),
+                    per_pubkey: 0.into(),
+                    per_byte: 0.into(),
+                },
+                bls12381_verify_pop: bls12381::Bls12381VerifyPopGasParameters {
+                    base: 0.into(),
+                    per_byte: 0.into(),
+                },
+                secp256r1_verify: secp256r1::Secp256r1VerifyGasParameters {
+                    base: 0.into(),
+                    per_byte: 0.into(),
+                },
             },
             token: token::GasParameters {
                 name_of: token::NameOfGasParameters { base: 0.into() },
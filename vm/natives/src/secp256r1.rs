@@ -0,0 +1,124 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use log::debug;
+use move_binary_format::errors::PartialVMResult;
+use move_core_types::gas_algebra::{InternalGas, InternalGasPerByte, NumBytes};
+use move_vm_runtime::native_functions::{NativeContext, NativeFunction};
+use move_vm_types::{
+    loaded_data::runtime_types::Type, natives::function::NativeResult, pop_arg, values::Value,
+};
+use p256::ecdsa::signature::Verifier;
+use p256::ecdsa::{Signature, VerifyingKey};
+use smallvec::smallvec;
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+/***************************************************************************************************
+ * native fun native_secp256r1_verify
+ *
+ *   gas cost: base_cost + unit_cost * data_length
+ *
+ **************************************************************************************************/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Secp256r1VerifyGasParameters {
+    pub base: InternalGas,
+    pub per_byte: InternalGasPerByte,
+}
+
+/// Verify the ECDSA P-256 signature of the SHA-256 digest of the message.
+/// The public key is a SEC1 encoded point, compressed or uncompressed, and the signature is the
+/// 64 bytes `r || s` with a low `s`, high `s` signatures are rejected to avoid malleability.
+fn secp256r1_verify(signature: &[u8], pubkey: &[u8], msg: &[u8]) -> bool {
+    let pk = match VerifyingKey::from_sec1_bytes(pubkey) {
+        Ok(pk) => pk,
+        Err(e) => {
+            debug!("invalid secp256r1 public key: {:?}", e);
+            return false;
+        }
+    };
+    let sig = match Signature::try_from(signature) {
+        Ok(sig) => sig,
+        Err(e) => {
+            debug!("invalid secp256r1 signature: {:?}", e);
+            return false;
+        }
+    };
+    if sig.normalize_s().is_some() {
+        debug!("secp256r1 signature with high s");
+        return false;
+    }
+    pk.verify(msg, &sig).is_ok()
+}
+
+pub fn native_secp256r1_verify(
+    gas_params: &Secp256r1VerifyGasParameters,
+    _context: &mut NativeContext,
+    _ty_args: Vec<Type>,
+    mut arguments: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(_ty_args.is_empty());
+    debug_assert!(arguments.len() == 3);
+
+    let msg = pop_arg!(arguments, Vec<u8>);
+    let pubkey = pop_arg!(arguments, Vec<u8>);
+    let signature = pop_arg!(arguments, Vec<u8>);
+
+    let cost = gas_params.base + gas_params.per_byte * NumBytes::new(msg.len() as u64);
+
+    let valid = secp256r1_verify(signature.as_slice(), pubkey.as_slice(), msg.as_slice());
+    Ok(NativeResult::ok(cost, smallvec![Value::bool(valid)]))
+}
+
+pub fn make_native_secp256r1_verify(gas_params: Secp256r1VerifyGasParameters) -> NativeFunction {
+    Arc::new(move |context, ty_args, args| {
+        native_secp256r1_verify(&gas_params, context, ty_args, args)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use p256::ecdsa::signature::Signer;
+    use p256::ecdsa::SigningKey;
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn test_secp256r1_verify() {
+        let sk = SigningKey::random(&mut OsRng);
+        let pk = sk.verifying_key();
+        let compressed = pk.to_encoded_point(true);
+        let uncompressed = pk.to_encoded_point(false);
+        let msg = b"starcoin";
+        let sig: Signature = sk.sign(msg);
+        let sig = sig.normalize_s().unwrap_or(sig);
+
+        assert!(secp256r1_verify(sig.as_ref(), compressed.as_bytes(), msg));
+        assert!(secp256r1_verify(sig.as_ref(), uncompressed.as_bytes(), msg));
+        assert!(!secp256r1_verify(
+            sig.as_ref(),
+            compressed.as_bytes(),
+            b"starcoin2"
+        ));
+        assert!(!secp256r1_verify(&[0u8; 64], compressed.as_bytes(), msg));
+        assert!(!secp256r1_verify(sig.as_ref(), &[0u8; 33], msg));
+    }
+
+    #[test]
+    fn test_secp256r1_reject_high_s() {
+        let sk = SigningKey::random(&mut OsRng);
+        let compressed = sk.verifying_key().to_encoded_point(true);
+        let msg = b"starcoin";
+        let sig: Signature = sk.sign(msg);
+        let low_s = sig.normalize_s().unwrap_or(sig);
+        let (r, s) = low_s.split_scalars();
+        let high_s = Signature::from_scalars(r, -*s).unwrap();
+
+        assert!(secp256r1_verify(low_s.as_ref(), compressed.as_bytes(), msg));
+        assert!(!secp256r1_verify(
+            high_s.as_ref(),
+            compressed.as_bytes(),
+            msg
+        ));
+    }
+}
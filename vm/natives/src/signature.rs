@@ -1,7 +1,13 @@
 // Copyright (c) The Diem Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::bls12381::{
+    make_native_bls12381_aggregate_verify, make_native_bls12381_verify,
+    make_native_bls12381_verify_proof_of_possession, Bls12381AggregateVerifyGasParameters,
+    Bls12381VerifyGasParameters, Bls12381VerifyPopGasParameters,
+};
 use crate::ecrecover::{make_native_ecrecover, EcrecoverGasParameters};
+use crate::secp256r1::{make_native_secp256r1_verify, Secp256r1VerifyGasParameters};
 use move_binary_format::errors::PartialVMResult;
 use move_core_types::gas_algebra::{InternalGas, InternalGasPerByte, NumBytes};
 use move_vm_runtime::native_functions::{NativeContext, NativeFunction};
@@ -113,6 +119,10 @@ pub struct GasParameters {
     pub ed25519_validate_key: Ed25519ValidateKeyGasParameters,
     pub ed25519_verify: Ed25519VerifyGasParameters,
     pub ec_recover: EcrecoverGasParameters,
+    pub bls12381_verify: Bls12381VerifyGasParameters,
+    pub bls12381_aggregate_verify: Bls12381AggregateVerifyGasParameters,
+    pub bls12381_verify_pop: Bls12381VerifyPopGasParameters,
+    pub secp256r1_verify: Secp256r1VerifyGasParameters,
 }
 
 pub fn make_all(gas_params: GasParameters) -> impl Iterator<Item = (String, NativeFunction)> {
@@ -129,6 +139,22 @@ pub fn make_all(gas_params: GasParameters) -> impl Iterator<Item = (String, Nati
             "native_ecrecover",
             make_native_ecrecover(gas_params.ec_recover),
         ),
+        (
+            "bls12381_verify",
+            make_native_bls12381_verify(gas_params.bls12381_verify),
+        ),
+        (
+            "bls12381_aggregate_verify",
+            make_native_bls12381_aggregate_verify(gas_params.bls12381_aggregate_verify),
+        ),
+        (
+            "bls12381_verify_proof_of_possession",
+            make_native_bls12381_verify_proof_of_possession(gas_params.bls12381_verify_pop),
+        ),
+        (
+            "native_secp256r1_verify",
+            make_native_secp256r1_verify(gas_params.secp256r1_verify),
+        ),
     ];
 
     crate::helpers::make_module_natives(natives)
@@ -0,0 +1,102 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use clap::Parser;
+use starcoin_gas::calibration::{
+    calibrate, diff_gas_schedule, CalibrationOptions, GasScheduleResource, DEFAULT_REFERENCE,
+};
+use starcoin_gas::StarcoinGasParameters;
+use starcoin_gas_algebra_ext::{InitialGasSchedule, ToOnChainGasSchedule};
+use std::fs::File;
+use std::path::PathBuf;
+
+#[derive(Debug, Parser)]
+#[clap(name = "starcoin-gas-calibration")]
+/// Calibrate the instruction and native gas parameters by micro-benchmarks, and propose a gas
+/// schedule for the gas schedule upgrade proposal of the DAO.
+struct CalibrationOpt {
+    #[clap(long = "current")]
+    /// the json of the current on-chain `0x1::GasSchedule::GasSchedule` resource, in the shape of
+    /// `{"entries": [{"key": "0x<hex of the name>", "val": 1}]}`.
+    /// Default to the initial gas schedule.
+    current: Option<PathBuf>,
+
+    #[clap(long = "reference", default_value = DEFAULT_REFERENCE)]
+    /// the gas parameter which keeps its current value, the others are priced relative to it.
+    reference: String,
+
+    #[clap(long = "iterations", default_value = "1000")]
+    /// the loop iterations of every benchmark.
+    iterations: u64,
+
+    #[clap(long = "samples", default_value = "10")]
+    /// how many times every benchmark is timed.
+    samples: usize,
+
+    #[clap(long = "ridge", default_value = "0.01")]
+    /// how strongly the costs are pulled towards the current gas schedule.
+    ridge: f64,
+
+    #[clap(short = 'o', long = "output-dir")]
+    /// dir to save the proposed gas schedule, in the json shape of `--current`, and the diff.
+    /// Default to current dir.
+    output_dir: Option<PathBuf>,
+}
+
+fn main() -> Result<()> {
+    let _logger = starcoin_logger::init();
+    let opt: CalibrationOpt = CalibrationOpt::parse();
+    let current = match opt.current.as_ref() {
+        Some(path) => serde_json::from_reader::<_, GasScheduleResource>(File::open(path)?)?
+            .into_gas_schedule()?,
+        None => StarcoinGasParameters::initial().to_on_chain_gas_schedule(),
+    };
+
+    let calibration = calibrate(
+        &current,
+        &CalibrationOptions {
+            iterations: opt.iterations,
+            samples: opt.samples,
+            ridge: opt.ridge,
+        },
+    )?;
+    let proposed = calibration.propose(&current, opt.reference.as_str())?;
+    let diff = diff_gas_schedule(&current, &proposed);
+
+    let output_dir = opt.output_dir.unwrap_or(std::env::current_dir()?);
+    serde_json::to_writer_pretty(
+        File::create(output_dir.join("gas_schedule.json"))?,
+        &GasScheduleResource::new(&proposed),
+    )?;
+    serde_json::to_writer_pretty(
+        File::create(output_dir.join("gas_schedule_diff.json"))?,
+        &diff,
+    )?;
+
+    for change in &diff {
+        println!(
+            "{}: {} -> {}",
+            change.key,
+            change
+                .current
+                .map_or_else(|| "-".to_string(), |v| v.to_string()),
+            change
+                .proposed
+                .map_or_else(|| "-".to_string(), |v| v.to_string())
+        );
+    }
+    let uncalibrated = calibration.uncalibrated(&current);
+    if !uncalibrated.is_empty() {
+        eprintln!(
+            "The gas parameters not measured by any benchmark are kept: {:?}",
+            uncalibrated
+        );
+    }
+    println!(
+        "{} gas parameters changed, the proposed gas schedule and the diff are saved to {}",
+        diff.len(),
+        output_dir.display()
+    );
+    Ok(())
+}
@@ -0,0 +1,353 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A minimal builder of the compiled modules of the benchmarks, the benchmarks are generated as
+//! bytecode to control exactly which instructions are executed.
+
+use crate::calibration::Benchmark;
+use move_binary_format::file_format::{
+    empty_module, AbilitySet, Bytecode, CodeOffset, CodeUnit, CompiledModule, Constant,
+    ConstantPoolIndex, FieldDefinition, FieldHandle, FieldHandleIndex, FieldInstantiation,
+    FieldInstantiationIndex, FunctionDefinition, FunctionHandle, FunctionHandleIndex,
+    FunctionInstantiation, FunctionInstantiationIndex, IdentifierIndex, LocalIndex, Signature,
+    SignatureIndex, SignatureToken, StructDefInstantiation, StructDefInstantiationIndex,
+    StructDefinition, StructDefinitionIndex, StructFieldInformation, StructHandle,
+    StructHandleIndex, StructTypeParameter, TableIndex, TypeSignature, Visibility,
+};
+use move_core_types::account_address::AccountAddress;
+use move_core_types::identifier::Identifier;
+use move_core_types::language_storage::{ModuleId, CORE_CODE_ADDRESS};
+use move_core_types::value::MoveValue;
+
+/// The parameters of every benchmark function are `(account: signer, addr: address, n: u64)`,
+/// where `addr` is the address of `account` and `n` is the number of the loop iterations.
+pub(crate) const ACCOUNT: LocalIndex = 0;
+pub(crate) const ADDR: LocalIndex = 1;
+pub(crate) const N: LocalIndex = 2;
+/// The loop counter, from 0 to `n`.
+pub(crate) const I: LocalIndex = 3;
+/// The first local declared by a benchmark.
+pub(crate) const FIRST_LOCAL: LocalIndex = 4;
+
+fn intern<T: PartialEq>(pool: &mut Vec<T>, item: T) -> TableIndex {
+    match pool.iter().position(|existing| *existing == item) {
+        Some(idx) => idx as TableIndex,
+        None => {
+            pool.push(item);
+            (pool.len() - 1) as TableIndex
+        }
+    }
+}
+
+pub(crate) struct ModuleBuilder {
+    module: CompiledModule,
+}
+
+impl ModuleBuilder {
+    pub fn new(address: AccountAddress, name: &str) -> Self {
+        let mut module = empty_module();
+        module.address_identifiers[0] = address;
+        module.identifiers[0] = Identifier::new(name).expect("module name should be valid");
+        Self { module }
+    }
+
+    pub fn module_id(&self) -> ModuleId {
+        ModuleId::new(
+            self.module.address_identifiers[0],
+            self.module.identifiers[0].clone(),
+        )
+    }
+
+    pub fn identifier(&mut self, name: &str) -> IdentifierIndex {
+        let name = Identifier::new(name).expect("identifier should be valid");
+        IdentifierIndex(intern(&mut self.module.identifiers, name))
+    }
+
+    pub fn signature(&mut self, tokens: Vec<SignatureToken>) -> SignatureIndex {
+        SignatureIndex(intern(&mut self.module.signatures, Signature(tokens)))
+    }
+
+    pub fn constant(&mut self, type_: SignatureToken, value: MoveValue) -> ConstantPoolIndex {
+        let data = value
+            .simple_serialize()
+            .expect("constant should be serializable");
+        ConstantPoolIndex(intern(
+            &mut self.module.constant_pool,
+            Constant { type_, data },
+        ))
+    }
+
+    pub fn bytes(&mut self, bytes: Vec<u8>) -> ConstantPoolIndex {
+        self.constant(
+            SignatureToken::Vector(Box::new(SignatureToken::U8)),
+            MoveValue::vector_u8(bytes),
+        )
+    }
+
+    pub fn add_struct(
+        &mut self,
+        name: &str,
+        abilities: AbilitySet,
+        type_parameters: Vec<StructTypeParameter>,
+        fields: Vec<(&str, SignatureToken)>,
+    ) -> StructDefinitionIndex {
+        let handle = StructHandle {
+            module: self.module.self_module_handle_idx,
+            name: self.identifier(name),
+            abilities,
+            type_parameters,
+        };
+        let handle = StructHandleIndex(intern(&mut self.module.struct_handles, handle));
+        let fields = fields
+            .into_iter()
+            .map(|(name, token)| FieldDefinition {
+                name: self.identifier(name),
+                signature: TypeSignature(token),
+            })
+            .collect();
+        self.module.struct_defs.push(StructDefinition {
+            struct_handle: handle,
+            field_information: StructFieldInformation::Declared(fields),
+        });
+        StructDefinitionIndex((self.module.struct_defs.len() - 1) as TableIndex)
+    }
+
+    pub fn struct_handle(&self, def: StructDefinitionIndex) -> StructHandleIndex {
+        self.module.struct_defs[def.0 as usize].struct_handle
+    }
+
+    pub fn struct_instantiation(
+        &mut self,
+        def: StructDefinitionIndex,
+        type_args: Vec<SignatureToken>,
+    ) -> StructDefInstantiationIndex {
+        let type_parameters = self.signature(type_args);
+        StructDefInstantiationIndex(intern(
+            &mut self.module.struct_def_instantiations,
+            StructDefInstantiation {
+                def,
+                type_parameters,
+            },
+        ))
+    }
+
+    pub fn field(&mut self, owner: StructDefinitionIndex, field: u16) -> FieldHandleIndex {
+        FieldHandleIndex(intern(
+            &mut self.module.field_handles,
+            FieldHandle { owner, field },
+        ))
+    }
+
+    pub fn field_instantiation(
+        &mut self,
+        handle: FieldHandleIndex,
+        type_args: Vec<SignatureToken>,
+    ) -> FieldInstantiationIndex {
+        let type_parameters = self.signature(type_args);
+        FieldInstantiationIndex(intern(
+            &mut self.module.field_instantiations,
+            FieldInstantiation {
+                handle,
+                type_parameters,
+            },
+        ))
+    }
+
+    fn function_handle(
+        &mut self,
+        name: &str,
+        type_parameters: Vec<AbilitySet>,
+        parameters: Vec<SignatureToken>,
+        return_: Vec<SignatureToken>,
+    ) -> FunctionHandleIndex {
+        let handle = FunctionHandle {
+            module: self.module.self_module_handle_idx,
+            name: self.identifier(name),
+            parameters: self.signature(parameters),
+            return_: self.signature(return_),
+            type_parameters,
+        };
+        FunctionHandleIndex(intern(&mut self.module.function_handles, handle))
+    }
+
+    /// Declare a public native function, which is implemented in the native function table.
+    pub fn add_native(
+        &mut self,
+        name: &str,
+        type_parameters: Vec<AbilitySet>,
+        parameters: Vec<SignatureToken>,
+        return_: Vec<SignatureToken>,
+    ) -> FunctionHandleIndex {
+        let function = self.function_handle(name, type_parameters, parameters, return_);
+        self.module.function_defs.push(FunctionDefinition {
+            function,
+            visibility: Visibility::Public,
+            is_entry: false,
+            acquires_global_resources: vec![],
+            code: None,
+        });
+        function
+    }
+
+    pub fn add_function(
+        &mut self,
+        name: &str,
+        type_parameters: Vec<AbilitySet>,
+        parameters: Vec<SignatureToken>,
+        locals: Vec<SignatureToken>,
+        acquires: Vec<StructDefinitionIndex>,
+        code: Vec<Bytecode>,
+    ) -> FunctionHandleIndex {
+        let function = self.function_handle(name, type_parameters, parameters, vec![]);
+        let locals = self.signature(locals);
+        self.module.function_defs.push(FunctionDefinition {
+            function,
+            visibility: Visibility::Public,
+            is_entry: false,
+            acquires_global_resources: acquires,
+            code: Some(CodeUnit { locals, code }),
+        });
+        function
+    }
+
+    pub fn function_instantiation(
+        &mut self,
+        handle: FunctionHandleIndex,
+        type_args: Vec<SignatureToken>,
+    ) -> FunctionInstantiationIndex {
+        let type_parameters = self.signature(type_args);
+        FunctionInstantiationIndex(intern(
+            &mut self.module.function_instantiations,
+            FunctionInstantiation {
+                handle,
+                type_parameters,
+            },
+        ))
+    }
+
+    pub fn finish(self) -> CompiledModule {
+        self.module
+    }
+}
+
+/// A benchmark which runs its body in a loop of `n` iterations.
+#[derive(Default)]
+pub(crate) struct LoopBenchmark {
+    /// The locals declared by the benchmark, starting from `FIRST_LOCAL`.
+    pub locals: Vec<SignatureToken>,
+    pub setup: Vec<Bytecode>,
+    /// The stack neutral body, the branch offsets are relative to the start of the body.
+    pub body: Vec<Bytecode>,
+    /// How many times the body is repeated in one iteration.
+    pub repeat: usize,
+    pub teardown: Vec<Bytecode>,
+    pub acquires: Vec<StructDefinitionIndex>,
+}
+
+impl LoopBenchmark {
+    pub fn new(body: Vec<Bytecode>, repeat: usize) -> Self {
+        Self {
+            body,
+            repeat,
+            ..Default::default()
+        }
+    }
+
+    fn code(&self) -> Vec<Bytecode> {
+        let mut code = self.setup.clone();
+        code.extend([Bytecode::LdU64(0), Bytecode::StLoc(I)]);
+        let head = code.len() as CodeOffset;
+        code.extend([
+            Bytecode::CopyLoc(I),
+            Bytecode::CopyLoc(N),
+            Bytecode::Lt,
+            Bytecode::BrFalse(0),
+        ]);
+        let exit_branch = code.len() - 1;
+        for _ in 0..self.repeat {
+            let start = code.len() as CodeOffset;
+            code.extend(self.body.iter().map(|op| match op {
+                Bytecode::BrTrue(offset) => Bytecode::BrTrue(start + offset),
+                Bytecode::BrFalse(offset) => Bytecode::BrFalse(start + offset),
+                Bytecode::Branch(offset) => Bytecode::Branch(start + offset),
+                op => op.clone(),
+            }));
+        }
+        code.extend([
+            Bytecode::MoveLoc(I),
+            Bytecode::LdU64(1),
+            Bytecode::Add,
+            Bytecode::StLoc(I),
+            Bytecode::Branch(head),
+        ]);
+        code[exit_branch] = Bytecode::BrFalse(code.len() as CodeOffset);
+        code.extend(self.teardown.iter().cloned());
+        code.push(Bytecode::Ret);
+        code
+    }
+}
+
+/// A module of benchmarks, the native functions measured by the benchmarks are declared in the
+/// module too.
+pub(crate) struct BenchmarkModule {
+    pub builder: ModuleBuilder,
+    iterations: u64,
+    benchmarks: Vec<Benchmark>,
+}
+
+impl BenchmarkModule {
+    pub fn new(name: &str, iterations: u64) -> Self {
+        Self {
+            builder: ModuleBuilder::new(CORE_CODE_ADDRESS, name),
+            iterations: iterations.max(1),
+            benchmarks: vec![],
+        }
+    }
+
+    fn parameters() -> Vec<SignatureToken> {
+        vec![
+            SignatureToken::Signer,
+            SignatureToken::Address,
+            SignatureToken::U64,
+        ]
+    }
+
+    /// Add a loop benchmark, which measures the given native gas parameters besides the
+    /// instructions.
+    pub fn add_loop(&mut self, name: &str, benchmark: LoopBenchmark, native_keys: &[&str]) {
+        let mut locals = vec![SignatureToken::U64];
+        locals.extend(benchmark.locals.iter().cloned());
+        let code = benchmark.code();
+        self.builder.add_function(
+            name,
+            vec![],
+            Self::parameters(),
+            locals,
+            benchmark.acquires,
+            code,
+        );
+        self.push(name, self.iterations, native_keys, false);
+    }
+
+    /// Add a benchmark which runs the code once, such as the one aborts.
+    pub fn add_once(&mut self, name: &str, code: Vec<Bytecode>, aborts: bool) {
+        self.builder
+            .add_function(name, vec![], Self::parameters(), vec![], vec![], code);
+        self.push(name, 0, &[], aborts);
+    }
+
+    fn push(&mut self, name: &str, iterations: u64, native_keys: &[&str], aborts: bool) {
+        self.benchmarks.push(Benchmark {
+            name: format!("{}::{}", self.builder.module_id().name(), name),
+            module: self.builder.module_id(),
+            function: Identifier::new(name).expect("function name should be valid"),
+            iterations,
+            native_keys: native_keys.iter().map(|key| key.to_string()).collect(),
+            aborts,
+        });
+    }
+
+    pub fn finish(self) -> (CompiledModule, Vec<Benchmark>) {
+        (self.builder.finish(), self.benchmarks)
+    }
+}
@@ -0,0 +1,320 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The benchmarks of the instructions, every instruction is executed by at least one benchmark,
+//! together with the instructions which push its operands and pop its results.
+
+use crate::calibration::builder::{BenchmarkModule, LoopBenchmark, ACCOUNT, ADDR, FIRST_LOCAL};
+use crate::calibration::Benchmark;
+use move_binary_format::file_format::{
+    Ability, AbilitySet, Bytecode, CompiledModule, SignatureToken, StructTypeParameter,
+};
+use move_core_types::u256::U256;
+
+/// How many times the body is repeated in one iteration, to amortize the loop overhead.
+const REPEAT: usize = 10;
+
+/// The locals shared by all the instruction benchmarks.
+const X: u8 = FIRST_LOCAL;
+const BYTES: u8 = FIRST_LOCAL + 1;
+const S: u8 = FIRST_LOCAL + 2;
+const G: u8 = FIRST_LOCAL + 3;
+const NUMBERS: u8 = FIRST_LOCAL + 4;
+
+pub(crate) fn instruction_benchmarks(iterations: u64) -> (CompiledModule, Vec<Benchmark>) {
+    use Bytecode::*;
+
+    let mut module = BenchmarkModule::new("Bytecode", iterations);
+    let b = &mut module.builder;
+    let u8_sig = b.signature(vec![SignatureToken::U8]);
+    let u64_sig = b.signature(vec![SignatureToken::U64]);
+    let small_bytes = b.bytes(vec![7u8; 32]);
+    let large_bytes = b.bytes(vec![7u8; 1024]);
+
+    // struct S has copy, drop, store, key { a: u64, b: u64 }
+    let s_def = b.add_struct(
+        "S",
+        AbilitySet::ALL,
+        vec![],
+        vec![("a", SignatureToken::U64), ("b", SignatureToken::U64)],
+    );
+    let s_ty = SignatureToken::Struct(b.struct_handle(s_def));
+    let s_field = b.field(s_def, 0);
+    // struct G<T> has copy, drop, store, key { a: T }
+    let g_def = b.add_struct(
+        "G",
+        AbilitySet::ALL,
+        vec![StructTypeParameter {
+            constraints: AbilitySet::EMPTY,
+            is_phantom: false,
+        }],
+        vec![("a", SignatureToken::TypeParameter(0))],
+    );
+    let g_ty =
+        SignatureToken::StructInstantiation(b.struct_handle(g_def), vec![SignatureToken::U64]);
+    let g_inst = b.struct_instantiation(g_def, vec![SignatureToken::U64]);
+    let g_field = b.field(g_def, 0);
+    let g_field_inst = b.field_instantiation(g_field, vec![SignatureToken::U64]);
+
+    let drop = AbilitySet::EMPTY | Ability::Drop;
+    let empty_fun = b.add_function("empty", vec![], vec![], vec![], vec![], vec![Ret]);
+    let args_fun = b.add_function(
+        "args",
+        vec![],
+        vec![SignatureToken::U64; 3],
+        vec![],
+        vec![],
+        vec![Ret],
+    );
+    let generic_empty_fun = b.add_function(
+        "generic_empty",
+        vec![drop],
+        vec![],
+        vec![],
+        vec![],
+        vec![Ret],
+    );
+    let generic_empty_inst = b.function_instantiation(generic_empty_fun, vec![SignatureToken::U64]);
+    let generic_args_fun = b.add_function(
+        "generic_args",
+        vec![drop],
+        vec![SignatureToken::TypeParameter(0); 3],
+        vec![],
+        vec![],
+        vec![Ret],
+    );
+    let generic_args_inst = b.function_instantiation(generic_args_fun, vec![SignatureToken::U64]);
+
+    let locals = vec![
+        SignatureToken::U64,
+        SignatureToken::Vector(Box::new(SignatureToken::U8)),
+        s_ty,
+        g_ty,
+        SignatureToken::Vector(Box::new(SignatureToken::U64)),
+    ];
+    let setup = vec![
+        LdU64(7),
+        StLoc(X),
+        LdConst(small_bytes),
+        StLoc(BYTES),
+        LdU64(1),
+        LdU64(2),
+        Pack(s_def),
+        StLoc(S),
+        LdU64(3),
+        PackGeneric(g_inst),
+        StLoc(G),
+        LdU64(1),
+        LdU64(2),
+        VecPack(u64_sig, 2),
+        StLoc(NUMBERS),
+    ];
+    // publish the resources for the global storage instructions.
+    let mut global_setup = setup.clone();
+    global_setup.extend([
+        ImmBorrowLoc(ACCOUNT),
+        CopyLoc(S),
+        MoveTo(s_def),
+        ImmBorrowLoc(ACCOUNT),
+        CopyLoc(G),
+        MoveToGeneric(g_inst),
+    ]);
+
+    let benchmarks: Vec<(&str, Vec<Bytecode>)> = vec![
+        // the loop overhead only, with no body.
+        ("loop", vec![]),
+        ("pop", vec![LdU64(1), Pop]),
+        ("st_loc", vec![LdU64(1), StLoc(X)]),
+        ("ld_u8", vec![LdU8(1), Pop]),
+        ("ld_u16", vec![LdU16(1), Pop]),
+        ("ld_u32", vec![LdU32(1), Pop]),
+        ("ld_u128", vec![LdU128(1), Pop]),
+        ("ld_u256", vec![LdU256(U256::from(1u64)), Pop]),
+        ("ld_true", vec![LdTrue, Pop]),
+        ("ld_false", vec![LdFalse, Pop]),
+        ("ld_const", vec![LdConst(small_bytes), Pop]),
+        ("ld_const_large", vec![LdConst(large_bytes), Pop]),
+        ("copy_loc", vec![CopyLoc(X), Pop]),
+        ("copy_loc_vector", vec![CopyLoc(BYTES), Pop]),
+        ("move_loc", vec![MoveLoc(X), StLoc(X)]),
+        ("move_loc_vector", vec![MoveLoc(BYTES), StLoc(BYTES)]),
+        ("mut_borrow_loc", vec![MutBorrowLoc(X), Pop]),
+        ("imm_borrow_loc", vec![ImmBorrowLoc(X), Pop]),
+        ("freeze_ref", vec![MutBorrowLoc(X), FreezeRef, Pop]),
+        ("read_ref", vec![ImmBorrowLoc(X), ReadRef, Pop]),
+        ("read_ref_vector", vec![ImmBorrowLoc(BYTES), ReadRef, Pop]),
+        ("write_ref", vec![LdU64(1), MutBorrowLoc(X), WriteRef]),
+        (
+            "imm_borrow_field",
+            vec![ImmBorrowLoc(S), ImmBorrowField(s_field), Pop],
+        ),
+        (
+            "mut_borrow_field",
+            vec![MutBorrowLoc(S), MutBorrowField(s_field), Pop],
+        ),
+        (
+            "imm_borrow_field_generic",
+            vec![ImmBorrowLoc(G), ImmBorrowFieldGeneric(g_field_inst), Pop],
+        ),
+        (
+            "mut_borrow_field_generic",
+            vec![MutBorrowLoc(G), MutBorrowFieldGeneric(g_field_inst), Pop],
+        ),
+        ("pack", vec![LdU64(1), LdU64(2), Pack(s_def), Pop]),
+        ("unpack", vec![CopyLoc(S), Unpack(s_def), Pop, Pop]),
+        ("pack_generic", vec![LdU64(1), PackGeneric(g_inst), Pop]),
+        (
+            "unpack_generic",
+            vec![CopyLoc(G), UnpackGeneric(g_inst), Pop],
+        ),
+        ("add", vec![LdU64(1000), LdU64(7), Add, Pop]),
+        ("sub", vec![LdU64(1000), LdU64(7), Sub, Pop]),
+        ("mul", vec![LdU64(1000), LdU64(7), Mul, Pop]),
+        ("mod", vec![LdU64(1000), LdU64(7), Mod, Pop]),
+        ("div", vec![LdU64(1000), LdU64(7), Div, Pop]),
+        ("bit_or", vec![LdU64(1000), LdU64(7), BitOr, Pop]),
+        ("bit_and", vec![LdU64(1000), LdU64(7), BitAnd, Pop]),
+        ("xor", vec![LdU64(1000), LdU64(7), Xor, Pop]),
+        ("shl", vec![LdU64(1000), LdU8(3), Shl, Pop]),
+        ("shr", vec![LdU64(1000), LdU8(3), Shr, Pop]),
+        ("lt", vec![LdU64(1000), LdU64(7), Lt, Pop]),
+        ("gt", vec![LdU64(1000), LdU64(7), Gt, Pop]),
+        ("le", vec![LdU64(1000), LdU64(7), Le, Pop]),
+        ("ge", vec![LdU64(1000), LdU64(7), Ge, Pop]),
+        ("eq", vec![LdU64(1000), LdU64(7), Eq, Pop]),
+        ("neq", vec![LdU64(1000), LdU64(7), Neq, Pop]),
+        ("eq_vector", vec![CopyLoc(BYTES), CopyLoc(BYTES), Eq, Pop]),
+        ("neq_vector", vec![CopyLoc(BYTES), CopyLoc(BYTES), Neq, Pop]),
+        ("or", vec![LdTrue, LdFalse, Or, Pop]),
+        ("and", vec![LdTrue, LdFalse, And, Pop]),
+        ("not", vec![LdTrue, Not, Pop]),
+        ("cast_u8", vec![LdU64(1), CastU8, Pop]),
+        ("cast_u16", vec![LdU64(1), CastU16, Pop]),
+        ("cast_u32", vec![LdU64(1), CastU32, Pop]),
+        ("cast_u64", vec![LdU8(1), CastU64, Pop]),
+        ("cast_u128", vec![LdU64(1), CastU128, Pop]),
+        ("cast_u256", vec![LdU64(1), CastU256, Pop]),
+        ("nop", vec![Nop]),
+        ("branch", vec![Branch(1)]),
+        ("br_true", vec![LdFalse, BrTrue(2)]),
+        ("br_false", vec![LdTrue, BrFalse(2)]),
+        ("call", vec![Call(empty_fun)]),
+        (
+            "call_args",
+            vec![CopyLoc(X), CopyLoc(X), CopyLoc(X), Call(args_fun)],
+        ),
+        ("call_generic", vec![CallGeneric(generic_empty_inst)]),
+        (
+            "call_generic_args",
+            vec![
+                CopyLoc(X),
+                CopyLoc(X),
+                CopyLoc(X),
+                CallGeneric(generic_args_inst),
+            ],
+        ),
+        (
+            "vec_pack",
+            vec![LdU64(1), LdU64(2), LdU64(3), VecPack(u64_sig, 3), Pop],
+        ),
+        (
+            "vec_unpack",
+            vec![CopyLoc(NUMBERS), VecUnpack(u64_sig, 2), Pop, Pop],
+        ),
+        ("vec_len", vec![ImmBorrowLoc(BYTES), VecLen(u8_sig), Pop]),
+        (
+            "vec_imm_borrow",
+            vec![ImmBorrowLoc(BYTES), LdU64(0), VecImmBorrow(u8_sig), Pop],
+        ),
+        (
+            "vec_mut_borrow",
+            vec![MutBorrowLoc(BYTES), LdU64(0), VecMutBorrow(u8_sig), Pop],
+        ),
+        (
+            "vec_push_back",
+            vec![MutBorrowLoc(BYTES), LdU8(1), VecPushBack(u8_sig)],
+        ),
+        (
+            "vec_pop_back",
+            vec![
+                MutBorrowLoc(BYTES),
+                LdU8(1),
+                VecPushBack(u8_sig),
+                MutBorrowLoc(BYTES),
+                VecPopBack(u8_sig),
+                Pop,
+            ],
+        ),
+        (
+            "vec_swap",
+            vec![MutBorrowLoc(BYTES), LdU64(0), LdU64(1), VecSwap(u8_sig)],
+        ),
+    ];
+    for (name, body) in benchmarks {
+        let mut benchmark = LoopBenchmark::new(body, REPEAT);
+        benchmark.locals = locals.clone();
+        benchmark.setup = setup.clone();
+        module.add_loop(name, benchmark, &[]);
+    }
+
+    let global_benchmarks: Vec<(&str, Vec<Bytecode>, _)> = vec![
+        ("exists", vec![CopyLoc(ADDR), Exists(s_def), Pop], vec![]),
+        (
+            "exists_generic",
+            vec![CopyLoc(ADDR), ExistsGeneric(g_inst), Pop],
+            vec![],
+        ),
+        (
+            "imm_borrow_global",
+            vec![CopyLoc(ADDR), ImmBorrowGlobal(s_def), Pop],
+            vec![s_def],
+        ),
+        (
+            "mut_borrow_global",
+            vec![CopyLoc(ADDR), MutBorrowGlobal(s_def), Pop],
+            vec![s_def],
+        ),
+        (
+            "imm_borrow_global_generic",
+            vec![CopyLoc(ADDR), ImmBorrowGlobalGeneric(g_inst), Pop],
+            vec![g_def],
+        ),
+        (
+            "mut_borrow_global_generic",
+            vec![CopyLoc(ADDR), MutBorrowGlobalGeneric(g_inst), Pop],
+            vec![g_def],
+        ),
+        (
+            "move_from_to",
+            vec![
+                ImmBorrowLoc(ACCOUNT),
+                CopyLoc(ADDR),
+                MoveFrom(s_def),
+                MoveTo(s_def),
+            ],
+            vec![s_def],
+        ),
+        (
+            "move_from_to_generic",
+            vec![
+                ImmBorrowLoc(ACCOUNT),
+                CopyLoc(ADDR),
+                MoveFromGeneric(g_inst),
+                MoveToGeneric(g_inst),
+            ],
+            vec![g_def],
+        ),
+    ];
+    for (name, body, acquires) in global_benchmarks {
+        let mut benchmark = LoopBenchmark::new(body, REPEAT);
+        benchmark.locals = locals.clone();
+        benchmark.setup = global_setup.clone();
+        benchmark.acquires = acquires;
+        module.add_loop(name, benchmark, &[]);
+    }
+
+    // the overhead of executing a function, and the abort which stops the execution.
+    module.add_once("ret", vec![Ret], false);
+    module.add_once("abort", vec![LdU64(0), Abort], true);
+    module.finish()
+}
@@ -0,0 +1,537 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Calibrate the instruction and native gas parameters against the execution time.
+//!
+//! Every benchmark is a generated Move function, which is timed in a bare Move VM and traced with
+//! all the gas parameters set to 1 to count the gas units charged by every parameter. The cost of
+//! a gas unit of every parameter is fitted from all the benchmarks, and the proposed gas schedule
+//! scales the fitted costs so the reference parameter keeps its current value.
+
+mod builder;
+mod instructions;
+mod natives;
+mod runner;
+
+use crate::calibration::runner::BenchmarkRunner;
+use anyhow::{ensure, format_err, Result};
+use move_binary_format::file_format::CompiledModule;
+use move_core_types::identifier::Identifier;
+use move_core_types::language_storage::ModuleId;
+use serde::{Deserialize, Serialize};
+use starcoin_logger::prelude::*;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The parameter whose value is kept by default, the other parameters are priced relative to it.
+pub const DEFAULT_REFERENCE: &str = "instr.ld_u64";
+
+/// The limit of the coordinate descent sweeps of the fitting.
+const MAX_SWEEPS: usize = 10_000;
+
+/// A benchmark function `fun f(account: signer, addr: address, n: u64)` generated for calibration.
+#[derive(Clone, Debug)]
+pub struct Benchmark {
+    /// The name of the benchmark, such as `Bytecode::add`.
+    pub name: String,
+    pub module: ModuleId,
+    pub function: Identifier,
+    /// The loop iterations of the benchmark, passed as `n`.
+    pub iterations: u64,
+    /// The native gas parameters measured by the benchmark.
+    pub native_keys: Vec<String>,
+    /// Whether the benchmark is expected to abort.
+    pub aborts: bool,
+}
+
+/// The execution time of a benchmark and the gas units charged by every gas parameter.
+#[derive(Clone, Debug, Serialize)]
+pub struct Measurement {
+    pub benchmark: String,
+    /// The median execution time in nanoseconds.
+    pub time_ns: f64,
+    pub units: BTreeMap<String, u64>,
+}
+
+#[derive(Clone, Debug)]
+pub struct CalibrationOptions {
+    /// The loop iterations of every benchmark, the signature benchmarks run 1% of them.
+    pub iterations: u64,
+    /// How many times every benchmark is timed.
+    pub samples: usize,
+    /// How strongly the fitted costs are pulled towards the current gas schedule, it keeps the
+    /// parameters which can not be told apart by the benchmarks in their current proportion.
+    pub ridge: f64,
+}
+
+impl Default for CalibrationOptions {
+    fn default() -> Self {
+        Self {
+            iterations: 1000,
+            samples: 10,
+            ridge: 0.01,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Calibration {
+    pub measurements: Vec<Measurement>,
+    /// The fitted nanoseconds of a gas unit of every calibrated parameter.
+    pub costs: BTreeMap<String, f64>,
+    /// The fitted nanoseconds of a function execution, not charged by any parameter.
+    pub overhead: f64,
+}
+
+impl Calibration {
+    /// Propose a gas schedule, the calibrated parameters are priced relative to the `reference`
+    /// parameter, which keeps its current value. The other parameters are kept as they are.
+    pub fn propose(
+        &self,
+        current: &[(String, u64)],
+        reference: &str,
+    ) -> Result<Vec<(String, u64)>> {
+        let reference_cost = self.costs.get(reference).copied().ok_or_else(|| {
+            format_err!("reference gas parameter {} is not calibrated", reference)
+        })?;
+        ensure!(
+            reference_cost > 0.0,
+            "reference gas parameter {} is fitted to zero cost",
+            reference
+        );
+        let reference_value = current
+            .iter()
+            .find(|(key, _)| key == reference)
+            .map(|(_, value)| *value)
+            .ok_or_else(|| format_err!("reference gas parameter {} does not exist", reference))?;
+        let scale = reference_value as f64 / reference_cost;
+        Ok(current
+            .iter()
+            .map(|(key, value)| match self.costs.get(key) {
+                Some(cost) => (key.clone(), ((cost * scale).round() as u64).max(1)),
+                None => (key.clone(), *value),
+            })
+            .collect())
+    }
+
+    /// The instruction and native gas parameters not measured by any benchmark.
+    pub fn uncalibrated(&self, current: &[(String, u64)]) -> Vec<String> {
+        current
+            .iter()
+            .filter(|(key, _)| !key.starts_with("txn.") && !self.costs.contains_key(key))
+            .map(|(key, _)| key.clone())
+            .collect()
+    }
+}
+
+/// A gas parameter changed by the proposed gas schedule.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct GasScheduleChange {
+    pub key: String,
+    pub current: Option<u64>,
+    pub proposed: Option<u64>,
+}
+
+/// The json of the on-chain `0x1::GasSchedule::GasSchedule` resource, whose keys are the hex
+/// encoded names of the gas parameters.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct GasScheduleResource {
+    pub entries: Vec<GasEntry>,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct GasEntry {
+    /// The hex encoded name of the gas parameter, such as `0x696e7374722e706f70`.
+    pub key: String,
+    pub val: u64,
+}
+
+impl GasScheduleResource {
+    pub fn new(gas_schedule: &[(String, u64)]) -> Self {
+        Self {
+            entries: gas_schedule
+                .iter()
+                .map(|(key, val)| GasEntry {
+                    key: format!("0x{}", hex::encode(key)),
+                    val: *val,
+                })
+                .collect(),
+        }
+    }
+
+    pub fn into_gas_schedule(self) -> Result<Vec<(String, u64)>> {
+        self.entries
+            .into_iter()
+            .map(|entry| {
+                let key = hex::decode(entry.key.strip_prefix("0x").unwrap_or(&entry.key))
+                    .map_err(|e| format_err!("invalid gas schedule key {}: {}", entry.key, e))?;
+                Ok((String::from_utf8(key)?, entry.val))
+            })
+            .collect()
+    }
+}
+
+/// The changed, added and removed parameters of the proposed gas schedule, in the order of the
+/// current gas schedule.
+pub fn diff_gas_schedule(
+    current: &[(String, u64)],
+    proposed: &[(String, u64)],
+) -> Vec<GasScheduleChange> {
+    let current_map: BTreeMap<_, _> = current.iter().cloned().collect();
+    let proposed_map: BTreeMap<_, _> = proposed.iter().cloned().collect();
+    let mut changes: Vec<_> = current
+        .iter()
+        .filter(|(key, value)| proposed_map.get(key) != Some(value))
+        .map(|(key, value)| GasScheduleChange {
+            key: key.clone(),
+            current: Some(*value),
+            proposed: proposed_map.get(key).copied(),
+        })
+        .collect();
+    changes.extend(
+        proposed
+            .iter()
+            .filter(|(key, _)| !current_map.contains_key(key))
+            .map(|(key, value)| GasScheduleChange {
+                key: key.clone(),
+                current: None,
+                proposed: Some(*value),
+            }),
+    );
+    changes
+}
+
+/// The modules of all the benchmarks, and the benchmarks running `iterations` loop iterations.
+fn benchmarks(iterations: u64) -> (Vec<CompiledModule>, Vec<Benchmark>) {
+    let mut modules = vec![];
+    let mut benchmarks = vec![];
+    let all = std::iter::once(instructions::instruction_benchmarks(iterations))
+        .chain(natives::native_benchmarks(iterations));
+    for (module, module_benchmarks) in all {
+        modules.push(module);
+        benchmarks.extend(module_benchmarks);
+    }
+    (modules, benchmarks)
+}
+
+/// Run all the benchmarks and fit the costs of the gas parameters of the `current` gas schedule.
+pub fn calibrate(current: &[(String, u64)], options: &CalibrationOptions) -> Result<Calibration> {
+    let (modules, benchmarks) = benchmarks(options.iterations);
+    let current_map: BTreeMap<_, _> = current.iter().cloned().collect();
+    let runner = BenchmarkRunner::new(&modules, &current_map)?;
+    let mut measurements = vec![];
+    for benchmark in &benchmarks {
+        let time_ns = runner.measure_time(benchmark, options.samples)?;
+        let units = runner.measure_units(benchmark)?;
+        debug!(
+            "benchmark {} takes {}ns, units: {:?}",
+            benchmark.name, time_ns, units
+        );
+        measurements.push(Measurement {
+            benchmark: benchmark.name.clone(),
+            time_ns,
+            units,
+        });
+    }
+    let (costs, overhead) = fit(&measurements, &current_map, options.ridge);
+    Ok(Calibration {
+        measurements,
+        costs,
+        overhead,
+    })
+}
+
+/// Fit the nanoseconds of a gas unit of every parameter and the overhead of an execution, by the
+/// non-negative least squares of the relative errors of the execution time, with a ridge penalty
+/// towards the current gas schedule scaled to nanoseconds.
+fn fit(
+    measurements: &[Measurement],
+    current: &BTreeMap<String, u64>,
+    ridge: f64,
+) -> (BTreeMap<String, f64>, f64) {
+    let keys: Vec<String> = measurements
+        .iter()
+        .flat_map(|m| m.units.keys().cloned())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    if keys.is_empty() {
+        return (BTreeMap::new(), 0.0);
+    }
+    // the last column is the overhead, which is charged once by every execution.
+    let overhead = keys.len();
+    let rows: Vec<Vec<f64>> = measurements
+        .iter()
+        .map(|m| {
+            let mut row: Vec<f64> = keys
+                .iter()
+                .map(|key| m.units.get(key).copied().unwrap_or_default() as f64)
+                .collect();
+            row.push(1.0);
+            row
+        })
+        .collect();
+    let times: Vec<f64> = measurements.iter().map(|m| m.time_ns).collect();
+    let weights: Vec<f64> = times
+        .iter()
+        .map(|t| if *t > 0.0 { 1.0 / (t * t) } else { 0.0 })
+        .collect();
+
+    let current_gas: f64 = rows
+        .iter()
+        .map(|row| {
+            keys.iter()
+                .enumerate()
+                .map(|(k, key)| row[k] * current.get(key).copied().unwrap_or_default() as f64)
+                .sum::<f64>()
+        })
+        .sum();
+    let ns_per_gas = if current_gas > 0.0 {
+        times.iter().sum::<f64>() / current_gas
+    } else {
+        1.0
+    };
+    let mut priors: Vec<f64> = keys
+        .iter()
+        .map(|key| current.get(key).copied().unwrap_or_default() as f64 * ns_per_gas)
+        .collect();
+    priors.push(times.iter().copied().fold(f64::INFINITY, f64::min));
+
+    let mut x = priors.clone();
+    let mut residuals: Vec<f64> = rows
+        .iter()
+        .zip(&times)
+        .map(|(row, t)| t - row.iter().zip(&x).map(|(u, c)| u * c).sum::<f64>())
+        .collect();
+    for _ in 0..MAX_SWEEPS {
+        let mut max_change: f64 = 0.0;
+        for k in 0..=overhead {
+            let (mut a, mut g) = (0.0, 0.0);
+            for (r, row) in rows.iter().enumerate() {
+                let u = row[k];
+                if u != 0.0 {
+                    a += weights[r] * u * u;
+                    g += weights[r] * u * (residuals[r] + u * x[k]);
+                }
+            }
+            if a == 0.0 {
+                continue;
+            }
+            let value = ((g + ridge * a * priors[k]) / (a * (1.0 + ridge))).max(0.0);
+            let delta = value - x[k];
+            if delta != 0.0 {
+                for (r, row) in rows.iter().enumerate() {
+                    residuals[r] -= row[k] * delta;
+                }
+                x[k] = value;
+                max_change = max_change.max(delta.abs() / value.max(f64::EPSILON));
+            }
+        }
+        if max_change < 1e-9 {
+            break;
+        }
+    }
+    let costs = keys.into_iter().zip(x.iter().copied()).collect();
+    (costs, x[overhead])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::StarcoinGasParameters;
+    use starcoin_gas_algebra_ext::{InitialGasSchedule, ToOnChainGasSchedule};
+
+    fn measurement(
+        units: &[(&str, u64)],
+        costs: &BTreeMap<&str, f64>,
+        overhead: f64,
+    ) -> Measurement {
+        let time_ns = units
+            .iter()
+            .map(|(key, u)| *u as f64 * costs[key])
+            .sum::<f64>()
+            + overhead;
+        Measurement {
+            benchmark: String::new(),
+            time_ns,
+            units: units.iter().map(|(key, u)| (key.to_string(), *u)).collect(),
+        }
+    }
+
+    #[test]
+    fn test_fit_and_propose() {
+        let costs: BTreeMap<_, _> = [
+            ("instr.ld_u64", 2.0),
+            ("instr.add", 5.0),
+            ("instr.pop", 1.0),
+        ]
+        .into_iter()
+        .collect();
+        let measurements = vec![
+            measurement(&[("instr.ld_u64", 10), ("instr.pop", 10)], &costs, 100.0),
+            measurement(
+                &[("instr.ld_u64", 20), ("instr.add", 10), ("instr.pop", 10)],
+                &costs,
+                100.0,
+            ),
+            measurement(&[("instr.ld_u64", 200), ("instr.pop", 100)], &costs, 100.0),
+            measurement(
+                &[("instr.ld_u64", 5), ("instr.add", 50), ("instr.pop", 1)],
+                &costs,
+                100.0,
+            ),
+        ];
+        let current: Vec<(String, u64)> = vec![
+            ("instr.ld_u64".to_string(), 400),
+            ("instr.add".to_string(), 400),
+            ("instr.pop".to_string(), 400),
+            ("instr.nop".to_string(), 7),
+            ("txn.gas_unit_scaling_factor".to_string(), 1000),
+        ];
+        let (fitted, overhead) = fit(&measurements, &current.iter().cloned().collect(), 1e-9);
+        for (key, cost) in &costs {
+            assert!(
+                (fitted[*key] - cost).abs() < 1e-3,
+                "{}: {}",
+                key,
+                fitted[*key]
+            );
+        }
+        assert!((overhead - 100.0).abs() < 1e-1, "overhead: {}", overhead);
+
+        let calibration = Calibration {
+            measurements,
+            costs: fitted,
+            overhead,
+        };
+        let proposed = calibration.propose(&current, DEFAULT_REFERENCE).unwrap();
+        assert_eq!(
+            proposed,
+            vec![
+                ("instr.ld_u64".to_string(), 400),
+                ("instr.add".to_string(), 1000),
+                ("instr.pop".to_string(), 200),
+                ("instr.nop".to_string(), 7),
+                ("txn.gas_unit_scaling_factor".to_string(), 1000),
+            ]
+        );
+        assert_eq!(calibration.uncalibrated(&current), vec!["instr.nop"]);
+        assert!(calibration.propose(&current, "instr.nop").is_err());
+
+        assert_eq!(
+            diff_gas_schedule(&current, &proposed),
+            vec![
+                GasScheduleChange {
+                    key: "instr.add".to_string(),
+                    current: Some(400),
+                    proposed: Some(1000),
+                },
+                GasScheduleChange {
+                    key: "instr.pop".to_string(),
+                    current: Some(400),
+                    proposed: Some(200),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_added_and_removed() {
+        let current = vec![("a".to_string(), 1), ("b".to_string(), 2)];
+        let proposed = vec![("b".to_string(), 2), ("c".to_string(), 3)];
+        assert_eq!(
+            diff_gas_schedule(&current, &proposed),
+            vec![
+                GasScheduleChange {
+                    key: "a".to_string(),
+                    current: Some(1),
+                    proposed: None,
+                },
+                GasScheduleChange {
+                    key: "c".to_string(),
+                    current: None,
+                    proposed: Some(3),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_gas_schedule_resource() {
+        let resource: GasScheduleResource = serde_json::from_str(
+            r#"{"entries": [
+                {"key": "0x696e7374722e706f70", "val": 2},
+                {"key": "0x696e7374722e726574", "val": 639}
+            ]}"#,
+        )
+        .unwrap();
+        let gas_schedule = vec![("instr.pop".to_string(), 2), ("instr.ret".to_string(), 639)];
+        assert_eq!(resource.clone().into_gas_schedule().unwrap(), gas_schedule);
+        assert_eq!(GasScheduleResource::new(&gas_schedule), resource);
+        assert!(GasScheduleResource {
+            entries: vec![GasEntry {
+                key: "instr.pop".to_string(),
+                val: 2,
+            }],
+        }
+        .into_gas_schedule()
+        .is_err());
+    }
+
+    #[test]
+    fn test_run_all_benchmarks() {
+        let current = StarcoinGasParameters::initial().to_on_chain_gas_schedule();
+        let (modules, benchmarks) = benchmarks(1);
+        let runner = BenchmarkRunner::new(&modules, &current.into_iter().collect()).unwrap();
+        let mut charged = BTreeSet::new();
+        for benchmark in &benchmarks {
+            runner.measure_time(benchmark, 1).unwrap();
+            let units = runner.measure_units(benchmark).unwrap();
+            assert!(
+                units.keys().any(|key| key.starts_with("instr.")),
+                "benchmark {} charges no instruction",
+                benchmark.name
+            );
+            charged.extend(
+                units
+                    .into_iter()
+                    .filter(|(_, units)| *units > 0)
+                    .map(|(key, _)| key),
+            );
+        }
+        for benchmark in &benchmarks {
+            for key in &benchmark.native_keys {
+                assert!(
+                    charged.contains(key),
+                    "native gas parameter {} of benchmark {} is never charged",
+                    key,
+                    benchmark.name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_calibrate_smoke() {
+        let current = StarcoinGasParameters::initial().to_on_chain_gas_schedule();
+        let calibration = calibrate(
+            &current,
+            &CalibrationOptions {
+                iterations: 1,
+                samples: 1,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(calibration.measurements.len(), benchmarks(1).1.len());
+        assert!(calibration.costs.contains_key(DEFAULT_REFERENCE));
+
+        let proposed = calibration.propose(&current, DEFAULT_REFERENCE).unwrap();
+        assert_eq!(
+            proposed.iter().map(|(key, _)| key).collect::<Vec<_>>(),
+            current.iter().map(|(key, _)| key).collect::<Vec<_>>()
+        );
+        assert!(diff_gas_schedule(&current, &proposed)
+            .iter()
+            .all(|change| change.key != DEFAULT_REFERENCE && !change.key.starts_with("txn.")));
+    }
+}
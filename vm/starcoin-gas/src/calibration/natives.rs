@@ -0,0 +1,966 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The benchmarks of the native functions, every module declares the natives it measures with
+//! the same module name as the native function table, so the natives are linked by the VM.
+//! The natives charged per byte are measured with inputs of different sizes.
+
+use crate::calibration::builder::{BenchmarkModule, LoopBenchmark, ACCOUNT, ADDR, FIRST_LOCAL};
+use crate::calibration::Benchmark;
+use move_binary_format::file_format::{
+    Ability, AbilitySet, Bytecode, CompiledModule, SignatureToken, StructTypeParameter,
+};
+use move_core_types::value::MoveValue;
+use starcoin_crypto::ed25519::{Ed25519PrivateKey, Ed25519PublicKey};
+use starcoin_crypto::traits::SigningKey;
+use std::convert::TryFrom;
+
+/// How many times the body is repeated in one iteration, to amortize the loop overhead.
+const REPEAT: usize = 10;
+
+/// The sizes of the inputs of the natives charged per byte.
+const SMALL: usize = 32;
+const LARGE: usize = 1024;
+
+const L0: u8 = FIRST_LOCAL;
+const L1: u8 = FIRST_LOCAL + 1;
+const L2: u8 = FIRST_LOCAL + 2;
+const L3: u8 = FIRST_LOCAL + 3;
+
+fn bytes_type() -> SignatureToken {
+    SignatureToken::Vector(Box::new(SignatureToken::U8))
+}
+
+fn benchmark(
+    locals: Vec<SignatureToken>,
+    setup: Vec<Bytecode>,
+    body: Vec<Bytecode>,
+) -> LoopBenchmark {
+    LoopBenchmark {
+        locals,
+        setup,
+        ..LoopBenchmark::new(body, REPEAT)
+    }
+}
+
+pub(crate) fn native_benchmarks(iterations: u64) -> Vec<(CompiledModule, Vec<Benchmark>)> {
+    vec![
+        hash(iterations),
+        bcs(iterations),
+        vector(iterations),
+        signer(iterations),
+        event(iterations),
+        account(iterations),
+        token(iterations),
+        u256(iterations),
+        string(iterations),
+        // the signature verification is orders of magnitude slower than the other natives.
+        signature((iterations / 100).max(1)),
+        table(iterations),
+    ]
+}
+
+fn hash(iterations: u64) -> (CompiledModule, Vec<Benchmark>) {
+    use Bytecode::*;
+
+    let mut module = BenchmarkModule::new("Hash", iterations);
+    let natives = [
+        ("sha2_256", "move_stdlib.hash.sha2_256.per_byte"),
+        ("sha3_256", "move_stdlib.hash.sha3_256.per_byte"),
+        ("keccak_256", "starcoin_natives.hash.keccak256.per_byte"),
+        ("ripemd160", "starcoin_natives.hash.ripemd160.per_byte"),
+    ];
+    for (name, key) in natives {
+        let native =
+            module
+                .builder
+                .add_native(name, vec![], vec![bytes_type()], vec![bytes_type()]);
+        for size in [SMALL, LARGE] {
+            let input = module.builder.bytes(vec![7u8; size]);
+            module.add_loop(
+                &format!("{}_{}", name, size),
+                benchmark(
+                    vec![bytes_type()],
+                    vec![LdConst(input), StLoc(L0)],
+                    vec![CopyLoc(L0), Call(native), Pop],
+                ),
+                &[key],
+            );
+        }
+    }
+    module.finish()
+}
+
+fn bcs(iterations: u64) -> (CompiledModule, Vec<Benchmark>) {
+    use Bytecode::*;
+
+    let mut module = BenchmarkModule::new("BCS", iterations);
+    let b = &mut module.builder;
+    let to_bytes = b.add_native(
+        "to_bytes",
+        vec![AbilitySet::EMPTY],
+        vec![SignatureToken::Reference(Box::new(
+            SignatureToken::TypeParameter(0),
+        ))],
+        vec![bytes_type()],
+    );
+    let to_bytes_inst = b.function_instantiation(to_bytes, vec![bytes_type()]);
+    let to_address = b.add_native(
+        "to_address",
+        vec![],
+        vec![bytes_type()],
+        vec![SignatureToken::Address],
+    );
+    let address = b.bytes(vec![7u8; 16]);
+
+    for size in [SMALL, LARGE] {
+        let input = module.builder.bytes(vec![7u8; size]);
+        module.add_loop(
+            &format!("to_bytes_{}", size),
+            benchmark(
+                vec![bytes_type()],
+                vec![LdConst(input), StLoc(L0)],
+                vec![ImmBorrowLoc(L0), CallGeneric(to_bytes_inst), Pop],
+            ),
+            &["move_stdlib.bcs.to_bytes.per_byte_serialized"],
+        );
+    }
+    module.add_loop(
+        "to_address",
+        benchmark(
+            vec![bytes_type()],
+            vec![LdConst(address), StLoc(L0)],
+            vec![CopyLoc(L0), Call(to_address), Pop],
+        ),
+        &["move_stdlib.bcs.to_address.per_byte"],
+    );
+    module.finish()
+}
+
+fn vector(iterations: u64) -> (CompiledModule, Vec<Benchmark>) {
+    use Bytecode::*;
+    use SignatureToken::*;
+
+    let mut module = BenchmarkModule::new("Vector", iterations);
+    let b = &mut module.builder;
+    let t = || TypeParameter(0);
+    let vec_t = || Vector(Box::new(t()));
+    let ref_vec_t = || Reference(Box::new(vec_t()));
+    let mut_vec_t = || MutableReference(Box::new(vec_t()));
+    let any = vec![AbilitySet::EMPTY];
+
+    let mut native = |name: &str, params, returns| {
+        let handle = b.add_native(name, any.clone(), params, returns);
+        b.function_instantiation(handle, vec![U8])
+    };
+    let empty = native("empty", vec![], vec![vec_t()]);
+    let length = native("length", vec![ref_vec_t()], vec![U64]);
+    let borrow = native(
+        "borrow",
+        vec![ref_vec_t(), U64],
+        vec![Reference(Box::new(t()))],
+    );
+    let push_back = native("push_back", vec![mut_vec_t(), t()], vec![]);
+    let pop_back = native("pop_back", vec![mut_vec_t()], vec![t()]);
+    let destroy_empty = native("destroy_empty", vec![vec_t()], vec![]);
+    let swap = native("swap", vec![mut_vec_t(), U64, U64], vec![]);
+    let append = native("append", vec![mut_vec_t(), vec_t()], vec![]);
+    let remove = native("remove", vec![mut_vec_t(), U64], vec![t()]);
+    let reverse = native("reverse", vec![mut_vec_t()], vec![]);
+    let input = b.bytes(vec![7u8; SMALL]);
+    let one = b.bytes(vec![7u8]);
+
+    let key = |name: &str| format!("move_stdlib.vector.{}", name);
+    let benchmarks: Vec<(&str, Vec<Bytecode>, Vec<String>)> = vec![
+        (
+            "empty_destroy_empty",
+            vec![CallGeneric(empty), CallGeneric(destroy_empty)],
+            vec![key("empty.base"), key("destroy_empty.base")],
+        ),
+        (
+            "length",
+            vec![ImmBorrowLoc(L0), CallGeneric(length), Pop],
+            vec![key("length.base")],
+        ),
+        (
+            "borrow",
+            vec![ImmBorrowLoc(L0), LdU64(0), CallGeneric(borrow), Pop],
+            vec![key("borrow.base")],
+        ),
+        (
+            "push_back_pop_back",
+            vec![
+                MutBorrowLoc(L0),
+                LdU8(1),
+                CallGeneric(push_back),
+                MutBorrowLoc(L0),
+                CallGeneric(pop_back),
+                Pop,
+            ],
+            vec![
+                key("push_back.legacy_per_abstract_memory_unit"),
+                key("pop_back.base"),
+            ],
+        ),
+        (
+            "swap",
+            vec![MutBorrowLoc(L0), LdU64(0), LdU64(1), CallGeneric(swap)],
+            vec![key("swap.base")],
+        ),
+        (
+            "append_pop_back",
+            vec![
+                MutBorrowLoc(L0),
+                LdConst(one),
+                CallGeneric(append),
+                MutBorrowLoc(L0),
+                CallGeneric(pop_back),
+                Pop,
+            ],
+            vec![
+                key("append.legacy_per_abstract_memory_unit"),
+                key("pop_back.base"),
+            ],
+        ),
+        (
+            "remove_push_back",
+            vec![
+                MutBorrowLoc(L0),
+                LdU64(0),
+                CallGeneric(remove),
+                Pop,
+                MutBorrowLoc(L0),
+                LdU8(1),
+                CallGeneric(push_back),
+            ],
+            vec![
+                key("remove.legacy_per_abstract_memory_unit"),
+                key("push_back.legacy_per_abstract_memory_unit"),
+            ],
+        ),
+        (
+            "reverse",
+            vec![MutBorrowLoc(L0), CallGeneric(reverse)],
+            vec![key("reverse.legacy_per_abstract_memory_unit")],
+        ),
+    ];
+    for (name, body, keys) in benchmarks {
+        let keys: Vec<&str> = keys.iter().map(String::as_str).collect();
+        module.add_loop(
+            name,
+            benchmark(vec![bytes_type()], vec![LdConst(input), StLoc(L0)], body),
+            &keys,
+        );
+    }
+    module.finish()
+}
+
+fn signer(iterations: u64) -> (CompiledModule, Vec<Benchmark>) {
+    use Bytecode::*;
+
+    let mut module = BenchmarkModule::new("Signer", iterations);
+    let borrow_address = module.builder.add_native(
+        "borrow_address",
+        vec![],
+        vec![SignatureToken::Reference(Box::new(SignatureToken::Signer))],
+        vec![SignatureToken::Reference(Box::new(SignatureToken::Address))],
+    );
+    module.add_loop(
+        "borrow_address",
+        benchmark(
+            vec![],
+            vec![],
+            vec![ImmBorrowLoc(ACCOUNT), Call(borrow_address), Pop],
+        ),
+        &["move_stdlib.signer.borrow_address.base"],
+    );
+    module.finish()
+}
+
+fn event(iterations: u64) -> (CompiledModule, Vec<Benchmark>) {
+    use Bytecode::*;
+
+    let mut module = BenchmarkModule::new("Event", iterations);
+    let b = &mut module.builder;
+    let write_to_event_store = b.add_native(
+        "write_to_event_store",
+        vec![AbilitySet::EMPTY | Ability::Drop | Ability::Store],
+        vec![
+            bytes_type(),
+            SignatureToken::U64,
+            SignatureToken::TypeParameter(0),
+        ],
+        vec![],
+    );
+    let write_to_event_store =
+        b.function_instantiation(write_to_event_store, vec![SignatureToken::U64]);
+    let guid = b.bytes(vec![7u8; 24]);
+    module.add_loop(
+        "write_to_event_store",
+        benchmark(
+            vec![],
+            vec![],
+            vec![
+                LdConst(guid),
+                LdU64(0),
+                LdU64(1),
+                CallGeneric(write_to_event_store),
+            ],
+        ),
+        &["nursery.event.write_to_event_store.unit_cost"],
+    );
+    module.finish()
+}
+
+fn account(iterations: u64) -> (CompiledModule, Vec<Benchmark>) {
+    use Bytecode::*;
+
+    let mut module = BenchmarkModule::new("Account", iterations);
+    let b = &mut module.builder;
+    let create_signer = b.add_native(
+        "create_signer",
+        vec![],
+        vec![SignatureToken::Address],
+        vec![SignatureToken::Signer],
+    );
+    let destroy_signer = b.add_native(
+        "destroy_signer",
+        vec![],
+        vec![SignatureToken::Signer],
+        vec![],
+    );
+    module.add_loop(
+        "create_destroy_signer",
+        benchmark(
+            vec![],
+            vec![],
+            vec![CopyLoc(ADDR), Call(create_signer), Call(destroy_signer)],
+        ),
+        &[
+            "starcoin_natives.account.create_signer.base",
+            "starcoin_natives.account.destroy_signer.base",
+        ],
+    );
+    module.finish()
+}
+
+fn token(iterations: u64) -> (CompiledModule, Vec<Benchmark>) {
+    use Bytecode::*;
+
+    let mut module = BenchmarkModule::new("Token", iterations);
+    let b = &mut module.builder;
+    let stc = b.add_struct(
+        "STC",
+        AbilitySet::EMPTY | Ability::Copy | Ability::Drop | Ability::Store,
+        vec![],
+        vec![("dummy_field", SignatureToken::Bool)],
+    );
+    let name_of = b.add_native(
+        "name_of",
+        vec![AbilitySet::EMPTY],
+        vec![],
+        vec![SignatureToken::Address, bytes_type(), bytes_type()],
+    );
+    let stc = SignatureToken::Struct(b.struct_handle(stc));
+    let name_of = b.function_instantiation(name_of, vec![stc]);
+    module.add_loop(
+        "name_of",
+        benchmark(vec![], vec![], vec![CallGeneric(name_of), Pop, Pop, Pop]),
+        &["starcoin_natives.token.name_of.base"],
+    );
+    module.finish()
+}
+
+fn u256(iterations: u64) -> (CompiledModule, Vec<Benchmark>) {
+    use Bytecode::*;
+
+    let mut module = BenchmarkModule::new("U256", iterations);
+    let b = &mut module.builder;
+    let u256 = b.add_struct(
+        "U256",
+        AbilitySet::EMPTY | Ability::Copy | Ability::Drop | Ability::Store,
+        vec![],
+        vec![(
+            "bits",
+            SignatureToken::Vector(Box::new(SignatureToken::U64)),
+        )],
+    );
+    let u256_type = SignatureToken::Struct(b.struct_handle(u256));
+    let u64_vector = b.signature(vec![SignatureToken::U64]);
+    let from_bytes = b.add_native(
+        "from_bytes",
+        vec![],
+        vec![
+            SignatureToken::Reference(Box::new(bytes_type())),
+            SignatureToken::Bool,
+        ],
+        vec![u256_type.clone()],
+    );
+    let operators: Vec<_> = ["add", "sub", "mul", "div", "rem", "pow"]
+        .into_iter()
+        .map(|op| {
+            let native = b.add_native(
+                &format!("native_{}", op),
+                vec![],
+                vec![
+                    SignatureToken::MutableReference(Box::new(u256_type.clone())),
+                    SignatureToken::Reference(Box::new(u256_type.clone())),
+                ],
+                vec![],
+            );
+            (op, native)
+        })
+        .collect();
+
+    let locals = vec![
+        u256_type.clone(),
+        u256_type.clone(),
+        u256_type,
+        bytes_type(),
+    ];
+    let pack = |n: u64| {
+        vec![
+            LdU64(n),
+            LdU64(0),
+            LdU64(0),
+            LdU64(0),
+            VecPack(u64_vector, 4),
+            Pack(u256),
+        ]
+    };
+    let mut setup = pack(1000);
+    setup.push(StLoc(L0));
+    setup.extend(pack(7));
+    setup.push(StLoc(L1));
+
+    for (op, native) in operators {
+        // the operand is copied before every operation, to keep the result in range.
+        module.add_loop(
+            op,
+            benchmark(
+                locals.clone(),
+                setup.clone(),
+                vec![
+                    CopyLoc(L0),
+                    StLoc(L2),
+                    MutBorrowLoc(L2),
+                    ImmBorrowLoc(L1),
+                    Call(native),
+                ],
+            ),
+            &[&format!("starcoin_natives.u256.{}.base", op)],
+        );
+    }
+    for size in [8, SMALL] {
+        let input = module.builder.bytes(vec![7u8; size]);
+        let mut setup = setup.clone();
+        setup.extend([LdConst(input), StLoc(L3)]);
+        module.add_loop(
+            &format!("from_bytes_{}", size),
+            benchmark(
+                locals.clone(),
+                setup,
+                vec![ImmBorrowLoc(L3), LdFalse, Call(from_bytes), Pop],
+            ),
+            &["starcoin_natives.u256.from_bytes.per_byte"],
+        );
+    }
+    module.finish()
+}
+
+fn string(iterations: u64) -> (CompiledModule, Vec<Benchmark>) {
+    use Bytecode::*;
+    use SignatureToken::*;
+
+    let mut module = BenchmarkModule::new("String", iterations);
+    let b = &mut module.builder;
+    let bytes_ref = || Reference(Box::new(bytes_type()));
+    let check_utf8 = b.add_native("internal_check_utf8", vec![], vec![bytes_ref()], vec![Bool]);
+    let is_char_boundary = b.add_native(
+        "internal_is_char_boundary",
+        vec![],
+        vec![bytes_ref(), U64],
+        vec![Bool],
+    );
+    let sub_string = b.add_native(
+        "internal_sub_string",
+        vec![],
+        vec![bytes_ref(), U64, U64],
+        vec![bytes_type()],
+    );
+    let index_of = b.add_native(
+        "internal_index_of",
+        vec![],
+        vec![bytes_ref(), bytes_ref()],
+        vec![U64],
+    );
+    // the searched pattern is never found, so the whole string is searched.
+    let pattern = b.bytes(b"b".to_vec());
+
+    let key = |name: &str| format!("move_stdlib.string.{}", name);
+    module.add_loop(
+        "is_char_boundary",
+        benchmark(
+            vec![bytes_type()],
+            vec![LdConst(pattern), StLoc(L0)],
+            vec![ImmBorrowLoc(L0), LdU64(0), Call(is_char_boundary), Pop],
+        ),
+        &[&key("is_char_boundary.base")],
+    );
+    for size in [SMALL, LARGE] {
+        let input = module.builder.bytes(vec![b'a'; size]);
+        let setup = vec![LdConst(input), StLoc(L0), LdConst(pattern), StLoc(L1)];
+        let benchmarks = [
+            (
+                "check_utf8",
+                vec![ImmBorrowLoc(L0), Call(check_utf8), Pop],
+                key("check_utf8.per_byte"),
+            ),
+            (
+                "sub_string",
+                vec![
+                    ImmBorrowLoc(L0),
+                    LdU64(0),
+                    LdU64(size as u64),
+                    Call(sub_string),
+                    Pop,
+                ],
+                key("sub_string.per_byte"),
+            ),
+            (
+                "index_of",
+                vec![ImmBorrowLoc(L0), ImmBorrowLoc(L1), Call(index_of), Pop],
+                key("index_of.per_byte_searched"),
+            ),
+        ];
+        for (name, body, key) in benchmarks {
+            module.add_loop(
+                &format!("{}_{}", name, size),
+                benchmark(vec![bytes_type(); 2], setup.clone(), body),
+                &[&key],
+            );
+        }
+    }
+    module.finish()
+}
+
+/// The valid inputs of the signature natives, the signatures are generated from fixed keys.
+struct SignatureInputs {
+    ed25519_public_key: Vec<u8>,
+    ed25519_signatures: Vec<(Vec<u8>, Vec<u8>)>,
+    ecrecover_hash: Vec<u8>,
+    ecrecover_signature: Vec<u8>,
+    bls12381_public_keys: Vec<Vec<u8>>,
+    bls12381_signatures: Vec<(Vec<u8>, Vec<u8>)>,
+    bls12381_aggregate_signature: Vec<u8>,
+    bls12381_proof_of_possession: Vec<u8>,
+    secp256r1_public_key: Vec<u8>,
+    secp256r1_signatures: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+/// The number of the public keys aggregated by `bls12381_aggregate_verify`.
+const BLS12381_AGGREGATED_KEYS: usize = 8;
+
+impl SignatureInputs {
+    fn new() -> Self {
+        use p256::ecdsa::signature::Signer;
+        use starcoin_natives::bls12381::{DST_POP, DST_SIG};
+
+        let messages = [vec![7u8; SMALL], vec![7u8; LARGE]];
+
+        let ed25519_key = Ed25519PrivateKey::try_from(&[7u8; 32][..])
+            .expect("ed25519 private key should be valid");
+        let ed25519_public_key = Ed25519PublicKey::from(&ed25519_key).to_bytes().to_vec();
+        let ed25519_signatures = messages
+            .iter()
+            .map(|msg| {
+                let signature = ed25519_key.sign_arbitrary_message(msg);
+                (msg.clone(), signature.to_bytes().to_vec())
+            })
+            .collect();
+
+        let secp256k1_key = libsecp256k1::SecretKey::parse(&[7u8; 32])
+            .expect("secp256k1 private key should be valid");
+        let ecrecover_hash = vec![7u8; 32];
+        let (signature, recovery_id) = libsecp256k1::sign(
+            &libsecp256k1::Message::parse_slice(&ecrecover_hash)
+                .expect("message hash should be 32 bytes"),
+            &secp256k1_key,
+        );
+        let mut ecrecover_signature = signature.serialize().to_vec();
+        ecrecover_signature.push(recovery_id.serialize());
+
+        let bls12381_keys: Vec<_> = (0..BLS12381_AGGREGATED_KEYS)
+            .map(|i| {
+                blst::min_pk::SecretKey::key_gen(&[i as u8 + 1; 32], &[])
+                    .expect("bls12381 private key should be valid")
+            })
+            .collect();
+        let bls12381_public_keys = bls12381_keys
+            .iter()
+            .map(|key| key.sk_to_pk().compress().to_vec())
+            .collect();
+        let signatures: Vec<_> = bls12381_keys
+            .iter()
+            .map(|key| key.sign(&messages[0], DST_SIG, &[]))
+            .collect();
+        let bls12381_signatures = messages
+            .iter()
+            .map(|msg| {
+                let signature = bls12381_keys[0].sign(msg, DST_SIG, &[]);
+                (msg.clone(), signature.compress().to_vec())
+            })
+            .collect();
+        let bls12381_aggregate_signature = blst::min_pk::AggregateSignature::aggregate(
+            &signatures.iter().collect::<Vec<_>>(),
+            true,
+        )
+        .expect("bls12381 signatures should be valid")
+        .to_signature()
+        .compress()
+        .to_vec();
+        let bls12381_proof_of_possession = bls12381_keys[0]
+            .sign(&bls12381_keys[0].sk_to_pk().compress(), DST_POP, &[])
+            .compress()
+            .to_vec();
+
+        let secp256r1_key = p256::ecdsa::SigningKey::from_bytes(&[7u8; 32])
+            .expect("secp256r1 private key should be valid");
+        let secp256r1_public_key = secp256r1_key
+            .verifying_key()
+            .to_encoded_point(true)
+            .as_bytes()
+            .to_vec();
+        let secp256r1_signatures = messages
+            .iter()
+            .map(|msg| {
+                let signature: p256::ecdsa::Signature = secp256r1_key.sign(msg);
+                let signature = signature.normalize_s().unwrap_or(signature);
+                (msg.clone(), signature.as_ref().to_vec())
+            })
+            .collect();
+
+        Self {
+            ed25519_public_key,
+            ed25519_signatures,
+            ecrecover_hash,
+            ecrecover_signature,
+            bls12381_public_keys,
+            bls12381_signatures,
+            bls12381_aggregate_signature,
+            bls12381_proof_of_possession,
+            secp256r1_public_key,
+            secp256r1_signatures,
+        }
+    }
+}
+
+fn signature(iterations: u64) -> (CompiledModule, Vec<Benchmark>) {
+    use Bytecode::*;
+    use SignatureToken::*;
+
+    let inputs = SignatureInputs::new();
+    let mut module = BenchmarkModule::new("Signature", iterations);
+    let b = &mut module.builder;
+    let bytes_vector = || Vector(Box::new(bytes_type()));
+    let ed25519_validate_pubkey = b.add_native(
+        "ed25519_validate_pubkey",
+        vec![],
+        vec![bytes_type()],
+        vec![Bool],
+    );
+    let ed25519_verify = b.add_native("ed25519_verify", vec![], vec![bytes_type(); 3], vec![Bool]);
+    let ecrecover = b.add_native(
+        "native_ecrecover",
+        vec![],
+        vec![bytes_type(); 2],
+        vec![bytes_type()],
+    );
+    let bls12381_verify =
+        b.add_native("bls12381_verify", vec![], vec![bytes_type(); 3], vec![Bool]);
+    let bls12381_aggregate_verify = b.add_native(
+        "bls12381_aggregate_verify",
+        vec![],
+        vec![bytes_vector(), bytes_type(), bytes_type()],
+        vec![Bool],
+    );
+    let bls12381_verify_pop = b.add_native(
+        "bls12381_verify_proof_of_possession",
+        vec![],
+        vec![bytes_type(); 2],
+        vec![Bool],
+    );
+    let secp256r1_verify = b.add_native(
+        "native_secp256r1_verify",
+        vec![],
+        vec![bytes_type(); 3],
+        vec![Bool],
+    );
+
+    let key = |name: &str| format!("starcoin_natives.signature.{}", name);
+    let mut benchmarks: Vec<(String, Vec<Bytecode>, Vec<String>)> = vec![];
+
+    let ed25519_public_key = b.bytes(inputs.ed25519_public_key);
+    benchmarks.push((
+        "ed25519_validate_pubkey".to_string(),
+        vec![
+            LdConst(ed25519_public_key),
+            Call(ed25519_validate_pubkey),
+            Pop,
+        ],
+        vec![key("ed25519_validate_key.per_byte")],
+    ));
+    for (msg, signature) in inputs.ed25519_signatures {
+        let size = msg.len();
+        let (msg, signature) = (b.bytes(msg), b.bytes(signature));
+        benchmarks.push((
+            format!("ed25519_verify_{}", size),
+            vec![
+                LdConst(signature),
+                LdConst(ed25519_public_key),
+                LdConst(msg),
+                Call(ed25519_verify),
+                Pop,
+            ],
+            vec![key("ed25519_verify.per_byte")],
+        ));
+    }
+
+    let (hash, signature) = (
+        b.bytes(inputs.ecrecover_hash),
+        b.bytes(inputs.ecrecover_signature),
+    );
+    benchmarks.push((
+        "ecrecover".to_string(),
+        vec![LdConst(hash), LdConst(signature), Call(ecrecover), Pop],
+        vec![key("ec_recover.per_byte")],
+    ));
+
+    let bls12381_msg = b.bytes(vec![7u8; SMALL]);
+    let bls12381_public_key = b.bytes(inputs.bls12381_public_keys[0].clone());
+    let bls12381_public_keys = b.constant(
+        bytes_vector(),
+        MoveValue::Vector(
+            inputs
+                .bls12381_public_keys
+                .into_iter()
+                .map(MoveValue::vector_u8)
+                .collect(),
+        ),
+    );
+    let bls12381_aggregate_signature = b.bytes(inputs.bls12381_aggregate_signature);
+    let bls12381_pop = b.bytes(inputs.bls12381_proof_of_possession);
+    for (msg, signature) in inputs.bls12381_signatures {
+        let size = msg.len();
+        let (msg, signature) = (b.bytes(msg), b.bytes(signature));
+        benchmarks.push((
+            format!("bls12381_verify_{}", size),
+            vec![
+                LdConst(bls12381_public_key),
+                LdConst(msg),
+                LdConst(signature),
+                Call(bls12381_verify),
+                Pop,
+            ],
+            vec![key("bls12381_verify.base"), key("bls12381_verify.per_byte")],
+        ));
+    }
+    benchmarks.push((
+        "bls12381_aggregate_verify".to_string(),
+        vec![
+            LdConst(bls12381_public_keys),
+            LdConst(bls12381_msg),
+            LdConst(bls12381_aggregate_signature),
+            Call(bls12381_aggregate_verify),
+            Pop,
+        ],
+        vec![
+            key("bls12381_aggregate_verify.base"),
+            key("bls12381_aggregate_verify.per_pubkey"),
+            key("bls12381_aggregate_verify.per_byte"),
+        ],
+    ));
+    benchmarks.push((
+        "bls12381_verify_pop".to_string(),
+        vec![
+            LdConst(bls12381_public_key),
+            LdConst(bls12381_pop),
+            Call(bls12381_verify_pop),
+            Pop,
+        ],
+        vec![
+            key("bls12381_verify_pop.base"),
+            key("bls12381_verify_pop.per_byte"),
+        ],
+    ));
+
+    let secp256r1_public_key = b.bytes(inputs.secp256r1_public_key);
+    for (msg, signature) in inputs.secp256r1_signatures {
+        let size = msg.len();
+        let (msg, signature) = (b.bytes(msg), b.bytes(signature));
+        benchmarks.push((
+            format!("secp256r1_verify_{}", size),
+            vec![
+                LdConst(signature),
+                LdConst(secp256r1_public_key),
+                LdConst(msg),
+                Call(secp256r1_verify),
+                Pop,
+            ],
+            vec![
+                key("secp256r1_verify.base"),
+                key("secp256r1_verify.per_byte"),
+            ],
+        ));
+    }
+
+    for (name, body, keys) in benchmarks {
+        let keys: Vec<&str> = keys.iter().map(String::as_str).collect();
+        module.add_loop(&name, benchmark(vec![], vec![], body), &keys);
+    }
+    module.finish()
+}
+
+fn table(iterations: u64) -> (CompiledModule, Vec<Benchmark>) {
+    use Bytecode::*;
+    use SignatureToken::*;
+
+    let mut module = BenchmarkModule::new("Table", iterations);
+    let b = &mut module.builder;
+    let phantom = StructTypeParameter {
+        constraints: AbilitySet::EMPTY,
+        is_phantom: true,
+    };
+    let table = b.add_struct(
+        "Table",
+        AbilitySet::EMPTY | Ability::Store,
+        vec![phantom, phantom],
+        vec![("handle", Address)],
+    );
+    let box_ = b.add_struct(
+        "Box",
+        AbilitySet::EMPTY | Ability::Drop | Ability::Store,
+        vec![StructTypeParameter {
+            constraints: AbilitySet::EMPTY,
+            is_phantom: false,
+        }],
+        vec![("val", TypeParameter(0))],
+    );
+    let table_handle = b.struct_handle(table);
+    let box_handle = b.struct_handle(box_);
+    let table_of = |k, v| StructInstantiation(table_handle, vec![k, v]);
+    let box_of = |v| StructInstantiation(box_handle, vec![v]);
+    let table_type = || table_of(TypeParameter(0), TypeParameter(1));
+    let key = AbilitySet::EMPTY | Ability::Copy | Ability::Drop;
+    let any = AbilitySet::EMPTY;
+
+    let table_inst = b.struct_instantiation(table, vec![U64, U64]);
+    let box_inst = b.struct_instantiation(box_, vec![U64]);
+    let new_table_handle = b.add_native("new_table_handle", vec![key, any], vec![], vec![Address]);
+    let new_table_handle = b.function_instantiation(new_table_handle, vec![U64, U64]);
+    let mut native = |name: &str, params, returns| {
+        let handle = b.add_native(name, vec![key, any, any], params, returns);
+        b.function_instantiation(handle, vec![U64, U64, box_of(U64)])
+    };
+    let add_box = native(
+        "add_box",
+        vec![
+            MutableReference(Box::new(table_type())),
+            TypeParameter(0),
+            TypeParameter(2),
+        ],
+        vec![],
+    );
+    let borrow_box = native(
+        "borrow_box",
+        vec![Reference(Box::new(table_type())), TypeParameter(0)],
+        vec![Reference(Box::new(TypeParameter(2)))],
+    );
+    let contains_box = native(
+        "contains_box",
+        vec![Reference(Box::new(table_type())), TypeParameter(0)],
+        vec![Bool],
+    );
+    let remove_box = native(
+        "remove_box",
+        vec![MutableReference(Box::new(table_type())), TypeParameter(0)],
+        vec![TypeParameter(2)],
+    );
+    let destroy_empty_box = native(
+        "destroy_empty_box",
+        vec![Reference(Box::new(table_type()))],
+        vec![],
+    );
+    let drop_unchecked_box = native("drop_unchecked_box", vec![table_type()], vec![]);
+
+    let locals = vec![table_of(U64, U64), table_of(U64, U64)];
+    // a table with the key 0 added.
+    let setup = vec![
+        CallGeneric(new_table_handle),
+        PackGeneric(table_inst),
+        StLoc(L0),
+        MutBorrowLoc(L0),
+        LdU64(0),
+        LdU64(1),
+        PackGeneric(box_inst),
+        CallGeneric(add_box),
+    ];
+    let teardown = vec![MoveLoc(L0), CallGeneric(drop_unchecked_box)];
+
+    let key = |name: &str| format!("table.{}", name);
+    let benchmarks: Vec<(&str, Vec<Bytecode>, Vec<String>)> = vec![
+        (
+            "new_destroy_empty",
+            vec![
+                CallGeneric(new_table_handle),
+                PackGeneric(table_inst),
+                StLoc(L1),
+                ImmBorrowLoc(L1),
+                CallGeneric(destroy_empty_box),
+                MoveLoc(L1),
+                CallGeneric(drop_unchecked_box),
+            ],
+            vec![
+                key("new_table_handle.base"),
+                key("destroy_empty_box.base"),
+                key("drop_unchecked_box.base"),
+            ],
+        ),
+        (
+            "add_remove",
+            vec![
+                MutBorrowLoc(L0),
+                LdU64(1),
+                LdU64(1),
+                PackGeneric(box_inst),
+                CallGeneric(add_box),
+                MutBorrowLoc(L0),
+                LdU64(1),
+                CallGeneric(remove_box),
+                UnpackGeneric(box_inst),
+                Pop,
+            ],
+            vec![
+                key("add_box.per_byte_serialized"),
+                key("remove_box.per_byte_serialized"),
+            ],
+        ),
+        (
+            "borrow",
+            vec![ImmBorrowLoc(L0), LdU64(0), CallGeneric(borrow_box), Pop],
+            vec![key("borrow_box.per_byte_serialized")],
+        ),
+        (
+            "contains",
+            vec![ImmBorrowLoc(L0), LdU64(0), CallGeneric(contains_box), Pop],
+            vec![key("contains_box.per_byte_serialized")],
+        ),
+    ];
+    for (name, body, keys) in benchmarks {
+        let keys: Vec<&str> = keys.iter().map(String::as_str).collect();
+        let mut benchmark = benchmark(locals.clone(), setup.clone(), body);
+        benchmark.teardown = teardown.clone();
+        module.add_loop(name, benchmark, &keys);
+    }
+    module.finish()
+}
@@ -0,0 +1,257 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Run the benchmarks in a bare Move VM, to measure the execution time and the gas units used by
+//! every gas parameter.
+
+use crate::calibration::Benchmark;
+use crate::gas_meter::{StarcoinGasMeter, StarcoinGasParameters, TransactionGasMeter};
+use crate::gas_tracer::{FrameGasTrace, GasTracer};
+use crate::natives::starcoin_natives;
+use anyhow::{bail, format_err, Result};
+use move_binary_format::errors::{PartialVMError, VMError, VMResult};
+use move_binary_format::file_format::CompiledModule;
+use move_core_types::account_address::AccountAddress;
+use move_core_types::language_storage::{ModuleId, StructTag};
+use move_core_types::resolver::{ModuleResolver, ResourceResolver};
+use move_core_types::value::MoveValue;
+use move_core_types::vm_status::StatusCode;
+use move_table_extension::{NativeTableContext, TableHandle, TableResolver};
+use move_vm_runtime::move_vm::MoveVM;
+use move_vm_runtime::native_extensions::NativeContextExtensions;
+use move_vm_types::gas::GasMeter;
+use starcoin_gas_algebra_ext::FromOnChainGasSchedule;
+use std::collections::{BTreeMap, HashMap};
+use std::time::Instant;
+
+/// The account running the benchmarks.
+const BENCHMARK_ACCOUNT: AccountAddress = AccountAddress::new([0xAu8; AccountAddress::LENGTH]);
+
+/// The balance of the gas meter which measures the gas units, it is never used up by the
+/// benchmarks with all the gas parameters set to 1.
+const UNITS_BALANCE: u64 = 1 << 40;
+
+/// The storage of the benchmark modules, the benchmarks never read any resource or table item
+/// from the storage.
+struct BenchmarkStorage {
+    modules: HashMap<ModuleId, Vec<u8>>,
+}
+
+impl BenchmarkStorage {
+    fn new(modules: &[CompiledModule]) -> Result<Self> {
+        let mut storage = HashMap::new();
+        for module in modules {
+            let mut bytes = vec![];
+            module.serialize(&mut bytes)?;
+            storage.insert(module.self_id(), bytes);
+        }
+        Ok(Self { modules: storage })
+    }
+}
+
+impl ModuleResolver for BenchmarkStorage {
+    type Error = VMError;
+
+    fn get_module(&self, module_id: &ModuleId) -> VMResult<Option<Vec<u8>>> {
+        Ok(self.modules.get(module_id).cloned())
+    }
+}
+
+impl ResourceResolver for BenchmarkStorage {
+    type Error = PartialVMError;
+
+    fn get_resource(
+        &self,
+        _address: &AccountAddress,
+        _tag: &StructTag,
+    ) -> Result<Option<Vec<u8>>, Self::Error> {
+        Ok(None)
+    }
+}
+
+impl TableResolver for BenchmarkStorage {
+    fn resolve_table_entry(
+        &self,
+        _handle: &TableHandle,
+        _key: &[u8],
+    ) -> Result<Option<Vec<u8>>, anyhow::Error> {
+        Ok(None)
+    }
+}
+
+pub(crate) struct BenchmarkRunner {
+    storage: BenchmarkStorage,
+    /// The gas parameters of the current gas schedule.
+    params: StarcoinGasParameters,
+    /// The gas schedule with all the instruction and native gas parameters set to 1, so the gas
+    /// charged by a parameter is the units it is charged for.
+    units_schedule: BTreeMap<String, u64>,
+    vm: MoveVM,
+    units_vm: MoveVM,
+}
+
+impl BenchmarkRunner {
+    pub fn new(modules: &[CompiledModule], current: &BTreeMap<String, u64>) -> Result<Self> {
+        let params = StarcoinGasParameters::from_on_chain_gas_schedule(current)
+            .ok_or_else(|| format_err!("the current gas schedule misses some gas parameters"))?;
+        let units_schedule: BTreeMap<_, _> = current
+            .iter()
+            .map(|(key, value)| {
+                // the transaction parameters scale the gas units, which are kept as they are.
+                let value = if key.starts_with("txn.") { *value } else { 1 };
+                (key.clone(), value)
+            })
+            .collect();
+        let vm = Self::new_vm(current)?;
+        let units_vm = Self::new_vm(&units_schedule)?;
+        Ok(Self {
+            storage: BenchmarkStorage::new(modules)?,
+            params,
+            units_schedule,
+            vm,
+            units_vm,
+        })
+    }
+
+    fn new_vm(schedule: &BTreeMap<String, u64>) -> Result<MoveVM> {
+        let params = StarcoinGasParameters::from_on_chain_gas_schedule(schedule)
+            .ok_or_else(|| format_err!("the gas schedule misses some gas parameters"))?;
+        Ok(MoveVM::new(starcoin_natives(params.natives))?)
+    }
+
+    fn units_params(&self) -> Result<StarcoinGasParameters> {
+        StarcoinGasParameters::from_on_chain_gas_schedule(&self.units_schedule)
+            .ok_or_else(|| format_err!("the gas schedule misses some gas parameters"))
+    }
+
+    fn execute<G: GasMeter>(
+        &self,
+        vm: &MoveVM,
+        benchmark: &Benchmark,
+        gas_meter: &mut G,
+    ) -> Result<()> {
+        let mut extensions = NativeContextExtensions::default();
+        extensions.add(NativeTableContext::new([0u8; 32], &self.storage));
+        let mut session = vm.new_session_with_extensions(&self.storage, extensions);
+        let args = vec![
+            MoveValue::Signer(BENCHMARK_ACCOUNT),
+            MoveValue::Address(BENCHMARK_ACCOUNT),
+            MoveValue::U64(benchmark.iterations),
+        ]
+        .into_iter()
+        .map(|arg| {
+            arg.simple_serialize()
+                .expect("benchmark arguments should be serializable")
+        })
+        .collect::<Vec<_>>();
+        let result = session.execute_function_bypass_visibility(
+            &benchmark.module,
+            &benchmark.function,
+            vec![],
+            args,
+            gas_meter,
+        );
+        match result {
+            Ok(_) if !benchmark.aborts => Ok(()),
+            Err(err) if benchmark.aborts && err.major_status() == StatusCode::ABORTED => Ok(()),
+            Ok(_) => bail!("benchmark {} should abort", benchmark.name),
+            Err(err) => bail!("benchmark {} failed: {:?}", benchmark.name, err),
+        }
+    }
+
+    /// The median execution time in nanoseconds of `samples` runs, after a warm up run which
+    /// loads the module.
+    pub fn measure_time(&self, benchmark: &Benchmark, samples: usize) -> Result<f64> {
+        let samples = samples.max(1);
+        let mut times = Vec::with_capacity(samples);
+        for i in 0..=samples {
+            let mut gas_meter = StarcoinGasMeter::new(self.params.clone(), 0);
+            gas_meter.set_metering(false);
+            let start = Instant::now();
+            self.execute(&self.vm, benchmark, &mut gas_meter)?;
+            let elapsed = start.elapsed().as_nanos() as f64;
+            if i > 0 {
+                times.push(elapsed);
+            }
+        }
+        times.sort_by(|a, b| a.total_cmp(b));
+        Ok(times[times.len() / 2])
+    }
+
+    /// The gas units charged by every instruction gas parameter, and by the native gas
+    /// parameters measured by the benchmark.
+    pub fn measure_units(&self, benchmark: &Benchmark) -> Result<BTreeMap<String, u64>> {
+        let mut tracer = GasTracer::new(
+            StarcoinGasMeter::new(self.units_params()?, UNITS_BALANCE),
+            benchmark.name.clone(),
+        );
+        self.execute(&self.units_vm, benchmark, &mut tracer)?;
+        let trace = tracer.finish();
+
+        let mut instructions = BTreeMap::new();
+        collect_instructions(&trace.execution, &mut instructions);
+        let mut units = BTreeMap::new();
+        for (opcode, gas) in instructions {
+            if gas == 0 {
+                continue;
+            }
+            if let Some(key) = self.instruction_key(&opcode) {
+                *units.entry(key).or_default() += gas;
+            }
+        }
+
+        // the units of a native gas parameter is the increase of the native gas when the
+        // parameter is set to 2.
+        let native_gas = trace
+            .natives
+            .values()
+            .map(|counter| counter.gas)
+            .sum::<u64>();
+        for key in &benchmark.native_keys {
+            if !self.units_schedule.contains_key(key) {
+                continue;
+            }
+            let mut schedule = self.units_schedule.clone();
+            schedule.insert(key.clone(), 2);
+            let params = StarcoinGasParameters::from_on_chain_gas_schedule(&schedule)
+                .ok_or_else(|| format_err!("the gas schedule misses some gas parameters"))?;
+            let mut tracer = GasTracer::new(
+                StarcoinGasMeter::new(params, UNITS_BALANCE),
+                benchmark.name.clone(),
+            );
+            self.execute(&Self::new_vm(&schedule)?, benchmark, &mut tracer)?;
+            let bumped_gas = tracer
+                .finish()
+                .natives
+                .values()
+                .map(|counter| counter.gas)
+                .sum::<u64>();
+            units.insert(key.clone(), bumped_gas.saturating_sub(native_gas));
+        }
+        Ok(units)
+    }
+
+    /// The gas parameter of an instruction, such as `instr.ld_u64` for `LD_U64` and
+    /// `instr.copy_loc.per_abs_mem_unit` for `COPY_LOC`.
+    fn instruction_key(&self, opcode: &str) -> Option<String> {
+        let key = format!("instr.{}", opcode.to_lowercase());
+        if self.units_schedule.contains_key(&key) {
+            return Some(key);
+        }
+        let prefix = format!("{}.", key);
+        self.units_schedule
+            .range(prefix.clone()..)
+            .next()
+            .filter(|(existing, _)| existing.starts_with(&prefix))
+            .map(|(existing, _)| existing.clone())
+    }
+}
+
+fn collect_instructions(frame: &FrameGasTrace, instructions: &mut BTreeMap<String, u64>) {
+    for (opcode, counter) in &frame.instructions {
+        *instructions.entry(opcode.clone()).or_default() += counter.gas;
+    }
+    for child in &frame.children {
+        collect_instructions(child, instructions);
+    }
+}
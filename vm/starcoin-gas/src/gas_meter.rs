@@ -153,13 +153,36 @@ impl StarcoinGasMeter {
             charge: true,
         }
     }
+}
+
+/// The gas meter interfaces used by the Starcoin VM to meter a transaction, besides the
+/// instruction charging of the `GasMeter`.
+pub trait TransactionGasMeter: GasMeter {
+    fn balance(&self) -> Gas;
+
+    fn deduct_gas(&mut self, amount: InternalGas) -> PartialVMResult<()>;
+
+    fn set_metering(&mut self, enabled: bool);
+
+    fn get_metering(&self) -> bool;
+
+    fn charge_intrinsic_gas_for_transaction(&mut self, txn_size: NumBytes) -> VMResult<()>;
+
+    fn cal_write_set_gas(&self) -> InternalGas;
+
+    /// Charge the gas of writing the global storage at the end of the transaction.
+    fn charge_write_set_gas(&mut self, amount: InternalGas) -> PartialVMResult<()> {
+        self.deduct_gas(amount)
+    }
+}
 
-    pub fn balance(&self) -> Gas {
+impl TransactionGasMeter for StarcoinGasMeter {
+    fn balance(&self) -> Gas {
         self.balance
             .to_unit_round_down_with_params(&self.gas_params.txn)
     }
 
-    pub fn deduct_gas(&mut self, amount: InternalGas) -> PartialVMResult<()> {
+    fn deduct_gas(&mut self, amount: InternalGas) -> PartialVMResult<()> {
         if !self.charge {
             return Ok(());
         }
@@ -175,15 +198,15 @@ impl StarcoinGasMeter {
         }
     }
 
-    pub fn set_metering(&mut self, enabled: bool) {
+    fn set_metering(&mut self, enabled: bool) {
         self.charge = enabled;
     }
 
-    pub fn get_metering(&self) -> bool {
+    fn get_metering(&self) -> bool {
         self.charge
     }
 
-    pub fn charge_intrinsic_gas_for_transaction(&mut self, txn_size: NumBytes) -> VMResult<()> {
+    fn charge_intrinsic_gas_for_transaction(&mut self, txn_size: NumBytes) -> VMResult<()> {
         let cost = self.gas_params.txn.calculate_intrinsic_gas(txn_size);
         #[cfg(testing)]
         info!(
@@ -194,7 +217,7 @@ impl StarcoinGasMeter {
             .map_err(|e| e.finish(Location::Undefined))
     }
 
-    pub fn cal_write_set_gas(&self) -> InternalGas {
+    fn cal_write_set_gas(&self) -> InternalGas {
         self.gas_params.txn.cal_write_set_gas()
     }
 }
@@ -220,9 +243,8 @@ fn cal_instr_with_byte(per_arg: InternalGasPerByte, size: NumBytes) -> InternalG
     per_arg * size
 }
 
-#[allow(dead_code)]
 #[inline]
-fn simple_instr_to_opcode(instr: SimpleInstruction) -> Opcodes {
+pub(crate) fn simple_instr_to_opcode(instr: SimpleInstruction) -> Opcodes {
     match instr {
         SimpleInstruction::Nop => Opcodes::NOP,
         SimpleInstruction::Ret => Opcodes::RET,
@@ -0,0 +1,655 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! An opt-in gas meter wrapper which records where the gas of a transaction goes.
+
+use crate::gas_meter::{simple_instr_to_opcode, TransactionGasMeter};
+use move_binary_format::errors::{PartialVMResult, VMResult};
+use move_binary_format::file_format_common::Opcodes;
+use move_core_types::gas_algebra::{InternalGas, NumArgs, NumBytes};
+use move_core_types::language_storage::ModuleId;
+use move_core_types::vm_status::StatusCode;
+use move_vm_types::gas::{GasMeter, SimpleInstruction};
+use move_vm_types::views::{TypeView, ValueView};
+use starcoin_gas_algebra_ext::Gas;
+use std::collections::BTreeMap;
+
+/// The instruction key of the gas charged by a native function.
+const NATIVE_INSTRUCTION: &str = "NATIVE";
+
+/// The count and the used gas of an instruction or a native function.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct GasCounter {
+    pub count: u64,
+    pub gas: u64,
+}
+
+impl GasCounter {
+    fn add(&mut self, gas: u64) {
+        self.count = self.count.saturating_add(1);
+        self.gas = self.gas.saturating_add(gas);
+    }
+
+    fn merge(&mut self, other: &GasCounter) {
+        self.count = self.count.saturating_add(other.count);
+        self.gas = self.gas.saturating_add(other.gas);
+    }
+}
+
+/// The gas profile of a function, the calls of a function from the same caller are merged.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct FrameGasTrace {
+    /// The function name, such as `0x1::Account::pay_from`.
+    pub name: String,
+    pub calls: u64,
+    /// The gas used by the function itself, excluding its callees.
+    pub self_gas: u64,
+    /// The gas of each instruction executed by the function itself, keyed by the opcode name.
+    pub instructions: BTreeMap<String, GasCounter>,
+    pub children: Vec<FrameGasTrace>,
+}
+
+impl FrameGasTrace {
+    fn new(name: String) -> Self {
+        Self {
+            name,
+            calls: 1,
+            ..Default::default()
+        }
+    }
+
+    /// The gas used by the function and all its callees.
+    pub fn total_gas(&self) -> u64 {
+        self.children.iter().fold(self.self_gas, |acc, child| {
+            acc.saturating_add(child.total_gas())
+        })
+    }
+
+    fn record(&mut self, instruction: &str, gas: u64) {
+        self.self_gas = self.self_gas.saturating_add(gas);
+        self.instructions
+            .entry(instruction.to_string())
+            .or_default()
+            .add(gas);
+    }
+
+    fn add_child(&mut self, child: FrameGasTrace) {
+        match self.children.iter_mut().find(|c| c.name == child.name) {
+            Some(existing) => existing.merge(child),
+            None => self.children.push(child),
+        }
+    }
+
+    fn merge(&mut self, other: FrameGasTrace) {
+        self.calls = self.calls.saturating_add(other.calls);
+        self.self_gas = self.self_gas.saturating_add(other.self_gas);
+        for (instruction, counter) in &other.instructions {
+            self.instructions
+                .entry(instruction.clone())
+                .or_default()
+                .merge(counter);
+        }
+        for child in other.children {
+            self.add_child(child);
+        }
+    }
+
+    /// Remove the callees which use no gas, such as the ones called with metering disabled.
+    fn prune(&mut self) {
+        self.children.retain(|child| child.total_gas() > 0);
+        for child in self.children.iter_mut() {
+            child.prune();
+        }
+    }
+
+    fn fold_stacks(&self, prefix: Option<&str>, lines: &mut Vec<String>) {
+        let stack = match prefix {
+            Some(prefix) => format!("{};{}", prefix, self.name),
+            None => self.name.clone(),
+        };
+        if self.self_gas > 0 {
+            lines.push(format!("{} {}", stack, self.self_gas));
+        }
+        for child in &self.children {
+            child.fold_stacks(Some(stack.as_str()), lines);
+        }
+    }
+}
+
+/// The gas profile of a transaction, all the gas is in internal gas units.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TransactionGasTrace {
+    pub intrinsic_gas: u64,
+    pub storage_write_gas: u64,
+    /// The call tree rooted at the entry function of the transaction.
+    pub execution: FrameGasTrace,
+    /// The gas of each native function, keyed by the function name.
+    pub natives: BTreeMap<String, GasCounter>,
+    /// The call stack when the transaction ran out of gas, the entry function is first.
+    pub out_of_gas_at: Option<Vec<String>>,
+}
+
+impl TransactionGasTrace {
+    pub fn total_gas(&self) -> u64 {
+        self.intrinsic_gas
+            .saturating_add(self.storage_write_gas)
+            .saturating_add(self.execution.total_gas())
+    }
+
+    /// Render the trace as folded stacks, one `frame;frame;... gas` line per stack,
+    /// which can be turned into a flamegraph by `inferno-flamegraph` or `flamegraph.pl`.
+    pub fn to_folded_stacks(&self) -> String {
+        let mut lines = vec![];
+        if self.intrinsic_gas > 0 {
+            lines.push(format!("intrinsic {}", self.intrinsic_gas));
+        }
+        self.execution.fold_stacks(None, &mut lines);
+        if self.storage_write_gas > 0 {
+            lines.push(format!("storage_write {}", self.storage_write_gas));
+        }
+        lines.join("\n")
+    }
+}
+
+/// A gas meter wrapper which records the gas of the call frames, the instructions, the native
+/// functions and the storage writes, while the metering is delegated to the inner gas meter.
+pub struct GasTracer<G> {
+    base: G,
+    /// The frames being executed, the root frame is the entry function of the transaction.
+    frames: Vec<FrameGasTrace>,
+    intrinsic_gas: u64,
+    storage_write_gas: u64,
+    natives: BTreeMap<String, GasCounter>,
+    out_of_gas_at: Option<Vec<String>>,
+}
+
+impl<G: TransactionGasMeter> GasTracer<G> {
+    pub fn new(base: G, entry: impl Into<String>) -> Self {
+        Self {
+            base,
+            frames: vec![FrameGasTrace::new(entry.into())],
+            intrinsic_gas: 0,
+            storage_write_gas: 0,
+            natives: BTreeMap::new(),
+            out_of_gas_at: None,
+        }
+    }
+
+    pub fn finish(mut self) -> TransactionGasTrace {
+        // The frames are left unfinished if the execution is aborted.
+        while self.frames.len() > 1 {
+            self.pop_frame();
+        }
+        let mut execution = self.frames.pop().unwrap_or_default();
+        execution.prune();
+        TransactionGasTrace {
+            intrinsic_gas: self.intrinsic_gas,
+            storage_write_gas: self.storage_write_gas,
+            execution,
+            natives: self.natives,
+            out_of_gas_at: self.out_of_gas_at,
+        }
+    }
+
+    fn current(&mut self) -> &mut FrameGasTrace {
+        self.frames
+            .last_mut()
+            .expect("The root frame should always exist")
+    }
+
+    fn push_frame(&mut self, module_id: &ModuleId, func_name: &str) {
+        self.frames.push(FrameGasTrace::new(format!(
+            "{}::{}",
+            module_id.short_str_lossless(),
+            func_name
+        )));
+    }
+
+    fn pop_frame(&mut self) {
+        // The root frame is never popped, the return of the entry function, the prologue and
+        // the epilogue reach here.
+        if self.frames.len() > 1 {
+            if let Some(frame) = self.frames.pop() {
+                self.current().add_child(frame);
+            }
+        }
+    }
+
+    fn used_gas_since(&self, before: InternalGas) -> u64 {
+        u64::from(
+            before
+                .checked_sub(self.base.balance_internal())
+                .unwrap_or_else(|| 0.into()),
+        )
+    }
+
+    fn charge<F>(&mut self, instruction: &str, f: F) -> PartialVMResult<()>
+    where
+        F: FnOnce(&mut G) -> PartialVMResult<()>,
+    {
+        let before = self.base.balance_internal();
+        let result = f(&mut self.base);
+        let gas = self.used_gas_since(before);
+        self.current().record(instruction, gas);
+        if let Err(err) = &result {
+            if err.major_status() == StatusCode::OUT_OF_GAS {
+                self.record_out_of_gas();
+            }
+        }
+        result
+    }
+
+    fn record_out_of_gas(&mut self) {
+        if self.out_of_gas_at.is_none() {
+            self.out_of_gas_at = Some(self.frames.iter().map(|f| f.name.clone()).collect());
+        }
+    }
+}
+
+impl<G: TransactionGasMeter> GasMeter for GasTracer<G> {
+    fn balance_internal(&self) -> InternalGas {
+        self.base.balance_internal()
+    }
+
+    fn charge_simple_instr(&mut self, instr: SimpleInstruction) -> PartialVMResult<()> {
+        let opcode = format!("{:?}", simple_instr_to_opcode(instr));
+        let result = self.charge(opcode.as_str(), |base| base.charge_simple_instr(instr));
+        if matches!(instr, SimpleInstruction::Ret) && result.is_ok() {
+            self.pop_frame();
+        }
+        result
+    }
+
+    fn charge_pop(&mut self, popped_val: impl ValueView) -> PartialVMResult<()> {
+        self.charge("POP", |base| base.charge_pop(popped_val))
+    }
+
+    fn charge_call(
+        &mut self,
+        module_id: &ModuleId,
+        func_name: &str,
+        args: impl ExactSizeIterator<Item = impl ValueView>,
+        num_locals: NumArgs,
+    ) -> PartialVMResult<()> {
+        self.charge("CALL", |base| {
+            base.charge_call(module_id, func_name, args, num_locals)
+        })?;
+        self.push_frame(module_id, func_name);
+        Ok(())
+    }
+
+    fn charge_call_generic(
+        &mut self,
+        module_id: &ModuleId,
+        func_name: &str,
+        ty_args: impl ExactSizeIterator<Item = impl TypeView>,
+        args: impl ExactSizeIterator<Item = impl ValueView>,
+        num_locals: NumArgs,
+    ) -> PartialVMResult<()> {
+        self.charge("CALL_GENERIC", |base| {
+            base.charge_call_generic(module_id, func_name, ty_args, args, num_locals)
+        })?;
+        self.push_frame(module_id, func_name);
+        Ok(())
+    }
+
+    fn charge_ld_const(&mut self, size: NumBytes) -> PartialVMResult<()> {
+        self.charge("LD_CONST", |base| base.charge_ld_const(size))
+    }
+
+    fn charge_ld_const_after_deserialization(
+        &mut self,
+        val: impl ValueView,
+    ) -> PartialVMResult<()> {
+        self.charge("LD_CONST", |base| {
+            base.charge_ld_const_after_deserialization(val)
+        })
+    }
+
+    fn charge_copy_loc(&mut self, val: impl ValueView) -> PartialVMResult<()> {
+        self.charge("COPY_LOC", |base| base.charge_copy_loc(val))
+    }
+
+    fn charge_move_loc(&mut self, val: impl ValueView) -> PartialVMResult<()> {
+        self.charge("MOVE_LOC", |base| base.charge_move_loc(val))
+    }
+
+    fn charge_store_loc(&mut self, val: impl ValueView) -> PartialVMResult<()> {
+        self.charge("ST_LOC", |base| base.charge_store_loc(val))
+    }
+
+    fn charge_pack(
+        &mut self,
+        is_generic: bool,
+        args: impl ExactSizeIterator<Item = impl ValueView>,
+    ) -> PartialVMResult<()> {
+        let opcode = if is_generic {
+            Opcodes::PACK_GENERIC
+        } else {
+            Opcodes::PACK
+        };
+        self.charge(format!("{:?}", opcode).as_str(), |base| {
+            base.charge_pack(is_generic, args)
+        })
+    }
+
+    fn charge_unpack(
+        &mut self,
+        is_generic: bool,
+        args: impl ExactSizeIterator<Item = impl ValueView>,
+    ) -> PartialVMResult<()> {
+        let opcode = if is_generic {
+            Opcodes::UNPACK_GENERIC
+        } else {
+            Opcodes::UNPACK
+        };
+        self.charge(format!("{:?}", opcode).as_str(), |base| {
+            base.charge_unpack(is_generic, args)
+        })
+    }
+
+    fn charge_read_ref(&mut self, val: impl ValueView) -> PartialVMResult<()> {
+        self.charge("READ_REF", |base| base.charge_read_ref(val))
+    }
+
+    fn charge_write_ref(
+        &mut self,
+        val: impl ValueView,
+        old_val: impl ValueView,
+    ) -> PartialVMResult<()> {
+        self.charge("WRITE_REF", |base| base.charge_write_ref(val, old_val))
+    }
+
+    fn charge_eq(&mut self, lhs: impl ValueView, rhs: impl ValueView) -> PartialVMResult<()> {
+        self.charge("EQ", |base| base.charge_eq(lhs, rhs))
+    }
+
+    fn charge_neq(&mut self, lhs: impl ValueView, rhs: impl ValueView) -> PartialVMResult<()> {
+        self.charge("NEQ", |base| base.charge_neq(lhs, rhs))
+    }
+
+    fn charge_borrow_global(
+        &mut self,
+        is_mut: bool,
+        is_generic: bool,
+        ty: impl TypeView,
+        is_success: bool,
+    ) -> PartialVMResult<()> {
+        let opcode = match (is_mut, is_generic) {
+            (false, false) => Opcodes::IMM_BORROW_GLOBAL,
+            (false, true) => Opcodes::IMM_BORROW_GLOBAL_GENERIC,
+            (true, false) => Opcodes::MUT_BORROW_GLOBAL,
+            (true, true) => Opcodes::MUT_BORROW_GLOBAL_GENERIC,
+        };
+        self.charge(format!("{:?}", opcode).as_str(), |base| {
+            base.charge_borrow_global(is_mut, is_generic, ty, is_success)
+        })
+    }
+
+    fn charge_exists(
+        &mut self,
+        is_generic: bool,
+        ty: impl TypeView,
+        exists: bool,
+    ) -> PartialVMResult<()> {
+        let opcode = if is_generic {
+            Opcodes::EXISTS_GENERIC
+        } else {
+            Opcodes::EXISTS
+        };
+        self.charge(format!("{:?}", opcode).as_str(), |base| {
+            base.charge_exists(is_generic, ty, exists)
+        })
+    }
+
+    fn charge_move_from(
+        &mut self,
+        is_generic: bool,
+        ty: impl TypeView,
+        val: Option<impl ValueView>,
+    ) -> PartialVMResult<()> {
+        let opcode = if is_generic {
+            Opcodes::MOVE_FROM_GENERIC
+        } else {
+            Opcodes::MOVE_FROM
+        };
+        self.charge(format!("{:?}", opcode).as_str(), |base| {
+            base.charge_move_from(is_generic, ty, val)
+        })
+    }
+
+    fn charge_move_to(
+        &mut self,
+        is_generic: bool,
+        ty: impl TypeView,
+        val: impl ValueView,
+        is_success: bool,
+    ) -> PartialVMResult<()> {
+        let opcode = if is_generic {
+            Opcodes::MOVE_TO_GENERIC
+        } else {
+            Opcodes::MOVE_TO
+        };
+        self.charge(format!("{:?}", opcode).as_str(), |base| {
+            base.charge_move_to(is_generic, ty, val, is_success)
+        })
+    }
+
+    fn charge_vec_pack<'a>(
+        &mut self,
+        ty: impl TypeView + 'a,
+        args: impl ExactSizeIterator<Item = impl ValueView>,
+    ) -> PartialVMResult<()> {
+        self.charge("VEC_PACK", |base| base.charge_vec_pack(ty, args))
+    }
+
+    fn charge_vec_len(&mut self, ty: impl TypeView) -> PartialVMResult<()> {
+        self.charge("VEC_LEN", |base| base.charge_vec_len(ty))
+    }
+
+    fn charge_vec_borrow(
+        &mut self,
+        is_mut: bool,
+        ty: impl TypeView,
+        is_success: bool,
+    ) -> PartialVMResult<()> {
+        let opcode = if is_mut {
+            Opcodes::VEC_MUT_BORROW
+        } else {
+            Opcodes::VEC_IMM_BORROW
+        };
+        self.charge(format!("{:?}", opcode).as_str(), |base| {
+            base.charge_vec_borrow(is_mut, ty, is_success)
+        })
+    }
+
+    fn charge_vec_push_back(
+        &mut self,
+        ty: impl TypeView,
+        val: impl ValueView,
+    ) -> PartialVMResult<()> {
+        self.charge("VEC_PUSH_BACK", |base| base.charge_vec_push_back(ty, val))
+    }
+
+    fn charge_vec_pop_back(
+        &mut self,
+        ty: impl TypeView,
+        val: Option<impl ValueView>,
+    ) -> PartialVMResult<()> {
+        self.charge("VEC_POP_BACK", |base| base.charge_vec_pop_back(ty, val))
+    }
+
+    fn charge_vec_unpack(
+        &mut self,
+        ty: impl TypeView,
+        expect_num_elements: NumArgs,
+        elems: impl ExactSizeIterator<Item = impl ValueView>,
+    ) -> PartialVMResult<()> {
+        self.charge("VEC_UNPACK", |base| {
+            base.charge_vec_unpack(ty, expect_num_elements, elems)
+        })
+    }
+
+    fn charge_vec_swap(&mut self, ty: impl TypeView) -> PartialVMResult<()> {
+        self.charge("VEC_SWAP", |base| base.charge_vec_swap(ty))
+    }
+
+    fn charge_load_resource(
+        &mut self,
+        loaded: Option<(NumBytes, impl ValueView)>,
+    ) -> PartialVMResult<()> {
+        self.charge("LOAD_RESOURCE", |base| base.charge_load_resource(loaded))
+    }
+
+    fn charge_native_function(
+        &mut self,
+        amount: InternalGas,
+        ret_vals: Option<impl ExactSizeIterator<Item = impl ValueView>>,
+    ) -> PartialVMResult<()> {
+        // The frame of a native function never executes any instruction, otherwise the native
+        // function is called without a frame and the gas is recorded to the caller.
+        let is_native_frame = self.frames.len() > 1 && self.current().instructions.is_empty();
+        let before = self.base.balance_internal();
+        let result = self.charge(NATIVE_INSTRUCTION, |base| {
+            base.charge_native_function(amount, ret_vals)
+        });
+        let gas = self.used_gas_since(before);
+        let name = self.current().name.clone();
+        self.natives.entry(name).or_default().add(gas);
+        if is_native_frame {
+            self.pop_frame();
+        }
+        result
+    }
+
+    fn charge_native_function_before_execution(
+        &mut self,
+        ty_args: impl ExactSizeIterator<Item = impl TypeView>,
+        args: impl ExactSizeIterator<Item = impl ValueView>,
+    ) -> PartialVMResult<()> {
+        self.base
+            .charge_native_function_before_execution(ty_args, args)
+    }
+
+    fn charge_drop_frame(
+        &mut self,
+        locals: impl Iterator<Item = impl ValueView>,
+    ) -> PartialVMResult<()> {
+        self.charge("RET", |base| base.charge_drop_frame(locals))
+    }
+}
+
+impl<G: TransactionGasMeter> TransactionGasMeter for GasTracer<G> {
+    fn balance(&self) -> Gas {
+        self.base.balance()
+    }
+
+    fn deduct_gas(&mut self, amount: InternalGas) -> PartialVMResult<()> {
+        self.base.deduct_gas(amount)
+    }
+
+    fn set_metering(&mut self, enabled: bool) {
+        self.base.set_metering(enabled)
+    }
+
+    fn get_metering(&self) -> bool {
+        self.base.get_metering()
+    }
+
+    fn charge_intrinsic_gas_for_transaction(&mut self, txn_size: NumBytes) -> VMResult<()> {
+        let before = self.base.balance_internal();
+        let result = self.base.charge_intrinsic_gas_for_transaction(txn_size);
+        self.intrinsic_gas = self
+            .intrinsic_gas
+            .saturating_add(self.used_gas_since(before));
+        if let Err(err) = &result {
+            if err.major_status() == StatusCode::OUT_OF_GAS {
+                self.record_out_of_gas();
+            }
+        }
+        result
+    }
+
+    fn cal_write_set_gas(&self) -> InternalGas {
+        self.base.cal_write_set_gas()
+    }
+
+    fn charge_write_set_gas(&mut self, amount: InternalGas) -> PartialVMResult<()> {
+        let before = self.base.balance_internal();
+        let result = self.base.charge_write_set_gas(amount);
+        self.storage_write_gas = self
+            .storage_write_gas
+            .saturating_add(self.used_gas_since(before));
+        if let Err(err) = &result {
+            if err.major_status() == StatusCode::OUT_OF_GAS {
+                self.record_out_of_gas();
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(
+        name: &str,
+        instructions: &[(&str, u64)],
+        children: Vec<FrameGasTrace>,
+    ) -> FrameGasTrace {
+        let mut frame = FrameGasTrace::new(name.to_string());
+        for (instruction, gas) in instructions {
+            frame.record(instruction, *gas);
+        }
+        for child in children {
+            frame.add_child(child);
+        }
+        frame
+    }
+
+    #[test]
+    fn test_merge_and_fold_frames() {
+        let execution = frame(
+            "0x1::TransferScripts::peer_to_peer_v2",
+            &[("CALL", 10), ("RET", 1)],
+            vec![
+                frame("0x1::Account::deposit", &[("MOVE_LOC", 5)], vec![]),
+                frame(
+                    "0x1::Account::deposit",
+                    &[("MOVE_LOC", 5)],
+                    vec![frame(
+                        "0x1::Hash::sha3_256",
+                        &[(NATIVE_INSTRUCTION, 7)],
+                        vec![],
+                    )],
+                ),
+                frame("0x1::Account::unmetered", &[("RET", 0)], vec![]),
+            ],
+        );
+        let mut trace = TransactionGasTrace {
+            intrinsic_gas: 100,
+            storage_write_gas: 20,
+            execution,
+            natives: BTreeMap::new(),
+            out_of_gas_at: None,
+        };
+        trace.execution.prune();
+
+        assert_eq!(trace.execution.children.len(), 1);
+        let deposit = &trace.execution.children[0];
+        assert_eq!(deposit.calls, 2);
+        assert_eq!(deposit.self_gas, 10);
+        assert_eq!(deposit.instructions["MOVE_LOC"].count, 2);
+        assert_eq!(deposit.total_gas(), 17);
+        assert_eq!(trace.total_gas(), 148);
+        assert_eq!(
+            trace.to_folded_stacks(),
+            "intrinsic 100\n\
+             0x1::TransferScripts::peer_to_peer_v2 11\n\
+             0x1::TransferScripts::peer_to_peer_v2;0x1::Account::deposit 10\n\
+             0x1::TransferScripts::peer_to_peer_v2;0x1::Account::deposit;0x1::Hash::sha3_256 7\n\
+             storage_write 20"
+        );
+    }
+}
@@ -11,6 +11,8 @@
 //!     move-stdlib and starcoin-framework ones.
 //!   - Defines a bi-directional mapping between the (Rust) gas parameter structs and their
 //!     corresponding representation on-chain.
+//!   - Builds the native function table and calibrates the gas parameters against the execution
+//!     time, see the `starcoin-gas-calibration` binary.
 //!
 //! The reason why we need two different representations is that they serve different purposes:
 //!   - The Rust structs are used for quick (static) lookups by the gas meter and native functions
@@ -18,11 +20,18 @@
 //!   - The on-chain gas schedule needs to be extensible and unordered so we can upgrate it easily
 //!     in the future.
 
+pub mod calibration;
 mod gas_meter;
+mod gas_tracer;
+mod natives;
 
-pub use gas_meter::{NativeGasParameters, StarcoinGasMeter, StarcoinGasParameters};
+pub use gas_meter::{
+    NativeGasParameters, StarcoinGasMeter, StarcoinGasParameters, TransactionGasMeter,
+};
+pub use gas_tracer::{FrameGasTrace, GasCounter, GasTracer, TransactionGasTrace};
 pub use move_core_types::gas_algebra::{
     Arg, Byte, GasQuantity, InternalGas, InternalGasPerArg, InternalGasPerByte, InternalGasUnit,
     NumArgs, NumBytes, UnitDiv,
 };
+pub use natives::starcoin_natives;
 pub use starcoin_gas_algebra_ext::InstructionGasParameters;
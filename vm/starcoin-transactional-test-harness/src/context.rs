@@ -1,7 +1,7 @@
 // Copyright (c) The Starcoin Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, format_err, Result};
 use futures::executor::block_on;
 use starcoin_config::{BuiltinNetworkID, ChainNetwork};
 use starcoin_crypto::HashValue;
@@ -10,18 +10,20 @@ use starcoin_rpc_server::module::StateRpcImpl;
 use starcoin_state_api::{ChainStateReader, ChainStateWriter, StateNodeStore};
 use starcoin_statedb::ChainStateDB;
 use starcoin_types::write_set::WriteSet;
+use std::path::Path;
 use std::sync::{Arc, Mutex};
 use std::thread::{self, JoinHandle};
 use tokio::runtime::Runtime;
 
-use jsonrpc_client_transports::RawClient;
+use jsonrpc_client_transports::{RawClient, RpcChannel};
 use jsonrpc_core::{IoHandler, Params, Value};
 use jsonrpc_core_client::transports::local;
 use starcoin_rpc_api::chain::ChainApi;
 use starcoin_rpc_api::state::StateApi;
 use starcoin_state_tree;
 
-use crate::fork_chain::{ForkBlockChain, MockChainApi};
+use crate::db_state::{open_db, DbChainApi};
+use crate::fork_chain::{ForkBlockChain, ForkChainSnapshot, MockChainApi};
 use crate::fork_state::{MockChainStateAsyncService, MockStateNodeStore};
 use crate::remote_state::RemoteRpcAsyncClient;
 
@@ -30,15 +32,15 @@ pub struct MockServer {
 }
 
 impl MockServer {
-    pub fn create_and_start(
-        chain_api: MockChainApi,
+    pub fn create_and_start<C: From<RpcChannel>>(
+        chain_api: impl ChainApi,
         state_api: impl StateApi,
-    ) -> Result<(Self, RawClient)> {
+    ) -> Result<(Self, C)> {
         let mut io = IoHandler::new();
         io.extend_with(ChainApi::to_delegate(chain_api));
         io.extend_with(StateApi::to_delegate(state_api));
 
-        let (client, server) = local::connect::<RawClient, _, _>(io);
+        let (client, server) = local::connect::<C, _, _>(io);
         let server_handle = thread::spawn(move || block_on(server).unwrap());
 
         Ok((
@@ -54,9 +56,13 @@ pub struct ForkContext {
     pub chain: Arc<Mutex<ForkBlockChain>>,
     pub storage: ChainStateDB,
     _server: MockServer,
+    /// The server of the local db forked from, it serves the fork as a remote node.
+    _fork_db_server: Option<MockServer>,
     client: RawClient,
     rt: Arc<Runtime>,
     state_root: Arc<Mutex<HashValue>>,
+    /// The snapshots of the chain and the state root, indexed by snapshot id.
+    snapshots: Vec<(ForkChainSnapshot, HashValue)>,
 }
 
 impl ForkContext {
@@ -82,7 +88,7 @@ impl ForkContext {
         let state_root = state_db.state_root();
         let state_root = Arc::new(Mutex::new(state_root));
         let chain = Arc::new(Mutex::new(ForkBlockChain::new(state_root.clone())?));
-        Self::new_inner(chain, state_db, data_store, rt, state_root)
+        Self::new_inner(chain, state_db, data_store, rt, state_root, None)
     }
 
     pub fn new_fork(rpc: &str, block_number: Option<u64>) -> Result<Self> {
@@ -96,6 +102,38 @@ impl ForkContext {
         let remote_async_client = Arc::new(
             rt.block_on(async { RemoteRpcAsyncClient::from_url(rpc, block_number).await })?,
         );
+        Self::new_fork_inner(remote_async_client, rt, None)
+    }
+
+    /// Fork from the starcoin db in the data dir of a local node, the db is served in process
+    /// through the same rpc apis as a remote node, so the fork behaves the same as `new_fork`.
+    pub fn new_fork_db(data_dir: &Path, block_number: Option<u64>) -> Result<Self> {
+        let rt = Arc::new(
+            tokio::runtime::Builder::new_multi_thread()
+                .thread_name("fork-context-worker")
+                .enable_all()
+                .build()?,
+        );
+
+        let storage = open_db(data_dir)?;
+        let chain_api = DbChainApi::new(storage.clone())?;
+        let head_state_root = Arc::new(Mutex::new(chain_api.head_state_root()));
+        let state_svc = MockChainStateAsyncService::new(storage.clone(), head_state_root);
+        let state_api = StateRpcImpl::new(state_svc, storage);
+        let (db_server, rpc_channel) =
+            MockServer::create_and_start::<RpcChannel>(chain_api, state_api)?;
+
+        let remote_async_client = Arc::new(rt.block_on(async {
+            RemoteRpcAsyncClient::from_channel(rpc_channel, block_number).await
+        })?);
+        Self::new_fork_inner(remote_async_client, rt, Some(db_server))
+    }
+
+    fn new_fork_inner(
+        remote_async_client: Arc<RemoteRpcAsyncClient>,
+        rt: Arc<Runtime>,
+        fork_db_server: Option<MockServer>,
+    ) -> Result<Self> {
         let state_api_client = Arc::new(remote_async_client.get_state_client().clone());
         let root_hash = remote_async_client.get_fork_state_root();
         let data_store = Arc::new(MockStateNodeStore::new(state_api_client, rt.clone()));
@@ -110,7 +148,7 @@ impl ForkContext {
             fork_block_hash,
             state_root.clone(),
         )?));
-        Self::new_inner(chain, state_db, data_store, rt, state_root)
+        Self::new_inner(chain, state_db, data_store, rt, state_root, fork_db_server)
     }
 
     fn new_inner(
@@ -119,6 +157,7 @@ impl ForkContext {
         data_store: Arc<dyn StateNodeStore>,
         rt: Arc<Runtime>,
         state_root: Arc<Mutex<HashValue>>,
+        fork_db_server: Option<MockServer>,
     ) -> Result<Self> {
         let chain_api = MockChainApi::new(chain.clone());
         let state_svc = MockChainStateAsyncService::new(data_store.clone(), state_root.clone());
@@ -129,9 +168,11 @@ impl ForkContext {
             chain,
             storage,
             _server: server,
+            _fork_db_server: fork_db_server,
             client,
             rt,
             state_root,
+            snapshots: vec![],
         })
     }
 
@@ -150,4 +191,26 @@ impl ForkContext {
         self.storage.flush()?;
         Ok(())
     }
+    /// Take a snapshot of the chain and the state, return the snapshot id.
+    pub fn snapshot(&mut self) -> Result<u64> {
+        let chain_snapshot = self.chain.lock().unwrap().snapshot()?;
+        let state_root = *self.state_root.lock().unwrap();
+        self.snapshots.push((chain_snapshot, state_root));
+        Ok((self.snapshots.len() - 1) as u64)
+    }
+
+    /// Revert the chain and the state to the snapshot `id`. The snapshot is kept so it can be
+    /// reverted to again, the snapshots taken after it are dropped.
+    pub fn revert(&mut self, id: u64) -> Result<()> {
+        let (chain_snapshot, state_root) = self
+            .snapshots
+            .get(id as usize)
+            .cloned()
+            .ok_or_else(|| format_err!("cannot find snapshot {}", id))?;
+        self.chain.lock().unwrap().revert(&chain_snapshot);
+        self.storage = self.storage.fork_at(state_root);
+        *self.state_root.lock().unwrap() = state_root;
+        self.snapshots.truncate(id as usize + 1);
+        Ok(())
+    }
 }
@@ -0,0 +1,609 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Serve the chain and the state of a local starcoin db through the rpc apis, so the test can
+//! fork from the db in the same way as from a remote node.
+
+use crate::fork_chain::{try_decode_block_txns, try_decode_txn_payload};
+use anyhow::{bail, format_err, Result};
+use futures::future::ready;
+use starcoin_accumulator::inmemory::InMemoryAccumulator;
+use starcoin_accumulator::{node::AccumulatorStoreType, Accumulator, MerkleAccumulator};
+use starcoin_chain_api::{EventWithProof, TransactionInfoWithProof};
+use starcoin_config::{BuiltinNetworkID, ChainNetworkID, RpcConfig};
+use starcoin_crypto::hash::PlainCryptoHash;
+use starcoin_crypto::HashValue;
+use starcoin_resource_viewer::MoveValueAnnotator;
+use starcoin_rpc_api::chain::{ChainApi, GetBlockOption, GetBlocksOption, GetEventOption};
+use starcoin_rpc_api::types::pubsub::EventFilter;
+use starcoin_rpc_api::types::{
+    BlockHeaderView, BlockInfoView, BlockView, ChainId, ChainInfoView, StrView,
+    TransactionEventResponse, TransactionInfoView, TransactionInfoWithProofView, TransactionView,
+};
+use starcoin_rpc_api::FutureResult;
+use starcoin_rpc_server::module::map_err;
+use starcoin_state_api::ChainStateReader;
+use starcoin_statedb::ChainStateDB;
+use starcoin_storage::block_info::BlockInfoStore;
+use starcoin_storage::cache_storage::CacheStorage;
+use starcoin_storage::db_storage::DBStorage;
+use starcoin_storage::storage::StorageInstance;
+use starcoin_storage::{
+    BlockStore, BlockTransactionInfoStore, ContractEventStore, Storage, StorageVersion, Store,
+    TransactionStore,
+};
+use starcoin_types::block::{Block, BlockHeader, BlockInfo, BlockNumber};
+use starcoin_types::contract_event::ContractEventInfo;
+use starcoin_types::filter::Filter;
+use starcoin_types::startup_info::{ChainInfo, ChainStatus};
+use starcoin_vm_types::access_path::AccessPath;
+use starcoin_vm_types::transaction::RichTransactionInfo;
+use std::convert::{TryFrom, TryInto};
+use std::path::Path;
+use std::sync::Arc;
+
+/// Open the starcoin db in the data dir of a node read-only, such as `~/.starcoin/main`.
+pub fn open_db(data_dir: &Path) -> Result<Arc<Storage>> {
+    let db_path = data_dir.join("starcoindb/db/starcoindb");
+    if !db_path.exists() {
+        bail!("cannot find starcoin db at {}", db_path.display());
+    }
+    let db_storage = DBStorage::open_with_cfs(
+        db_path,
+        StorageVersion::current_version()
+            .get_column_family_names()
+            .to_vec(),
+        true,
+        Default::default(),
+        None,
+    )?;
+    Ok(Arc::new(Storage::new(
+        StorageInstance::new_cache_and_db_instance(CacheStorage::new(None), db_storage),
+    )?))
+}
+
+fn future<T: Send + 'static>(result: Result<T>) -> FutureResult<T> {
+    Box::pin(ready(result.map_err(map_err)))
+}
+
+/// The main chain of a local starcoin db, the head is the head of the db when it is opened.
+#[derive(Clone)]
+pub struct DbChainApi {
+    storage: Arc<Storage>,
+    head: BlockHeader,
+    head_info: BlockInfo,
+    genesis_hash: HashValue,
+}
+
+impl DbChainApi {
+    pub fn new(storage: Arc<Storage>) -> Result<Self> {
+        let startup_info = storage
+            .get_startup_info()?
+            .ok_or_else(|| format_err!("cannot find startup info in the db"))?;
+        let head_hash = *startup_info.get_main();
+        let head = storage
+            .get_block_header_by_hash(head_hash)?
+            .ok_or_else(|| format_err!("cannot find head block {} in the db", head_hash))?;
+        let head_info = storage
+            .get_block_info(head_hash)?
+            .ok_or_else(|| format_err!("cannot find block info of head {} in the db", head_hash))?;
+        let genesis_hash = storage
+            .get_genesis()?
+            .ok_or_else(|| format_err!("cannot find genesis in the db"))?;
+        Ok(Self {
+            storage,
+            head,
+            head_info,
+            genesis_hash,
+        })
+    }
+
+    pub fn head_state_root(&self) -> HashValue {
+        self.head.state_root()
+    }
+
+    fn block_accumulator(&self) -> MerkleAccumulator {
+        MerkleAccumulator::new_with_info(
+            self.head_info.block_accumulator_info.clone(),
+            self.storage
+                .get_accumulator_store(AccumulatorStoreType::Block),
+        )
+    }
+
+    fn txn_accumulator(&self) -> MerkleAccumulator {
+        MerkleAccumulator::new_with_info(
+            self.head_info.txn_accumulator_info.clone(),
+            self.storage
+                .get_accumulator_store(AccumulatorStoreType::Transaction),
+        )
+    }
+
+    /// The hash of the main chain block at `number`, the block accumulator leaves are the main
+    /// chain block hashes.
+    fn main_block_hash(&self, number: BlockNumber) -> Result<Option<HashValue>> {
+        if number > self.head.number() {
+            return Ok(None);
+        }
+        self.block_accumulator().get_leaf(number)
+    }
+
+    fn is_main_block(&self, number: BlockNumber, hash: HashValue) -> Result<bool> {
+        Ok(self.main_block_hash(number)? == Some(hash))
+    }
+
+    fn block_view(&self, block: Block, option: Option<GetBlockOption>) -> Result<BlockView> {
+        let option = option.unwrap_or_default();
+        let mut block_view = BlockView::try_from_block(block, false, option.raw)?;
+        if option.decode {
+            let state = ChainStateDB::new(self.storage.clone(), Some(self.head.state_root()));
+            try_decode_block_txns(&state, &mut block_view)?;
+        }
+        Ok(block_view)
+    }
+
+    /// The info of the transaction in the main chain, a transaction may be included by the
+    /// blocks of different branches.
+    fn main_txn_info(&self, txn_hash: HashValue) -> Result<Option<RichTransactionInfo>> {
+        for txn_info in self.storage.get_transaction_info_by_txn_hash(txn_hash)? {
+            if self.is_main_block(txn_info.block_number, txn_info.block_id)? {
+                return Ok(Some(txn_info));
+            }
+        }
+        Ok(None)
+    }
+
+    /// The main chain blocks from `number` backwards, or forwards if `reverse` is false.
+    fn main_blocks(
+        &self,
+        number: Option<BlockNumber>,
+        reverse: bool,
+        count: u64,
+    ) -> Result<Vec<Block>> {
+        let end = number.unwrap_or_else(|| self.head.number());
+        if end > self.head.number() {
+            bail!("Can not find block by number {}", end);
+        }
+        let count = if reverse {
+            count.min(end + 1)
+        } else {
+            count.min(self.head.number() - end + 1)
+        };
+        let ids = self.block_accumulator().get_leaves(end, reverse, count)?;
+        self.storage
+            .get_blocks(ids.clone())?
+            .into_iter()
+            .zip(ids)
+            .map(|(block, id)| block.ok_or_else(|| format_err!("cannot find block {}", id)))
+            .collect()
+    }
+
+    /// The events of the main chain matching the `filter`, in the same order as the chain service.
+    fn main_events(&self, filter: Filter) -> Result<Vec<ContractEventInfo>> {
+        let to_block = self.head.number().min(filter.to_block);
+        if filter.from_block > to_block {
+            return Ok(vec![]);
+        }
+        let mut numbers: Vec<_> = (filter.from_block..=to_block).collect();
+        if filter.reverse {
+            numbers.reverse();
+        }
+        let mut event_infos = vec![];
+        for number in numbers {
+            let block_id = self
+                .main_block_hash(number)?
+                .ok_or_else(|| format_err!("cannot find block {} on main chain", number))?;
+            let mut txn_info_ids = self.storage.get_block_txn_info_ids(block_id)?;
+            if filter.reverse {
+                txn_info_ids.reverse();
+            }
+            for txn_info_id in txn_info_ids {
+                let txn_info = self
+                    .storage
+                    .get_transaction_info(txn_info_id)?
+                    .ok_or_else(|| format_err!("cannot find txn info {}", txn_info_id))?;
+                let mut events: Vec<_> = self
+                    .event_infos(&txn_info)?
+                    .into_iter()
+                    .filter(|info| filter.matching(number, &info.event))
+                    .collect();
+                if filter.reverse {
+                    events.reverse();
+                }
+                event_infos.extend(events);
+                if let Some(limit) = filter.limit {
+                    if event_infos.len() >= limit {
+                        event_infos.truncate(limit);
+                        return Ok(event_infos);
+                    }
+                }
+            }
+        }
+        Ok(event_infos)
+    }
+
+    fn event_infos(&self, txn_info: &RichTransactionInfo) -> Result<Vec<ContractEventInfo>> {
+        Ok(self
+            .storage
+            .get_contract_events(txn_info.id())?
+            .unwrap_or_default()
+            .into_iter()
+            .enumerate()
+            .map(|(idx, event)| ContractEventInfo {
+                block_hash: txn_info.block_id,
+                block_number: txn_info.block_number,
+                transaction_hash: txn_info.transaction_hash(),
+                transaction_index: txn_info.transaction_index,
+                transaction_global_index: txn_info.transaction_global_index,
+                event_index: idx as u32,
+                event,
+            })
+            .collect())
+    }
+
+    fn event_responses(
+        &self,
+        events: Vec<ContractEventInfo>,
+        option: Option<GetEventOption>,
+    ) -> Result<Vec<TransactionEventResponse>> {
+        let decode = option.unwrap_or_default().decode;
+        let state = ChainStateDB::new(self.storage.clone(), Some(self.head.state_root()));
+        let annotator = MoveValueAnnotator::new(&state);
+        events
+            .into_iter()
+            .map(|event| {
+                let decode_event_data = if decode {
+                    Some(
+                        annotator
+                            .view_value(event.event.type_tag(), event.event.event_data())?
+                            .into(),
+                    )
+                } else {
+                    None
+                };
+                Ok(TransactionEventResponse {
+                    event: event.into(),
+                    decode_event_data,
+                })
+            })
+            .collect()
+    }
+
+    fn transaction_proof(
+        &self,
+        block_hash: HashValue,
+        transaction_global_index: u64,
+        event_index: Option<u64>,
+        access_path: Option<AccessPath>,
+    ) -> Result<Option<TransactionInfoWithProof>> {
+        let block_info = match self.storage.get_block_info(block_hash)? {
+            Some(block_info) => block_info,
+            None => return Ok(None),
+        };
+        let accumulator = MerkleAccumulator::new_with_info(
+            block_info.txn_accumulator_info,
+            self.storage
+                .get_accumulator_store(AccumulatorStoreType::Transaction),
+        );
+        let proof = match accumulator.get_proof(transaction_global_index)? {
+            Some(proof) => proof,
+            None => return Ok(None),
+        };
+        let txn_info_hash = accumulator
+            .get_leaf(transaction_global_index)?
+            .ok_or_else(|| {
+                format_err!(
+                    "Can not find txn info hash by index {}",
+                    transaction_global_index
+                )
+            })?;
+        let transaction_info = self
+            .storage
+            .get_transaction_info(txn_info_hash)?
+            .ok_or_else(|| format_err!("Can not find txn info by hash:{}", txn_info_hash))?;
+        let event_proof = match event_index {
+            Some(event_index) => {
+                let events = self
+                    .storage
+                    .get_contract_events(txn_info_hash)?
+                    .unwrap_or_default();
+                let event = events.get(event_index as usize).cloned().ok_or_else(|| {
+                    format_err!("event index out of range, events len:{}", events.len())
+                })?;
+                let event_hashes: Vec<_> = events.iter().map(|e| e.crypto_hash()).collect();
+                Some(EventWithProof {
+                    event,
+                    proof: InMemoryAccumulator::get_proof_from_leaves(
+                        event_hashes.as_slice(),
+                        event_index,
+                    )?,
+                })
+            }
+            None => None,
+        };
+        let state_proof = match access_path {
+            Some(access_path) => Some(
+                ChainStateDB::new(
+                    self.storage.clone(),
+                    Some(transaction_info.txn_info().state_root_hash()),
+                )
+                .get_with_proof(&access_path)?,
+            ),
+            None => None,
+        };
+        Ok(Some(TransactionInfoWithProof {
+            transaction_info,
+            proof,
+            event_proof,
+            state_proof,
+        }))
+    }
+}
+
+impl ChainApi for DbChainApi {
+    fn id(&self) -> jsonrpc_core::Result<ChainId> {
+        let chain_id = self.head.chain_id();
+        Ok(match BuiltinNetworkID::try_from(chain_id) {
+            Ok(net) => ChainId::from(&ChainNetworkID::Builtin(net)),
+            Err(_) => ChainId {
+                name: chain_id.to_string(),
+                id: chain_id.id(),
+            },
+        })
+    }
+
+    fn info(&self) -> FutureResult<ChainInfoView> {
+        future(Ok(ChainInfoView::from(ChainInfo::new(
+            self.head.chain_id(),
+            self.genesis_hash,
+            ChainStatus::new(self.head.clone(), self.head_info.clone()),
+        ))))
+    }
+
+    fn get_block_by_hash(
+        &self,
+        hash: HashValue,
+        option: Option<GetBlockOption>,
+    ) -> FutureResult<Option<BlockView>> {
+        future(
+            self.storage
+                .get_block_by_hash(hash)
+                .and_then(|block| block.map(|b| self.block_view(b, option)).transpose()),
+        )
+    }
+
+    fn get_block_by_number(
+        &self,
+        number: BlockNumber,
+        option: Option<GetBlockOption>,
+    ) -> FutureResult<Option<BlockView>> {
+        future((|| match self.main_block_hash(number)? {
+            Some(hash) => self
+                .storage
+                .get_block_by_hash(hash)?
+                .map(|b| self.block_view(b, option))
+                .transpose(),
+            None => Ok(None),
+        })())
+    }
+
+    fn get_blocks_by_number(
+        &self,
+        number: Option<BlockNumber>,
+        count: u64,
+        option: Option<GetBlocksOption>,
+    ) -> FutureResult<Vec<BlockView>> {
+        future((|| {
+            let count = count.min(RpcConfig::default().block_query_max_range());
+            self.main_blocks(number, !option.unwrap_or_default().reverse, count)?
+                .into_iter()
+                .map(|block| BlockView::try_from_block(block, true, false))
+                .collect()
+        })())
+    }
+
+    fn get_block_info_by_number(&self, number: BlockNumber) -> FutureResult<Option<BlockInfoView>> {
+        future((|| match self.main_block_hash(number)? {
+            Some(hash) => Ok(self.storage.get_block_info(hash)?.map(BlockInfoView::from)),
+            None => Ok(None),
+        })())
+    }
+
+    fn get_transaction(
+        &self,
+        transaction_hash: HashValue,
+        option: Option<starcoin_rpc_api::chain::GetTransactionOption>,
+    ) -> FutureResult<Option<TransactionView>> {
+        future((|| {
+            let (txn, txn_info) = match (
+                self.storage.get_transaction(transaction_hash)?,
+                self.main_txn_info(transaction_hash)?,
+            ) {
+                (Some(txn), Some(txn_info)) => (txn, txn_info),
+                _ => return Ok(None),
+            };
+            let block = self
+                .storage
+                .get_block_by_hash(txn_info.block_id)?
+                .ok_or_else(|| {
+                    format_err!(
+                        "cannot find block which includes the txn {}",
+                        transaction_hash
+                    )
+                })?;
+            let mut txn = TransactionView::new(txn, &block)?;
+            if option.unwrap_or_default().decode {
+                let state = ChainStateDB::new(self.storage.clone(), Some(self.head.state_root()));
+                if let Some(txn) = txn.user_transaction.as_mut() {
+                    try_decode_txn_payload(&state, txn)?;
+                }
+            }
+            Ok(Some(txn))
+        })())
+    }
+
+    fn get_transaction_info(
+        &self,
+        transaction_hash: HashValue,
+    ) -> FutureResult<Option<TransactionInfoView>> {
+        future(
+            self.main_txn_info(transaction_hash)
+                .map(|txn_info| txn_info.map(TransactionInfoView::from)),
+        )
+    }
+
+    fn get_block_txn_infos(&self, block_hash: HashValue) -> FutureResult<Vec<TransactionInfoView>> {
+        future(
+            self.storage
+                .get_block_transaction_infos(block_hash)
+                .map(|txn_infos| txn_infos.into_iter().map(Into::into).collect()),
+        )
+    }
+
+    fn get_txn_info_by_block_and_index(
+        &self,
+        block_hash: HashValue,
+        idx: u64,
+    ) -> FutureResult<Option<TransactionInfoView>> {
+        future(
+            self.storage
+                .get_transaction_info_by_block_and_index(block_hash, idx)
+                .map(|txn_info| txn_info.map(Into::into)),
+        )
+    }
+
+    fn get_events_by_txn_hash(
+        &self,
+        txn_hash: HashValue,
+        option: Option<GetEventOption>,
+    ) -> FutureResult<Vec<TransactionEventResponse>> {
+        future((|| {
+            let txn_info = self
+                .main_txn_info(txn_hash)?
+                .ok_or_else(|| format_err!("cannot find txn info of txn {}", txn_hash))?;
+            self.event_responses(self.event_infos(&txn_info)?, option)
+        })())
+    }
+
+    fn get_events(
+        &self,
+        mut filter: EventFilter,
+        option: Option<GetEventOption>,
+    ) -> FutureResult<Vec<TransactionEventResponse>> {
+        future((|| {
+            if filter.to_block.is_none() {
+                filter.to_block = Some(self.head.number());
+            }
+            let filter: Filter = filter.try_into()?;
+            let max_block_range = RpcConfig::default().block_query_max_range();
+            if filter
+                .to_block
+                .checked_sub(filter.from_block)
+                .filter(|r| *r > max_block_range)
+                .is_some()
+            {
+                bail!(
+                    "from_block is too far, max block range is {} ",
+                    max_block_range
+                );
+            }
+            self.event_responses(self.main_events(filter)?, option)
+        })())
+    }
+
+    fn get_headers(&self, ids: Vec<HashValue>) -> FutureResult<Vec<BlockHeaderView>> {
+        future((|| {
+            let mut headers = vec![];
+            for id in ids {
+                if let Some(header) = self.storage.get_block_header_by_hash(id)? {
+                    headers.push(header.into());
+                }
+            }
+            Ok(headers)
+        })())
+    }
+
+    fn get_transaction_infos(
+        &self,
+        start_global_index: u64,
+        reverse: bool,
+        max_size: u64,
+    ) -> FutureResult<Vec<TransactionInfoView>> {
+        future((|| {
+            let max_size = max_size.min(RpcConfig::default().txn_info_query_max_range());
+            let ids = self
+                .txn_accumulator()
+                .get_leaves(start_global_index, reverse, max_size)?;
+            self.storage
+                .get_transaction_infos(ids.clone())?
+                .into_iter()
+                .zip(ids)
+                .map(|(txn_info, id)| {
+                    txn_info
+                        .map(Into::into)
+                        .ok_or_else(|| format_err!("cannot find txn info {}", id))
+                })
+                .collect()
+        })())
+    }
+
+    fn get_transaction_proof(
+        &self,
+        block_hash: HashValue,
+        transaction_global_index: u64,
+        event_index: Option<u64>,
+        access_path: Option<StrView<AccessPath>>,
+    ) -> FutureResult<Option<TransactionInfoWithProofView>> {
+        future(
+            self.transaction_proof(
+                block_hash,
+                transaction_global_index,
+                event_index,
+                access_path.map(Into::into),
+            )
+            .map(|proof| proof.map(Into::into)),
+        )
+    }
+
+    fn get_transaction_proof_raw(
+        &self,
+        block_hash: HashValue,
+        transaction_global_index: u64,
+        event_index: Option<u64>,
+        access_path: Option<StrView<AccessPath>>,
+    ) -> FutureResult<Option<StrView<Vec<u8>>>> {
+        future((|| {
+            self.transaction_proof(
+                block_hash,
+                transaction_global_index,
+                event_index,
+                access_path.map(Into::into),
+            )?
+            .map(|proof| Ok(StrView(bcs_ext::to_bytes(&proof)?)))
+            .transpose()
+        })())
+    }
+
+    fn uncle_stats(
+        &self,
+        _count: Option<u64>,
+    ) -> FutureResult<Vec<starcoin_rpc_api::types::EpochUncleStatsView>> {
+        future(Err(format_err!("not implemented.")))
+    }
+
+    fn get_reorgs(
+        &self,
+        _from: Option<u64>,
+        _count: u64,
+    ) -> FutureResult<Vec<starcoin_rpc_api::types::ReorgRecordView>> {
+        future(Err(format_err!("not implemented.")))
+    }
+
+    fn wait_for_confirmations(
+        &self,
+        _txn_hash: HashValue,
+        _confirmations: u64,
+        _timeout: Option<u64>,
+    ) -> FutureResult<starcoin_rpc_api::types::WaitConfirmationsView> {
+        future(Err(format_err!("not implemented.")))
+    }
+}
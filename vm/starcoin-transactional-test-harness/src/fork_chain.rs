@@ -8,6 +8,7 @@ use futures::executor::block_on;
 use jsonrpc_core::futures_util::{FutureExt, TryFutureExt};
 use log::debug;
 use starcoin_abi_decoder::decode_txn_payload;
+use starcoin_accumulator::accumulator_info::AccumulatorInfo;
 use starcoin_accumulator::{node::AccumulatorStoreType, Accumulator, MerkleAccumulator};
 use starcoin_config::{BuiltinNetworkID, ChainNetworkID};
 use starcoin_crypto::HashValue;
@@ -40,6 +41,15 @@ pub struct ChainStatusWithBlock {
     pub head: Block,
 }
 
+/// The head of a `ForkBlockChain` which the chain can be reverted to.
+#[derive(Clone)]
+pub struct ForkChainSnapshot {
+    current_number: u64,
+    status: Option<ChainStatusWithBlock>,
+    txn_accumulator_info: AccumulatorInfo,
+    head_block_hash: HashValue,
+}
+
 // #[derive(Clone)]
 pub struct ForkBlockChain {
     remote_client: Option<Arc<RemoteRpcAsyncClient>>,
@@ -156,6 +166,30 @@ impl ForkBlockChain {
         Ok(())
     }
 
+    pub fn snapshot(&self) -> Result<ForkChainSnapshot> {
+        // the accumulator nodes must be in the store to fork the accumulator when reverting.
+        self.txn_accumulator.flush()?;
+        Ok(ForkChainSnapshot {
+            current_number: self.current_number,
+            status: self.status.clone(),
+            txn_accumulator_info: self.txn_accumulator.get_info(),
+            head_block_hash: self.head_block_hash,
+        })
+    }
+
+    /// Revert the head to the `snapshot`, the blocks after the snapshot are no longer in the
+    /// chain, but the blocks and txns are kept in storage and can still be got by hash.
+    pub fn revert(&mut self, snapshot: &ForkChainSnapshot) {
+        self.number_hash_map
+            .retain(|number, _| *number <= snapshot.current_number);
+        self.current_number = snapshot.current_number;
+        self.status = snapshot.status.clone();
+        self.txn_accumulator = self
+            .txn_accumulator
+            .fork(Some(snapshot.txn_accumulator_info.clone()));
+        self.head_block_hash = snapshot.head_block_hash;
+    }
+
     pub fn txn_accumulator_root(&self) -> HashValue {
         self.txn_accumulator.root_hash()
     }
@@ -498,9 +532,45 @@ impl ChainApi for MockChainApi {
         };
         Box::pin(fut.boxed().map_err(map_err))
     }
+
+    fn uncle_stats(
+        &self,
+        _count: Option<u64>,
+    ) -> starcoin_rpc_api::FutureResult<Vec<starcoin_rpc_api::types::EpochUncleStatsView>> {
+        let fut = async move {
+            bail!("not implemented.");
+        };
+        Box::pin(fut.boxed().map_err(map_err))
+    }
+
+    fn get_reorgs(
+        &self,
+        _from: Option<u64>,
+        _count: u64,
+    ) -> starcoin_rpc_api::FutureResult<Vec<starcoin_rpc_api::types::ReorgRecordView>> {
+        let fut = async move {
+            bail!("not implemented.");
+        };
+        Box::pin(fut.boxed().map_err(map_err))
+    }
+
+    fn wait_for_confirmations(
+        &self,
+        _txn_hash: HashValue,
+        _confirmations: u64,
+        _timeout: Option<u64>,
+    ) -> starcoin_rpc_api::FutureResult<starcoin_rpc_api::types::WaitConfirmationsView> {
+        let fut = async move {
+            bail!("not implemented.");
+        };
+        Box::pin(fut.boxed().map_err(map_err))
+    }
 }
 
-fn try_decode_block_txns(state: &dyn StateView, block: &mut BlockView) -> anyhow::Result<()> {
+pub(crate) fn try_decode_block_txns(
+    state: &dyn StateView,
+    block: &mut BlockView,
+) -> anyhow::Result<()> {
     if let BlockTransactionsView::Full(txns) = &mut block.body {
         for txn in txns.iter_mut() {
             try_decode_txn_payload(state, txn)?;
@@ -509,7 +579,7 @@ fn try_decode_block_txns(state: &dyn StateView, block: &mut BlockView) -> anyhow
     Ok(())
 }
 
-fn try_decode_txn_payload(
+pub(crate) fn try_decode_txn_payload(
     state: &dyn StateView,
     txn: &mut SignedUserTransactionView,
 ) -> anyhow::Result<()> {
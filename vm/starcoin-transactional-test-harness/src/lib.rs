@@ -3,7 +3,7 @@
 
 use crate::context::ForkContext;
 use anyhow::{bail, format_err, Result};
-use clap::{Args, CommandFactory, Parser};
+use clap::{ArgGroup, Args, CommandFactory, Parser};
 use move_binary_format::{file_format::CompiledScript, CompiledModule};
 use move_command_line_common::address::ParsedAddress;
 use move_command_line_common::files::verify_and_create_named_address_mapping;
@@ -81,6 +81,7 @@ use stdlib::{starcoin_framework_named_addresses, stdlib_files};
 use tempfile::{NamedTempFile, TempDir};
 
 pub mod context;
+pub mod db_state;
 pub mod fork_chain;
 pub mod fork_state;
 pub mod remote_state;
@@ -88,15 +89,20 @@ pub mod remote_state;
 pub static G_FLAG_RELOAD_STDLIB: Mutex<bool> = Mutex::new(false);
 
 #[derive(Parser, Debug, Default)]
+#[clap(group(ArgGroup::new("fork").args(&["rpc", "fork-db"])))]
 pub struct ExtraInitArgs {
     #[clap(name = "rpc", long)]
     /// use remote starcoin rpc as initial state.
     rpc: Option<String>,
-    #[clap(long = "block-number", requires("rpc"))]
+    #[clap(name = "fork-db", long = "fork-db", parse(from_os_str))]
+    /// use the starcoin db in a local node data dir as initial state, such as `~/.starcoin/main`.
+    /// the db is opened read-only.
+    fork_db: Option<PathBuf>,
+    #[clap(long = "block-number", requires("fork"))]
     /// block number to read state from. default to latest block number.
     block_number: Option<u64>,
 
-    #[clap(long = "network", short, conflicts_with("rpc"))]
+    #[clap(long = "network", short, conflicts_with("fork"))]
     /// genesis with the network
     network: Option<BuiltinNetworkID>,
 
@@ -255,6 +261,30 @@ pub enum StarcoinSubcommands {
         #[clap(long)]
         uncles: Option<u64>,
     },
+    #[clap(name = "snapshot")]
+    /// take a snapshot of the chain and the state, output the snapshot id.
+    Snapshot,
+    #[clap(name = "revert")]
+    Revert {
+        #[clap(name = "id")]
+        /// the snapshot id to revert the chain and the state to.
+        id: u64,
+    },
+    #[clap(name = "advance-time")]
+    AdvanceTime {
+        #[clap(name = "seconds")]
+        /// seconds to advance the chain time by, with a new block.
+        seconds: u64,
+    },
+    #[clap(name = "advance-blocks")]
+    AdvanceBlocks {
+        #[clap(name = "count")]
+        /// how many blocks to generate.
+        count: u64,
+        #[clap(long = "interval", default_value = "10000")]
+        /// milliseconds between the blocks.
+        interval: u64,
+    },
     #[clap(name = "call")]
     ContractCall {
         #[clap(name = "FUNCTION")]
@@ -874,6 +904,55 @@ impl<'a> StarcoinTestAdapter<'a> {
         Ok((None, Some(serde_json::to_value(&new_block_meta)?)))
     }
 
+    fn handle_snapshot(&mut self) -> Result<(Option<String>, Option<Value>)> {
+        let id = self.context.snapshot()?;
+        Ok((
+            Some(format!("snapshot {}", id)),
+            Some(serde_json::json!({ "id": id })),
+        ))
+    }
+
+    fn handle_revert(&mut self, id: u64) -> Result<(Option<String>, Option<Value>)> {
+        self.context.revert(id)?;
+        Ok((None, None))
+    }
+
+    fn handle_advance_time(&mut self, seconds: u64) -> Result<(Option<String>, Option<Value>)> {
+        let millis = seconds
+            .checked_mul(1000)
+            .ok_or_else(|| format_err!("advance-time {} seconds overflows", seconds))?;
+        let timestamp = self
+            .context
+            .storage
+            .get_timestamp()?
+            .milliseconds
+            .checked_add(millis)
+            .ok_or_else(|| format_err!("advance-time {} seconds overflows", seconds))?;
+        self.handle_new_block(None, Some(timestamp), None, None)
+    }
+
+    fn handle_advance_blocks(
+        &mut self,
+        count: u64,
+        interval: u64,
+    ) -> Result<(Option<String>, Option<Value>)> {
+        if count == 0 {
+            bail!("advance-blocks needs at least one block");
+        }
+        let mut output = (None, None);
+        for _ in 0..count {
+            let timestamp = self
+                .context
+                .storage
+                .get_timestamp()?
+                .milliseconds
+                .checked_add(interval)
+                .ok_or_else(|| format_err!("advance-blocks interval {} overflows", interval))?;
+            output = self.handle_new_block(None, Some(timestamp), None, None)?;
+        }
+        Ok(output)
+    }
+
     fn handle_call_api(
         &mut self,
         method: String,
@@ -1091,6 +1170,11 @@ impl<'a> MoveTestAdapter<'a> for StarcoinTestAdapter<'a> {
                 ForkContext::new_fork(&rpc, init_args.block_number).unwrap(),
                 true,
             )
+        } else if let Some(fork_db) = init_args.fork_db {
+            (
+                ForkContext::new_fork_db(&fork_db, init_args.block_number).unwrap(),
+                true,
+            )
         } else {
             let stdlib_modules = if *G_FLAG_RELOAD_STDLIB.lock().unwrap() {
                 assert!(
@@ -1336,6 +1420,12 @@ impl<'a> MoveTestAdapter<'a> for StarcoinTestAdapter<'a> {
                 number,
                 uncles,
             } => self.handle_new_block(author, timestamp, number, uncles),
+            StarcoinSubcommands::Snapshot => self.handle_snapshot(),
+            StarcoinSubcommands::Revert { id } => self.handle_revert(id),
+            StarcoinSubcommands::AdvanceTime { seconds } => self.handle_advance_time(seconds),
+            StarcoinSubcommands::AdvanceBlocks { count, interval } => {
+                self.handle_advance_blocks(count, interval)
+            }
             StarcoinSubcommands::ContractCall {
                 name,
                 args,
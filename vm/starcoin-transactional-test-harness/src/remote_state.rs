@@ -201,6 +201,11 @@ impl RemoteRpcAsyncClient {
         let rpc_channel: RpcChannel = jsonrpc_client_transports::transports::http::connect(rpc_url)
             .await
             .map_err(|e| anyhow!(format!("{}", e)))?;
+        Self::from_channel(rpc_channel, block_number).await
+    }
+
+    /// Fork from the node served by the `rpc_channel`, such as a local db served in process.
+    pub async fn from_channel(rpc_channel: RpcChannel, block_number: Option<u64>) -> Result<Self> {
         let chain_client: starcoin_rpc_api::chain::ChainApiClient = rpc_channel.clone().into();
         let (state_root, fork_number, fork_block_hash) = match block_number {
             None => {
@@ -0,0 +1,23 @@
+//# init -n dev
+
+//# block --author=0x2
+
+//# snapshot
+
+//# advance-blocks 3
+
+//# call 0x1::Block::get_current_block_number
+
+//# call 0x1::Timestamp::now_seconds
+
+//# revert {{$.snapshot[0].id}}
+
+//# call 0x1::Block::get_current_block_number
+
+//# call 0x1::Timestamp::now_seconds
+
+//# advance-time 86400
+
+//# call 0x1::Block::get_current_block_number
+
+//# call 0x1::Timestamp::now_seconds
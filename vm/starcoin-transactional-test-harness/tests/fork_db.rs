@@ -0,0 +1,155 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{format_err, Result};
+use futures::executor::block_on;
+use starcoin_chain::{BlockChain, ChainReader};
+use starcoin_chain_api::TransactionInfoWithProof;
+use starcoin_chain_mock::MockChain;
+use starcoin_config::{BuiltinNetworkID, ChainNetwork, RocksdbConfig};
+use starcoin_crypto::HashValue;
+use starcoin_genesis::Genesis;
+use starcoin_rpc_api::chain::ChainApi;
+use starcoin_rpc_api::types::pubsub::EventFilter;
+use starcoin_storage::block_info::BlockInfoStore;
+use starcoin_storage::cache_storage::CacheStorage;
+use starcoin_storage::db_storage::DBStorage;
+use starcoin_storage::storage::StorageInstance;
+use starcoin_storage::{BlockStore, Storage};
+use starcoin_transactional_test_harness::db_state::{open_db, DbChainApi};
+use starcoin_transactional_test_harness::run_test;
+use starcoin_types::startup_info::StartupInfo;
+use std::convert::TryFrom;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Generate a db of the test network with `blocks` blocks in the main chain, in the layout of the
+/// data dir of a node, and return the hash of the head block.
+fn generate_db(data_dir: &Path, blocks: u64) -> Result<HashValue> {
+    let net = ChainNetwork::new_builtin(BuiltinNetworkID::Test);
+    let db_path = data_dir.join("starcoindb/db/starcoindb");
+    std::fs::create_dir_all(&db_path)?;
+    let storage = Arc::new(Storage::new(StorageInstance::new_cache_and_db_instance(
+        CacheStorage::new(None),
+        DBStorage::new(db_path, RocksdbConfig::default(), None)?,
+    ))?);
+    let (chain_info, _) = Genesis::init_and_check_storage(&net, storage.clone(), data_dir)?;
+    let chain = BlockChain::new(
+        net.time_service(),
+        chain_info.head().id(),
+        storage.clone(),
+        None,
+    )?;
+    let mut mock_chain = MockChain::new_with_chain(net, chain)?;
+    mock_chain.produce_and_apply_times(blocks)?;
+    let head = mock_chain.head().current_header().id();
+    storage.save_startup_info(StartupInfo::new(head))?;
+    Ok(head)
+}
+
+#[test]
+fn test_fork_db() -> Result<()> {
+    let data_dir = tempfile::tempdir()?;
+    generate_db(data_dir.path(), 3)?;
+
+    let case_dir = tempfile::tempdir()?;
+    let case = case_dir.path().join("fork_db.move");
+    std::fs::write(
+        &case,
+        format!(
+            r#"//# init --fork-db {}
+
+//# call 0x1::Block::get_current_block_number
+
+//# call-api chain.get_block_by_number [2]
+
+//# block
+
+//# call 0x1::Block::get_current_block_number
+"#,
+            data_dir.path().display()
+        ),
+    )?;
+    std::fs::write(
+        case.with_extension("exp"),
+        r#"processed 5 tasks
+
+task 1 'call'. lines 3-3:
+3
+
+task 4 'call'. lines 9-9:
+4
+"#,
+    )?;
+    run_test(&case).map_err(|e| format_err!("{}", e))
+}
+
+#[test]
+fn test_db_chain_api() -> Result<()> {
+    let data_dir = tempfile::tempdir()?;
+    let head = generate_db(data_dir.path(), 3)?;
+    let storage = open_db(data_dir.path())?;
+    let head_info = storage
+        .get_block_info(head)?
+        .ok_or_else(|| format_err!("cannot find block info of head {}", head))?;
+    let api = DbChainApi::new(storage)?;
+
+    let blocks = block_on(api.get_blocks_by_number(None, 10, None))?;
+    assert_eq!(
+        blocks
+            .iter()
+            .map(|block| block.header.number.0)
+            .collect::<Vec<_>>(),
+        vec![3, 2, 1, 0]
+    );
+    let headers = block_on(api.get_headers(vec![head, HashValue::random()]))?;
+    assert_eq!(headers.len(), 1);
+    assert_eq!(headers[0].block_hash, head);
+
+    // the block metadata txn of the head block.
+    let txn_infos = block_on(api.get_block_txn_infos(head))?;
+    assert_eq!(txn_infos.len(), 1);
+    let txn_info = txn_infos[0].clone();
+    assert_eq!(txn_info.block_hash, head);
+    assert_eq!(
+        block_on(api.get_txn_info_by_block_and_index(head, 0))?,
+        Some(txn_info.clone())
+    );
+    assert_eq!(
+        block_on(api.get_txn_info_by_block_and_index(head, 1))?,
+        None
+    );
+    let global_index = txn_info.transaction_global_index.0;
+    assert_eq!(
+        block_on(api.get_transaction_infos(global_index, true, 1))?,
+        vec![txn_info.clone()]
+    );
+
+    let events = block_on(api.get_events_by_txn_hash(txn_info.transaction_hash, None))?;
+    assert!(!events.is_empty());
+    assert!(events
+        .iter()
+        .all(|event| event.event.block_hash == Some(head)));
+    let filter = EventFilter {
+        from_block: Some(3),
+        to_block: None,
+        event_keys: None,
+        addrs: None,
+        type_tags: None,
+        limit: None,
+    };
+    // the rpc event filter always queries in reverse order.
+    let mut reversed = events.clone();
+    reversed.reverse();
+    assert_eq!(block_on(api.get_events(filter, None))?, reversed);
+
+    let proof = block_on(api.get_transaction_proof(head, global_index, Some(0), None))?
+        .ok_or_else(|| format_err!("cannot find proof of txn {}", global_index))?;
+    TransactionInfoWithProof::try_from(proof)?.verify(
+        head_info.txn_accumulator_info.accumulator_root,
+        global_index,
+        Some(0),
+        None,
+    )?;
+    Ok(())
+}
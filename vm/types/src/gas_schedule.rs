@@ -49,11 +49,20 @@ pub enum NativeCostIndex {
     STRING_SUB_STR = 41,
     SRING_CHAR_BOUNDARY = 42,
     STRING_INDEX_OF = 43,
+    BLS12381_VERIFY = 44,
+    BLS12381_AGGREGATE_VERIFY = 45,
+    BLS12381_AGGREGATE_VERIFY_PER_PUBKEY = 46,
+    BLS12381_VERIFY_POP = 47,
+    SECP256R1_VERIFY = 48,
+    SECP256R1_VERIFY_PER_BYTE = 49,
+    BLS12381_VERIFY_PER_BYTE = 50,
+    BLS12381_AGGREGATE_VERIFY_PER_BYTE = 51,
+    BLS12381_VERIFY_POP_PER_BYTE = 52,
 }
 
 impl NativeCostIndex {
     //note: should change this value when add new native function.
-    pub const NUMBER_OF_NATIVE_FUNCTIONS: usize = 44;
+    pub const NUMBER_OF_NATIVE_FUNCTIONS: usize = 53;
 }
 
 pub static G_MAX_TRANSACTION_SIZE_IN_BYTES_V1: u64 = 4096 * 10;
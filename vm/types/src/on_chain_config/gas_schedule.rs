@@ -366,6 +366,57 @@ pub fn native_gas_schedule_v4() -> Vec<(String, u64)> {
     natives
 }
 
+// v5_native_table
+pub fn native_gas_schedule_v5() -> Vec<(String, u64)> {
+    let mut natives = native_gas_schedule_v4();
+    let mut natives_delta = native_gas_schedule_v5_delta();
+    natives.append(&mut natives_delta);
+    natives
+}
+
+// the natives added by v5_native_table, the older on-chain native_table don't have these.
+pub fn native_gas_schedule_v5_delta() -> Vec<(String, u64)> {
+    let gas_total = |x: u64, y: u64| -> u64 { x + y };
+    vec![
+        (
+            "starcoin_natives.signature.bls12381_verify.base".to_string(),
+            gas_total(48000, 1),
+        ),
+        (
+            "starcoin_natives.signature.bls12381_aggregate_verify.base".to_string(),
+            gas_total(48000, 1),
+        ),
+        (
+            "starcoin_natives.signature.bls12381_aggregate_verify.per_pubkey".to_string(),
+            gas_total(1600, 1),
+        ),
+        (
+            "starcoin_natives.signature.bls12381_verify_pop.base".to_string(),
+            gas_total(48000, 1),
+        ),
+        (
+            "starcoin_natives.signature.secp256r1_verify.base".to_string(),
+            gas_total(4000, 1),
+        ),
+        (
+            "starcoin_natives.signature.secp256r1_verify.per_byte".to_string(),
+            gas_total(21, 1),
+        ),
+        (
+            "starcoin_natives.signature.bls12381_verify.per_byte".to_string(),
+            gas_total(21, 1),
+        ),
+        (
+            "starcoin_natives.signature.bls12381_aggregate_verify.per_byte".to_string(),
+            gas_total(21, 1),
+        ),
+        (
+            "starcoin_natives.signature.bls12381_verify_pop.per_byte".to_string(),
+            gas_total(21, 1),
+        ),
+    ]
+}
+
 // G_GAS_CONSTANTS_V1
 pub fn txn_gas_schedule_v1() -> Vec<(String, u64)> {
     vec![
@@ -579,6 +630,15 @@ static G_NATIVE_STRS: Lazy<Vec<&str>> = Lazy::new(|| {
         "move_stdlib.string.sub_string.per_byte",
         "move_stdlib.string.is_char_boundary.base",
         "move_stdlib.string.index_of.per_byte_searched",
+        "starcoin_natives.signature.bls12381_verify.base",
+        "starcoin_natives.signature.bls12381_aggregate_verify.base",
+        "starcoin_natives.signature.bls12381_aggregate_verify.per_pubkey",
+        "starcoin_natives.signature.bls12381_verify_pop.base",
+        "starcoin_natives.signature.secp256r1_verify.base",
+        "starcoin_natives.signature.secp256r1_verify.per_byte",
+        "starcoin_natives.signature.bls12381_verify.per_byte",
+        "starcoin_natives.signature.bls12381_aggregate_verify.per_byte",
+        "starcoin_natives.signature.bls12381_verify_pop.per_byte",
     ]
 });
 
@@ -609,6 +669,13 @@ impl From<&VMConfig> for GasSchedule {
             }
             entries.push((G_NATIVE_STRS[idx].to_string(), cost.total()));
         }
+        // the older on-chain native_table don't have the v5 natives, charge them with the v5 cost
+        // instead of zero, the v5 natives are only callable after the stdlib upgrade anyway.
+        for (key, cost) in native_gas_schedule_v5_delta() {
+            if !entries.iter().any(|(entry_key, _)| entry_key == &key) {
+                entries.push((key, cost));
+            }
+        }
 
         // native_table don't have these
         entries.push(("nursery.debug.print.base_cost".to_string(), 1));
@@ -30,8 +30,8 @@ pub use self::{
     gas_schedule::{
         instruction_gas_schedule_v1, instruction_gas_schedule_v2, native_gas_schedule_v1,
         native_gas_schedule_v2, native_gas_schedule_v3, native_gas_schedule_v4,
-        txn_gas_schedule_test, txn_gas_schedule_v1, txn_gas_schedule_v2, txn_gas_schedule_v3,
-        GasSchedule,
+        native_gas_schedule_v5, native_gas_schedule_v5_delta, txn_gas_schedule_test,
+        txn_gas_schedule_v1, txn_gas_schedule_v2, txn_gas_schedule_v3, GasSchedule,
     },
     genesis_gas_schedule::{
         instruction_table_v1, instruction_table_v2, native_table_v1, native_table_v2,
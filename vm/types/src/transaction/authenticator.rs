@@ -3,6 +3,9 @@
 
 use crate::account_address::AccountAddress;
 use crate::sign_message::SigningMessage;
+use crate::transaction::secp256r1::{
+    verify_webauthn_assertion, Secp256r1PublicKey, Secp256r1Signature, SECP256R1_PUBLIC_KEY_LENGTH,
+};
 use anyhow::{ensure, Error, Result};
 #[cfg(any(test, feature = "fuzzing"))]
 use proptest_derive::Arbitrary;
@@ -42,6 +45,7 @@ use std::{convert::TryFrom, fmt, str::FromStr};
 pub enum Scheme {
     Ed25519 = 0,
     MultiEd25519 = 1,
+    Secp256r1 = 2,
     // ... add more schemes here
 }
 
@@ -50,6 +54,7 @@ impl fmt::Display for Scheme {
         let display = match self {
             Scheme::Ed25519 => "Ed25519",
             Scheme::MultiEd25519 => "MultiEd25519",
+            Scheme::Secp256r1 => "Secp256r1",
         };
         write!(f, "Scheme::{}", display)
     }
@@ -72,6 +77,17 @@ pub enum TransactionAuthenticator {
         #[schemars(with = "String")]
         signature: MultiEd25519Signature,
     },
+    /// WebAuthn assertion signed by a secp256r1 (P-256) key, such as a passkey
+    WebAuthn {
+        #[schemars(with = "String")]
+        public_key: Secp256r1PublicKey,
+        #[schemars(with = "String")]
+        signature: Secp256r1Signature,
+        #[serde(with = "serde_bytes")]
+        authenticator_data: Vec<u8>,
+        #[serde(with = "serde_bytes")]
+        client_data_json: Vec<u8>,
+    },
     // ... add more schemes here
 }
 
@@ -81,6 +97,7 @@ impl TransactionAuthenticator {
         match self {
             Self::Ed25519 { .. } => Scheme::Ed25519,
             Self::MultiEd25519 { .. } => Scheme::MultiEd25519,
+            Self::WebAuthn { .. } => Scheme::Secp256r1,
         }
     }
 
@@ -103,6 +120,21 @@ impl TransactionAuthenticator {
         }
     }
 
+    /// Create a WebAuthn authenticator with a secp256r1 signature
+    pub fn webauthn(
+        public_key: Secp256r1PublicKey,
+        signature: Secp256r1Signature,
+        authenticator_data: Vec<u8>,
+        client_data_json: Vec<u8>,
+    ) -> Self {
+        Self::WebAuthn {
+            public_key,
+            signature,
+            authenticator_data,
+            client_data_json,
+        }
+    }
+
     /// Return Ok if the authenticator's public key matches its signature, Err otherwise
     pub fn verify<T: Serialize + CryptoHash>(&self, message: &T) -> Result<()> {
         match self {
@@ -114,6 +146,18 @@ impl TransactionAuthenticator {
                 public_key,
                 signature,
             } => signature.verify(message, public_key),
+            Self::WebAuthn {
+                public_key,
+                signature,
+                authenticator_data,
+                client_data_json,
+            } => verify_webauthn_assertion(
+                public_key,
+                signature,
+                authenticator_data,
+                client_data_json,
+                message.hash(),
+            ),
         }
     }
 
@@ -122,6 +166,7 @@ impl TransactionAuthenticator {
         match self {
             Self::Ed25519 { public_key, .. } => public_key.to_bytes().to_vec(),
             Self::MultiEd25519 { public_key, .. } => public_key.to_bytes().to_vec(),
+            Self::WebAuthn { public_key, .. } => public_key.to_bytes(),
         }
     }
 
@@ -129,6 +174,7 @@ impl TransactionAuthenticator {
         match self {
             Self::Ed25519 { public_key, .. } => AccountPublicKey::Single(public_key.clone()),
             Self::MultiEd25519 { public_key, .. } => AccountPublicKey::Multi(public_key.clone()),
+            Self::WebAuthn { public_key, .. } => AccountPublicKey::Secp256r1(public_key.clone()),
         }
     }
 
@@ -137,6 +183,7 @@ impl TransactionAuthenticator {
         match self {
             Self::Ed25519 { signature, .. } => signature.to_bytes().to_vec(),
             Self::MultiEd25519 { signature, .. } => signature.to_bytes().to_vec(),
+            Self::WebAuthn { signature, .. } => signature.to_bytes(),
         }
     }
 
@@ -206,6 +253,11 @@ impl AuthenticationKey {
         Self::from_preimage(&AuthenticationKeyPreimage::multi_ed25519(public_key))
     }
 
+    /// Create an authentication key from a secp256r1 public key
+    pub fn secp256r1(public_key: &Secp256r1PublicKey) -> Self {
+        Self::from_preimage(&AuthenticationKeyPreimage::secp256r1(public_key))
+    }
+
     /// Return an address derived from the last `AccountAddress::LENGTH` bytes of this
     /// authentication key.
     pub fn derived_address(&self) -> AccountAddress {
@@ -266,6 +318,11 @@ impl AuthenticationKeyPreimage {
         Self::new(public_key.to_bytes(), Scheme::MultiEd25519)
     }
 
+    /// Construct a preimage from a secp256r1 public key
+    pub fn secp256r1(public_key: &Secp256r1PublicKey) -> AuthenticationKeyPreimage {
+        Self::new(public_key.to_bytes(), Scheme::Secp256r1)
+    }
+
     /// Construct a vector from this authentication key
     pub fn into_vec(self) -> Vec<u8> {
         self.0
@@ -342,8 +399,15 @@ pub enum AccountPublicKey {
     Single(Ed25519PublicKey),
     #[schemars(with = "String")]
     Multi(MultiEd25519PublicKey),
+    /// Encoded as the compressed public key followed by the scheme id, so that it can not be
+    /// confused with the encoding of the other keys.
+    #[schemars(with = "String")]
+    Secp256r1(Secp256r1PublicKey),
 }
 
+/// The length of the encoded secp256r1 `AccountPublicKey`.
+const SECP256R1_ACCOUNT_PUBLIC_KEY_LENGTH: usize = SECP256R1_PUBLIC_KEY_LENGTH + 1;
+
 #[derive(Eq, PartialEq, Debug, DeserializeKey, SerializeKey)]
 pub enum AccountPrivateKey {
     Single(Ed25519PrivateKey),
@@ -355,6 +419,7 @@ impl ValidCryptoMaterial for AccountPublicKey {
         match self {
             Self::Single(key) => key.to_bytes().to_vec(),
             Self::Multi(key) => key.to_bytes(),
+            Self::Secp256r1(key) => AuthenticationKeyPreimage::secp256r1(key).into_vec(),
         }
     }
 }
@@ -379,6 +444,7 @@ impl AccountPublicKey {
         match self {
             Self::Single(p) => AuthenticationKeyPreimage::ed25519(p),
             Self::Multi(p) => AuthenticationKeyPreimage::multi_ed25519(p),
+            Self::Secp256r1(p) => AuthenticationKeyPreimage::secp256r1(p),
         }
     }
 
@@ -392,6 +458,7 @@ impl AccountPublicKey {
         match self {
             Self::Single(public_key) => public_key.to_bytes().to_vec(),
             Self::Multi(public_key) => public_key.to_bytes().to_vec(),
+            Self::Secp256r1(public_key) => public_key.to_bytes(),
         }
     }
 
@@ -400,6 +467,7 @@ impl AccountPublicKey {
         match self {
             Self::Single { .. } => Scheme::Ed25519,
             Self::Multi { .. } => Scheme::MultiEd25519,
+            Self::Secp256r1 { .. } => Scheme::Secp256r1,
         }
     }
 
@@ -424,6 +492,11 @@ impl TryFrom<&[u8]> for AccountPublicKey {
     fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
         if value.len() == ED25519_PUBLIC_KEY_LENGTH {
             Ed25519PublicKey::try_from(value).map(Self::Single)
+        } else if value.len() == SECP256R1_ACCOUNT_PUBLIC_KEY_LENGTH {
+            if value[SECP256R1_PUBLIC_KEY_LENGTH] != Scheme::Secp256r1 as u8 {
+                return Err(CryptoMaterialError::DeserializationError);
+            }
+            Secp256r1PublicKey::try_from(&value[..SECP256R1_PUBLIC_KEY_LENGTH]).map(Self::Secp256r1)
         } else {
             MultiEd25519PublicKey::try_from(value).map(Self::Multi)
         }
@@ -444,6 +517,13 @@ impl Into<AccountPublicKey> for MultiEd25519PublicKey {
     }
 }
 
+#[allow(clippy::from_over_into)]
+impl Into<AccountPublicKey> for Secp256r1PublicKey {
+    fn into(self) -> AccountPublicKey {
+        AccountPublicKey::Secp256r1(self)
+    }
+}
+
 impl ValidCryptoMaterial for AccountPrivateKey {
     fn to_bytes(&self) -> Vec<u8> {
         match self {
@@ -505,11 +585,47 @@ impl TryFrom<&[u8]> for AccountPrivateKey {
 
 #[cfg(test)]
 mod tests {
-    use crate::transaction::authenticator::{AccountPublicKey, AuthenticationKey};
+    use crate::transaction::authenticator::{
+        AccountPublicKey, AuthenticationKey, TransactionAuthenticator,
+    };
+    use crate::transaction::secp256r1::{Secp256r1PublicKey, Secp256r1Signature};
+    use crate::transaction::RawUserTransaction;
+    use p256::ecdsa::signature::Signer;
+    use p256::ecdsa::{Signature, SigningKey};
+    use rand::rngs::OsRng;
+    use sha2::{Digest, Sha256};
+    use starcoin_crypto::hash::CryptoHash;
     use starcoin_crypto::keygen::KeyGen;
     use starcoin_crypto::multi_ed25519::MultiEd25519PublicKey;
+    use starcoin_crypto::{HashValue, ValidCryptoMaterial};
+    use std::convert::TryFrom;
     use std::str::FromStr;
 
+    fn webauthn_sign(
+        key: &SigningKey,
+        challenge: HashValue,
+        ty: &str,
+    ) -> (Secp256r1PublicKey, Secp256r1Signature, Vec<u8>, Vec<u8>) {
+        let public_key =
+            Secp256r1PublicKey::try_from(key.verifying_key().to_encoded_point(true).as_bytes())
+                .unwrap();
+        let mut authenticator_data = vec![0u8; 37];
+        // user present
+        authenticator_data[32] = 0x01;
+        let client_data_json = format!(
+            r#"{{"type":"{}","challenge":"{}","origin":"https://wallet.starcoin.org"}}"#,
+            ty,
+            base64::encode_config(challenge.as_ref(), base64::URL_SAFE_NO_PAD)
+        )
+        .into_bytes();
+        let mut signed_data = authenticator_data.clone();
+        signed_data.extend_from_slice(Sha256::digest(&client_data_json).as_slice());
+        let signature: Signature = key.sign(&signed_data);
+        let signature = signature.normalize_s().unwrap_or(signature);
+        let signature = Secp256r1Signature::try_from(signature.as_ref()).unwrap();
+        (public_key, signature, authenticator_data, client_data_json)
+    }
+
     #[test]
     fn test_from_str_should_not_panic_by_given_empty_string() {
         assert!(AuthenticationKey::from_str("").is_err());
@@ -530,4 +646,79 @@ mod tests {
         let auth_key2 = AuthenticationKey::multi_ed25519(&multi_pubkey);
         assert_eq!(auth_key, auth_key2);
     }
+
+    #[test]
+    fn test_webauthn_authenticator() {
+        let key = SigningKey::random(&mut OsRng);
+        let raw_txn = RawUserTransaction::mock();
+        let (public_key, signature, authenticator_data, client_data_json) =
+            webauthn_sign(&key, raw_txn.hash(), "webauthn.get");
+        let authenticator = TransactionAuthenticator::webauthn(
+            public_key.clone(),
+            signature.clone(),
+            authenticator_data.clone(),
+            client_data_json.clone(),
+        );
+        assert!(authenticator.verify(&raw_txn).is_ok());
+        assert!(authenticator.verify(&RawUserTransaction::mock()).is_err());
+        assert_eq!(
+            authenticator.authentication_key(),
+            AuthenticationKey::secp256r1(&public_key)
+        );
+
+        let authenticator = TransactionAuthenticator::webauthn(
+            public_key.clone(),
+            signature,
+            authenticator_data,
+            client_data_json.clone(),
+        );
+        let bytes = bcs_ext::to_bytes(&authenticator).unwrap();
+        assert_eq!(
+            authenticator,
+            bcs_ext::from_bytes::<TransactionAuthenticator>(&bytes).unwrap()
+        );
+
+        // the assertion must be signed for a `webauthn.get` ceremony with the user present.
+        let (_, signature, authenticator_data, client_data_json) =
+            webauthn_sign(&key, raw_txn.hash(), "webauthn.create");
+        let authenticator = TransactionAuthenticator::webauthn(
+            public_key.clone(),
+            signature,
+            authenticator_data,
+            client_data_json,
+        );
+        assert!(authenticator.verify(&raw_txn).is_err());
+        let (_, signature, mut authenticator_data, client_data_json) =
+            webauthn_sign(&key, raw_txn.hash(), "webauthn.get");
+        authenticator_data[32] = 0;
+        let authenticator = TransactionAuthenticator::webauthn(
+            public_key,
+            signature,
+            authenticator_data,
+            client_data_json,
+        );
+        assert!(authenticator.verify(&raw_txn).is_err());
+    }
+
+    #[test]
+    fn test_secp256r1_account_public_key() {
+        let key = SigningKey::random(&mut OsRng);
+        let public_key =
+            Secp256r1PublicKey::try_from(key.verifying_key().to_encoded_point(true).as_bytes())
+                .unwrap();
+        let account_public_key = AccountPublicKey::Secp256r1(public_key.clone());
+        assert_eq!(
+            account_public_key,
+            AccountPublicKey::try_from(account_public_key.to_bytes().as_slice()).unwrap()
+        );
+        assert_eq!(
+            account_public_key.authentication_key(),
+            AuthenticationKey::secp256r1(&public_key)
+        );
+        // uncompressed keys are rejected, so a key only has one authentication key.
+        assert!(Secp256r1PublicKey::try_from(
+            key.verifying_key().to_encoded_point(false).as_bytes()
+        )
+        .is_err());
+    }
 }
@@ -54,6 +54,7 @@ mod module;
 mod package;
 mod pending_transaction;
 mod script;
+pub mod secp256r1;
 #[cfg(test)]
 mod tests;
 mod transaction_argument;
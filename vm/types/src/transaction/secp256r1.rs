@@ -0,0 +1,189 @@
+// Copyright (c) The Starcoin Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! secp256r1 (P-256) keys and the WebAuthn assertion used by passkey wallets to sign transactions.
+
+use anyhow::{bail, ensure, format_err, Result};
+use bcs_ext::Sample;
+use p256::ecdsa::signature::Verifier;
+use p256::ecdsa::{Signature, VerifyingKey};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use starcoin_crypto::{
+    derive::{DeserializeKey, SerializeKey},
+    CryptoMaterialError, HashValue, ValidCryptoMaterial, ValidCryptoMaterialStringExt,
+};
+use std::convert::TryFrom;
+use std::fmt;
+
+/// The length of a compressed SEC1 encoded P-256 public key.
+pub const SECP256R1_PUBLIC_KEY_LENGTH: usize = 33;
+/// The length of a `r || s` encoded P-256 signature.
+pub const SECP256R1_SIGNATURE_LENGTH: usize = 64;
+/// The max length of the authenticator data and the client data json of a WebAuthn assertion.
+pub const MAX_WEBAUTHN_DATA_LENGTH: usize = 1024;
+
+/// The minimal authenticator data: rpIdHash (32 bytes) | flags (1 byte) | signCount (4 bytes).
+const MIN_AUTHENTICATOR_DATA_LENGTH: usize = 37;
+const AUTHENTICATOR_DATA_FLAGS_INDEX: usize = 32;
+/// The user present flag in the authenticator data.
+const FLAG_USER_PRESENT: u8 = 0x01;
+const WEBAUTHN_GET_TYPE: &str = "webauthn.get";
+
+/// A P-256 public key, in compressed SEC1 encoding so that every key has only one authentication key.
+#[derive(Clone, DeserializeKey, Eq, Hash, PartialEq, SerializeKey)]
+pub struct Secp256r1PublicKey([u8; SECP256R1_PUBLIC_KEY_LENGTH]);
+
+impl Secp256r1PublicKey {
+    fn verifying_key(&self) -> VerifyingKey {
+        VerifyingKey::from_sec1_bytes(&self.0).expect("checked when construct the public key")
+    }
+}
+
+impl TryFrom<&[u8]> for Secp256r1PublicKey {
+    type Error = CryptoMaterialError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, CryptoMaterialError> {
+        if bytes.len() != SECP256R1_PUBLIC_KEY_LENGTH {
+            return Err(CryptoMaterialError::WrongLengthError);
+        }
+        VerifyingKey::from_sec1_bytes(bytes)
+            .map_err(|_| CryptoMaterialError::PointNotOnCurveError)?;
+        let mut key = [0u8; SECP256R1_PUBLIC_KEY_LENGTH];
+        key.copy_from_slice(bytes);
+        Ok(Self(key))
+    }
+}
+
+impl ValidCryptoMaterial for Secp256r1PublicKey {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_vec()
+    }
+}
+
+impl fmt::Debug for Secp256r1PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Secp256r1PublicKey({})", hex::encode(self.0))
+    }
+}
+
+impl Sample for Secp256r1PublicKey {
+    fn sample() -> Self {
+        // the generator point of P-256.
+        Self::from_encoded_string(
+            "036b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296",
+        )
+        .expect("sample public key should be valid")
+    }
+}
+
+/// A P-256 ECDSA signature encoded as `r || s`, only the low `s` form is accepted.
+#[derive(Clone, DeserializeKey, Eq, Hash, PartialEq, SerializeKey)]
+pub struct Secp256r1Signature([u8; SECP256R1_SIGNATURE_LENGTH]);
+
+impl Secp256r1Signature {
+    fn signature(&self) -> Signature {
+        Signature::try_from(&self.0[..]).expect("checked when construct the signature")
+    }
+}
+
+impl TryFrom<&[u8]> for Secp256r1Signature {
+    type Error = CryptoMaterialError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, CryptoMaterialError> {
+        if bytes.len() != SECP256R1_SIGNATURE_LENGTH {
+            return Err(CryptoMaterialError::WrongLengthError);
+        }
+        let signature =
+            Signature::try_from(bytes).map_err(|_| CryptoMaterialError::DeserializationError)?;
+        if signature.normalize_s().is_some() {
+            return Err(CryptoMaterialError::CanonicalRepresentationError);
+        }
+        let mut sig = [0u8; SECP256R1_SIGNATURE_LENGTH];
+        sig.copy_from_slice(bytes);
+        Ok(Self(sig))
+    }
+}
+
+impl ValidCryptoMaterial for Secp256r1Signature {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_vec()
+    }
+}
+
+impl fmt::Debug for Secp256r1Signature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Secp256r1Signature({})", hex::encode(self.0))
+    }
+}
+
+impl Sample for Secp256r1Signature {
+    fn sample() -> Self {
+        let mut sig = [0u8; SECP256R1_SIGNATURE_LENGTH];
+        sig[31] = 1;
+        sig[63] = 1;
+        Self(sig)
+    }
+}
+
+#[derive(Deserialize)]
+struct CollectedClientData {
+    #[serde(rename = "type")]
+    ty: String,
+    challenge: String,
+}
+
+/// Verify a WebAuthn assertion whose challenge is the `message_hash`.
+/// The authenticator signs `authenticator_data || sha256(client_data_json)`, and the client data
+/// json carries the base64url encoded challenge.
+///
+/// The origin of the client data and the rpIdHash of the authenticator data are not checked.
+/// The authentication key is derived from the public key only, and a passkey is scoped to the
+/// rpId it was created for: the authenticator never signs for another rpId, and the browser never
+/// requests an rpId the origin does not own. So any valid assertion of the key comes from the
+/// wallet the passkey was registered with, and an account can use a passkey of any wallet.
+pub fn verify_webauthn_assertion(
+    public_key: &Secp256r1PublicKey,
+    signature: &Secp256r1Signature,
+    authenticator_data: &[u8],
+    client_data_json: &[u8],
+    message_hash: HashValue,
+) -> Result<()> {
+    ensure!(
+        authenticator_data.len() >= MIN_AUTHENTICATOR_DATA_LENGTH
+            && authenticator_data.len() <= MAX_WEBAUTHN_DATA_LENGTH,
+        "Invalid WebAuthn authenticator data length: {}",
+        authenticator_data.len()
+    );
+    ensure!(
+        client_data_json.len() <= MAX_WEBAUTHN_DATA_LENGTH,
+        "Invalid WebAuthn client data length: {}",
+        client_data_json.len()
+    );
+    ensure!(
+        authenticator_data[AUTHENTICATOR_DATA_FLAGS_INDEX] & FLAG_USER_PRESENT != 0,
+        "WebAuthn user present flag is not set"
+    );
+    let client_data: CollectedClientData = serde_json::from_slice(client_data_json)
+        .map_err(|e| format_err!("Invalid WebAuthn client data: {}", e))?;
+    ensure!(
+        client_data.ty == WEBAUTHN_GET_TYPE,
+        "Invalid WebAuthn client data type: {}",
+        client_data.ty
+    );
+    let challenge = base64::encode_config(message_hash.as_ref(), base64::URL_SAFE_NO_PAD);
+    if client_data.challenge.trim_end_matches('=') != challenge {
+        bail!(
+            "WebAuthn challenge mismatch, expect: {}, got: {}",
+            challenge,
+            client_data.challenge
+        );
+    }
+
+    let mut signed_data = authenticator_data.to_vec();
+    signed_data.extend_from_slice(Sha256::digest(client_data_json).as_slice());
+    public_key
+        .verifying_key()
+        .verify(signed_data.as_slice(), &signature.signature())
+        .map_err(|e| format_err!("Invalid WebAuthn signature: {}", e))
+}
@@ -14,7 +14,10 @@ use move_vm_runtime::move_vm_adapter::{PublishModuleBundleOption, SessionAdapter
 use move_vm_runtime::session::Session;
 use starcoin_config::genesis_config::G_LATEST_GAS_PARAMS;
 use starcoin_crypto::HashValue;
-use starcoin_gas::{NativeGasParameters, StarcoinGasMeter, StarcoinGasParameters};
+use starcoin_gas::{
+    GasTracer, NativeGasParameters, StarcoinGasMeter, StarcoinGasParameters, TransactionGasMeter,
+    TransactionGasTrace,
+};
 use starcoin_gas_algebra_ext::{
     CostTable, FromOnChainGasSchedule, Gas, GasConstants, GasCost, InitialGasSchedule,
 };
@@ -50,6 +53,7 @@ use starcoin_vm_types::on_chain_config::{
 };
 use starcoin_vm_types::state_store::state_key::StateKey;
 use starcoin_vm_types::state_view::StateReaderExt;
+use starcoin_vm_types::transaction::authenticator::Scheme;
 use starcoin_vm_types::transaction::{DryRunTransaction, Package, TransactionPayloadType};
 use starcoin_vm_types::transaction_metadata::TransactionPayloadMetadata;
 use starcoin_vm_types::value::{serialize_values, MoveValue};
@@ -84,6 +88,9 @@ pub struct StarcoinVM {
 
 /// marking of stdlib version which includes vmconfig upgrades.
 const VMCONFIG_UPGRADE_VERSION_MARK: u64 = 10;
+/// marking of stdlib version from which the secp256r1 authenticator is enabled,
+/// it must be a version not released yet, the released chains enable it by the stdlib upgrade.
+const SECP256R1_AUTHENTICATOR_VERSION_MARK: u64 = 13;
 
 impl StarcoinVM {
     #[cfg(feature = "metrics")]
@@ -263,6 +270,21 @@ impl StarcoinVM {
         Ok(())
     }
 
+    fn check_authenticator_scheme(&self, txn_data: &TransactionMetadata) -> Result<(), VMStatus> {
+        // the scheme id is the last byte of the authentication key preimage.
+        if txn_data.authentication_key_preimage().last() == Some(&(Scheme::Secp256r1 as u8)) {
+            let stdlib_version = self.get_version()?.into_stdlib_version();
+            if stdlib_version < StdlibVersion::Version(SECP256R1_AUTHENTICATOR_VERSION_MARK) {
+                warn!(
+                    "[VM] secp256r1 authenticator is not enabled at stdlib version {}",
+                    stdlib_version
+                );
+                return Err(VMStatus::Error(StatusCode::FEATURE_UNDER_GATING));
+            }
+        }
+        Ok(())
+    }
+
     fn check_gas(&self, txn_data: &TransactionMetadata) -> Result<(), VMStatus> {
         let txn_gas_params = &self.get_gas_parameters()?.txn;
         let raw_bytes_len = txn_data.transaction_size;
@@ -487,10 +509,10 @@ impl StarcoinVM {
         }
     }
 
-    fn execute_package<S: StateView>(
+    fn execute_package<S: StateView, G: TransactionGasMeter>(
         &self,
         remote_cache: &StateViewCache<'_, S>,
-        gas_meter: &mut StarcoinGasMeter,
+        gas_meter: &mut G,
         txn_data: &TransactionMetadata,
         package: &Package,
     ) -> Result<(VMStatus, TransactionOutput), VMStatus> {
@@ -595,10 +617,10 @@ impl StarcoinVM {
         }
     }
 
-    fn execute_script_or_script_function<S: StateView>(
+    fn execute_script_or_script_function<S: StateView, G: TransactionGasMeter>(
         &self,
         remote_cache: &StateViewCache<'_, S>,
-        gas_meter: &mut StarcoinGasMeter,
+        gas_meter: &mut G,
         txn_data: &TransactionMetadata,
         payload: &TransactionPayload,
     ) -> Result<(VMStatus, TransactionOutput), VMStatus> {
@@ -665,12 +687,13 @@ impl StarcoinVM {
 
     /// Run the prologue of a transaction by calling into `PROLOGUE_NAME` function stored
     /// in the `ACCOUNT_MODULE` on chain.
-    fn run_prologue<R: MoveResolverExt>(
+    fn run_prologue<R: MoveResolverExt, G: TransactionGasMeter>(
         &self,
         session: &mut SessionAdapter<R>,
-        gas_meter: &mut StarcoinGasMeter,
+        gas_meter: &mut G,
         txn_data: &TransactionMetadata,
     ) -> Result<(), VMStatus> {
+        self.check_authenticator_scheme(txn_data)?;
         let genesis_address = genesis_address();
         let gas_token_ty =
             TypeTag::Struct(Box::new(txn_data.gas_token_code().try_into().map_err(
@@ -724,10 +747,10 @@ impl StarcoinVM {
 
     /// Run the epilogue of a transaction by calling into `EPILOGUE_NAME` function stored
     /// in the `ACCOUNT_MODULE` on chain.
-    fn run_epilogue<R: MoveResolverExt>(
+    fn run_epilogue<R: MoveResolverExt, G: TransactionGasMeter>(
         &self,
         session: &mut SessionAdapter<R>,
-        gas_meter: &mut StarcoinGasMeter,
+        gas_meter: &mut G,
         txn_data: &TransactionMetadata,
         success: bool,
     ) -> Result<(), VMStatus> {
@@ -950,6 +973,39 @@ impl StarcoinVM {
         state_view: &S,
         txn: DryRunTransaction,
     ) -> Result<(VMStatus, TransactionOutput)> {
+        self.dry_run_transaction_with_gas_meter(state_view, txn, |gas_meter, _payload| gas_meter)
+            .map(|(vm_status, output, _gas_meter)| (vm_status, output))
+    }
+
+    /// Dry run the transaction with a `GasTracer`, and return the gas profile of the transaction.
+    pub fn dry_run_transaction_with_trace<S: StateView>(
+        &mut self,
+        state_view: &S,
+        txn: DryRunTransaction,
+    ) -> Result<(VMStatus, TransactionOutput, TransactionGasTrace)> {
+        self.dry_run_transaction_with_gas_meter(state_view, txn, |gas_meter, payload| {
+            GasTracer::new(gas_meter, gas_trace_entry_name(payload))
+        })
+        .map(|(vm_status, output, gas_tracer)| {
+            (
+                vm_status,
+                output,
+                gas_tracer.map(GasTracer::finish).unwrap_or_default(),
+            )
+        })
+    }
+
+    fn dry_run_transaction_with_gas_meter<S, G, F>(
+        &mut self,
+        state_view: &S,
+        txn: DryRunTransaction,
+        new_gas_meter: F,
+    ) -> Result<(VMStatus, TransactionOutput, Option<G>)>
+    where
+        S: StateView,
+        G: TransactionGasMeter,
+        F: FnOnce(StarcoinGasMeter, &TransactionPayload) -> G,
+    {
         let remote_cache = StateViewCache::new(state_view);
         //TODO load config by config change event.
         self.load_configs(&remote_cache)?;
@@ -959,7 +1015,8 @@ impl StarcoinVM {
                 if remote_cache.is_genesis() {
                     &G_LATEST_GAS_PARAMS
                 } else {
-                    return Ok(discard_error_vm_status(e));
+                    let (vm_status, output) = discard_error_vm_status(e);
+                    return Ok((vm_status, output, None));
                 }
             }
         };
@@ -968,34 +1025,94 @@ impl StarcoinVM {
             txn.public_key.authentication_key_preimage(),
         ) {
             Ok(txn_data) => txn_data,
-            Err(e) => return Ok(discard_error_vm_status(e)),
+            Err(e) => {
+                let (vm_status, output) = discard_error_vm_status(e);
+                return Ok((vm_status, output, None));
+            }
         };
-        let mut gas_meter = StarcoinGasMeter::new(gas_params.clone(), txn_data.max_gas_amount());
+        let gas_meter = StarcoinGasMeter::new(gas_params.clone(), txn_data.max_gas_amount());
+        let mut gas_meter = new_gas_meter(gas_meter, txn.raw_txn.payload());
         gas_meter.set_metering(false);
-        let result = match txn.raw_txn.payload() {
-            payload @ TransactionPayload::Script(_)
-            | payload @ TransactionPayload::ScriptFunction(_) => self
-                .execute_script_or_script_function(
-                    &remote_cache,
-                    &mut gas_meter,
-                    &txn_data,
-                    payload,
-                ),
+        let (vm_status, output) = self.execute_transaction_payload(
+            &remote_cache,
+            &mut gas_meter,
+            &txn_data,
+            txn.raw_txn.payload(),
+        );
+        Ok((vm_status, output, Some(gas_meter)))
+    }
+
+    /// Re-execute the user transaction with a `GasTracer` against the given state, which should be
+    /// the state before the transaction is executed, and return the gas profile of the transaction.
+    pub fn trace_transaction<S: StateView>(
+        &mut self,
+        state_view: &S,
+        txn: SignedUserTransaction,
+    ) -> Result<(VMStatus, TransactionOutput, TransactionGasTrace)> {
+        let remote_cache = StateViewCache::new(state_view);
+        self.load_configs(&remote_cache)?;
+        let gas_params = match self.get_gas_parameters() {
+            Ok(gas_params) => gas_params,
+            Err(e) => {
+                let (vm_status, output) = discard_error_vm_status(e);
+                return Ok((vm_status, output, TransactionGasTrace::default()));
+            }
+        };
+        let txn_data = match TransactionMetadata::new(&txn) {
+            Ok(txn_data) => txn_data,
+            Err(e) => {
+                let (vm_status, output) = discard_error_vm_status(e);
+                return Ok((vm_status, output, TransactionGasTrace::default()));
+            }
+        };
+        let txn = match txn.check_signature() {
+            Ok(txn) => txn,
+            Err(_) => {
+                let (vm_status, output) =
+                    discard_error_vm_status(VMStatus::Error(StatusCode::INVALID_SIGNATURE));
+                return Ok((vm_status, output, TransactionGasTrace::default()));
+            }
+        };
+        let mut gas_meter = GasTracer::new(
+            StarcoinGasMeter::new(gas_params.clone(), txn_data.max_gas_amount()),
+            gas_trace_entry_name(txn.payload()),
+        );
+        gas_meter.set_metering(false);
+        let (vm_status, output) = self.execute_transaction_payload(
+            &remote_cache,
+            &mut gas_meter,
+            &txn_data,
+            txn.payload(),
+        );
+        Ok((vm_status, output, gas_meter.finish()))
+    }
+
+    fn execute_transaction_payload<S: StateView, G: TransactionGasMeter>(
+        &self,
+        remote_cache: &StateViewCache<'_, S>,
+        gas_meter: &mut G,
+        txn_data: &TransactionMetadata,
+        payload: &TransactionPayload,
+    ) -> (VMStatus, TransactionOutput) {
+        let result = match payload {
+            TransactionPayload::Script(_) | TransactionPayload::ScriptFunction(_) => {
+                self.execute_script_or_script_function(remote_cache, gas_meter, txn_data, payload)
+            }
             TransactionPayload::Package(p) => {
-                self.execute_package(&remote_cache, &mut gas_meter, &txn_data, p)
+                self.execute_package(remote_cache, gas_meter, txn_data, p)
             }
         };
-        Ok(match result {
+        match result {
             Ok(status_and_output) => status_and_output,
             Err(err) => {
                 let txn_status = TransactionStatus::from(err.clone());
                 if txn_status.is_discarded() {
                     discard_error_vm_status(err)
                 } else {
-                    self.failed_transaction_cleanup(err, &mut gas_meter, &txn_data, &remote_cache)
+                    self.failed_transaction_cleanup(err, gas_meter, txn_data, remote_cache)
                 }
             }
-        })
+        }
     }
 
     fn check_reconfigure<S: StateView>(
@@ -1164,8 +1281,6 @@ impl StarcoinVM {
                 .with_label_values(&["execute_readonly_function"])
                 .start_timer()
         });
-        let data_cache = state_view.as_move_resolver();
-
         let mut gas_meter = if check_gas {
             if let Err(err) = self.load_configs(state_view) {
                 warn!(
@@ -1188,15 +1303,65 @@ impl StarcoinVM {
             gas_meter.set_metering(false);
             gas_meter
         };
+        self.execute_readonly_function_with_gas_meter(
+            state_view,
+            module,
+            function_name,
+            type_params,
+            args,
+            &mut gas_meter,
+        )
+    }
+
+    /// Execute the readonly function with a `GasTracer`, and return the gas profile of the call.
+    pub fn execute_readonly_function_with_trace<S: StateView>(
+        &mut self,
+        state_view: &S,
+        module: &ModuleId,
+        function_name: &IdentStr,
+        type_params: Vec<TypeTag>,
+        args: Vec<Vec<u8>>,
+    ) -> Result<(Vec<Vec<u8>>, TransactionGasTrace), VMStatus> {
+        if let Err(err) = self.load_configs(state_view) {
+            warn!(
+                "Load config error at execute_readonly_function_with_trace: {}",
+                err
+            );
+            return Err(VMStatus::Error(StatusCode::VM_STARTUP_FAILURE));
+        }
+        let gas_params = self.get_gas_parameters()?;
+        let mut gas_meter = GasTracer::new(
+            StarcoinGasMeter::new(
+                G_LATEST_GAS_PARAMS.clone(),
+                gas_params.txn.maximum_number_of_gas_units,
+            ),
+            format!("{}::{}", module.short_str_lossless(), function_name),
+        );
+        gas_meter.set_metering(true);
+        let result = self.execute_readonly_function_with_gas_meter(
+            state_view,
+            module,
+            function_name,
+            type_params,
+            args,
+            &mut gas_meter,
+        )?;
+        Ok((result, gas_meter.finish()))
+    }
+
+    fn execute_readonly_function_with_gas_meter<S: StateView, G: TransactionGasMeter>(
+        &self,
+        state_view: &S,
+        module: &ModuleId,
+        function_name: &IdentStr,
+        type_params: Vec<TypeTag>,
+        args: Vec<Vec<u8>>,
+        gas_meter: &mut G,
+    ) -> Result<Vec<Vec<u8>>, VMStatus> {
+        let data_cache = state_view.as_move_resolver();
         let mut session = self.move_vm.new_session(&data_cache, SessionId::void());
         let result = session
-            .execute_function_bypass_visibility(
-                module,
-                function_name,
-                type_params,
-                args,
-                &mut gas_meter,
-            )
+            .execute_function_bypass_visibility(module, function_name, type_params, args, gas_meter)
             .map_err(|e| e.into_vm_status())?
             .return_values
             .into_iter()
@@ -1223,10 +1388,10 @@ impl StarcoinVM {
         Ok(result)
     }
 
-    fn success_transaction_cleanup<R: MoveResolverExt>(
+    fn success_transaction_cleanup<R: MoveResolverExt, G: TransactionGasMeter>(
         &self,
         mut session: SessionAdapter<R>,
-        gas_meter: &mut StarcoinGasMeter,
+        gas_meter: &mut G,
         txn_data: &TransactionMetadata,
     ) -> Result<(VMStatus, TransactionOutput), VMStatus> {
         gas_meter.set_metering(false);
@@ -1244,10 +1409,10 @@ impl StarcoinVM {
         ))
     }
 
-    fn failed_transaction_cleanup<S: StateView>(
+    fn failed_transaction_cleanup<S: StateView, G: TransactionGasMeter>(
         &self,
         error_code: VMStatus,
-        gas_meter: &mut StarcoinGasMeter,
+        gas_meter: &mut G,
         txn_data: &TransactionMetadata,
         remote_cache: &StateViewCache<'_, S>,
     ) -> (VMStatus, TransactionOutput) {
@@ -1330,8 +1495,8 @@ pub fn chunk_block_transactions(txns: Vec<Transaction>) -> Vec<TransactionBlock>
     blocks
 }
 
-pub(crate) fn charge_global_write_gas_usage<R: MoveResolverExt>(
-    gas_meter: &mut StarcoinGasMeter,
+pub(crate) fn charge_global_write_gas_usage<R: MoveResolverExt, G: TransactionGasMeter>(
+    gas_meter: &mut G,
     session: &SessionAdapter<R>,
     sender: &AccountAddress,
 ) -> Result<(), VMStatus> {
@@ -1345,10 +1510,23 @@ pub(crate) fn charge_global_write_gas_usage<R: MoveResolverExt>(
         gas_meter.get_metering()
     );
     gas_meter
-        .deduct_gas(total_cost)
+        .charge_write_set_gas(total_cost)
         .map_err(|p_err| p_err.finish(Location::Undefined).into_vm_status())
 }
 
+/// The name of the root frame in the gas profile of a transaction.
+fn gas_trace_entry_name(payload: &TransactionPayload) -> String {
+    match payload {
+        TransactionPayload::Script(_) => "script".to_string(),
+        TransactionPayload::ScriptFunction(script_function) => format!(
+            "{}::{}",
+            script_function.module().short_str_lossless(),
+            script_function.function()
+        ),
+        TransactionPayload::Package(_) => "package".to_string(),
+    }
+}
+
 pub(crate) fn discard_error_vm_status(err: VMStatus) -> (VMStatus, TransactionOutput) {
     info!("discard error vm_status output: {:?}", err);
     let vm_status = err.clone();